@@ -1,3 +1,49 @@
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use ratatui::layout::{Position, Rect};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::app_event::AppEvent;
+use crate::attachments;
+use crate::audit::AuditLog;
+use crate::buffer::TextBuffer;
+use crate::clipboard;
+use crate::config::Config;
+use crate::context::GitContext;
+use crate::entry_templates::{EntryTemplate, find_placeholder};
+use crate::extractors::{ExtractTarget, extract_all};
+use crate::fields::InputField;
+use crate::fuzzy;
+use crate::links;
+use crate::markdown;
+use crate::markup::looks_like_diff;
+use crate::network::Health;
+use crate::notion::activity::ApiCall;
+use crate::notion::capabilities::Capabilities;
+use crate::notion::client::{AppendedBlocks, NotionError, sha256_hex};
+use crate::notion::entries::LoggedEntry;
+use crate::notion::languages::{self, LANGUAGES};
+use crate::notion::preview::PreviewLine;
+use crate::notion::template::BlockTemplate;
+use crate::notion::tokens::TokenMap;
+use crate::page_settings::{PageSetting, PageSettings, PageSettingsStore};
+use crate::page_tree::tree_order;
+use crate::queue::{QueuedSubmission, SubmissionQueue};
+use crate::sink::{Receipt, Sink};
+use crate::storage::{History, HistoryEntry};
+use crate::submit::find_page;
+use crate::tags;
+use crate::trace_ids;
+use crate::vars::Vars;
+use crate::wrap::WrapCache;
+
 /// Which major section of the UI has focus
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum FocusArea {
@@ -6,6 +52,200 @@ pub enum FocusArea {
     InputSection,
 }
 
+/// The most recent successful submission, kept so it can be undone
+#[derive(Debug, Clone)]
+pub struct LastSubmission {
+    pub page_id: String,
+    pub entry: FaultLogEntry,
+    /// Top-level blocks created by the submission
+    pub block_ids: Vec<String>,
+    /// Token that created the blocks, so the same one deletes them
+    pub token: String,
+    pub submitted_at: Instant,
+    /// Row in the local history, marked undone if the blocks are deleted
+    pub history_id: Option<i64>,
+}
+
+/// A submission running on a background task while the UI stays live
+#[derive(Debug)]
+pub struct InFlightSubmission {
+    /// Pages and databases the entry goes to, usually one
+    pub targets: Vec<PageInfo>,
+    pub entry: FaultLogEntry,
+    pub task: SubmissionTask,
+}
+
+/// The task behind a submission; each resolves to one result per target,
+/// in order
+#[derive(Debug)]
+pub enum SubmissionTask {
+    /// The token that succeeded and the created blocks
+    Notion(JoinHandle<Vec<Result<(String, AppendedBlocks), NotionError>>>),
+    /// What a sink other than Notion created
+    Sink(JoinHandle<Vec<Result<Receipt, String>>>),
+}
+
+/// Queued entries being sent again on a background task, each with the
+/// token that succeeded and the created blocks
+pub type RetryTask = JoinHandle<Vec<(QueuedSubmission, Result<(String, AppendedBlocks), NotionError>)>>;
+
+/// Which full-screen view is shown in the main area
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Screen {
+    #[default]
+    Main,
+    Queue,
+    History,
+    /// Entries already on the selected Notion page
+    Entries,
+    /// The selected page's whole content, read-only
+    Preview,
+    /// Remembered formatting for the selected page
+    PageSettings,
+    /// Rows of a database, to pick where entries go or what they link to
+    Rows,
+    /// Relation properties of the selected database
+    Relations,
+}
+
+/// An irreversible action waiting for Yes in the confirmation dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingAction {
+    Submit,
+    ClearInputs,
+    /// Drop the selected entry from the offline queue
+    DiscardQueued,
+}
+
+/// What the row picker is choosing
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum RowPick {
+    /// The row of the selected database that entries are appended to
+    #[default]
+    Target,
+    /// A page new rows link to through a relation property
+    Relation { property: String, database_id: String },
+}
+
+/// A relation property and the database its pages come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelationProperty {
+    pub name: String,
+    pub database_id: String,
+}
+
+/// Title and parent typed into the new page prompt
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NewPagePrompt {
+    pub title: String,
+    /// Id of the page the new one goes under; a reload may reorder the
+    /// page list while the prompt is open
+    pub parent: String,
+}
+
+/// What a status message reports, which decides how long it stays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusKind {
+    Info,
+    /// Fades after `SUCCESS_LIFETIME`
+    Success,
+    /// Stays until dismissed or replaced
+    Error,
+    /// Work in progress, shown with a spinner until replaced
+    Loading,
+}
+
+impl StatusKind {
+    /// Level shown in the activity log
+    pub fn level(self) -> &'static str {
+        match self {
+            StatusKind::Info => "INFO",
+            StatusKind::Success => "OK",
+            StatusKind::Error => "ERROR",
+            StatusKind::Loading => "WAIT",
+        }
+    }
+}
+
+/// What added a record to the activity log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogSource {
+    /// A status message
+    App,
+    /// A Notion request
+    Api,
+}
+
+impl LogSource {
+    pub fn label(self) -> &'static str {
+        match self {
+            LogSource::App => "app",
+            LogSource::Api => "api",
+        }
+    }
+}
+
+/// The status line shown in the title bar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusMessage {
+    pub text: String,
+    pub kind: StatusKind,
+    pub since: Instant,
+}
+
+impl StatusMessage {
+    /// How long a success stays up
+    pub const SUCCESS_LIFETIME: Duration = Duration::from_secs(3);
+    /// How long other news stays up
+    pub const INFO_LIFETIME: Duration = Duration::from_secs(6);
+
+    pub fn new(kind: StatusKind, text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            kind,
+            since: Instant::now(),
+        }
+    }
+
+    /// True once the message has been up for its lifetime at `now`;
+    /// errors and loading messages never expire
+    pub fn is_expired(&self, now: Instant) -> bool {
+        let lifetime = match self.kind {
+            StatusKind::Success => Self::SUCCESS_LIFETIME,
+            StatusKind::Info => Self::INFO_LIFETIME,
+            StatusKind::Error | StatusKind::Loading => return false,
+        };
+        now.saturating_duration_since(self.since) >= lifetime
+    }
+}
+
+impl std::ops::Deref for StatusMessage {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl std::fmt::Display for StatusMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// A status line as it was shown, or a Notion request, kept in the
+/// activity log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusRecord {
+    pub at: DateTime<Local>,
+    pub message: String,
+    pub kind: StatusKind,
+    pub source: LogSource,
+    /// Times it was shown in a row; repeats update the record instead of
+    /// adding new ones
+    pub repeats: usize,
+}
+
 /// Current input mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum InputMode {
@@ -14,57 +254,608 @@ pub enum InputMode {
     Editing,
 }
 
+/// What a search result is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetKind {
+    #[default]
+    Page,
+    /// Each entry becomes a new row, unless a row is picked to append to
+    Database,
+}
+
+/// Where a fault stands, kept in a database's Status property so boards
+/// built on it stay current
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FaultStatus {
+    #[default]
+    Open,
+    Investigating,
+    Fixed,
+}
+
+impl FaultStatus {
+    /// Option name in Notion's select or status property
+    pub fn name(self) -> &'static str {
+        match self {
+            FaultStatus::Open => "Open",
+            FaultStatus::Investigating => "Investigating",
+            FaultStatus::Fixed => "Fixed",
+        }
+    }
+
+    /// Page icon of a row in this state, so gallery and board views scan
+    /// at a glance
+    pub fn emoji(self) -> &'static str {
+        match self {
+            FaultStatus::Open => "🔴",
+            FaultStatus::Investigating => "🟡",
+            FaultStatus::Fixed => "🟢",
+        }
+    }
+
+    /// Next step of the lifecycle, wrapping back to Open
+    pub fn next(self) -> Self {
+        match self {
+            FaultStatus::Open => FaultStatus::Investigating,
+            FaultStatus::Investigating => FaultStatus::Fixed,
+            FaultStatus::Fixed => FaultStatus::Open,
+        }
+    }
+}
+
+/// How bad a fault is; colors the entry heading and leads it with an emoji
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 4] = [Severity::Critical, Severity::High, Severity::Medium, Severity::Low];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Critical => "Critical",
+            Severity::High => "High",
+            Severity::Medium => "Medium",
+            Severity::Low => "Low",
+        }
+    }
+
+    /// Leads the entry heading in place of the bug emoji
+    pub fn emoji(self) -> &'static str {
+        match self {
+            Severity::Critical => "🚨",
+            Severity::High => "🔥",
+            Severity::Medium => "⚠️",
+            Severity::Low => "🔹",
+        }
+    }
+
+    /// Notion color of the entry heading, replacing the configured one
+    pub fn color(self) -> &'static str {
+        match self {
+            Severity::Critical => "red_background",
+            Severity::High => "red",
+            Severity::Medium => "orange",
+            Severity::Low => "blue",
+        }
+    }
+
+    /// Next level down, from no severity through Critical to Low and back
+    /// to none
+    pub fn cycle(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(Severity::Critical),
+            Some(Severity::Critical) => Some(Severity::High),
+            Some(Severity::High) => Some(Severity::Medium),
+            Some(Severity::Medium) => Some(Severity::Low),
+            Some(Severity::Low) => None,
+        }
+    }
+}
+
 /// Simplified Notion page info for UI display
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct PageInfo {
     pub id: String,
     pub title: String,
+    pub kind: TargetKind,
+    /// Page or database this one is nested in, when known
+    pub parent: Option<String>,
+}
+
+impl PageInfo {
+    pub fn is_database(&self) -> bool {
+        self.kind == TargetKind::Database
+    }
 }
 
 /// Data to be sent to Notion when submitting a fault log
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct FaultLogEntry {
     pub error: String,
     pub problem: String,
     pub solution: String,
     pub code: Option<String>,
+    /// Steps to confirm the fix, one per line
+    pub verification: Option<String>,
+    /// Environment details as `key: value` lines
+    pub context: Option<String>,
+    /// Notion language for the code block
+    pub language: Option<String>,
+    /// Files uploaded to Notion and embedded in the entry
+    pub attachments: Vec<Attachment>,
+    /// Paths and links still to attach; files are uploaded on submission
+    pub files: Vec<String>,
+    /// Labels such as "db" or "prod" listed at the end of the entry
+    pub tags: Vec<String>,
+    /// Pages linked from a new database row, by relation property name
+    pub relations: BTreeMap<String, Vec<String>>,
+    /// Status of the fault when it becomes a database row
+    pub status: FaultStatus,
+    /// How bad the fault is, if marked
+    pub severity: Option<Severity>,
+    /// Git checkout the entry was logged from
+    pub git: Option<GitContext>,
+    /// Related entries grouped under this one, e.g. several stack traces
+    /// from one incident; this entry is then their summary
+    pub sub_entries: Vec<FaultLogEntry>,
+}
+
+impl FaultLogEntry {
+    /// Hash of what the entry says (error, problem, solution and code,
+    /// ignoring surrounding whitespace, and its sub-entries), to spot the
+    /// same entry logged twice
+    pub fn content_hash(&self) -> String {
+        let fields = [
+            self.error.trim(),
+            self.problem.trim(),
+            self.solution.trim(),
+            self.code.as_deref().unwrap_or_default().trim(),
+        ];
+        let mut content = fields.join("\u{1f}");
+        for sub_entry in &self.sub_entries {
+            content.push('\u{1e}');
+            content.push_str(&sub_entry.content_hash());
+        }
+        sha256_hex(content.as_bytes())
+    }
+
+    /// Text of one field, empty when an optional field is unset; tags are
+    /// joined with ", " as they are typed
+    pub fn field(&self, field: InputField) -> Cow<'_, str> {
+        let text = match field {
+            InputField::Error => &self.error,
+            InputField::Problem => &self.problem,
+            InputField::Solution => &self.solution,
+            InputField::Code => self.code.as_deref().unwrap_or_default(),
+            InputField::Verification => self.verification.as_deref().unwrap_or_default(),
+            InputField::Context => self.context.as_deref().unwrap_or_default(),
+            InputField::Tags => return Cow::Owned(self.tags.join(", ")),
+        };
+        Cow::Borrowed(text)
+    }
+}
+
+/// A file already uploaded through Notion's file upload API, or a link
+/// Notion embeds as an external file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attachment {
+    pub name: String,
+    pub upload_id: String,
+    /// Link to the file, for attachments Notion does not host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// Kind of change made to a field; consecutive changes of the same kind
+/// undo together
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditKind {
+    /// Typing a word; a space or line break ends the word
+    Typing,
+    /// Backspace or Delete presses in a row
+    Deleting,
+    /// A paste or line break, always undone on its own
+    Block,
+}
+
+/// Earlier values of a field being stepped through with Up/Down while
+/// editing, like shell history
+#[derive(Debug, Clone)]
+pub struct FieldRecall {
+    pub field: InputField,
+    /// Distinct earlier values, most recent first
+    pub values: Vec<String>,
+    /// How far back the field is, 1 for the most recent value
+    pub position: usize,
+    /// What the field held before stepping back
+    pub draft: TextBuffer,
+}
+
+/// Undo and redo snapshots of one field. Snapshots share structure with
+/// the live buffer, so keeping many is cheap.
+#[derive(Debug, Clone, Default)]
+pub struct EditHistory {
+    undo: Vec<TextBuffer>,
+    redo: Vec<TextBuffer>,
+    /// Kind of the change still being grouped, if any
+    group: Option<EditKind>,
+}
+
+impl EditHistory {
+    /// Most snapshots kept per field
+    pub const LIMIT: usize = 200;
+
+    /// Note that `before` is about to change. A change that continues the
+    /// current group keeps the snapshot taken at its start.
+    pub fn record(&mut self, kind: EditKind, before: &TextBuffer) {
+        if kind != EditKind::Block && self.group == Some(kind) {
+            return;
+        }
+        if self.undo.len() == Self::LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push(before.clone());
+        self.redo.clear();
+        self.group = (kind != EditKind::Block).then_some(kind);
+    }
+
+    /// End the current group, e.g. when the cursor moves
+    pub fn break_group(&mut self) {
+        self.group = None;
+    }
+
+    /// Restore the snapshot before the last change; false when there is none
+    pub fn undo(&mut self, buffer: &mut TextBuffer) -> bool {
+        let Some(previous) = self.undo.pop() else {
+            return false;
+        };
+        self.redo.push(std::mem::replace(buffer, previous));
+        self.group = None;
+        true
+    }
+
+    /// Reapply the last undone change; false when there is none
+    pub fn redo(&mut self, buffer: &mut TextBuffer) -> bool {
+        let Some(next) = self.redo.pop() else {
+            return false;
+        };
+        self.undo.push(std::mem::replace(buffer, next));
+        self.group = None;
+        true
+    }
 }
 
 /// Main application state
 #[derive(Debug)]
 pub struct AppState {
     pub running: bool,
+    /// Rendering inline because the terminal has no alternate screen
+    pub inline: bool,
+    /// Compact layout for a thin pane: the status and the active field
+    /// only, until expanded again
+    pub mini: bool,
+    /// Distraction-free writing: the active field alone, centered
+    pub zen: bool,
+    /// Opened by the quick-capture shortcut: typing goes to the Error
+    /// field and FaultNote closes once the entry is logged
+    pub capture: bool,
+    /// Paths and links attached to the entry being written
+    pub attached_files: Vec<String>,
+    /// Path or link typed into the attach prompt, while it is open
+    pub attach_input: Option<String>,
+    /// The new page prompt, while it is open
+    pub new_page: Option<NewPagePrompt>,
+    /// Page named with `--page`, selected once the page list arrives
+    pub wanted_page: Option<String>,
+    /// Ctrl+Z was pressed; the main loop restores the terminal and stops
+    pub suspend_requested: bool,
+    /// F12 was pressed; the next rendered frame is written to a file
+    pub screenshot_requested: bool,
+    /// `r` was pressed; the main loop starts a background page fetch
+    pub refresh_requested: bool,
+    /// `E` was pressed; the main loop opens the active field in `$EDITOR`
+    pub editor_requested: bool,
+    /// A background page fetch is running
+    pub pages_loading: bool,
+    pub screen: Screen,
     pub current_focus: FocusArea,
     pub input_mode: InputMode,
+    /// Pages in tree order: every page follows the page it is nested in
     pub notion_pages: Vec<PageInfo>,
+    /// Depth of each page in the tree, by index into `notion_pages`
+    pub page_depths: Vec<usize>,
+    /// Pages whose subpages are folded away, by id
+    pub collapsed_pages: HashSet<String>,
+    /// Indices of the pages listed while not filtering: those under no
+    /// collapsed page
+    pub page_rows: Vec<usize>,
     pub selected_page_index: usize,
+    /// Keys go to the page filter instead of navigating
+    pub page_filtering: bool,
+    /// Text typed into the page filter
+    pub page_filter: String,
+    /// Indices of the pages matching the filter, best first
+    pub page_matches: Vec<usize>,
+    pub selected_match: usize,
+    /// Rows the page list showed on the last frame, used for PgUp/PgDn
+    pub page_list_height: Cell<usize>,
+    /// Where the page list was drawn on the last frame, for mouse clicks
+    pub page_list_area: Cell<Rect>,
     pub active_input_field: usize,
-    pub error_input: String,
-    pub problem_input: String,
-    pub solution_input: String,
-    pub code_input: String,
-    pub status_message: Option<String>,
+    pub error_input: TextBuffer,
+    pub problem_input: TextBuffer,
+    pub solution_input: TextBuffer,
+    pub code_input: TextBuffer,
+    pub verification_input: TextBuffer,
+    pub context_input: TextBuffer,
+    /// Comma separated tags, added to the page's Tags property or shown as
+    /// colored chips
+    pub tags_input: TextBuffer,
+    pub code_language: String,
+    /// Status given to the entry when it becomes a database row
+    pub fault_status: FaultStatus,
+    /// Severity of the entry being composed
+    pub severity: Option<Severity>,
+    /// Git state of the working directory, refreshed on each submission
+    pub git_context: Option<GitContext>,
+    /// Keys go to the language picker popup
+    pub language_picking: bool,
+    /// Text typed into the language picker
+    pub language_filter: String,
+    /// Indices into `LANGUAGES` matching the filter, best first
+    pub language_matches: Vec<usize>,
+    pub selected_language: usize,
+    /// Keys go to the entry template picker popup
+    pub template_picking: bool,
+    /// Text typed into the template picker
+    pub template_filter: String,
+    /// Templates matching the filter, best first
+    pub template_matches: Vec<(String, EntryTemplate)>,
+    pub selected_template: usize,
+    /// Keys go to the confirmation dialog for this action
+    pub pending_action: Option<PendingAction>,
+    /// Yes is highlighted in the confirmation dialog, rather than No
+    pub confirm_yes: bool,
+    /// Wrapped layout of each input field, reused between frames
+    pub field_layouts: RefCell<[WrapCache; AppState::MAX_INPUTS]>,
+    /// Rows scrolled off the top of each field; follows the cursor while
+    /// editing and is clamped to the content when drawn
+    pub field_scroll: Cell<[usize; AppState::MAX_INPUTS]>,
+    /// Rows each field showed on the last frame, used for PgUp/PgDn
+    pub field_heights: Cell<[usize; AppState::MAX_INPUTS]>,
+    /// Where each field was drawn on the last frame, for mouse clicks
+    pub field_areas: Cell<[Rect; AppState::MAX_INPUTS]>,
+    /// Undo/redo snapshots of each field while editing
+    pub edit_history: [EditHistory; AppState::MAX_INPUTS],
+    /// Earlier values being stepped through in the field being edited
+    pub recall: Option<FieldRecall>,
+    /// The status line; successes and news clear themselves on a tick
+    pub status_message: Option<StatusMessage>,
+    /// Every status line shown and Notion request made, oldest first, up
+    /// to `STATUS_LOG_LIMIT`
+    pub status_log: VecDeque<StatusRecord>,
+    /// The activity log drawer is shown above the command bar
+    pub status_log_open: bool,
+    /// Records scrolled back from the newest in the activity log
+    pub status_log_scroll: usize,
+    /// Where the activity log was drawn on the last frame, for the wheel
+    pub status_log_area: Cell<Rect>,
     pub is_loading: bool,
+    /// When the current loading state began, drives the spinner
+    pub loading_since: Option<Instant>,
+    pub submission: Option<InFlightSubmission>,
+    /// Queued entries being retried while the UI stays live
+    pub retry: Option<RetryTask>,
+    /// Where background tasks report back to the main loop; `None` outside
+    /// the TUI, where their results are awaited instead
+    pub events: Option<mpsc::UnboundedSender<AppEvent>>,
+    /// Last known connectivity to Notion
+    pub online: bool,
+    /// Latest health ping, once one has completed
+    pub health: Option<Health>,
+    /// What the integration token may do, once probed
+    pub capabilities: Option<Capabilities>,
+    /// Set after warning that the entry exceeds Notion's limits;
+    /// the next submit attaches the overflow as a file
+    pub overflow_confirmed: bool,
+    /// What the last Markdown export held, entry and targets, so trying
+    /// again after a failed submit does not write the file twice
+    pub exported: Option<String>,
+    pub config: Config,
+    /// Set when `[sink] kind` is not Notion: entries go to the listed
+    /// repositories or notes instead
+    pub sink: Option<Sink>,
+    pub queue: SubmissionQueue,
+    pub selected_queue_index: usize,
+    /// Which token last succeeded for each page
+    pub token_map: TokenMap,
+    /// Local record of every write to Notion
+    pub audit_log: AuditLog,
+    /// Local copy of every entry, searchable without Notion
+    pub history: History,
+    /// Entries shown on the history screen, newest first
+    pub history_entries: Vec<HistoryEntry>,
+    pub selected_history_index: usize,
+    /// Filter typed on the history screen
+    pub history_query: String,
+    /// Keys go to the history filter instead of navigating
+    pub history_searching: bool,
+    /// Entries read back from the selected Notion page
+    pub page_entries: Vec<LoggedEntry>,
+    pub selected_page_entry: usize,
+    /// Lines scrolled in the entry reader
+    pub entry_scroll: u16,
+    /// Content of the page being previewed, and its title
+    pub page_preview: Vec<PreviewLine>,
+    pub preview_title: String,
+    /// Lines scrolled in the page preview
+    pub preview_scroll: u16,
+    /// Formatting remembered for each page
+    pub page_settings: PageSettingsStore,
+    pub selected_setting: usize,
+    /// Text being typed for the selected setting
+    pub setting_input: Option<String>,
+    /// Rows of the selected database, newest first
+    pub database_rows: Vec<PageInfo>,
+    /// Indices of the rows matching `row_query`, best first
+    pub row_matches: Vec<usize>,
+    pub selected_row_match: usize,
+    /// Filter typed on the row picker
+    pub row_query: String,
+    /// Keys go to the row filter instead of navigating
+    pub row_searching: bool,
+    /// Row picked in each database, by database id
+    pub chosen_rows: HashMap<String, PageInfo>,
+    /// Pages checked with Space, in the order checked; entries go to all
+    /// of them instead of the selected page
+    pub checked_pages: Vec<String>,
+    /// Position in the links panel
+    pub selected_link: usize,
+    /// Drafts stacked with "add sub-entry", submitted under the next entry
+    pub sub_entries: Vec<FaultLogEntry>,
+    pub row_pick: RowPick,
+    /// Relation properties of the selected database, once fetched
+    pub relation_properties: Vec<RelationProperty>,
+    pub selected_relation: usize,
+    /// Pages linked from new rows, by database id then property name
+    pub relations: HashMap<String, BTreeMap<String, Vec<PageInfo>>>,
+    pub last_submission: Option<LastSubmission>,
 }
 
 impl AppState {
-    const MAX_INPUTS: usize = 4;
+    pub const MAX_INPUTS: usize = InputField::ALL.len();
+    const DEFAULT_PAGE_LIST_HEIGHT: usize = 10;
+    /// Most earlier values Up steps through in a field
+    pub const RECALL_LIMIT: usize = 50;
+    /// Records kept in the activity log
+    pub const STATUS_LOG_LIMIT: usize = 500;
+    /// Records PgUp/PgDn move through the open activity log
+    pub const STATUS_LOG_PAGE: usize = 5;
+    pub const DEFAULT_CODE_LANGUAGE: &'static str = languages::DEFAULT_LANGUAGE;
+    pub const MERMAID_LANGUAGE: &'static str = "mermaid";
+    pub const DIFF_LANGUAGE: &'static str = "diff";
+    /// How long a submission can be undone
+    pub const UNDO_WINDOW: Duration = Duration::from_secs(30);
+    /// Most entries loaded into the history screen at once
+    pub const HISTORY_LIMIT: usize = 500;
 
     /// Create a new AppState with default values
     pub fn new() -> Self {
         Self {
             running: true,
+            inline: false,
+            mini: false,
+            zen: false,
+            capture: false,
+            attached_files: Vec::new(),
+            attach_input: None,
+            new_page: None,
+            wanted_page: None,
+            suspend_requested: false,
+            screenshot_requested: false,
+            refresh_requested: false,
+            editor_requested: false,
+            pages_loading: false,
+            screen: Screen::Main,
             current_focus: FocusArea::PageList,
             input_mode: InputMode::Normal,
             notion_pages: Vec::new(),
+            page_depths: Vec::new(),
+            collapsed_pages: HashSet::new(),
+            page_rows: Vec::new(),
             selected_page_index: 0,
+            page_filtering: false,
+            page_filter: String::new(),
+            page_matches: Vec::new(),
+            selected_match: 0,
+            page_list_height: Cell::new(Self::DEFAULT_PAGE_LIST_HEIGHT),
+            page_list_area: Cell::default(),
             active_input_field: 0,
-            error_input: String::new(),
-            problem_input: String::new(),
-            solution_input: String::new(),
-            code_input: String::new(),
+            error_input: TextBuffer::new(),
+            problem_input: TextBuffer::new(),
+            solution_input: TextBuffer::new(),
+            code_input: TextBuffer::new(),
+            verification_input: TextBuffer::new(),
+            context_input: TextBuffer::new(),
+            tags_input: TextBuffer::new(),
+            code_language: Self::DEFAULT_CODE_LANGUAGE.to_string(),
+            fault_status: FaultStatus::Open,
+            severity: None,
+            git_context: None,
+            language_picking: false,
+            language_filter: String::new(),
+            language_matches: Vec::new(),
+            selected_language: 0,
+            template_picking: false,
+            template_filter: String::new(),
+            template_matches: Vec::new(),
+            selected_template: 0,
+            pending_action: None,
+            confirm_yes: false,
+            field_layouts: RefCell::default(),
+            field_scroll: Cell::default(),
+            field_heights: Cell::default(),
+            field_areas: Cell::default(),
+            edit_history: Default::default(),
+            recall: None,
             status_message: None,
+            status_log: VecDeque::new(),
+            status_log_open: false,
+            status_log_scroll: 0,
+            status_log_area: Cell::default(),
             is_loading: false,
+            loading_since: None,
+            submission: None,
+            retry: None,
+            events: None,
+            online: true,
+            health: None,
+            capabilities: None,
+            overflow_confirmed: false,
+            exported: None,
+            config: Config::default(),
+            sink: None,
+            queue: SubmissionQueue::default(),
+            selected_queue_index: 0,
+            token_map: TokenMap::default(),
+            audit_log: AuditLog::default(),
+            history: History::default(),
+            history_entries: Vec::new(),
+            selected_history_index: 0,
+            history_query: String::new(),
+            history_searching: false,
+            page_entries: Vec::new(),
+            selected_page_entry: 0,
+            entry_scroll: 0,
+            page_preview: Vec::new(),
+            preview_title: String::new(),
+            preview_scroll: 0,
+            page_settings: PageSettingsStore::default(),
+            selected_setting: 0,
+            setting_input: None,
+            database_rows: Vec::new(),
+            row_matches: Vec::new(),
+            selected_row_match: 0,
+            row_query: String::new(),
+            row_searching: false,
+            chosen_rows: HashMap::new(),
+            checked_pages: Vec::new(),
+            selected_link: 0,
+            sub_entries: Vec::new(),
+            row_pick: RowPick::Target,
+            relation_properties: Vec::new(),
+            selected_relation: 0,
+            relations: HashMap::new(),
+            last_submission: None,
         }
     }
 
@@ -97,157 +888,1894 @@ impl AppState {
     }
 
     pub fn next_page(&mut self) {
-        if self.notion_pages.is_empty() {
+        let total = self.page_rows.len();
+        if total == 0 {
             return;
         }
-        let total = self.notion_pages.len();
-        self.selected_page_index = (self.selected_page_index + 1) % total;
+        self.select_page_row((self.selected_page_row() + 1) % total);
     }
 
     pub fn previous_page(&mut self) {
-        if self.notion_pages.is_empty() {
+        let total = self.page_rows.len();
+        if total == 0 {
             return;
         }
-        let total = self.notion_pages.len();
-        if self.selected_page_index == 0 {
-            self.selected_page_index = total - 1;
-        } else {
-            self.selected_page_index -= 1;
+        self.select_page_row((self.selected_page_row() + total - 1) % total);
+    }
+
+    /// Move the selection a whole screen of pages, clamping at the ends
+    pub fn page_down(&mut self) {
+        if self.page_rows.is_empty() {
+            return;
         }
+        let jump = self.page_list_height.get().max(1);
+        self.select_page_row((self.selected_page_row() + jump).min(self.page_rows.len() - 1));
     }
 
-    pub fn get_selected_page(&self) -> Option<&PageInfo> {
-        self.notion_pages.get(self.selected_page_index)
+    pub fn page_up(&mut self) {
+        let jump = self.page_list_height.get().max(1);
+        self.select_page_row(self.selected_page_row().saturating_sub(jump));
     }
 
-    pub fn set_pages(&mut self, pages: Vec<PageInfo>) {
-        self.notion_pages = pages;
-        self.selected_page_index = 0;
-        self.is_loading = false;
+    pub fn select_first_page(&mut self) {
+        self.select_page_row(0);
     }
 
-    pub fn next_input(&mut self) {
-        self.active_input_field = (self.active_input_field + 1) % Self::MAX_INPUTS;
+    pub fn select_last_page(&mut self) {
+        self.select_page_row(self.page_rows.len().saturating_sub(1));
     }
 
-    pub fn previous_input(&mut self) {
-        if self.active_input_field == 0 {
-            self.active_input_field = Self::MAX_INPUTS - 1;
+    /// Move the page selection and preselect the new page's code language,
+    /// unless the user already picked a different one
+    fn select_page(&mut self, index: usize) {
+        self.reveal_page(index);
+        self.retarget(|app| app.selected_page_index = index);
+    }
+
+    /// Select the page shown on a row of the unfiltered list
+    fn select_page_row(&mut self, row: usize) {
+        if let Some(&index) = self.page_rows.get(row) {
+            self.select_page(index);
+        }
+    }
+
+    /// Row of the unfiltered list showing the selected page, or the page
+    /// it is folded into
+    fn selected_page_row(&self) -> usize {
+        self.page_rows
+            .binary_search(&self.selected_page_index)
+            .unwrap_or_else(|row| row.saturating_sub(1))
+    }
+
+    /// Depth of a page in the tree; 0 at the top
+    pub fn page_depth(&self, index: usize) -> usize {
+        self.page_depths.get(index).copied().unwrap_or_default()
+    }
+
+    /// Whether pages are nested under this one
+    pub fn has_subpages(&self, index: usize) -> bool {
+        index + 1 < self.notion_pages.len() && self.page_depth(index + 1) > self.page_depth(index)
+    }
+
+    /// Whether this page's subpages are folded away
+    pub fn is_page_collapsed(&self, index: usize) -> bool {
+        self.notion_pages.get(index).is_some_and(|page| self.collapsed_pages.contains(&page.id))
+    }
+
+    /// The page a page is nested in
+    pub fn parent_page_index(&self, index: usize) -> Option<usize> {
+        let depth = self.page_depth(index);
+        (0..index).rev().find(|&candidate| self.page_depth(candidate) < depth)
+    }
+
+    /// List the pages that no collapsed page hides
+    fn refresh_page_rows(&mut self) {
+        let mut rows = Vec::with_capacity(self.notion_pages.len());
+        let mut folded_below = None;
+        for index in 0..self.notion_pages.len() {
+            let depth = self.page_depth(index);
+            match folded_below {
+                Some(limit) if depth > limit => continue,
+                _ => folded_below = None,
+            }
+            rows.push(index);
+            if self.is_page_collapsed(index) {
+                folded_below = Some(depth);
+            }
+        }
+        self.page_rows = rows;
+    }
+
+    /// Unfold every page above this one so it is listed
+    fn reveal_page(&mut self, index: usize) {
+        let mut changed = false;
+        let mut current = index;
+        while let Some(parent) = self.parent_page_index(current) {
+            changed |= self.collapsed_pages.remove(&self.notion_pages[parent].id);
+            current = parent;
+        }
+        if changed {
+            self.refresh_page_rows();
+        }
+    }
+
+    /// Left in the page list: fold the selected page's subpages away, or
+    /// go up to the page it is nested in
+    pub fn collapse_page(&mut self) {
+        let index = self.selected_page_index;
+        if self.has_subpages(index) && !self.is_page_collapsed(index) {
+            self.collapsed_pages.insert(self.notion_pages[index].id.clone());
+            self.refresh_page_rows();
+        } else if let Some(parent) = self.parent_page_index(index) {
+            self.select_page(parent);
+        }
+    }
+
+    /// Right in the page list: unfold the selected page's subpages, or go
+    /// down to the first of them
+    pub fn expand_page(&mut self) {
+        let index = self.selected_page_index;
+        if !self.has_subpages(index) {
+            return;
+        }
+        if self.is_page_collapsed(index) {
+            self.collapsed_pages.remove(&self.notion_pages[index].id);
+            self.refresh_page_rows();
         } else {
-            self.active_input_field -= 1;
+            self.select_page(index + 1);
         }
     }
 
-    pub fn enter_edit_mode(&mut self) {
-        if self.is_input_section_focused() {
-            self.input_mode = InputMode::Editing;
+    /// Change what entries are submitted to, preselecting the new target's
+    /// code language unless the user already picked a different one
+    fn retarget(&mut self, change: impl FnOnce(&mut Self)) {
+        let previous = self.page_code_language().to_string();
+        change(self);
+        if self.code_language == previous {
+            self.code_language = self.page_code_language().to_string();
         }
     }
 
-    pub fn exit_edit_mode(&mut self) {
-        self.input_mode = InputMode::Normal;
+    /// Where entries go: the selected page, the row picked in the selected
+    /// database, or the database itself to add a new row
+    pub fn submission_target(&self) -> Option<PageInfo> {
+        self.get_selected_page().map(|page| self.target_for(page))
     }
 
-    pub fn is_editing(&self) -> bool {
-        matches!(self.input_mode, InputMode::Editing)
+    /// Every place the next entry goes: the checked pages, or else the
+    /// submission target
+    pub fn submission_targets(&self) -> Vec<PageInfo> {
+        if self.checked_pages.is_empty() {
+            return self.submission_target().into_iter().collect();
+        }
+        self.checked_pages
+            .iter()
+            .filter_map(|id| self.notion_pages.iter().find(|page| page.id == *id))
+            .map(|page| self.target_for(page))
+            .collect()
     }
 
-    fn get_active_input_mut(&mut self) -> &mut String {
-        match self.active_input_field {
-            0 => &mut self.error_input,
-            1 => &mut self.problem_input,
-            2 => &mut self.solution_input,
-            3 => &mut self.code_input,
-            _ => &mut self.error_input,
+    /// A listed page, or the row picked in it when it is a database
+    fn target_for(&self, page: &PageInfo) -> PageInfo {
+        let Some(row) = self.chosen_rows.get(&page.id) else {
+            return page.clone();
+        };
+        PageInfo {
+            id: row.id.clone(),
+            title: format!("{} › {}", page.title, row.title),
+            kind: TargetKind::Page,
+            parent: None,
         }
     }
 
-    pub fn add_char(&mut self, c: char) {
-        self.get_active_input_mut().push(c);
+    /// Check or uncheck the selected page for the next submission
+    pub fn toggle_page_check(&mut self) {
+        let Some(page) = self.get_selected_page() else {
+            return;
+        };
+        let (id, title) = (page.id.clone(), page.title.clone());
+        let verb = match self.checked_pages.iter().position(|checked| *checked == id) {
+            Some(index) => {
+                self.checked_pages.remove(index);
+                "Unchecked"
+            }
+            None => {
+                self.checked_pages.push(id);
+                "Checked"
+            }
+        };
+        match self.checked_pages.len() {
+            0 => self.set_status(format!("Unchecked {}; entries go to the selected page", title)),
+            1 => self.set_status(format!("{} {}; entries go to the checked page", verb, title)),
+            count => self.set_status(format!("{} {}; entries go to {} checked pages", verb, title, count)),
+        }
     }
 
-    pub fn delete_char(&mut self) {
-        self.get_active_input_mut().pop();
+    /// Uncheck the pages whose target is not in `target_ids`, so submitting
+    /// again only goes where the last submission failed
+    pub fn keep_checked_targets(&mut self, target_ids: &[String]) {
+        let keep: Vec<String> = self
+            .checked_pages
+            .iter()
+            .filter(|id| {
+                self.notion_pages
+                    .iter()
+                    .find(|page| page.id == **id)
+                    .is_some_and(|page| target_ids.contains(&self.target_for(page).id))
+            })
+            .cloned()
+            .collect();
+        self.checked_pages = keep;
     }
 
-    pub fn add_newline(&mut self) {
-        self.get_active_input_mut().push('\n');
+    pub fn is_page_checked(&self, page_id: &str) -> bool {
+        self.checked_pages.iter().any(|checked| checked == page_id)
     }
 
-    pub fn clear_inputs(&mut self) {
-        self.error_input.clear();
-        self.problem_input.clear();
-        self.solution_input.clear();
-        self.code_input.clear();
-        self.active_input_field = 0;
+    /// Code language remembered for the submission target
+    pub fn page_code_language(&self) -> &str {
+        self.selected_page_settings()
+            .and_then(|settings| settings.code_language.as_deref())
+            .unwrap_or(Self::DEFAULT_CODE_LANGUAGE)
     }
 
-    pub fn can_submit(&self) -> bool {
-        let has_error = !self.error_input.trim().is_empty();
-        let has_problem = !self.problem_input.trim().is_empty();
-        let has_solution = !self.solution_input.trim().is_empty();
-        let has_page = !self.notion_pages.is_empty();
-        has_error && has_problem && has_solution && has_page
+    pub fn selected_page_settings(&self) -> Option<&PageSettings> {
+        let page = self.submission_target()?;
+        self.page_settings.get(&page.id)
     }
 
-    pub fn get_submission_data(&self) -> Option<(String, FaultLogEntry)> {
-        if !self.can_submit() {
-            return None;
+    /// The template for submitting `entry` to a page, with its variables
+    /// resolved as of now
+    pub fn submission_template(&self, page_id: &str, entry: &FaultLogEntry) -> BlockTemplate {
+        let template = self.template_for(page_id);
+        let vars = Vars::for_entry(entry, self.config.project.as_deref(), template.locale);
+        template.resolve(&vars)
+    }
+
+    /// The config's template for a page with its remembered settings on top
+    pub fn template_for(&self, page_id: &str) -> BlockTemplate {
+        let mut template = self.config.template_for(page_id);
+        if let Some(settings) = self.page_settings.get(page_id) {
+            settings.apply(&mut template);
         }
+        template
+    }
 
-        let page = self.get_selected_page()?;
-        let page_id = page.id.clone();
+    /// Rows of the page list visible in `height` rows; the window moves a
+    /// screen at a time so only those rows need building
+    pub fn visible_page_range(&self, height: usize) -> Range<usize> {
+        let total = self.listed_page_count();
+        if height == 0 || total == 0 {
+            return 0..0;
+        }
+        let start = (self.listed_selection() / height) * height;
+        start..(start + height).min(total)
+    }
 
-        let entry = FaultLogEntry {
-            error: self.error_input.clone(),
-            problem: self.problem_input.clone(),
-            solution: self.solution_input.clone(),
-            code: if self.code_input.trim().is_empty() {
-                None
-            } else {
-                Some(self.code_input.clone())
-            },
-        };
+    /// Position indicator like "120/3400", counting matches while filtering
+    pub fn page_position(&self) -> String {
+        let total = self.listed_page_count();
+        if total == 0 {
+            return "0/0".to_string();
+        }
+        format!("{}/{}", self.listed_selection() + 1, total)
+    }
 
-        Some((page_id, entry))
+    /// Rows in the page list: the filter matches while filtering, else
+    /// every page not folded away
+    pub fn listed_page_count(&self) -> usize {
+        if self.page_filtering {
+            self.page_matches.len()
+        } else {
+            self.page_rows.len()
+        }
     }
 
-    pub fn start_loading(&mut self) {
-        self.is_loading = true;
-        self.status_message = Some("Submitting...".to_string());
+    /// Highlighted row of the page list
+    pub fn listed_selection(&self) -> usize {
+        if self.page_filtering {
+            self.selected_match
+        } else {
+            self.selected_page_row()
+        }
     }
 
-    pub fn set_status(&mut self, message: impl Into<String>) {
-        self.status_message = Some(message.into());
+    /// Index into `notion_pages` of a page list row
+    pub fn listed_page(&self, row: usize) -> usize {
+        if self.page_filtering {
+            self.page_matches[row]
+        } else {
+            self.page_rows[row]
+        }
     }
 
-    pub fn set_success(&mut self, message: impl Into<String>) {
-        self.status_message = Some(format!("✓ {}", message.into()));
-        self.is_loading = false;
+    /// Open the filter box above the page list
+    pub fn start_page_filter(&mut self) {
+        self.current_focus = FocusArea::PageList;
+        self.input_mode = InputMode::Normal;
+        self.page_filtering = true;
+        self.page_filter.clear();
+        self.refresh_page_filter();
     }
 
-    pub fn set_error(&mut self, message: impl Into<String>) {
-        self.status_message = Some(format!("✗ {}", message.into()));
-        self.is_loading = false;
+    /// Re-match the pages against the filter, highlighting the best match
+    pub fn refresh_page_filter(&mut self) {
+        self.page_matches = fuzzy::rank(&self.page_filter, self.notion_pages.iter().map(|p| p.title.as_str()));
+        self.selected_match = 0;
     }
 
-    pub fn clear_status(&mut self) {
-        self.status_message = None;
+    pub fn next_match(&mut self) {
+        let total = self.page_matches.len();
+        if total > 0 {
+            self.selected_match = (self.selected_match + 1) % total;
+        }
     }
 
-    pub fn handle_up(&mut self) {
-        match self.current_focus {
-            FocusArea::PageList => self.previous_page(),
-            FocusArea::InputSection => self.previous_input(),
+    pub fn previous_match(&mut self) {
+        let total = self.page_matches.len();
+        if total > 0 {
+            self.selected_match = (self.selected_match + total - 1) % total;
         }
     }
 
-    pub fn handle_down(&mut self) {
-        match self.current_focus {
-            FocusArea::PageList => self.next_page(),
-            FocusArea::InputSection => self.next_input(),
+    /// Jump to the highlighted match and close the filter
+    pub fn accept_page_filter(&mut self) {
+        if let Some(&index) = self.page_matches.get(self.selected_match) {
+            self.select_page(index);
+        }
+        self.cancel_page_filter();
+    }
+
+    /// Close the filter, leaving the selection where it was
+    pub fn cancel_page_filter(&mut self) {
+        self.page_filtering = false;
+        self.page_filter.clear();
+        self.page_matches.clear();
+        self.selected_match = 0;
+    }
+
+    pub fn get_selected_page(&self) -> Option<&PageInfo> {
+        self.notion_pages.get(self.selected_page_index)
+    }
+
+    pub fn set_pages(&mut self, pages: Vec<PageInfo>) {
+        // Checks on pages that are gone would send entries nowhere visible
+        self.checked_pages.retain(|id| pages.iter().any(|page| page.id == *id));
+        let (pages, depths) = tree_order(pages).into_iter().unzip();
+        self.retarget(|app| {
+            app.notion_pages = pages;
+            app.page_depths = depths;
+            app.selected_page_index = 0;
+        });
+        self.refresh_page_rows();
+        if self.page_filtering {
+            self.refresh_page_filter();
+        }
+        self.is_loading = false;
+    }
+
+    /// Select the page named with `--page` once there are pages to choose
+    /// from; says so when none matches
+    pub fn select_wanted_page(&mut self) {
+        if self.notion_pages.is_empty() {
+            return;
+        }
+        let Some(wanted) = self.wanted_page.take() else {
+            return;
+        };
+        let found = find_page(&self.notion_pages, &wanted)
+            .and_then(|page| self.notion_pages.iter().position(|candidate| candidate.id == page.id));
+        match found {
+            Some(index) => self.select_page(index),
+            None => self.set_error(format!("No page matches \"{}\"", wanted.trim())),
+        }
+    }
+
+    /// Swap in a freshly fetched page list, keeping the selected page when
+    /// it is still there
+    pub fn replace_pages(&mut self, pages: Vec<PageInfo>) {
+        let selected = self.get_selected_page().map(|page| page.id.clone());
+        self.set_pages(pages);
+        if let Some(index) = selected.and_then(|id| self.notion_pages.iter().position(|page| page.id == id)) {
+            self.select_page(index);
         }
     }
+
+    pub fn next_input(&mut self) {
+        self.active_input_field = (self.active_input_field + 1) % Self::MAX_INPUTS;
+    }
+
+    pub fn previous_input(&mut self) {
+        if self.active_input_field == 0 {
+            self.active_input_field = Self::MAX_INPUTS - 1;
+        } else {
+            self.active_input_field -= 1;
+        }
+    }
+
+    pub fn enter_edit_mode(&mut self) {
+        if self.is_input_section_focused() {
+            self.input_mode = InputMode::Editing;
+        }
+    }
+
+    pub fn exit_edit_mode(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.recall = None;
+    }
+
+    pub fn is_editing(&self) -> bool {
+        matches!(self.input_mode, InputMode::Editing)
+    }
+
+    /// Buffer holding a field's text
+    pub fn field(&self, field: InputField) -> &TextBuffer {
+        match field {
+            InputField::Error => &self.error_input,
+            InputField::Problem => &self.problem_input,
+            InputField::Solution => &self.solution_input,
+            InputField::Code => &self.code_input,
+            InputField::Verification => &self.verification_input,
+            InputField::Context => &self.context_input,
+            InputField::Tags => &self.tags_input,
+        }
+    }
+
+    pub fn field_mut(&mut self, field: InputField) -> &mut TextBuffer {
+        match field {
+            InputField::Error => &mut self.error_input,
+            InputField::Problem => &mut self.problem_input,
+            InputField::Solution => &mut self.solution_input,
+            InputField::Code => &mut self.code_input,
+            InputField::Verification => &mut self.verification_input,
+            InputField::Context => &mut self.context_input,
+            InputField::Tags => &mut self.tags_input,
+        }
+    }
+
+    /// The field that currently has focus
+    pub fn active_field(&self) -> InputField {
+        InputField::from_index(self.active_input_field).unwrap_or(InputField::Error)
+    }
+
+    fn get_active_input_mut(&mut self) -> &mut TextBuffer {
+        self.field_mut(self.active_field())
+    }
+
+    /// Snapshot the active field before a change of `kind`
+    fn record_edit(&mut self, kind: EditKind) {
+        let index = self.active_input_field;
+        let before = self.field(self.active_field()).clone();
+        self.edit_history[index].record(kind, &before);
+        // Editing a recalled value makes it the new draft
+        self.recall = None;
+    }
+
+    /// Replace the field being edited with its next older value. False
+    /// when the field does not recall values or has none, so Up can move
+    /// to the previous field instead.
+    pub fn recall_previous(&mut self) -> bool {
+        let field = self.active_field();
+        if !field.recalls_values() {
+            return false;
+        }
+        let mut recall = match self.recall.take() {
+            Some(recall) if recall.field == field => recall,
+            _ => {
+                let current = self.field(field).clone();
+                let values = match self.history.field_values(field, Self::RECALL_LIMIT) {
+                    Ok(values) => values,
+                    Err(e) => {
+                        self.set_error(e);
+                        return false;
+                    }
+                };
+                let values: Vec<String> = values.into_iter().filter(|value| current != value.as_str()).collect();
+                if values.is_empty() {
+                    return false;
+                }
+                // Undo goes back to what was typed
+                self.record_edit(EditKind::Block);
+                FieldRecall {
+                    field,
+                    values,
+                    position: 0,
+                    draft: current,
+                }
+            }
+        };
+        recall.position = (recall.position + 1).min(recall.values.len());
+        self.show_recalled(recall);
+        true
+    }
+
+    /// Step back towards the value being typed; false when no earlier
+    /// value is shown, so Down can move to the next field instead
+    pub fn recall_next(&mut self) -> bool {
+        let field = self.active_field();
+        let Some(mut recall) = self.recall.take().filter(|recall| recall.field == field) else {
+            return false;
+        };
+        recall.position -= 1;
+        if recall.position == 0 {
+            *self.field_mut(field) = recall.draft;
+            self.set_status("Back to the text being typed");
+        } else {
+            self.show_recalled(recall);
+        }
+        true
+    }
+
+    fn show_recalled(&mut self, recall: FieldRecall) {
+        self.overflow_confirmed = false;
+        *self.field_mut(recall.field) = recall.values[recall.position - 1].as_str().into();
+        self.set_status(format!("Earlier value {} of {} (Up/Down for more)", recall.position, recall.values.len()));
+        self.recall = Some(recall);
+    }
+
+    pub fn add_char(&mut self, c: char) {
+        self.overflow_confirmed = false;
+        self.record_edit(EditKind::Typing);
+        self.get_active_input_mut().insert_at_cursor(c);
+        // Words undo one at a time
+        if c.is_whitespace() {
+            self.edit_history[self.active_input_field].break_group();
+        }
+    }
+
+    /// Undo the last change to the active field
+    pub fn undo_edit(&mut self) {
+        let field = self.active_field();
+        let mut buffer = std::mem::take(self.field_mut(field));
+        let undone = self.edit_history[field.index()].undo(&mut buffer);
+        *self.field_mut(field) = buffer;
+        if undone {
+            self.overflow_confirmed = false;
+        } else {
+            self.set_status("Nothing to undo");
+        }
+    }
+
+    /// Reapply the last change undone in the active field
+    pub fn redo_edit(&mut self) {
+        let field = self.active_field();
+        let mut buffer = std::mem::take(self.field_mut(field));
+        let redone = self.edit_history[field.index()].redo(&mut buffer);
+        *self.field_mut(field) = buffer;
+        if redone {
+            self.overflow_confirmed = false;
+        } else {
+            self.set_status("Nothing to redo");
+        }
+    }
+
+    /// Replace the active field with text saved in the external editor;
+    /// the old text can be undone
+    pub fn apply_external_edit(&mut self, text: &str) {
+        let field = self.active_field();
+        if *self.field(field) == text {
+            self.set_status("No changes made in the editor");
+            return;
+        }
+        self.overflow_confirmed = false;
+        self.record_edit(EditKind::Block);
+        *self.field_mut(field) = text.into();
+        let label = self.config.template.labels.label(field).to_string();
+        self.set_success(format!("Loaded {} from the editor", label));
+    }
+
+    /// Insert pasted text at the cursor of the active field; line breaks
+    /// stay line breaks
+    pub fn paste(&mut self, text: &str) {
+        let text = clipboard::normalize_newlines(text);
+        if text.is_empty() {
+            return;
+        }
+        self.overflow_confirmed = false;
+        self.record_edit(EditKind::Block);
+        let input = self.get_active_input_mut();
+        input.insert(input.cursor(), &text);
+        let lines = text.lines().count();
+        let filled = self.apply_extractors(&text);
+        if !filled.is_empty() {
+            self.set_status(format!("Pasted {} lines; extracted {}", lines, filled.join(", ")));
+        } else if lines > 1 {
+            self.set_status(format!("Pasted {} lines", lines));
+        }
+    }
+
+    /// Run `[[extractors]]` over pasted or piped text: values fill empty
+    /// fields, become tags or are added to Context as `name: value` lines.
+    /// Filled fields can be undone like an edit. Returns what was filled,
+    /// e.g. "Problem" or "tag prod".
+    pub fn apply_extractors(&mut self, text: &str) -> Vec<String> {
+        let mut filled = Vec::new();
+        for extracted in extract_all(&self.config.extractors, text) {
+            match (extracted.target, extracted.target.field()) {
+                (_, Some(field)) => {
+                    if !self.field(field).is_blank() {
+                        continue;
+                    }
+                    self.edit_history[field.index()].record(EditKind::Block, &self.field(field).clone());
+                    *self.field_mut(field) = extracted.value.as_str().into();
+                    filled.push(self.config.template.labels.label(field).to_string());
+                }
+                (ExtractTarget::Tag, None) => {
+                    let typed = self.tags_input.to_string();
+                    let wanted = tags::key(&extracted.value);
+                    if tags::parse(&typed).iter().any(|tag| tags::key(tag) == wanted) {
+                        continue;
+                    }
+                    self.edit_history[InputField::Tags.index()].record(EditKind::Block, &self.tags_input.clone());
+                    let typed = match typed.trim_end().trim_end_matches(',') {
+                        "" => extracted.value.clone(),
+                        existing => format!("{}, {}", existing, extracted.value),
+                    };
+                    self.tags_input = typed.as_str().into();
+                    filled.push(format!("tag {}", extracted.value));
+                }
+                (_, None) => {
+                    let key = format!("{}:", extracted.name);
+                    let context = self.field(InputField::Context).to_string();
+                    if context.lines().any(|line| line.trim_start().starts_with(&key)) {
+                        continue;
+                    }
+                    let before = self.field(InputField::Context).clone();
+                    self.edit_history[InputField::Context.index()].record(EditKind::Block, &before);
+                    let line = format!("{} {}", key, extracted.value);
+                    let context = match context.trim_end() {
+                        "" => line,
+                        existing => format!("{}\n{}", existing, line),
+                    };
+                    *self.field_mut(InputField::Context) = context.as_str().into();
+                    filled.push(extracted.name);
+                }
+            }
+        }
+        filled
+    }
+
+    /// Backspace in the active field
+    pub fn delete_char(&mut self) {
+        self.overflow_confirmed = false;
+        self.record_edit(EditKind::Deleting);
+        self.get_active_input_mut().delete_before_cursor();
+    }
+
+    /// Delete key in the active field
+    pub fn delete_char_forward(&mut self) {
+        self.overflow_confirmed = false;
+        self.record_edit(EditKind::Deleting);
+        self.get_active_input_mut().delete_at_cursor();
+    }
+
+    pub fn add_newline(&mut self) {
+        self.overflow_confirmed = false;
+        self.record_edit(EditKind::Block);
+        self.get_active_input_mut().insert_at_cursor('\n');
+    }
+
+    pub fn move_cursor_left(&mut self) {
+        self.edit_history[self.active_input_field].break_group();
+        self.get_active_input_mut().move_left();
+    }
+
+    pub fn move_cursor_right(&mut self) {
+        self.edit_history[self.active_input_field].break_group();
+        self.get_active_input_mut().move_right();
+    }
+
+    pub fn move_cursor_line_start(&mut self) {
+        self.edit_history[self.active_input_field].break_group();
+        self.get_active_input_mut().move_line_start();
+    }
+
+    pub fn move_cursor_line_end(&mut self) {
+        self.edit_history[self.active_input_field].break_group();
+        self.get_active_input_mut().move_line_end();
+    }
+
+    /// Move the cursor to the wrapped row above, keeping its column.
+    /// False on the first row, so Up can leave the field instead.
+    pub fn move_cursor_up(&mut self) -> bool {
+        self.move_cursor_row(false)
+    }
+
+    /// Move the cursor to the wrapped row below; false on the last row
+    pub fn move_cursor_down(&mut self) -> bool {
+        self.move_cursor_row(true)
+    }
+
+    fn move_cursor_row(&mut self, down: bool) -> bool {
+        let index = self.active_input_field;
+        let target = self.field_layouts.borrow_mut()[index].vertical_target(self.field(self.active_field()), down);
+        let Some(target) = target else {
+            return false;
+        };
+        self.edit_history[index].break_group();
+        self.get_active_input_mut().set_cursor(target);
+        true
+    }
+
+    pub fn clear_inputs(&mut self) {
+        for field in InputField::ALL {
+            self.field_mut(field).clear();
+        }
+        self.active_input_field = 0;
+        self.overflow_confirmed = false;
+        self.fault_status = FaultStatus::Open;
+        self.severity = None;
+        self.sub_entries.clear();
+        self.attached_files.clear();
+        self.field_scroll.set(Default::default());
+        self.edit_history = Default::default();
+    }
+
+    /// Scroll the focused field by `pages` screenfuls, negative for up
+    pub fn scroll_field(&mut self, pages: isize) {
+        let index = self.active_input_field;
+        let height = self.field_heights.get()[index].max(1);
+        self.scroll_field_rows(index, pages.saturating_mul(height as isize));
+    }
+
+    /// Scroll field `index` by `rows`, negative for up
+    fn scroll_field_rows(&mut self, index: usize, rows: isize) {
+        let height = self.field_heights.get()[index].max(1);
+        let row_count = self.field_layouts.get_mut()[index].row_count();
+        let mut scroll = self.field_scroll.get();
+        let offset = scroll[index].saturating_add_signed(rows);
+        scroll[index] = offset.min(row_count.saturating_sub(height));
+        self.field_scroll.set(scroll);
+    }
+
+    /// Click at a screen cell: a page list row selects that page, a field
+    /// focuses it, keeping edit mode if it was on
+    pub fn click(&mut self, column: u16, row: u16) {
+        let position = Position::new(column, row);
+        if let Some(listed) = self.page_row_at(position) {
+            self.current_focus = FocusArea::PageList;
+            self.input_mode = InputMode::Normal;
+            self.select_listed(listed);
+        } else if let Some(index) = self.field_at(position) {
+            self.current_focus = FocusArea::InputSection;
+            if index != self.active_input_field {
+                self.edit_history[self.active_input_field].break_group();
+                self.active_input_field = index;
+            }
+        }
+    }
+
+    /// Mouse wheel at a screen cell: moves the page selection over the
+    /// page list and scrolls a field's text over that field
+    pub fn scroll_at(&mut self, column: u16, row: u16, rows: isize) {
+        let position = Position::new(column, row);
+        if self.status_log_open && self.status_log_area.get().contains(position) {
+            // The wheel up goes back in time
+            self.scroll_status_log(-rows);
+        } else if self.page_list_area.get().contains(position) {
+            let total = self.listed_page_count();
+            if total > 0 {
+                self.select_listed(self.listed_selection().saturating_add_signed(rows).min(total - 1));
+            }
+        } else if let Some(index) = self.field_at(position) {
+            self.scroll_field_rows(index, rows);
+        }
+    }
+
+    /// Highlight a page list row: a filter match while filtering, else a page
+    fn select_listed(&mut self, listed: usize) {
+        if self.page_filtering {
+            self.selected_match = listed;
+        } else {
+            self.select_page_row(listed);
+        }
+    }
+
+    /// Page list row under a screen cell, inside the list's borders
+    fn page_row_at(&self, position: Position) -> Option<usize> {
+        let area = self.page_list_area.get();
+        let inner = Rect::new(area.x + 1, area.y + 1, area.width.saturating_sub(2), area.height.saturating_sub(2));
+        if !inner.contains(position) {
+            return None;
+        }
+        let visible = self.visible_page_range(inner.height as usize);
+        let listed = visible.start + usize::from(position.y - inner.y);
+        visible.contains(&listed).then_some(listed)
+    }
+
+    /// Index of the field drawn at a screen cell
+    fn field_at(&self, position: Position) -> Option<usize> {
+        self.field_areas.get().iter().position(|area| area.contains(position))
+    }
+
+    /// True when any field holds non-whitespace text
+    pub fn has_input(&self) -> bool {
+        !self.sub_entries.is_empty() || !self.attached_files.is_empty() || InputField::ALL.iter().any(|field| !self.field(*field).is_blank())
+    }
+
+    /// Open the confirmation dialog for `action`. Submitting starts on Yes,
+    /// so pressing Enter twice still submits; the rest start on No.
+    pub fn request_confirmation(&mut self, action: PendingAction) {
+        self.pending_action = Some(action);
+        self.confirm_yes = action == PendingAction::Submit;
+    }
+
+    /// The question the confirmation dialog asks
+    pub fn confirmation_prompt(&self) -> Option<String> {
+        let entry = match self.sub_entries.len() {
+            0 => "this entry".to_string(),
+            1 => "this entry and its sub-entry".to_string(),
+            n => format!("this entry and its {} sub-entries", n),
+        };
+        let prompt = match self.pending_action? {
+            PendingAction::Submit if self.config.export.replaces_notion() => {
+                let dir = self.config.export.dir().unwrap_or_default();
+                format!("Save {} as Markdown in {}?", entry, dir.display())
+            }
+            PendingAction::Submit => match self.submission_targets().as_slice() {
+                [] => format!("Submit {}?", entry),
+                [page] => format!("Submit {} to {}?", entry, page.title),
+                pages => {
+                    let titles: Vec<&str> = pages.iter().map(|page| page.title.as_str()).collect();
+                    format!("Submit {} to {} pages: {}?", entry, pages.len(), titles.join(", "))
+                }
+            },
+            PendingAction::ClearInputs => "Clear all fields? This cannot be undone.".to_string(),
+            PendingAction::DiscardQueued => match self.queue.get(self.selected_queue_index) {
+                Some(item) => format!("Discard the queued entry for {}? It will not be sent.", item.page_title),
+                None => "Discard the queued entry?".to_string(),
+            },
+        };
+        Some(prompt)
+    }
+
+    pub fn can_submit(&self) -> bool {
+        let has_error = !self.error_input.is_blank();
+        let has_problem = !self.problem_input.is_blank();
+        let has_solution = !self.solution_input.is_blank();
+        let has_page = !self.notion_pages.is_empty();
+        has_error && has_problem && has_solution && has_page
+    }
+
+    pub fn get_submission_data(&self) -> Option<(String, FaultLogEntry)> {
+        if !self.can_submit() {
+            return None;
+        }
+
+        let page_id = self.submission_target()?.id;
+        Some((page_id, self.current_entry()))
+    }
+
+    /// URLs in the Error and Code fields, for the links panel
+    pub fn links(&self) -> Vec<String> {
+        links::links_in([self.error_input.to_string().as_str(), self.code_input.to_string().as_str()])
+    }
+
+    /// Trace and correlation IDs in the Error and Code fields
+    pub fn trace_ids(&self) -> Vec<String> {
+        trace_ids::trace_ids_in([self.error_input.to_string().as_str(), self.code_input.to_string().as_str()])
+    }
+
+    /// Open the history filtered to the first trace ID in the entry
+    pub fn find_trace(&mut self) {
+        let Some(trace_id) = self.trace_ids().into_iter().next() else {
+            self.set_error("No trace or correlation ID in the Error or Code field");
+            return;
+        };
+        self.history_query = trace_id.clone();
+        self.open_history();
+        match self.history_entries.len() {
+            0 => self.set_status(format!("Trace {} has not been logged before", trace_id)),
+            1 => self.set_status(format!("Trace {} was logged once before", trace_id)),
+            n => self.set_status(format!("Trace {} was logged {} times before", trace_id, n)),
+        }
+    }
+
+    /// The highlighted link; stays on the last one when links are removed
+    pub fn selected_link(&self) -> Option<String> {
+        let links = self.links();
+        let index = self.selected_link.min(links.len().saturating_sub(1));
+        links.into_iter().nth(index)
+    }
+
+    /// Highlight the next link, wrapping around
+    pub fn next_link(&mut self) {
+        let total = self.links().len();
+        if total > 0 {
+            self.selected_link = (self.selected_link.min(total - 1) + 1) % total;
+        }
+    }
+
+    pub fn previous_link(&mut self) {
+        let total = self.links().len();
+        if total > 0 {
+            self.selected_link = (self.selected_link.min(total - 1) + total - 1) % total;
+        }
+    }
+
+    /// The editor's content as Markdown, laid out like the entry in Notion
+    pub fn entry_markdown(&self) -> String {
+        let entry = self.current_entry();
+        let page_id = self.submission_target().map(|page| page.id).unwrap_or_default();
+        markdown::entry_markdown(&entry, &self.submission_template(&page_id, &entry))
+    }
+
+    /// The editor's content as an entry, with the tags and linked pages of
+    /// the submission target when there is one
+    pub fn current_entry(&self) -> FaultLogEntry {
+        let page_id = self.submission_target().map(|page| page.id).unwrap_or_default();
+        FaultLogEntry {
+            error: self.error_input.to_string(),
+            problem: self.problem_input.to_string(),
+            solution: self.solution_input.to_string(),
+            code: optional_input(&self.code_input),
+            verification: optional_input(&self.verification_input),
+            context: optional_input(&self.context_input),
+            language: Some(self.effective_code_language().to_string()),
+            attachments: Vec::new(),
+            files: self.attached_files.clone(),
+            tags: self.entry_tags(&page_id),
+            relations: self
+                .relations
+                .get(&page_id)
+                .map(|linked| {
+                    linked
+                        .iter()
+                        .map(|(property, pages)| (property.clone(), pages.iter().map(|p| p.id.clone()).collect()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            status: self.fault_status,
+            severity: self.severity,
+            git: self.git_context.clone(),
+            sub_entries: self.sub_entries.clone(),
+        }
+    }
+
+    /// Stack the draft as a sub-entry and empty the editor for the next
+    /// one. The entry submitted after them becomes their summary.
+    pub fn add_sub_entry(&mut self) {
+        if self.error_input.is_blank() {
+            self.set_error("Fill in Error before adding the draft as a sub-entry");
+            return;
+        }
+        let mut draft = self.current_entry();
+        // Tags, relations and the group belong to the summary
+        draft.tags.clear();
+        draft.relations.clear();
+        draft.sub_entries.clear();
+        draft.files.clear();
+        draft.git = None;
+        let mut stacked = std::mem::take(&mut self.sub_entries);
+        let files = std::mem::take(&mut self.attached_files);
+        stacked.push(draft);
+        let typed_tags = self.tags_input.clone();
+        self.clear_inputs();
+        self.sub_entries = stacked;
+        self.tags_input = typed_tags;
+        self.attached_files = files;
+        self.set_success(format!(
+            "Added sub-entry {}; write the next one or the summary, then submit",
+            self.sub_entries.len()
+        ));
+    }
+
+    /// Open the prompt for a file path or link to attach
+    pub fn open_attach_prompt(&mut self) {
+        self.attach_input = Some(String::new());
+    }
+
+    /// Attach the file or link typed into the prompt and close it; an
+    /// unusable path keeps the prompt open to be corrected
+    pub fn commit_attach_input(&mut self) {
+        let Some(input) = self.attach_input.as_deref() else {
+            return;
+        };
+        let source = match attachments::resolve(input) {
+            Ok(source) => source.to_entry_string(),
+            Err(e) => {
+                self.set_error(e);
+                return;
+            }
+        };
+        self.attach_input = None;
+        if self.attached_files.contains(&source) {
+            self.set_status(format!("{} is already attached", attachments::display_name(&source)));
+            return;
+        }
+        let name = attachments::display_name(&source);
+        self.attached_files.push(source);
+        self.set_success(format!("Attached {} ({} in all)", name, self.attached_files.len()));
+    }
+
+    /// Open the prompt for a new page, under the selected page or else the
+    /// first one; databases cannot hold plain pages
+    pub fn open_new_page_prompt(&mut self) {
+        let selected = self.get_selected_page().filter(|page| !page.is_database());
+        let Some(parent) = selected.or_else(|| self.notion_pages.iter().find(|page| !page.is_database())) else {
+            self.set_error("Share a page with the integration to create pages under it");
+            return;
+        };
+        self.new_page = Some(NewPagePrompt {
+            title: String::new(),
+            parent: parent.id.clone(),
+        });
+    }
+
+    /// Move the new page's parent to the next or previous page in the list
+    pub fn cycle_new_page_parent(&mut self, forward: bool) {
+        let Some(prompt) = self.new_page.as_mut() else {
+            return;
+        };
+        let total = self.notion_pages.len();
+        let current = self.notion_pages.iter().position(|page| page.id == prompt.parent).unwrap_or(0);
+        for step in 1..total {
+            let index = if forward { (current + step) % total } else { (current + total - step) % total };
+            if !self.notion_pages[index].is_database() {
+                prompt.parent = self.notion_pages[index].id.clone();
+                return;
+            }
+        }
+    }
+
+    /// The page the prompt creates the new page under
+    pub fn new_page_parent(&self) -> Option<&PageInfo> {
+        let prompt = self.new_page.as_ref()?;
+        self.notion_pages.iter().find(|page| page.id == prompt.parent)
+    }
+
+    /// Put a page just created under its parent in the list and select it
+    /// for the next submission
+    pub fn insert_created_page(&mut self, page: PageInfo) {
+        self.new_page = None;
+        let id = page.id.clone();
+        let mut pages = self.notion_pages.clone();
+        pages.push(page);
+        self.replace_pages(pages);
+        if let Some(index) = self.notion_pages.iter().position(|page| page.id == id) {
+            self.select_page(index);
+        }
+        self.current_focus = FocusArea::PageList;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Take the most recent attachment off the entry
+    pub fn remove_last_attachment(&mut self) {
+        match self.attached_files.pop() {
+            Some(source) => self.set_status(format!("Removed {}", attachments::display_name(&source))),
+            None => self.set_status("Nothing attached"),
+        }
+    }
+
+    pub fn is_mermaid(&self) -> bool {
+        self.code_language == Self::MERMAID_LANGUAGE
+    }
+
+    /// Switch the Code field between a Mermaid diagram and regular code
+    pub fn toggle_mermaid(&mut self) {
+        self.code_language = if self.is_mermaid() {
+            self.page_code_language().to_string()
+        } else {
+            Self::MERMAID_LANGUAGE.to_string()
+        };
+    }
+
+    /// Switch between the compact layout and the full one
+    pub fn toggle_mini(&mut self) {
+        self.mini = !self.mini;
+        self.zen = false;
+    }
+
+    /// Enter or leave zen mode; keys then go to the field shown
+    pub fn toggle_zen(&mut self) {
+        self.zen = !self.zen;
+        self.mini = false;
+        if self.zen {
+            self.current_focus = FocusArea::InputSection;
+        }
+    }
+
+    /// Start as the quick-capture window: editing the Error field
+    pub fn start_capture(&mut self) {
+        self.capture = true;
+        self.current_focus = FocusArea::InputSection;
+        self.active_input_field = InputField::Error.index();
+        self.input_mode = InputMode::Editing;
+    }
+
+    /// Toggle the Code field between a diff and regular code
+    pub fn toggle_diff(&mut self) {
+        self.code_language = if self.code_language == Self::DIFF_LANGUAGE {
+            self.page_code_language().to_string()
+        } else {
+            Self::DIFF_LANGUAGE.to_string()
+        };
+    }
+
+    /// Open the language picker with the current language highlighted
+    pub fn start_language_picker(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.language_picking = true;
+        self.language_filter.clear();
+        self.refresh_language_filter();
+        if let Some(row) = self.language_matches.iter().position(|&idx| LANGUAGES[idx] == self.code_language) {
+            self.selected_language = row;
+        }
+    }
+
+    /// Re-match the languages against the filter, highlighting the best match
+    pub fn refresh_language_filter(&mut self) {
+        self.language_matches = fuzzy::rank(&self.language_filter, LANGUAGES.iter().copied());
+        self.selected_language = 0;
+    }
+
+    pub fn next_language(&mut self) {
+        let total = self.language_matches.len();
+        if total > 0 {
+            self.selected_language = (self.selected_language + 1) % total;
+        }
+    }
+
+    pub fn previous_language(&mut self) {
+        let total = self.language_matches.len();
+        if total > 0 {
+            self.selected_language = (self.selected_language + total - 1) % total;
+        }
+    }
+
+    /// Use the highlighted language for the Code field and close the picker
+    pub fn accept_language(&mut self) {
+        if let Some(&index) = self.language_matches.get(self.selected_language) {
+            self.code_language = LANGUAGES[index].to_string();
+            self.set_status(format!("Code language: {}", self.code_language));
+        }
+        self.cancel_language_picker();
+    }
+
+    pub fn cancel_language_picker(&mut self) {
+        self.language_picking = false;
+        self.language_filter.clear();
+        self.language_matches.clear();
+        self.selected_language = 0;
+    }
+
+    pub fn start_template_picker(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.template_picking = true;
+        self.template_filter.clear();
+        self.refresh_template_filter();
+    }
+
+    /// Re-match the templates against the filter, highlighting the best match
+    pub fn refresh_template_filter(&mut self) {
+        let templates: Vec<(String, EntryTemplate)> = self.config.all_entry_templates().into_iter().collect();
+        let ranked = fuzzy::rank(&self.template_filter, templates.iter().map(|(name, _)| name.as_str()));
+        self.template_matches = ranked.into_iter().map(|idx| templates[idx].clone()).collect();
+        self.selected_template = 0;
+    }
+
+    pub fn next_template(&mut self) {
+        let total = self.template_matches.len();
+        if total > 0 {
+            self.selected_template = (self.selected_template + 1) % total;
+        }
+    }
+
+    pub fn previous_template(&mut self) {
+        let total = self.template_matches.len();
+        if total > 0 {
+            self.selected_template = (self.selected_template + total - 1) % total;
+        }
+    }
+
+    /// Fill the editor from the highlighted template and close the picker
+    pub fn accept_template(&mut self) {
+        let chosen = self.template_matches.get(self.selected_template).cloned();
+        self.cancel_template_picker();
+        if let Some((name, template)) = chosen {
+            self.apply_entry_template(&template);
+            self.set_status(format!("Template {}: Tab jumps to the next {{{{placeholder}}}}", name));
+        }
+    }
+
+    pub fn cancel_template_picker(&mut self) {
+        self.template_picking = false;
+        self.template_filter.clear();
+        self.template_matches.clear();
+        self.selected_template = 0;
+    }
+
+    /// Replace every field with the template's skeleton, then start editing
+    /// at its first placeholder. Each field's old text can be undone.
+    pub fn apply_entry_template(&mut self, template: &EntryTemplate) {
+        for field in InputField::ALL {
+            self.active_input_field = field.index();
+            self.record_edit(EditKind::Block);
+            *self.field_mut(field) = template.field(field).into();
+        }
+        if let Some(language) = template.language.as_deref() {
+            self.code_language = languages::normalize(language).unwrap_or(language).to_string();
+        }
+        self.overflow_confirmed = false;
+        self.field_scroll.set(Default::default());
+        self.active_input_field = 0;
+        self.current_focus = FocusArea::InputSection;
+        self.input_mode = InputMode::Editing;
+        self.field_mut(InputField::Error).set_cursor(0);
+        self.next_placeholder();
+    }
+
+    /// Move to the next `{{placeholder}}` after the cursor, in this field or
+    /// a later one, and remove it so typing replaces it. False when none is
+    /// left.
+    pub fn next_placeholder(&mut self) -> bool {
+        let first = self.active_input_field;
+        for index in first..Self::MAX_INPUTS {
+            let Some(field) = InputField::from_index(index) else {
+                continue;
+            };
+            let from = if index == first { self.field(field).cursor() } else { 0 };
+            let Some((start, end)) = find_placeholder(&self.field(field).to_string(), from) else {
+                continue;
+            };
+            self.active_input_field = index;
+            self.record_edit(EditKind::Block);
+            let buffer = self.field_mut(field);
+            buffer.remove(start, end);
+            buffer.set_cursor(start);
+            return true;
+        }
+        false
+    }
+
+    /// Only the first non-blank line decides, so large buffers are not copied
+    fn code_looks_like_diff(&self) -> bool {
+        self.code_input
+            .lines()
+            .map(|line| line.to_string())
+            .find(|line| !line.trim().is_empty())
+            .is_some_and(|line| looks_like_diff(&line))
+    }
+
+    /// Language used for the code block. Pasted diffs are detected
+    /// automatically while the default language is selected.
+    pub fn effective_code_language(&self) -> &str {
+        if self.code_language == Self::DEFAULT_CODE_LANGUAGE && self.code_looks_like_diff() {
+            Self::DIFF_LANGUAGE
+        } else {
+            &self.code_language
+        }
+    }
+
+    pub fn is_diff(&self) -> bool {
+        self.effective_code_language() == Self::DIFF_LANGUAGE
+    }
+
+    pub fn start_loading(&mut self) {
+        self.is_loading = true;
+        self.loading_since = Some(Instant::now());
+        self.set_loading("Submitting...");
+    }
+
+    /// Waiting on Notion, either inline or for a background submission
+    /// or retry
+    pub fn is_busy(&self) -> bool {
+        self.is_loading || self.submission.is_some() || self.retry.is_some()
+    }
+
+    /// Current spinner frame while busy or while a loading message shows
+    pub fn spinner(&self) -> Option<char> {
+        const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+        let loading = self.status_message.as_ref().filter(|message| message.kind == StatusKind::Loading);
+        let since = match (self.is_busy(), loading) {
+            (true, _) => self.loading_since,
+            (false, Some(message)) => Some(message.since),
+            (false, None) => return None,
+        };
+        let elapsed = since.map_or(0, |since| since.elapsed().as_millis() / 100);
+        Some(FRAMES[elapsed as usize % FRAMES.len()])
+    }
+
+    pub fn set_status(&mut self, message: impl Into<String>) {
+        self.show_status(StatusKind::Info, message.into());
+    }
+
+    /// Report work in progress; stays, with a spinner, until replaced
+    pub fn set_loading(&mut self, message: impl Into<String>) {
+        self.show_status(StatusKind::Loading, message.into());
+    }
+
+    pub fn set_success(&mut self, message: impl Into<String>) {
+        self.show_status(StatusKind::Success, format!("✓ {}", message.into()));
+        self.is_loading = false;
+    }
+
+    pub fn set_error(&mut self, message: impl Into<String>) {
+        self.show_status(StatusKind::Error, format!("✗ {}", message.into()));
+        self.is_loading = false;
+    }
+
+    /// Show a status line and keep it in the activity log
+    fn show_status(&mut self, kind: StatusKind, message: String) {
+        self.push_log(LogSource::App, kind, message.clone());
+        self.status_message = Some(StatusMessage::new(kind, message));
+    }
+
+    /// Add a settled Notion request to the activity log, leaving the
+    /// status line alone
+    pub fn log_api_call(&mut self, call: &ApiCall) {
+        let kind = if call.failed() { StatusKind::Error } else { StatusKind::Success };
+        self.push_log(LogSource::Api, kind, call.message());
+    }
+
+    fn push_log(&mut self, source: LogSource, kind: StatusKind, message: String) {
+        match self.status_log.back_mut() {
+            Some(last) if last.source == source && last.message == message => {
+                last.at = Local::now();
+                last.repeats += 1;
+            }
+            _ => {
+                if self.status_log.len() == Self::STATUS_LOG_LIMIT {
+                    self.status_log.pop_front();
+                }
+                self.status_log.push_back(StatusRecord {
+                    at: Local::now(),
+                    message,
+                    kind,
+                    source,
+                    repeats: 1,
+                });
+                // A log scrolled back stays on the same records
+                if self.status_log_scroll > 0 {
+                    self.status_log_scroll = (self.status_log_scroll + 1).min(self.status_log.len() - 1);
+                }
+            }
+        }
+    }
+
+    /// Clear a success or news message that has been up long enough
+    pub fn expire_status(&mut self, now: Instant) {
+        if self.status_message.as_ref().is_some_and(|message| message.is_expired(now)) {
+            self.status_message = None;
+        }
+    }
+
+    /// Show or hide the activity log drawer, opening it at the newest
+    pub fn toggle_status_log(&mut self) {
+        self.status_log_open = !self.status_log_open;
+        self.status_log_scroll = 0;
+    }
+
+    /// Scroll the activity log back (`rows` positive) or forward
+    pub fn scroll_status_log(&mut self, rows: isize) {
+        let last = self.status_log.len().saturating_sub(1);
+        self.status_log_scroll = self.status_log_scroll.saturating_add_signed(rows).min(last);
+    }
+
+    pub fn clear_status(&mut self) {
+        self.status_message = None;
+    }
+
+    /// Drop cached field layouts so the next frame rebuilds them for the
+    /// new terminal size
+    pub fn handle_resize(&mut self) {
+        *self.field_layouts.get_mut() = Default::default();
+    }
+
+    /// Store a health ping result; returns true when reachability flipped
+    pub fn record_health(&mut self, health: Health) -> bool {
+        let changed = self.online != health.reachable;
+        self.online = health.reachable;
+        self.health = Some(health);
+        changed
+    }
+
+    /// Default tags of a page from its settings
+    fn page_tags(&self, page_id: &str) -> &[String] {
+        self.page_settings.get(page_id).map(|s| s.tags.as_slice()).unwrap_or_default()
+    }
+
+    /// The page's tags, then the typed ones
+    fn entry_tags(&self, page_id: &str) -> Vec<String> {
+        let mut entry_tags = self.page_tags(page_id).to_vec();
+        for tag in tags::parse(&self.tags_input.to_string()) {
+            tags::push_unique(&mut entry_tags, &tag);
+        }
+        entry_tags
+    }
+
+    /// Load an entry back into the editor, e.g. to fix a queued submission
+    pub fn load_entry(&mut self, page_id: &str, entry: &FaultLogEntry) {
+        self.error_input = entry.error.as_str().into();
+        self.problem_input = entry.problem.as_str().into();
+        self.solution_input = entry.solution.as_str().into();
+        self.code_input = entry.code.as_deref().unwrap_or_default().into();
+        self.verification_input = entry.verification.as_deref().unwrap_or_default().into();
+        self.context_input = entry.context.as_deref().unwrap_or_default().into();
+        // The page's own tags come back with the page
+        let page_tags: Vec<String> = self.page_tags(page_id).iter().map(|tag| tags::key(tag)).collect();
+        let typed: Vec<&str> = entry.tags.iter().map(String::as_str).filter(|tag| !page_tags.contains(&tags::key(tag))).collect();
+        self.tags_input = typed.join(", ").as_str().into();
+        self.code_language = entry
+            .language
+            .clone()
+            .unwrap_or_else(|| Self::DEFAULT_CODE_LANGUAGE.to_string());
+        self.fault_status = entry.status;
+        self.severity = entry.severity;
+        self.sub_entries = entry.sub_entries.clone();
+        self.attached_files = entry.files.clone();
+        self.active_input_field = 0;
+        self.overflow_confirmed = false;
+        self.field_scroll.set(Default::default());
+        self.edit_history = Default::default();
+
+        if let Some(idx) = self.notion_pages.iter().position(|p| p.id == page_id) {
+            self.reveal_page(idx);
+            self.selected_page_index = idx;
+        }
+    }
+
+    /// Whether the last submission can still be undone at `now`
+    pub fn can_undo_at(&self, now: Instant) -> bool {
+        self.last_submission
+            .as_ref()
+            .is_some_and(|last| now.duration_since(last.submitted_at) <= Self::UNDO_WINDOW)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.can_undo_at(Instant::now())
+    }
+
+    pub fn open_queue(&mut self) {
+        self.screen = Screen::Queue;
+        self.input_mode = InputMode::Normal;
+        self.clamp_queue_selection();
+    }
+
+    pub fn close_queue(&mut self) {
+        self.screen = Screen::Main;
+    }
+
+    /// The page list and the fields, rather than another screen
+    pub fn is_main_screen(&self) -> bool {
+        matches!(self.screen, Screen::Main)
+    }
+
+    /// A picker, prompt or confirmation is open over the main screen and
+    /// takes the keys and clicks
+    pub fn has_modal(&self) -> bool {
+        self.language_picking
+            || self.template_picking
+            || self.pending_action.is_some()
+            || self.attach_input.is_some()
+            || self.new_page.is_some()
+    }
+
+    pub fn is_queue_screen(&self) -> bool {
+        matches!(self.screen, Screen::Queue)
+    }
+
+    pub fn next_queue_item(&mut self) {
+        let total = self.queue.len();
+        if total > 0 {
+            self.selected_queue_index = (self.selected_queue_index + 1) % total;
+        }
+    }
+
+    pub fn previous_queue_item(&mut self) {
+        let total = self.queue.len();
+        if total == 0 {
+            return;
+        }
+        if self.selected_queue_index == 0 {
+            self.selected_queue_index = total - 1;
+        } else {
+            self.selected_queue_index -= 1;
+        }
+    }
+
+    /// Keep the queue selection in range after items are removed
+    pub fn clamp_queue_selection(&mut self) {
+        let total = self.queue.len();
+        if self.selected_queue_index >= total {
+            self.selected_queue_index = total.saturating_sub(1);
+        }
+    }
+
+    pub fn open_history(&mut self) {
+        self.screen = Screen::History;
+        self.input_mode = InputMode::Normal;
+        self.history_searching = false;
+        self.refresh_history();
+    }
+
+    pub fn close_history(&mut self) {
+        self.screen = Screen::Main;
+        self.history_searching = false;
+    }
+
+    pub fn is_history_screen(&self) -> bool {
+        matches!(self.screen, Screen::History)
+    }
+
+    /// Reload the history screen using the current filter
+    pub fn refresh_history(&mut self) {
+        match self.history.search(&self.history_query, Self::HISTORY_LIMIT) {
+            Ok(entries) => self.history_entries = entries,
+            Err(e) => self.set_error(e),
+        }
+        if self.selected_history_index >= self.history_entries.len() {
+            self.selected_history_index = self.history_entries.len().saturating_sub(1);
+        }
+    }
+
+    pub fn selected_history_entry(&self) -> Option<&HistoryEntry> {
+        self.history_entries.get(self.selected_history_index)
+    }
+
+    pub fn next_history_item(&mut self) {
+        let total = self.history_entries.len();
+        if total > 0 {
+            self.selected_history_index = (self.selected_history_index + 1) % total;
+        }
+    }
+
+    pub fn previous_history_item(&mut self) {
+        let total = self.history_entries.len();
+        if total == 0 {
+            return;
+        }
+        if self.selected_history_index == 0 {
+            self.selected_history_index = total - 1;
+        } else {
+            self.selected_history_index -= 1;
+        }
+    }
+
+    /// Show entries fetched from the selected page
+    pub fn open_entries(&mut self, entries: Vec<LoggedEntry>) {
+        self.page_entries = entries;
+        self.selected_page_entry = 0;
+        self.entry_scroll = 0;
+        self.screen = Screen::Entries;
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn close_entries(&mut self) {
+        self.screen = Screen::Main;
+    }
+
+    pub fn is_entries_screen(&self) -> bool {
+        matches!(self.screen, Screen::Entries)
+    }
+
+    pub fn selected_logged_entry(&self) -> Option<&LoggedEntry> {
+        self.page_entries.get(self.selected_page_entry)
+    }
+
+    pub fn next_page_entry(&mut self) {
+        let total = self.page_entries.len();
+        if total > 0 {
+            self.selected_page_entry = (self.selected_page_entry + 1) % total;
+            self.entry_scroll = 0;
+        }
+    }
+
+    pub fn previous_page_entry(&mut self) {
+        let total = self.page_entries.len();
+        if total == 0 {
+            return;
+        }
+        self.selected_page_entry = if self.selected_page_entry == 0 {
+            total - 1
+        } else {
+            self.selected_page_entry - 1
+        };
+        self.entry_scroll = 0;
+    }
+
+    /// Scroll the entry reader; the renderer clamps to the text height
+    pub fn scroll_entry(&mut self, lines: i32) {
+        self.entry_scroll = (i32::from(self.entry_scroll) + lines).clamp(0, i32::from(u16::MAX)) as u16;
+    }
+
+    /// Show the content fetched from a page
+    pub fn open_preview(&mut self, title: &str, lines: Vec<PreviewLine>) {
+        self.page_preview = lines;
+        self.preview_title = title.to_string();
+        self.preview_scroll = 0;
+        self.screen = Screen::Preview;
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn close_preview(&mut self) {
+        self.screen = Screen::Main;
+    }
+
+    pub fn is_preview_screen(&self) -> bool {
+        matches!(self.screen, Screen::Preview)
+    }
+
+    /// Scroll the page preview, never past its last line
+    pub fn scroll_preview(&mut self, lines: i32) {
+        let last = self.page_preview.len().saturating_sub(1).min(usize::from(u16::MAX)) as i32;
+        self.preview_scroll = (i32::from(self.preview_scroll) + lines).clamp(0, last) as u16;
+    }
+
+    /// Edit what is remembered for the selected page
+    pub fn open_page_settings(&mut self) {
+        if self.submission_target().is_none() {
+            self.set_error("Select a page or database row first");
+            return;
+        }
+        self.screen = Screen::PageSettings;
+        self.input_mode = InputMode::Normal;
+        self.setting_input = None;
+    }
+
+    pub fn close_page_settings(&mut self) {
+        self.screen = Screen::Main;
+        self.setting_input = None;
+    }
+
+    pub fn is_page_settings_screen(&self) -> bool {
+        matches!(self.screen, Screen::PageSettings)
+    }
+
+    pub fn selected_setting(&self) -> PageSetting {
+        PageSetting::ALL[self.selected_setting]
+    }
+
+    pub fn next_setting(&mut self) {
+        self.selected_setting = (self.selected_setting + 1) % PageSetting::ALL.len();
+    }
+
+    pub fn previous_setting(&mut self) {
+        let total = PageSetting::ALL.len();
+        self.selected_setting = (self.selected_setting + total - 1) % total;
+    }
+
+    /// Cycle a choice, or start typing a text setting
+    pub fn change_setting(&mut self) {
+        let setting = self.selected_setting();
+        if setting.is_choice() {
+            self.update_page_settings(|settings| settings.cycle(setting));
+        } else {
+            let current = self.selected_page_settings().cloned().unwrap_or_default();
+            self.setting_input = Some(current.text(setting));
+        }
+    }
+
+    /// Save the typed value of the selected setting
+    pub fn commit_setting_input(&mut self) {
+        if let Some(text) = self.setting_input.take() {
+            let setting = self.selected_setting();
+            self.update_page_settings(|settings| settings.set_text(setting, &text));
+        }
+    }
+
+    pub fn reset_setting(&mut self) {
+        let setting = self.selected_setting();
+        self.update_page_settings(|settings| settings.reset(setting));
+    }
+
+    /// Change and save the selected page's settings, keeping the editor's
+    /// code language in step when the user has not overridden it
+    fn update_page_settings(&mut self, change: impl FnOnce(&mut PageSettings)) {
+        let Some(page) = self.submission_target() else {
+            return;
+        };
+        let mut settings = self.page_settings.get(&page.id).cloned().unwrap_or_default();
+        change(&mut settings);
+        let mut result = Ok(());
+        self.retarget(|app| result = app.page_settings.set(&page.id, settings));
+        match result {
+            Ok(()) => self.set_success(format!("Saved settings for {}", page.title)),
+            Err(e) => self.set_error(e),
+        }
+    }
+
+    /// Show the rows of the selected database to pick one
+    pub fn open_rows(&mut self, rows: Vec<PageInfo>) {
+        self.database_rows = rows;
+        self.screen = Screen::Rows;
+        self.input_mode = InputMode::Normal;
+        self.refresh_row_filter();
+    }
+
+    pub fn close_rows(&mut self) {
+        self.screen = match self.row_pick {
+            RowPick::Target => Screen::Main,
+            RowPick::Relation { .. } => Screen::Relations,
+        };
+        self.row_searching = false;
+    }
+
+    /// Database the row picker lists rows of
+    pub fn row_database_id(&self) -> Option<String> {
+        match &self.row_pick {
+            RowPick::Target => self.get_selected_page().filter(|p| p.is_database()).map(|p| p.id.clone()),
+            RowPick::Relation { database_id, .. } => Some(database_id.clone()),
+        }
+    }
+
+    pub fn is_rows_screen(&self) -> bool {
+        matches!(self.screen, Screen::Rows)
+    }
+
+    /// Re-match the loaded rows against the typed filter
+    pub fn refresh_row_filter(&mut self) {
+        self.row_matches = fuzzy::rank(&self.row_query, self.database_rows.iter().map(|r| r.title.as_str()));
+        self.selected_row_match = 0;
+    }
+
+    pub fn selected_row(&self) -> Option<&PageInfo> {
+        let index = *self.row_matches.get(self.selected_row_match)?;
+        self.database_rows.get(index)
+    }
+
+    pub fn next_row(&mut self) {
+        let total = self.row_matches.len();
+        if total > 0 {
+            self.selected_row_match = (self.selected_row_match + 1) % total;
+        }
+    }
+
+    pub fn previous_row(&mut self) {
+        let total = self.row_matches.len();
+        if total > 0 {
+            self.selected_row_match = (self.selected_row_match + total - 1) % total;
+        }
+    }
+
+    /// Send entries for the selected database to the highlighted row, or
+    /// link new rows to it when picking a relation
+    pub fn choose_row(&mut self) {
+        let (Some(database), Some(row)) = (self.get_selected_page().cloned(), self.selected_row().cloned()) else {
+            return;
+        };
+        if let RowPick::Relation { property, .. } = self.row_pick.clone() {
+            let linked = self.relations.entry(database.id).or_default().entry(property.clone()).or_default();
+            if !linked.iter().any(|page| page.id == row.id) {
+                linked.push(row.clone());
+            }
+            self.close_rows();
+            self.set_success(format!("New rows will link {} to {}", property, row.title));
+            return;
+        }
+        self.retarget(|app| {
+            app.chosen_rows.insert(database.id.clone(), row);
+        });
+        self.close_rows();
+        if let Some(target) = self.submission_target() {
+            self.set_success(format!("Entries will be added to {}", target.title));
+        }
+    }
+
+    /// Go back to adding a new row per entry in the selected database
+    pub fn clear_row(&mut self) {
+        let Some(database) = self.get_selected_page().cloned() else {
+            return;
+        };
+        self.retarget(|app| {
+            app.chosen_rows.remove(&database.id);
+        });
+        self.close_rows();
+        self.set_success(format!("Entries will be new rows in {}", database.title));
+    }
+
+    /// Show the relation properties of the selected database
+    pub fn open_relations(&mut self, properties: Vec<RelationProperty>) {
+        self.relation_properties = properties;
+        self.selected_relation = 0;
+        self.screen = Screen::Relations;
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn close_relations(&mut self) {
+        self.screen = Screen::Main;
+        self.row_pick = RowPick::Target;
+    }
+
+    pub fn is_relations_screen(&self) -> bool {
+        matches!(self.screen, Screen::Relations)
+    }
+
+    pub fn selected_relation_property(&self) -> Option<&RelationProperty> {
+        self.relation_properties.get(self.selected_relation)
+    }
+
+    pub fn next_relation(&mut self) {
+        let total = self.relation_properties.len();
+        if total > 0 {
+            self.selected_relation = (self.selected_relation + 1) % total;
+        }
+    }
+
+    pub fn previous_relation(&mut self) {
+        let total = self.relation_properties.len();
+        if total > 0 {
+            self.selected_relation = (self.selected_relation + total - 1) % total;
+        }
+    }
+
+    /// Pages new rows of the selected database link to through a property
+    pub fn linked_pages(&self, property: &str) -> &[PageInfo] {
+        self.get_selected_page()
+            .and_then(|database| self.relations.get(&database.id))
+            .and_then(|linked| linked.get(property))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Stop linking new rows through the selected relation property
+    pub fn clear_relation(&mut self) {
+        let (Some(database), Some(property)) = (self.get_selected_page(), self.selected_relation_property()) else {
+            return;
+        };
+        let (database_id, property) = (database.id.clone(), property.name.clone());
+        if let Some(linked) = self.relations.get_mut(&database_id) {
+            linked.remove(&property);
+        }
+        self.set_status(format!("{} cleared", property));
+    }
+
+    pub fn handle_up(&mut self) {
+        match self.current_focus {
+            FocusArea::PageList => self.previous_page(),
+            FocusArea::InputSection => self.previous_input(),
+        }
+    }
+
+    pub fn handle_down(&mut self) {
+        match self.current_focus {
+            FocusArea::PageList => self.next_page(),
+            FocusArea::InputSection => self.next_input(),
+        }
+    }
+
+    pub fn handle_left(&mut self) {
+        if self.is_page_list_focused() {
+            self.collapse_page();
+        }
+    }
+
+    pub fn handle_right(&mut self) {
+        if self.is_page_list_focused() {
+            self.expand_page();
+        }
+    }
+
+    /// PgUp: a screen up the page list, or up the focused field
+    pub fn handle_page_up(&mut self) {
+        if self.status_log_open {
+            self.scroll_status_log(Self::STATUS_LOG_PAGE as isize);
+            return;
+        }
+        match self.current_focus {
+            FocusArea::PageList => self.page_up(),
+            FocusArea::InputSection => self.scroll_field(-1),
+        }
+    }
+
+    pub fn handle_page_down(&mut self) {
+        if self.status_log_open {
+            self.scroll_status_log(-(Self::STATUS_LOG_PAGE as isize));
+            return;
+        }
+        match self.current_focus {
+            FocusArea::PageList => self.page_down(),
+            FocusArea::InputSection => self.scroll_field(1),
+        }
+    }
+}
+
+/// Treat whitespace-only optional inputs as absent
+fn optional_input(input: &TextBuffer) -> Option<String> {
+    if input.is_blank() {
+        None
+    } else {
+        Some(input.to_string())
+    }
 }
 
 impl Default for AppState {
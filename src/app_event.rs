@@ -0,0 +1,188 @@
+// Everything the main loop reacts to arrives as an `AppEvent` on one
+// channel: terminal input from a dedicated thread, results from network
+// tasks, and a periodic tick for housekeeping
+use std::io;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyEvent, KeyEventKind, MouseEvent};
+use tokio::sync::mpsc;
+use tokio::time::{self, Interval, MissedTickBehavior};
+
+use crate::network::Health;
+use crate::notion::activity::ApiCall;
+use crate::notion::retry::RetryNotice;
+use crate::page_loader::PageLoad;
+
+/// How often `Tick` fires while nothing else happens
+pub const TICK_INTERVAL: Duration = Duration::from_millis(100);
+/// How long the input thread waits for a key before checking whether the
+/// app is still listening
+const INPUT_POLL: Duration = Duration::from_millis(250);
+
+/// Something the app state machine reacts to
+#[derive(Debug)]
+pub enum AppEvent {
+    /// A key press; releases and repeats are dropped by the input thread
+    Key(KeyEvent),
+    /// Bracketed paste: the terminal's paste arrives as one event
+    Paste(String),
+    Mouse(MouseEvent),
+    /// The terminal was resized; the next draw uses the new size
+    Resize,
+    /// A background page fetch finished
+    PagesLoaded(PageLoad),
+    /// The background submission finished; its result is taken from the
+    /// task in `AppState::submission`
+    SubmissionFinished,
+    /// The background retry of queued entries finished; its results are
+    /// taken from the task in `AppState::retry`
+    RetryFinished,
+    /// A new health ping result
+    Health(Health),
+    /// A request started waiting to retry
+    Retrying(RetryNotice),
+    /// A Notion request settled
+    ApiCall(ApiCall),
+    /// Reading the terminal failed; the app exits with the error
+    InputFailed(io::Error),
+    /// Fired every `TICK_INTERVAL`, to retry queued entries when due
+    Tick,
+}
+
+impl AppEvent {
+    /// The event for what the terminal reported, `None` for events the app
+    /// ignores
+    pub fn from_terminal(event: Event) -> Option<Self> {
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => Some(Self::Key(key)),
+            Event::Paste(text) => Some(Self::Paste(text)),
+            Event::Mouse(mouse) => Some(Self::Mouse(mouse)),
+            Event::Resize(_, _) => Some(Self::Resize),
+            _ => None,
+        }
+    }
+}
+
+/// The channel events arrive on, plus the tick
+pub struct EventBus {
+    sender: mpsc::UnboundedSender<AppEvent>,
+    receiver: mpsc::UnboundedReceiver<AppEvent>,
+    tick: Interval,
+    pause: InputPause,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut tick = time::interval(TICK_INTERVAL);
+        // A slow frame skips ticks rather than firing a burst of them
+        tick.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        Self {
+            sender,
+            receiver,
+            tick,
+            pause: InputPause::default(),
+        }
+    }
+
+    /// Where tasks send their events
+    pub fn sender(&self) -> mpsc::UnboundedSender<AppEvent> {
+        self.sender.clone()
+    }
+
+    /// Stops the input thread reading the terminal
+    pub fn input_pause(&self) -> InputPause {
+        self.pause.clone()
+    }
+
+    /// Wait for the next event, or the next tick when none comes sooner
+    pub async fn next(&mut self) -> AppEvent {
+        tokio::select! {
+            Some(event) = self.receiver.recv() => event,
+            _ = self.tick.tick() => AppEvent::Tick,
+        }
+    }
+
+    /// An event already waiting, without blocking
+    pub fn try_next(&mut self) -> Option<AppEvent> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lets the main loop take the terminal from the input thread, so a
+/// program it runs in the foreground, such as an editor, gets every key
+#[derive(Debug, Clone, Default)]
+pub struct InputPause {
+    state: Arc<(Mutex<PauseState>, Condvar)>,
+}
+
+#[derive(Debug, Default)]
+struct PauseState {
+    requested: bool,
+    /// The input thread is parked and not reading
+    parked: bool,
+}
+
+impl InputPause {
+    /// Stop reading the terminal; returns once the input thread has
+    /// finished its current poll, or gave up waiting for a thread that
+    /// already ended
+    pub fn pause(&self) {
+        let (lock, parked) = &*self.state;
+        let mut state = lock.lock().unwrap_or_else(|e| e.into_inner());
+        state.requested = true;
+        let _ = parked.wait_timeout_while(state, INPUT_POLL * 2, |state| !state.parked);
+    }
+
+    /// Read the terminal again
+    pub fn resume(&self) {
+        let (lock, changed) = &*self.state;
+        lock.lock().unwrap_or_else(|e| e.into_inner()).requested = false;
+        changed.notify_all();
+    }
+
+    /// Called by the input thread between polls: block while paused
+    fn wait_while_paused(&self) {
+        let (lock, changed) = &*self.state;
+        let mut state = lock.lock().unwrap_or_else(|e| e.into_inner());
+        if !state.requested {
+            return;
+        }
+        state.parked = true;
+        changed.notify_all();
+        let mut state = changed.wait_while(state, |state| state.requested).unwrap_or_else(|e| e.into_inner());
+        state.parked = false;
+    }
+}
+
+/// Read the terminal on its own thread, so a blocking read never stalls
+/// the draw loop or network tasks. The thread ends once the app stops
+/// listening or reading fails, and stops reading while `pause` holds it.
+pub fn spawn_input(sender: mpsc::UnboundedSender<AppEvent>, pause: InputPause) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        while !sender.is_closed() {
+            pause.wait_while_paused();
+            let read = event::poll(INPUT_POLL).and_then(|ready| if ready { event::read().map(Some) } else { Ok(None) });
+            let event = match read {
+                Ok(event) => event.and_then(AppEvent::from_terminal),
+                Err(e) => {
+                    let _ = sender.send(AppEvent::InputFailed(e));
+                    return;
+                }
+            };
+            if let Some(event) = event {
+                if sender.send(event).is_err() {
+                    return;
+                }
+            }
+        }
+    })
+}
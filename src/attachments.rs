@@ -0,0 +1,87 @@
+// Files attached to entries: screenshots, logs and the like uploaded
+// through Notion's file upload API, or links Notion shows as external files
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::expand_home;
+
+/// Largest file Notion takes in a single-part upload
+pub const MAX_UPLOAD_BYTES: u64 = 20 * 1024 * 1024;
+
+/// What the attach prompt was given
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttachSource {
+    /// A file to upload
+    File(PathBuf),
+    /// An http(s) link embedded as an external file
+    Link(String),
+}
+
+impl AttachSource {
+    /// As kept in the entry until it is submitted
+    pub fn to_entry_string(&self) -> String {
+        match self {
+            AttachSource::File(path) => path.display().to_string(),
+            AttachSource::Link(url) => url.clone(),
+        }
+    }
+}
+
+/// Check a path or link typed or dropped into the attach prompt. Quotes
+/// and backslash-escaped spaces, as terminals add to dropped files, are
+/// removed; a leading `~/` is the home directory.
+pub fn resolve(input: &str) -> Result<AttachSource, String> {
+    let input = input.trim().trim_matches(|c| c == '\'' || c == '"');
+    if input.is_empty() {
+        return Err("Type a file path or a link".to_string());
+    }
+    if input.starts_with("http://") || input.starts_with("https://") {
+        return Ok(AttachSource::Link(input.to_string()));
+    }
+    let input = input.strip_prefix("file://").unwrap_or(input).replace("\\ ", " ");
+    let path = expand_home(&input);
+    let metadata = fs::metadata(&path).map_err(|_| format!("{} not found", path.display()))?;
+    if metadata.is_dir() {
+        return Err(format!("{} is a directory", path.display()));
+    }
+    if metadata.len() > MAX_UPLOAD_BYTES {
+        return Err(format!(
+            "{} is {} MB; Notion uploads take up to {} MB",
+            path.display(),
+            metadata.len().div_ceil(1024 * 1024),
+            MAX_UPLOAD_BYTES / (1024 * 1024)
+        ));
+    }
+    Ok(AttachSource::File(path))
+}
+
+/// Name shown for an attachment: the last part of its path or link
+pub fn display_name(source: &str) -> String {
+    let trimmed = source.split(['?', '#']).next().unwrap_or(source).trim_end_matches('/');
+    let name = trimmed.rsplit(['/', '\\']).next().unwrap_or(trimmed);
+    if name.is_empty() { source.to_string() } else { name.to_string() }
+}
+
+/// Media type Notion is told for a file, from its extension
+pub fn content_type(name: &str) -> &'static str {
+    let extension = Path::new(name)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "json" => "application/json",
+        "txt" | "log" | "md" | "csv" | "out" => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Shown as an image in the entry rather than as a file to download
+pub fn is_image(name: &str) -> bool {
+    content_type(name).starts_with("image/")
+}
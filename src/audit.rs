@@ -0,0 +1,118 @@
+// Append-only local audit log of every write FaultNote makes to Notion
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::config::data_dir;
+
+/// Kind of write sent to Notion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    AppendBlocks,
+    /// A new database row holding the entry, or a page created from the
+    /// page list
+    CreatePage,
+    /// Properties of an existing row changed, e.g. its status
+    UpdatePage,
+    UploadFile,
+    DeleteBlocks,
+}
+
+/// One write to Notion
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// RFC 3339 UTC timestamp
+    pub timestamp: String,
+    pub action: AuditAction,
+    /// Page written to, when the write targets a page
+    pub page_id: Option<String>,
+    /// Ids of blocks or uploads created by the write
+    pub created_ids: Vec<String>,
+    pub payload_sha256: String,
+    /// Name of the token used, when known
+    pub token: Option<String>,
+}
+
+impl AuditRecord {
+    /// A record stamped with the current time
+    pub fn now(action: AuditAction, payload_sha256: impl Into<String>) -> Self {
+        Self {
+            timestamp: Utc::now().to_rfc3339(),
+            action,
+            page_id: None,
+            created_ids: Vec::new(),
+            payload_sha256: payload_sha256.into(),
+            token: None,
+        }
+    }
+}
+
+/// JSON Lines file that is only ever appended to
+#[derive(Debug, Default)]
+pub struct AuditLog {
+    path: Option<PathBuf>,
+}
+
+impl AuditLog {
+    /// Default location of the audit log
+    pub fn default_path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("audit.jsonl"))
+    }
+
+    pub fn new(path: PathBuf) -> Self {
+        Self { path: Some(path) }
+    }
+
+    /// Append one record. In-memory logs (no path) drop records.
+    #[instrument(skip_all, fields(action = ?record.action), err)]
+    pub fn append(&self, record: &AuditRecord) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let line = serde_json::to_string(record)
+            .map_err(|e| format!("Failed to serialize audit record: {}", e))?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        writeln!(file, "{}", line).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Read every record in order
+    pub fn read_all(&self) -> Result<Vec<AuditRecord>, String> {
+        let Some(path) = &self.path else {
+            return Ok(Vec::new());
+        };
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(number, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| format!("Corrupt audit record on line {}: {}", number + 1, e))
+            })
+            .collect()
+    }
+
+    /// All records as a pretty-printed JSON array
+    pub fn export_json(&self) -> Result<String, String> {
+        let records = self.read_all()?;
+        serde_json::to_string_pretty(&records).map_err(|e| format!("Failed to export audit log: {}", e))
+    }
+}
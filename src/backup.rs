@@ -0,0 +1,152 @@
+// Backup and restore of all local state (config, queue, history, audit log)
+use std::{
+    fs::{self, File},
+    path::{Component, Path, PathBuf},
+};
+
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use tar::EntryType;
+use tracing::instrument;
+
+/// Top-level folder in the archive for the config directory
+const CONFIG_PREFIX: &str = "config";
+/// Top-level folder in the archive for the data directory
+const DATA_PREFIX: &str = "data";
+
+/// Default archive name, e.g. `faultnote-backup-20260116-093000.tar.gz`
+pub fn default_backup_name() -> String {
+    format!(
+        "faultnote-backup-{}.tar.gz",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    )
+}
+
+/// Write the config and data directories into a gzipped tarball.
+/// Returns the number of files archived.
+#[instrument(skip(config_dir, data_dir), err)]
+pub fn create_backup(config_dir: &Path, data_dir: &Path, output: &Path) -> Result<usize, String> {
+    let file = File::create(output).map_err(|e| format!("Failed to create {}: {}", output.display(), e))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let mut count = 0;
+    for (prefix, dir) in [(CONFIG_PREFIX, config_dir), (DATA_PREFIX, data_dir)] {
+        for path in files_under(dir)? {
+            let relative = path.strip_prefix(dir).map_err(|e| e.to_string())?;
+            archive
+                .append_path_with_name(&path, Path::new(prefix).join(relative))
+                .map_err(|e| format!("Failed to archive {}: {}", path.display(), e))?;
+            count += 1;
+        }
+    }
+
+    archive
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| format!("Failed to finish {}: {}", output.display(), e))?;
+    Ok(count)
+}
+
+/// Extract a backup into the config and data directories.
+/// Existing files are only replaced when `force` is set.
+/// Returns the number of files restored.
+#[instrument(skip(config_dir, data_dir), err)]
+pub fn restore_backup(archive_path: &Path, config_dir: &Path, data_dir: &Path, force: bool) -> Result<usize, String> {
+    if !force && (has_files(config_dir)? || has_files(data_dir)?) {
+        return Err("Local state already exists; pass --force to overwrite it".to_string());
+    }
+
+    // Everything is unpacked next to the data dir first, so an archive that
+    // turns out to be bad halfway leaves the local state untouched
+    let staging = data_dir.with_file_name(format!(".faultnote-restore-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&staging);
+    let restored = unpack_staged(archive_path, &staging, config_dir, data_dir).and_then(|files| {
+        for path in &files {
+            let target = restore_target(path, config_dir, data_dir)?;
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            // Renaming replaces whatever is at the target, a link included,
+            // rather than writing through it
+            let staged = staging.join(path);
+            fs::rename(&staged, &target)
+                .or_else(|_| fs::copy(&staged, &target).map(|_| ()))
+                .map_err(|e| format!("Failed to restore {}: {}", target.display(), e))?;
+        }
+        Ok(files.len())
+    });
+    let _ = fs::remove_dir_all(&staging);
+    restored
+}
+
+/// Unpack the archive below `staging`, returning the archive paths of its
+/// files. Only regular files and directories inside the two known folders
+/// are accepted; links and devices fail the restore.
+fn unpack_staged(archive_path: &Path, staging: &Path, config_dir: &Path, data_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let file = File::open(archive_path).map_err(|e| format!("Failed to open {}: {}", archive_path.display(), e))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let entries = archive
+        .entries()
+        .map_err(|e| format!("Failed to read {}: {}", archive_path.display(), e))?;
+    fs::create_dir_all(staging).map_err(|e| format!("Failed to create {}: {}", staging.display(), e))?;
+
+    let mut files = Vec::new();
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Corrupt backup: {}", e))?;
+        let path = entry.path().map_err(|e| format!("Corrupt backup: {}", e))?.into_owned();
+        restore_target(&path, config_dir, data_dir)?;
+        match entry.header().entry_type() {
+            EntryType::Regular => files.push(path.clone()),
+            EntryType::Directory => {}
+            other => return Err(format!("Unsupported entry in backup: {} ({:?})", path.display(), other)),
+        }
+        let unpacked = entry
+            .unpack_in(staging)
+            .map_err(|e| format!("Failed to restore {}: {}", path.display(), e))?;
+        if !unpacked {
+            return Err(format!("Unsafe path in backup: {}", path.display()));
+        }
+    }
+    Ok(files)
+}
+
+/// Map an archive path like `data/queue.json` to its destination,
+/// rejecting anything outside the two known folders
+fn restore_target(path: &Path, config_dir: &Path, data_dir: &Path) -> Result<PathBuf, String> {
+    let mut components = path.components();
+    let root = match components.next() {
+        Some(Component::Normal(name)) if name == CONFIG_PREFIX => config_dir,
+        Some(Component::Normal(name)) if name == DATA_PREFIX => data_dir,
+        _ => return Err(format!("Unexpected path in backup: {}", path.display())),
+    };
+    let relative = components.as_path();
+    if relative.as_os_str().is_empty() || relative.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return Err(format!("Unsafe path in backup: {}", path.display()));
+    }
+    Ok(root.join(relative))
+}
+
+/// Every regular file below `dir`, recursively; a missing dir has none
+fn files_under(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    if !dir.exists() {
+        return Ok(files);
+    }
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let entries = fs::read_dir(&current).map_err(|e| format!("Failed to read {}: {}", current.display(), e))?;
+        for entry in entries {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.is_file() {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn has_files(dir: &Path) -> Result<bool, String> {
+    Ok(!files_under(dir)?.is_empty())
+}
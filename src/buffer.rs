@@ -0,0 +1,259 @@
+// Rope-backed text storage for the input fields
+use std::fmt;
+
+use ropey::{Rope, RopeSlice};
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
+
+/// Chars on either side of the cursor segmented to find a grapheme
+/// boundary; doubled while a grapheme runs past it
+const BOUNDARY_WINDOW: usize = 16;
+
+/// Editable text of one field. Inserts and deletes anywhere are
+/// O(log n), line lookups are indexed, and clones share structure so
+/// snapshots for undo are cheap.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextBuffer {
+    rope: Rope,
+    /// Char index the next typed char is inserted at
+    cursor: usize,
+}
+
+impl TextBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Length in chars
+    pub fn len_chars(&self) -> usize {
+        self.rope.len_chars()
+    }
+
+    /// Number of lines; a trailing newline starts an empty last line
+    pub fn len_lines(&self) -> usize {
+        self.rope.len_lines()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rope.len_chars() == 0
+    }
+
+    /// True when the buffer holds only whitespace
+    pub fn is_blank(&self) -> bool {
+        self.rope.chars().all(char::is_whitespace)
+    }
+
+    pub fn push(&mut self, ch: char) {
+        self.insert_char(self.rope.len_chars(), ch);
+    }
+
+    /// Remove and return the last char
+    pub fn pop(&mut self) -> Option<char> {
+        let len = self.rope.len_chars();
+        if len == 0 {
+            return None;
+        }
+        let ch = self.rope.char(len - 1);
+        self.remove(len - 1, len);
+        Some(ch)
+    }
+
+    /// Insert text at a char index; a cursor at or after it moves along
+    pub fn insert(&mut self, char_idx: usize, text: &str) {
+        self.rope.insert(char_idx, text);
+        if char_idx <= self.cursor {
+            self.cursor += text.chars().count();
+        }
+    }
+
+    pub fn insert_char(&mut self, char_idx: usize, ch: char) {
+        self.rope.insert_char(char_idx, ch);
+        if char_idx <= self.cursor {
+            self.cursor += 1;
+        }
+    }
+
+    /// Remove the chars in `start..end`, keeping the cursor on the same text
+    pub fn remove(&mut self, start: usize, end: usize) {
+        self.rope.remove(start..end);
+        if self.cursor >= end {
+            self.cursor -= end - start;
+        } else if self.cursor > start {
+            self.cursor = start;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.rope = Rope::new();
+        self.cursor = 0;
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn set_cursor(&mut self, char_idx: usize) {
+        self.cursor = char_idx.min(self.len_chars());
+    }
+
+    /// Cursor as (line, column), both counted in chars
+    pub fn cursor_position(&self) -> (usize, usize) {
+        let line = self.rope.char_to_line(self.cursor);
+        (line, self.cursor - self.rope.line_to_char(line))
+    }
+
+    pub fn insert_at_cursor(&mut self, ch: char) {
+        self.insert_char(self.cursor, ch);
+    }
+
+    /// Backspace: remove the grapheme before the cursor, e.g. a whole
+    /// emoji or a letter with its accents
+    pub fn delete_before_cursor(&mut self) {
+        if self.cursor > 0 {
+            self.remove(self.previous_boundary(self.cursor), self.cursor);
+        }
+    }
+
+    /// Delete: remove the grapheme under the cursor
+    pub fn delete_at_cursor(&mut self) {
+        if self.cursor < self.len_chars() {
+            self.remove(self.cursor, self.next_boundary(self.cursor));
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.previous_boundary(self.cursor);
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = self.next_boundary(self.cursor);
+    }
+
+    /// Char index where the grapheme ending at `char_idx` starts
+    fn previous_boundary(&self, char_idx: usize) -> usize {
+        if char_idx == 0 {
+            return 0;
+        }
+        let line = self.rope.char_to_line(char_idx);
+        if char_idx == self.rope.line_to_char(line) {
+            // The newline ending the line above
+            return char_idx - 1;
+        }
+        self.boundary(char_idx, line, false).unwrap_or(char_idx - 1)
+    }
+
+    /// Char index where the grapheme starting at `char_idx` ends
+    fn next_boundary(&self, char_idx: usize) -> usize {
+        if char_idx >= self.len_chars() {
+            return self.len_chars();
+        }
+        let line = self.rope.char_to_line(char_idx);
+        if char_idx >= self.rope.line_to_char(line) + self.line(line).len_chars() {
+            // The newline itself
+            return char_idx + 1;
+        }
+        self.boundary(char_idx, line, true).unwrap_or(char_idx + 1)
+    }
+
+    /// The grapheme boundary after (`forward`) or before `char_idx` on
+    /// `line`. Graphemes never span lines, and only a window around the
+    /// cursor is segmented, so a long line costs no more than a short one.
+    fn boundary(&self, char_idx: usize, line: usize, forward: bool) -> Option<usize> {
+        let start = self.rope.line_to_char(line);
+        let end = start + self.line(line).len_chars();
+        let line_byte = self.rope.char_to_byte(start);
+        let offset = self.rope.char_to_byte(char_idx) - line_byte;
+        let len = self.rope.char_to_byte(end) - line_byte;
+        let mut window = BOUNDARY_WINDOW;
+        loop {
+            let from = char_idx.saturating_sub(window).max(start);
+            let to = (char_idx + window).min(end);
+            let chunk = self.rope.slice(from..to).to_string();
+            let chunk_start = self.rope.char_to_byte(from) - line_byte;
+            let mut cursor = GraphemeCursor::new(offset, len, true);
+            let found = if forward {
+                cursor.next_boundary(&chunk, chunk_start)
+            } else {
+                cursor.prev_boundary(&chunk, chunk_start)
+            };
+            match found {
+                Ok(byte) => return byte.map(|byte| self.rope.byte_to_char(line_byte + byte)),
+                // The grapheme or what decides it runs past the window
+                Err(_) if from > start || to < end => window *= 2,
+                Err(_) => return None,
+            }
+        }
+    }
+
+    /// Char index where line `line_idx` starts
+    pub fn line_start(&self, line_idx: usize) -> usize {
+        self.rope.line_to_char(line_idx)
+    }
+
+    /// Move to the start of the cursor's line
+    pub fn move_line_start(&mut self) {
+        let (line, _) = self.cursor_position();
+        self.cursor = self.rope.line_to_char(line);
+    }
+
+    /// Move to the end of the cursor's line, before its newline
+    pub fn move_line_end(&mut self) {
+        let (line, _) = self.cursor_position();
+        self.cursor = self.rope.line_to_char(line) + self.line(line).len_chars();
+    }
+
+    /// A line without its trailing newline
+    pub fn line(&self, line_idx: usize) -> RopeSlice<'_> {
+        strip_newline(self.rope.line(line_idx))
+    }
+
+    /// Every line without trailing newlines
+    pub fn lines(&self) -> impl Iterator<Item = RopeSlice<'_>> {
+        self.rope.lines().map(strip_newline)
+    }
+}
+
+/// Remove the last grapheme of a one-line input such as a filter, so
+/// Backspace never leaves half an emoji behind
+pub fn pop_grapheme(text: &mut String) {
+    if let Some((start, _)) = text.grapheme_indices(true).next_back() {
+        text.truncate(start);
+    }
+}
+
+fn strip_newline(line: RopeSlice<'_>) -> RopeSlice<'_> {
+    let len = line.len_chars();
+    if len > 0 && line.char(len - 1) == '\n' {
+        line.slice(..len - 1)
+    } else {
+        line
+    }
+}
+
+impl fmt::Display for TextBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in self.rope.chunks() {
+            f.write_str(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for TextBuffer {
+    fn from(text: &str) -> Self {
+        let rope = Rope::from_str(text);
+        let cursor = rope.len_chars();
+        Self { rope, cursor }
+    }
+}
+
+impl From<String> for TextBuffer {
+    fn from(text: String) -> Self {
+        Self::from(text.as_str())
+    }
+}
+
+impl PartialEq<&str> for TextBuffer {
+    fn eq(&self, other: &&str) -> bool {
+        self.rope == *other
+    }
+}
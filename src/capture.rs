@@ -0,0 +1,98 @@
+// Quick capture: FaultNote opened in its own terminal window, ready to type
+// the error, from a desktop shortcut or the optional global hotkey daemon
+use std::env;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use serde::Deserialize;
+
+/// Hotkey `faultnote daemon` listens for unless told otherwise
+pub const DEFAULT_HOTKEY: &str = "Ctrl+Alt+E";
+
+/// `[capture]` in the config
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CaptureConfig {
+    /// Command opening a terminal window, followed by the command to run
+    /// in it, e.g. `alacritty -e` or `wezterm start --`; defaults to
+    /// `$TERMINAL -e`, else the platform's terminal
+    pub terminal: Option<String>,
+    /// Global hotkey for `faultnote daemon`, e.g. `Ctrl+Shift+F`
+    pub hotkey: Option<String>,
+}
+
+impl CaptureConfig {
+    pub fn hotkey(&self) -> &str {
+        self.hotkey.as_deref().map(str::trim).filter(|hotkey| !hotkey.is_empty()).unwrap_or(DEFAULT_HOTKEY)
+    }
+}
+
+/// The command line opening `program --capture` in a new terminal window
+pub fn window_command(config: &CaptureConfig, program: &Path) -> Vec<String> {
+    let program = program.display().to_string();
+    let terminal = config
+        .terminal
+        .clone()
+        .filter(|terminal| !terminal.trim().is_empty())
+        .or_else(|| env::var("TERMINAL").ok().filter(|terminal| !terminal.trim().is_empty()).map(|terminal| format!("{} -e", terminal)));
+    if let Some(terminal) = terminal {
+        let mut command: Vec<String> = terminal.split_whitespace().map(str::to_string).collect();
+        command.extend([program, "--capture".to_string()]);
+        return command;
+    }
+    if cfg!(target_os = "macos") {
+        let script = format!("tell application \"Terminal\" to do script \"'{}' --capture\"", program);
+        vec!["osascript".to_string(), "-e".to_string(), script, "-e".to_string(), "tell application \"Terminal\" to activate".to_string()]
+    } else if cfg!(windows) {
+        ["cmd", "/C", "start", "FaultNote"].iter().map(|part| part.to_string()).chain([program, "--capture".to_string()]).collect()
+    } else {
+        vec!["x-terminal-emulator".to_string(), "-e".to_string(), program, "--capture".to_string()]
+    }
+}
+
+/// Open the quick-capture window without waiting for it to close
+pub fn open_window(config: &CaptureConfig) -> Result<(), String> {
+    let program = env::current_exe().map_err(|e| format!("Failed to find the faultnote executable: {}", e))?;
+    let command = window_command(config, &program);
+    let (terminal, args) = command.split_first().ok_or("Set [capture] terminal")?;
+    Command::new(terminal)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to run {}: {} (set [capture] terminal)", terminal, e))
+}
+
+/// Open the quick-capture window every time `hotkey` is pressed, until
+/// the process is stopped
+#[cfg(all(feature = "hotkey", target_os = "linux"))]
+pub fn run_daemon(config: &CaptureConfig, hotkey: &str) -> Result<(), String> {
+    use global_hotkey::hotkey::HotKey;
+    use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+
+    let key: HotKey = hotkey.parse().map_err(|e| format!("Hotkey {}: {}", hotkey, e))?;
+    let manager = GlobalHotKeyManager::new().map_err(|e| format!("Global hotkeys unavailable: {}", e))?;
+    manager
+        .register(key)
+        .map_err(|e| format!("Failed to register {}: {}", hotkey, e))?;
+    eprintln!("Press {} to capture an error; Ctrl+C stops", hotkey);
+    while let Ok(event) = GlobalHotKeyEvent::receiver().recv() {
+        if event.id == key.id() && event.state == HotKeyState::Pressed {
+            if let Err(e) = open_window(config) {
+                eprintln!("Error: {}", e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Without the `hotkey` feature, or off X11, the desktop's own shortcut
+/// settings run `faultnote popup` instead
+#[cfg(not(all(feature = "hotkey", target_os = "linux")))]
+pub fn run_daemon(_config: &CaptureConfig, _hotkey: &str) -> Result<(), String> {
+    Err("Global hotkeys need a Linux build with the `hotkey` feature (X11); \
+         bind `faultnote popup` to a keyboard shortcut in your desktop's settings instead"
+        .to_string())
+}
@@ -0,0 +1,631 @@
+// Command-line interface: no subcommand launches the TUI
+use std::{fs, path::PathBuf};
+
+use chrono::Utc;
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::{Value, json};
+
+use crate::audit::AuditLog;
+use crate::backup::{create_backup, default_backup_name, restore_backup};
+use crate::capture;
+use crate::config::{config_dir, config_path, data_dir};
+use crate::config_file;
+use crate::failure::Failure;
+use crate::fields::InputField;
+use crate::flush::{self, FlushOutput};
+use crate::fsck;
+use crate::import::{self, ImportOptions, ImportStatus};
+use crate::sample;
+use crate::sink::SinkKind;
+use crate::storage::History;
+use crate::submit::{self, SubmitRequest, Submitted};
+
+#[derive(Debug, Parser)]
+#[command(name = "faultnote", version, about = "Log errors, problems and solutions to Notion")]
+pub struct Cli {
+    /// Write tracing spans to this file for diagnosing slowness
+    #[arg(long, global = true, value_name = "FILE")]
+    pub trace: Option<PathBuf>,
+
+    /// How subcommands print their results; `json` prints one object with
+    /// `"ok"` and either the result fields or `"error"`
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// Replay key presses from a script file instead of reading the keyboard
+    #[arg(long, value_name = "FILE")]
+    pub script: Option<PathBuf>,
+
+    /// Put only the failing line of piped output into the Error field,
+    /// leaving the Code field empty
+    #[arg(long)]
+    pub error_only: bool,
+
+    /// Start in the compact layout (the status and the active field, for a
+    /// thin tmux pane); `M` expands it
+    #[arg(long)]
+    pub mini: bool,
+
+    /// Render below the prompt instead of on the alternate screen, as it
+    /// does on its own when TERM is dumb
+    #[arg(long)]
+    pub inline: bool,
+
+    /// Open as the quick-capture window: straight into the Error field,
+    /// closing once the entry is logged
+    #[arg(long)]
+    pub capture: bool,
+
+    /// Select this page or database (id or title) once the page list loads
+    #[arg(long, short, value_name = "PAGE")]
+    pub page: Option<String>,
+
+    /// Log to the page or database behind a Notion link, opened directly
+    /// without loading the page list (`r` loads it later)
+    #[arg(long, value_name = "URL", conflicts_with = "page")]
+    pub target: Option<String>,
+
+    /// Fill a field before the TUI opens, e.g. `--field error="ECONNRESET"`;
+    /// repeat for more fields
+    #[arg(long = "field", value_name = "NAME=VALUE", value_parser = parse_field)]
+    pub fields: Vec<(InputField, String)>,
+
+    /// Where the TUI logs entries, overriding `[sink] kind`
+    #[arg(long, value_enum, value_name = "SINK")]
+    pub sink: Option<SinkKind>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Inspect the local audit log of writes to Notion
+    Audit {
+        #[command(subcommand)]
+        action: AuditCommand,
+    },
+    /// Create, read, change or check the config file
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+    /// Archive config, queue, history and audit log into a .tar.gz
+    Backup {
+        /// Archive to write (default: faultnote-backup-<timestamp>.tar.gz)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Check the local queue, history, audit log and other files; repair
+    /// interrupted writes and move unreadable records to `quarantine/`
+    Fsck {
+        /// Only report problems, changing nothing
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Log every entry of a JSON Lines file, one object per line with the
+    /// fields of `submit` (`error`, `problem`, `solution`, optional `page`,
+    /// `code`, `language`, `verification`, `context`)
+    Import {
+        file: PathBuf,
+        /// Page or database for lines without their own `page`
+        #[arg(long, short)]
+        page: Option<String>,
+        /// Continue an interrupted import, skipping entries already sent
+        #[arg(long)]
+        resume: bool,
+        /// Also skip entries already on the target page, not only those in
+        /// the local history
+        #[arg(long)]
+        check_page: bool,
+        /// Send entries even when they look like duplicates
+        #[arg(long)]
+        force: bool,
+    },
+    /// Delete old entries from the local history, following `[history]` in
+    /// the config unless overridden. Runs on every start as well.
+    Prune {
+        /// Drop entries older than this many days (0 keeps them forever)
+        #[arg(long, value_name = "DAYS")]
+        max_age_days: Option<u32>,
+        /// Keep only this many of the newest entries (0 keeps all)
+        #[arg(long, value_name = "COUNT")]
+        max_entries: Option<usize>,
+        /// Only report how many entries would be deleted
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Log a clearly marked test entry to a page and read it back, to
+    /// check that everything works before logging real entries
+    Sample {
+        /// Page or database id (dashes optional) or title
+        #[arg(long, short)]
+        page: String,
+        /// Delete the test entry again once it has been read back
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Log an entry without opening the TUI
+    Submit(SubmitArgs),
+    /// Open the quick-capture window in a new terminal, for a desktop
+    /// keyboard shortcut; the terminal is set by `[capture] terminal`
+    Popup,
+    /// Open the quick-capture window whenever a global hotkey is pressed
+    /// (Linux with X11, built with `--features hotkey`)
+    Daemon {
+        /// Hotkey such as `Ctrl+Shift+F` (default: `[capture] hotkey`, else
+        /// Ctrl+Alt+E)
+        #[arg(long)]
+        hotkey: Option<String>,
+    },
+    /// Work with submissions waiting in the offline queue
+    Queue {
+        #[command(subcommand)]
+        action: QueueCommand,
+    },
+    /// Restore local state from a backup archive
+    Restore {
+        /// Archive created by `faultnote backup`
+        archive: PathBuf,
+        /// Overwrite existing local state
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AuditCommand {
+    /// Print every recorded write as a JSON array
+    Export {
+        /// Write to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum QueueCommand {
+    /// Retry every pending submission now
+    Flush {
+        /// Print nothing per entry
+        #[arg(long, short, conflicts_with = "porcelain")]
+        quiet: bool,
+        /// Print `sent <id>` or `failed <id>` per entry on stdout, for
+        /// other tools; not with `--format json`, which also writes there
+        #[arg(long)]
+        porcelain: bool,
+    },
+}
+
+#[derive(Debug, clap::Args)]
+pub struct SubmitArgs {
+    /// Page or database id, link or title; without it a page picker opens
+    /// on the terminal
+    #[arg(long, short)]
+    pub page: Option<String>,
+    /// What went wrong, e.g. the error message
+    #[arg(long)]
+    pub error: String,
+    /// Why it happened
+    #[arg(long)]
+    pub problem: String,
+    /// How it was fixed
+    #[arg(long)]
+    pub solution: String,
+    #[arg(long)]
+    pub code: Option<String>,
+    /// Language of the code, e.g. "rust" or "py"
+    #[arg(long)]
+    pub language: Option<String>,
+    /// Steps to check the fix, one per line
+    #[arg(long)]
+    pub verification: Option<String>,
+    /// `key: value` lines describing the environment
+    #[arg(long)]
+    pub context: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Write a starter config with every setting commented out
+    Init {
+        /// Replace an existing config file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print the value of a dotted key, e.g. `template.heading.level`
+    Get { key: String },
+    /// Set a dotted key, keeping the file's comments; rejected when the
+    /// result is not a valid config
+    Set { key: String, value: String },
+    /// Print where the config file lives
+    Path,
+    /// Check the config file (or another file) for mistakes
+    Validate { file: Option<PathBuf> },
+    /// Write the shareable settings (template, entry templates, page
+    /// overrides) as a profile; tokens and key bindings are left out
+    Export {
+        /// Write to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Apply a profile exported by a teammate, replacing the sections it
+    /// holds; the previous config is kept next to it as config.toml.bak
+    Import { file: PathBuf },
+}
+
+/// What a subcommand did, for people and for scripts
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    /// Printed in text mode; `None` for commands silent on success
+    pub text: Option<String>,
+    /// Result fields printed in JSON mode
+    pub json: Value,
+}
+
+impl Report {
+    fn new(text: impl Into<String>, json: Value) -> Self {
+        Self {
+            text: Some(text.into()),
+            json,
+        }
+    }
+
+    /// What to print on stdout in `format`
+    pub fn render(&self, format: OutputFormat) -> Option<String> {
+        match format {
+            OutputFormat::Text => self.text.clone(),
+            OutputFormat::Json => {
+                let mut object = json!({ "ok": true });
+                if let (Some(object), Value::Object(fields)) = (object.as_object_mut(), &self.json) {
+                    object.extend(fields.clone());
+                }
+                Some(object.to_string())
+            }
+        }
+    }
+}
+
+/// A `--field` value: a field name, `=`, and the text to fill in
+pub fn parse_field(arg: &str) -> Result<(InputField, String), String> {
+    let (name, value) = arg.split_once('=').ok_or("expected NAME=VALUE, e.g. error=\"ECONNRESET\"")?;
+    let field = InputField::from_name(name).ok_or_else(|| {
+        let names: Vec<String> = InputField::ALL.iter().map(|field| field.default_label().to_lowercase()).collect();
+        format!("unknown field `{}`; expected one of {}", name.trim(), names.join(", "))
+    })?;
+    Ok((field, value.to_string()))
+}
+
+/// JSON printed on stdout when a subcommand fails in JSON mode
+pub fn error_json(failure: &Failure) -> String {
+    json!({
+        "ok": false,
+        "error": failure.message,
+        "class": failure.class.name(),
+        "exit_code": failure.class.exit_code(),
+    })
+    .to_string()
+}
+
+/// Run a subcommand without the TUI and print its result in `format`.
+/// Failures are also printed on stdout in JSON mode, so a pipeline always
+/// gets an object to read.
+pub async fn run(command: Command, format: OutputFormat) -> Result<(), Failure> {
+    let porcelain = matches!(
+        command,
+        Command::Queue {
+            action: QueueCommand::Flush { porcelain: true, .. }
+        }
+    );
+    let result = if porcelain && format == OutputFormat::Json {
+        Err(Failure::validation("--porcelain and --format json both write to stdout; use one"))
+    } else {
+        execute(command).await
+    };
+    match result {
+        Ok(report) => {
+            if let Some(output) = report.render(format) {
+                println!("{}", output);
+            }
+            Ok(())
+        }
+        Err(e) => {
+            if format == OutputFormat::Json {
+                println!("{}", error_json(&e));
+            }
+            Err(e)
+        }
+    }
+}
+
+async fn execute(command: Command) -> Result<Report, Failure> {
+    match command {
+        Command::Audit {
+            action: AuditCommand::Export { output },
+        } => {
+            let path = AuditLog::default_path().ok_or("No data directory available")?;
+            let log = AuditLog::new(path);
+            match output {
+                Some(path) => {
+                    let count = log.read_all()?.len();
+                    fs::write(&path, log.export_json()?)
+                        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                    Ok(Report {
+                        text: None,
+                        json: json!({ "path": path, "records": count }),
+                    })
+                }
+                None => {
+                    let records = log.read_all()?;
+                    Ok(Report::new(log.export_json()?, json!({ "records": records })))
+                }
+            }
+        }
+        Command::Config { action } => run_config(action),
+        Command::Backup { output } => {
+            let (config, data) = state_dirs()?;
+            let output = output.unwrap_or_else(|| PathBuf::from(default_backup_name()));
+            let count = create_backup(&config, &data, &output)?;
+            Ok(Report::new(
+                format!("Backed up {} files to {}", count, output.display()),
+                json!({ "archive": output, "files": count }),
+            ))
+        }
+        Command::Fsck { dry_run } => {
+            let dir = data_dir().ok_or("No data directory available")?;
+            let findings = fsck::check(&dir, !dry_run, Utc::now())?;
+            if findings.is_empty() {
+                return Ok(Report::new("All local files are intact", json!({ "findings": [] })));
+            }
+            let lines: Vec<String> = findings
+                .iter()
+                .map(|finding| format!("{}: {} ({})", finding.file, finding.problem, finding.fix))
+                .collect();
+            if dry_run {
+                return Err(Failure::validation(format!(
+                    "{} problems found; run without --dry-run to repair:\n{}",
+                    findings.len(),
+                    lines.join("\n")
+                )));
+            }
+            let json: Vec<Value> = findings
+                .iter()
+                .map(|finding| json!({ "file": finding.file, "problem": finding.problem, "fix": finding.fix }))
+                .collect();
+            Ok(Report::new(
+                format!("Repaired {} problems:\n{}", findings.len(), lines.join("\n")),
+                json!({ "findings": json }),
+            ))
+        }
+        Command::Import {
+            file,
+            page,
+            resume,
+            check_page,
+            force,
+        } => {
+            let options = ImportOptions {
+                page,
+                resume,
+                check_page,
+                force,
+            };
+            let imported = import::run(&file, &options).await?;
+            if let Some(failure) = import::failure(&imported) {
+                return Err(failure);
+            }
+            let progress = &imported.progress;
+            let (sent, queued) = (progress.count(ImportStatus::Sent), progress.count(ImportStatus::Queued));
+            let duplicates = progress.count(ImportStatus::Duplicate);
+            let mut text = format!("Imported {} entries from {}", sent, file.display());
+            if queued > 0 {
+                text.push_str(&format!(", {} kept in the offline queue", queued));
+            }
+            if duplicates > 0 {
+                text.push_str(&format!(", {} skipped as duplicates (--force sends them)", duplicates));
+            }
+            if imported.skipped > 0 {
+                text.push_str(&format!(" ({} done by an earlier run)", imported.skipped));
+            }
+            Ok(Report::new(
+                text,
+                json!({
+                    "sent": sent,
+                    "queued": queued,
+                    "duplicates": duplicates,
+                    "skipped": imported.skipped,
+                    "entries": progress.entries,
+                }),
+            ))
+        }
+        Command::Prune {
+            max_age_days,
+            max_entries,
+            dry_run,
+        } => {
+            let mut retention = submit::load_config()?.history;
+            retention.max_age_days = max_age_days.unwrap_or(retention.max_age_days);
+            retention.max_entries = max_entries.unwrap_or(retention.max_entries);
+            let path = History::default_path().ok_or("No data directory available")?;
+            let pruned = History::open(path)?.prune(&retention, Utc::now(), dry_run)?;
+            let text = if dry_run {
+                format!("Would delete {} history entries, keeping {}", pruned.removed, pruned.kept)
+            } else {
+                format!("Deleted {} history entries, kept {}", pruned.removed, pruned.kept)
+            };
+            Ok(Report::new(
+                text,
+                json!({ "removed": pruned.removed, "kept": pruned.kept, "dry_run": dry_run }),
+            ))
+        }
+        Command::Sample { page, remove } => {
+            let submitted = sample::run(&page, remove).await?;
+            let text = if remove {
+                format!("Wrote a sample entry to {}, read it back and removed it", submitted.page.title)
+            } else {
+                format!("Wrote a sample entry to {} and read it back: {}", submitted.page.title, submitted.url())
+            };
+            let mut report = submit_report(&submitted);
+            report.text = Some(text);
+            if let Some(json) = report.json.as_object_mut() {
+                json.insert("removed".to_string(), json!(remove));
+            }
+            Ok(report)
+        }
+        Command::Submit(args) => {
+            let request = SubmitRequest {
+                page: args.page,
+                error: args.error,
+                problem: args.problem,
+                solution: args.solution,
+                code: args.code,
+                language: args.language,
+                verification: args.verification,
+                context: args.context,
+            };
+            let submitted = submit::run(request).await?;
+            Ok(submit_report(&submitted))
+        }
+        Command::Popup => {
+            let config = submit::load_config()?.capture;
+            capture::open_window(&config)?;
+            Ok(Report {
+                text: None,
+                json: json!({}),
+            })
+        }
+        Command::Daemon { hotkey } => {
+            let config = submit::load_config()?.capture;
+            let hotkey = hotkey.unwrap_or_else(|| config.hotkey().to_string());
+            capture::run_daemon(&config, &hotkey)?;
+            Ok(Report {
+                text: None,
+                json: json!({ "hotkey": hotkey }),
+            })
+        }
+        Command::Queue {
+            action: QueueCommand::Flush { quiet, porcelain },
+        } => {
+            let output = match (quiet, porcelain) {
+                (_, true) => FlushOutput::Porcelain,
+                (true, false) => FlushOutput::Quiet,
+                (false, false) => FlushOutput::Progress,
+            };
+            let flushed = flush::run(output).await?;
+            if let Some(failure) = flush::failure(&flushed) {
+                return Err(failure);
+            }
+            let text = match (output, flushed.len()) {
+                (FlushOutput::Progress, 0) => Some("Nothing to retry".to_string()),
+                (FlushOutput::Progress, sent) => Some(format!("{} queued entries submitted", sent)),
+                _ => None,
+            };
+            let entries: Vec<Value> = flushed
+                .iter()
+                .map(|entry| json!({ "id": entry.id, "status": entry.status(), "page_title": entry.page_title }))
+                .collect();
+            Ok(Report {
+                text,
+                json: json!({ "sent": flushed.len(), "entries": entries }),
+            })
+        }
+        Command::Restore { archive, force } => {
+            let (config, data) = state_dirs()?;
+            let count = restore_backup(&archive, &config, &data, force)?;
+            Ok(Report::new(
+                format!("Restored {} files from {}", count, archive.display()),
+                json!({ "archive": archive, "files": count }),
+            ))
+        }
+    }
+}
+
+/// Report of `faultnote submit`: the page, created block ids and a link
+pub fn submit_report(submitted: &Submitted) -> Report {
+    let page = &submitted.page;
+    Report::new(
+        format!("Logged to {}", page.title),
+        json!({
+            "page_id": page.id,
+            "page_title": page.title,
+            "database": page.is_database(),
+            "block_ids": submitted.block_ids,
+            "url": submitted.url(),
+        }),
+    )
+}
+
+fn run_config(action: ConfigCommand) -> Result<Report, Failure> {
+    let path = config_path().ok_or("Could not determine the config directory")?;
+    let report = match action {
+        ConfigCommand::Init { force } => {
+            config_file::init(&path, force)?;
+            Report::new(format!("Wrote {}", path.display()), json!({ "path": path }))
+        }
+        ConfigCommand::Get { key } => {
+            let value = config_file::get(&config_file::read(&path)?, &key).map_err(Failure::validation)?;
+            let value = value.ok_or_else(|| format!("{} is not set", key))?;
+            Report::new(value.clone(), json!({ "key": key, "value": value }))
+        }
+        ConfigCommand::Set { key, value } => {
+            let updated = config_file::set(&config_file::read(&path)?, &key, &value).map_err(Failure::validation)?;
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            }
+            fs::write(&path, updated).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            Report {
+                text: None,
+                json: json!({ "path": path, "key": key, "value": value }),
+            }
+        }
+        ConfigCommand::Path => Report::new(path.display().to_string(), json!({ "path": path })),
+        ConfigCommand::Export { output } => {
+            let profile = config_file::export_profile(&config_file::read(&path)?).map_err(Failure::validation)?;
+            match output {
+                Some(output) => {
+                    fs::write(&output, &profile).map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+                    Report::new(format!("Wrote {}", output.display()), json!({ "path": output }))
+                }
+                None => Report::new(profile.trim_end(), json!({ "profile": profile })),
+            }
+        }
+        ConfigCommand::Import { file } => {
+            let profile = fs::read_to_string(&file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+            let current = config_file::read(&path)?;
+            let updated = config_file::import_profile(&current, &profile).map_err(Failure::validation)?;
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            }
+            if path.exists() {
+                let backup = path.with_extension("toml.bak");
+                fs::write(&backup, &current).map_err(|e| format!("Failed to write {}: {}", backup.display(), e))?;
+            }
+            fs::write(&path, updated).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            Report::new(
+                format!("Applied {} to {}", file.display(), path.display()),
+                json!({ "path": path, "profile": file }),
+            )
+        }
+        ConfigCommand::Validate { file } => {
+            let path = file.unwrap_or(path);
+            config_file::validate(&config_file::read(&path)?).map_err(Failure::validation)?;
+            Report::new(format!("{} is valid", path.display()), json!({ "path": path, "valid": true }))
+        }
+    };
+    Ok(report)
+}
+
+/// The config and data directories, which together hold all local state
+fn state_dirs() -> Result<(PathBuf, PathBuf), String> {
+    match (config_dir(), data_dir()) {
+        (Some(config), Some(data)) => Ok((config, data)),
+        _ => Err("Could not determine the config/data directories".to_string()),
+    }
+}
@@ -0,0 +1,38 @@
+// System clipboard access for pasting into input fields and copying
+// content out of them
+use std::sync::Mutex;
+
+use arboard::Clipboard;
+
+/// Clipboard kept open for the whole run: on X11 and Wayland copied text
+/// is served by the process that copied it, and is lost when the handle
+/// closes
+static CLIPBOARD: Mutex<Option<Clipboard>> = Mutex::new(None);
+
+/// Text on the system clipboard, with line endings normalized
+pub fn read_text() -> Result<String, String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| format!("Clipboard unavailable: {}", e))?;
+    let text = clipboard
+        .get_text()
+        .map_err(|e| format!("Clipboard has no text: {}", e))?;
+    Ok(normalize_newlines(&text))
+}
+
+/// Put `text` on the system clipboard
+pub fn write_text(text: &str) -> Result<(), String> {
+    let mut clipboard = CLIPBOARD.lock().map_err(|_| "Clipboard unavailable".to_string())?;
+    if clipboard.is_none() {
+        *clipboard = Some(Clipboard::new().map_err(|e| format!("Clipboard unavailable: {}", e))?);
+    }
+    clipboard
+        .as_mut()
+        .expect("clipboard was just opened")
+        .set_text(text)
+        .map_err(|e| format!("Failed to copy: {}", e))
+}
+
+/// Windows and old Mac line endings become `\n`, so pasted stack traces
+/// keep their lines in the editor
+pub fn normalize_newlines(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
@@ -0,0 +1,229 @@
+// Configuration loaded from ~/.config/faultnote/config.toml
+use std::{
+    collections::{BTreeMap, HashMap},
+    env,
+    path::PathBuf,
+};
+
+use serde::Deserialize;
+
+use crate::capture::CaptureConfig;
+use crate::config_file;
+use crate::entry_templates::{self, EntryTemplate};
+use crate::export::ExportConfig;
+use crate::extractors::Extractor;
+use crate::fuzzy;
+use crate::keys::KeyBindings;
+use crate::notify::NotifyConfig;
+use crate::notion::locale::Locale;
+use crate::notion::template::{AppendPosition, BlockTemplate, PropertySource};
+use crate::sink::SinkConfig;
+use crate::storage::Retention;
+use crate::ui::Theme;
+
+/// User configuration. Every section is optional and falls back to
+/// defaults; unknown keys are errors rather than silently ignored.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// How entries are laid out as Notion blocks
+    pub template: BlockTemplate,
+    /// Extra integration tokens tried after `API_KEY`, in order
+    pub tokens: Vec<TokenConfig>,
+    /// Overrides for individual pages, keyed by page id
+    pub pages: HashMap<String, PageConfig>,
+    /// Keys for main-screen actions; unlisted actions keep their defaults
+    pub keys: KeyBindings,
+    /// Value of `{project}` in templates; defaults to the git repository
+    /// or directory FaultNote was started in
+    pub project: Option<String>,
+    /// Page or database (id or title) selected once the page list loads,
+    /// unless `--page` names another
+    pub default_page: Option<String>,
+    /// Colors of the TUI
+    pub theme: Theme,
+    /// Pre-filled entries offered by the template picker, by name
+    pub entry_templates: BTreeMap<String, EntryTemplate>,
+    /// How long the local history keeps entries
+    pub history: Retention,
+    /// Markdown files written for each entry
+    pub export: ExportConfig,
+    /// Where the TUI logs entries: Notion (default), GitHub Issues or an
+    /// Obsidian vault
+    pub sink: SinkConfig,
+    /// Patterns that pull fields, tags and context out of pasted or piped
+    /// text
+    pub extractors: Vec<Extractor>,
+    /// Webhook told about every logged entry
+    pub notify: NotifyConfig,
+    /// Terminal and hotkey of the quick-capture window
+    pub capture: CaptureConfig,
+    /// Problems that did not stop the config from loading, such as a
+    /// `FAULTNOTE_*` variable naming no key
+    #[serde(skip)]
+    pub warnings: Vec<String>,
+}
+
+/// Settings that differ for one target page or database
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PageConfig {
+    pub position: Option<AppendPosition>,
+    pub after: Option<String>,
+    pub anchor: Option<String>,
+    /// Row properties for a database, added to `[template.properties]`
+    pub properties: BTreeMap<String, PropertySource>,
+    pub cover: Option<String>,
+    pub locale: Option<Locale>,
+}
+
+/// A named integration token, given inline or via an environment variable
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenConfig {
+    pub name: String,
+    pub key: Option<String>,
+    pub env: Option<String>,
+}
+
+impl TokenConfig {
+    /// The secret itself; an inline `key` wins over `env`
+    pub fn resolve(&self) -> Option<String> {
+        self.key
+            .clone()
+            .or_else(|| self.env.as_ref().and_then(|var| env::var(var).ok()))
+    }
+}
+
+impl Config {
+    /// Parse a config from TOML text
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|e| format!("Invalid config: {}", describe_error(text, &e)))
+    }
+
+    /// The block template for a page, with that page's overrides applied.
+    /// Page ids match with or without dashes.
+    pub fn template_for(&self, page_id: &str) -> BlockTemplate {
+        let mut template = self.template.clone();
+        let page = self
+            .pages
+            .iter()
+            .find(|(id, _)| same_page_id(id, page_id))
+            .map(|(_, page)| page);
+        if let Some(page) = page {
+            if let Some(position) = page.position {
+                template.position = position;
+            }
+            if page.after.is_some() {
+                template.after = page.after.clone();
+            }
+            if page.anchor.is_some() {
+                template.anchor = page.anchor.clone();
+            }
+            template.properties.extend(page.properties.clone());
+            if page.cover.is_some() {
+                template.cover = page.cover.clone();
+            }
+            if let Some(locale) = page.locale {
+                template.locale = locale;
+            }
+        }
+        template
+    }
+
+    /// Built-in entry templates with the configured ones added or replacing
+    /// them by name
+    pub fn all_entry_templates(&self) -> BTreeMap<String, EntryTemplate> {
+        let mut templates = entry_templates::builtin();
+        templates.extend(self.entry_templates.clone());
+        templates
+    }
+
+    /// Load the config file with `FAULTNOTE_*` environment overrides on
+    /// top, using defaults for whatever neither sets
+    pub fn load() -> Result<Self, String> {
+        let text = match config_path() {
+            Some(path) => config_file::read(&path)?,
+            None => String::new(),
+        };
+        // Variables that are not valid UTF-8 cannot be config values
+        let vars = env::vars_os().filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)));
+        let (text, warnings) = config_file::apply_env(&text, vars)?;
+        let mut config = Self::from_toml(&text)?;
+        config.warnings = warnings;
+        Ok(config)
+    }
+}
+
+/// A parse error as a readable message. Unknown keys and values say where
+/// they are and suggest the closest valid spelling, e.g. "unknown key
+/// 'colour' at template.heading (line 4) — did you mean 'color'?"
+fn describe_error(text: &str, err: &toml::de::Error) -> String {
+    let message = err.message();
+    let unknown = message
+        .strip_prefix("unknown field `")
+        .or_else(|| message.strip_prefix("unknown variant `"))
+        .and_then(|rest| rest.split_once('`'));
+    let (Some((name, expected)), Some(span)) = (unknown, err.span()) else {
+        return err.to_string().trim_end().to_string();
+    };
+    // Backquoted names after "expected", e.g. "expected `color` or `level`"
+    let candidates: Vec<&str> = expected.split('`').skip(1).step_by(2).collect();
+
+    let before = &text[..span.start.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    let line = before.matches('\n').count() + 1;
+    let table = before[..line_start]
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| line.starts_with('['))
+        .map(|header| header.trim_matches(['[', ']']).trim());
+    // What precedes the error on its line: a dotted key prefix such as
+    // "heading." for an unknown key, or "position = " for an unknown value
+    let prefix = before[line_start..].trim();
+    let is_value = prefix.contains('=');
+    let key = prefix.split('=').next().unwrap_or_default().trim().trim_end_matches('.');
+    let path: Vec<&str> = table.into_iter().chain(Some(key)).filter(|part| !part.is_empty()).collect();
+    let path = path.join(".");
+
+    let mut described = match (is_value, path.is_empty()) {
+        (true, _) => format!("unknown value '{}' for {} (line {})", name, path, line),
+        (false, true) => format!("unknown key '{}' at the top level (line {})", name, line),
+        (false, false) => format!("unknown key '{}' at {} (line {})", name, path, line),
+    };
+    match fuzzy::closest(name, candidates.iter().copied()) {
+        Some(suggestion) => described.push_str(&format!(" — did you mean '{}'?", suggestion)),
+        None if !candidates.is_empty() => described.push_str(&format!("; expected one of {}", candidates.join(", "))),
+        None => {}
+    }
+    described
+}
+
+/// Directory holding FaultNote's configuration files
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("faultnote"))
+}
+
+/// Directory holding FaultNote's local data (queue, history)
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("faultnote"))
+}
+
+/// Location of the main config file
+pub fn config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// A configured path; a leading `~/` is the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Compare Notion ids ignoring dashes, since URLs omit them
+pub fn same_page_id(a: &str, b: &str) -> bool {
+    a.chars().filter(|c| *c != '-').eq(b.chars().filter(|c| *c != '-'))
+}
@@ -0,0 +1,247 @@
+// `faultnote config`: reading and editing the config file from the shell.
+// Edits keep the file's comments and layout, and are checked against the
+// same `Config` model the TUI loads before anything is written.
+use std::fs;
+use std::path::Path;
+
+use toml_edit::{DocumentMut, Item, Value};
+
+use crate::config::Config;
+
+/// Starter file written by `faultnote config init`; every setting is
+/// commented out, so it behaves exactly like having no config
+pub const DEFAULT_CONFIG: &str = r###"# FaultNote configuration. Uncomment a setting to change it; unknown keys
+# are reported on startup and by `faultnote config validate`.
+
+# Value of {project} in templates; defaults to the git repository or directory name
+# project = "payments-api"
+
+# Page or database (id or title) selected on startup unless --page names another
+# default_page = "Backend errors"
+
+# theme = "default"               # or "mono" for terminals without color
+
+[template]
+# title = "{error}"
+# metadata = "Logged {date} {time} on {hostname} ({project})"
+# style = "emoji"                 # or "plain"
+# locale = "en"                   # language of headings and dates written to Notion
+# divider_between_entries = false
+# blank_line_between_entries = false
+# position = "bottom"             # or "top"
+# anchor = "## Fault Log"
+# cover = "red"
+
+# [template.heading]
+# level = "h2"
+# toggleable = true
+# color = "red"
+
+# [template.labels]
+# error = "Symptom"
+
+# [template.properties]
+# Team = { value = "Platform" }
+
+# [keys]
+# submit = "Ctrl+s"
+
+# [history]
+# max_age_days = 365              # 0 keeps entries forever (default)
+# max_entries = 10000             # 0 keeps all (default)
+"###;
+
+/// Write the starter config to `path`, creating its directory. An existing
+/// file is only replaced with `force`.
+pub fn init(path: &Path, force: bool) -> Result<(), String> {
+    if path.exists() && !force {
+        return Err(format!("{} already exists (use --force to replace it)", path.display()));
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    fs::write(path, DEFAULT_CONFIG).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Text of the config file at `path`; empty when there is none yet
+pub fn read(path: &Path) -> Result<String, String> {
+    if !path.exists() {
+        return Ok(String::new());
+    }
+    fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+/// Check config text against the config model
+pub fn validate(text: &str) -> Result<(), String> {
+    Config::from_toml(text).map(|_| ())
+}
+
+/// The value at a dotted key such as `template.heading.level` or
+/// `pages."1a2b".position`: strings as themselves, anything else as TOML.
+/// `None` when the key is not set.
+pub fn get(text: &str, key: &str) -> Result<Option<String>, String> {
+    let document = parse(text)?;
+    let mut item = document.as_item();
+    for part in split_key(key)? {
+        match item.get(&part) {
+            Some(next) => item = next,
+            None => return Ok(None),
+        }
+    }
+    let shown = match item {
+        Item::Value(Value::String(text)) => text.value().clone(),
+        Item::Value(value) => value.clone().decorated("", "").to_string(),
+        other => other.to_string().trim().to_string(),
+    };
+    Ok(Some(shown))
+}
+
+/// Config text with the dotted `key` set to `value`, creating tables on the
+/// way. The value is read as TOML (`true`, `3`, `["a", "b"]`) and otherwise
+/// taken as a string. Fails without changes when the result is not a valid
+/// config.
+pub fn set(text: &str, key: &str, value: &str) -> Result<String, String> {
+    let parsed = value.trim().parse::<Value>().ok();
+    let attempt = |value: Value| -> Result<String, String> {
+        let mut document = parse(text)?;
+        set_item(&mut document, key, value)?;
+        let updated = document.to_string();
+        validate(&updated)?;
+        Ok(updated)
+    };
+    match parsed {
+        // `project = 2024` should stay a string rather than fail
+        Some(parsed) if !parsed.is_str() => attempt(parsed).or_else(|e| attempt(Value::from(value)).map_err(|_| e)),
+        _ => attempt(Value::from(value)),
+    }
+}
+
+/// Config sections a settings profile carries: how entries are laid out
+/// and labelled, entry templates, per-page overrides, the sink and the
+/// webhook (which name the token's and URL's variables, never their
+/// values). Tokens, key bindings and history limits stay personal.
+pub const PROFILE_SECTIONS: &[&str] = &["template", "entry_templates", "pages", "sink", "extractors", "notify"];
+
+/// First line of an exported profile
+const PROFILE_HEADER: &str = "# FaultNote settings profile; apply with `faultnote config import FILE`\n";
+
+/// The shareable sections of config text, comments kept, as a profile
+pub fn export_profile(text: &str) -> Result<String, String> {
+    let mut document = parse(text)?;
+    let personal: Vec<String> = document
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !PROFILE_SECTIONS.contains(&key.as_str()))
+        .collect();
+    for key in personal {
+        document.remove(&key);
+    }
+    if document.is_empty() {
+        return Err(format!("Nothing to export: none of {} is set", PROFILE_SECTIONS.join(", ")));
+    }
+    document.decor_mut().clear();
+    let profile = format!("{}\n{}", PROFILE_HEADER, document.to_string().trim_start());
+    validate(&profile)?;
+    Ok(profile)
+}
+
+/// Config text with the sections of `profile` replacing the same sections,
+/// leaving the rest of the config alone. Profiles holding anything
+/// personal, such as tokens, are refused.
+pub fn import_profile(text: &str, profile: &str) -> Result<String, String> {
+    validate(profile).map_err(|e| format!("Invalid profile: {}", e.trim_start_matches("Invalid config: ")))?;
+    // The header explains the file; it has no place in the config
+    let profile = parse(profile.strip_prefix(PROFILE_HEADER).unwrap_or(profile).trim_start())?;
+    if let Some((key, _)) = profile.iter().find(|(key, _)| !PROFILE_SECTIONS.contains(key)) {
+        return Err(format!(
+            "Profiles may only hold {}; remove `{}` from it",
+            PROFILE_SECTIONS.join(", "),
+            key
+        ));
+    }
+    let mut document = parse(text)?;
+    for (key, item) in profile.iter() {
+        document.insert(key, item.clone());
+    }
+    let updated = document.to_string();
+    validate(&updated)?;
+    Ok(updated)
+}
+
+/// Prefix of environment variables that override config keys
+pub const ENV_PREFIX: &str = "FAULTNOTE_";
+
+/// Config text with `FAULTNOTE_*` variables layered on top. The rest of a
+/// name is the key, lowercased, with `__` between tables:
+/// `FAULTNOTE_PROJECT`, `FAULTNOTE_TEMPLATE__STYLE`,
+/// `FAULTNOTE_TEMPLATE__HEADING__LEVEL`. Values are read as in `set`.
+/// Variables naming no config key are skipped and returned as warnings,
+/// since the environment may hold them for another version.
+pub fn apply_env(
+    text: &str,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Result<(String, Vec<String>), String> {
+    let mut overrides: Vec<(String, String, String)> = vars
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let key = name.strip_prefix(ENV_PREFIX)?.to_lowercase().replace("__", ".");
+            (!key.is_empty()).then_some((key, name, value))
+        })
+        .collect();
+    overrides.sort();
+
+    let mut text = text.to_string();
+    let mut warnings = Vec::new();
+    for (key, name, value) in overrides {
+        match set(&text, &key, &value) {
+            Ok(updated) => text = updated,
+            Err(e) if e.contains("unknown key '") => warnings.push(format!("Ignored {}: {}", name, e)),
+            Err(e) => return Err(format!("{}: {}", name, e)),
+        }
+    }
+    Ok((text, warnings))
+}
+
+fn set_item(document: &mut DocumentMut, key: &str, value: Value) -> Result<(), String> {
+    let parts = split_key(key)?;
+    let (last, tables) = parts.split_last().ok_or("Empty key")?;
+    let mut item = document.as_item_mut();
+    for (depth, part) in tables.iter().enumerate() {
+        let table = item
+            .as_table_like_mut()
+            .ok_or_else(|| format!("{} is not a table", parts[..depth].join(".")))?;
+        item = table.entry(part).or_insert_with(|| {
+            let mut table = toml_edit::Table::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        });
+    }
+    let table = item
+        .as_table_like_mut()
+        .ok_or_else(|| format!("{} is not a table", tables.join(".")))?;
+    table.insert(last, Item::Value(value));
+    Ok(())
+}
+
+fn parse(text: &str) -> Result<DocumentMut, String> {
+    text.parse::<DocumentMut>().map_err(|e| format!("Invalid config: {}", e))
+}
+
+/// Parts of a dotted key; parts may be quoted to contain dots
+pub fn split_key(key: &str) -> Result<Vec<String>, String> {
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    let mut quoted = false;
+    for ch in key.trim().chars() {
+        match ch {
+            '"' => quoted = !quoted,
+            '.' if !quoted => parts.push(std::mem::take(&mut part)),
+            _ => part.push(ch),
+        }
+    }
+    parts.push(part);
+    if quoted || parts.iter().any(|part| part.trim().is_empty()) {
+        return Err(format!("Invalid key \"{}\"", key));
+    }
+    Ok(parts.into_iter().map(|part| part.trim().to_string()).collect())
+}
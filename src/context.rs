@@ -0,0 +1,86 @@
+// Git state of the directory FaultNote runs in, attached to entries so a
+// fix can be traced back to the code it was made against
+use std::path::Path;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+/// Branch, commit and working-tree state of a git checkout
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitContext {
+    /// `None` on a detached HEAD
+    pub branch: Option<String>,
+    /// Abbreviated commit; `None` before the first commit
+    pub sha: Option<String>,
+    /// Uncommitted or untracked changes
+    pub dirty: bool,
+}
+
+/// Length of the abbreviated commit shown and sent to Notion
+const SHORT_SHA: usize = 7;
+
+impl GitContext {
+    /// Git state of `dir`; `None` outside a repository or without git
+    pub fn detect(dir: &Path) -> Option<Self> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["status", "--porcelain=v2", "--branch"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Self::parse(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Git state of the working directory
+    pub fn current() -> Option<Self> {
+        Self::detect(&std::env::current_dir().ok()?)
+    }
+
+    /// Read the output of `git status --porcelain=v2 --branch`
+    pub fn parse(status: &str) -> Option<Self> {
+        let mut context = None::<Self>;
+        let mut dirty = false;
+        for line in status.lines() {
+            if let Some(oid) = line.strip_prefix("# branch.oid ") {
+                let sha = (oid != "(initial)").then(|| oid.chars().take(SHORT_SHA).collect());
+                context.get_or_insert_with(Self::default).sha = sha;
+            } else if let Some(head) = line.strip_prefix("# branch.head ") {
+                let branch = (head != "(detached)").then(|| head.to_string());
+                context.get_or_insert_with(Self::default).branch = branch;
+            } else if !line.starts_with('#') && !line.is_empty() {
+                dirty = true;
+            }
+        }
+        context.map(|context| Self { dirty, ..context })
+    }
+
+    /// Short form for the title bar, e.g. "main@a1b2c3d*"
+    pub fn summary(&self) -> String {
+        let branch = self.branch.as_deref().unwrap_or("detached");
+        let mut summary = match &self.sha {
+            Some(sha) => format!("{}@{}", branch, sha),
+            None => branch.to_string(),
+        };
+        if self.dirty {
+            summary.push('*');
+        }
+        summary
+    }
+
+    /// Sentence closing an entry's Context section
+    pub fn describe(&self) -> String {
+        let branch = match &self.branch {
+            Some(branch) => format!("branch {}", branch),
+            None => "detached HEAD".to_string(),
+        };
+        let commit = match &self.sha {
+            Some(sha) => format!(" at {}", sha),
+            None => " with no commits".to_string(),
+        };
+        let changes = if self.dirty { ", with uncommitted changes" } else { "" };
+        format!("Git: {}{}{}", branch, commit, changes)
+    }
+}
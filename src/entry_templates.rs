@@ -0,0 +1,100 @@
+// Entry templates: pre-filled skeletons for faults logged again and again,
+// with `{{placeholders}}` to fill in
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::fields::InputField;
+
+/// Field skeletons from `[entry_templates.<name>]`; unset fields start empty
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EntryTemplate {
+    pub error: Option<String>,
+    pub problem: Option<String>,
+    pub solution: Option<String>,
+    pub code: Option<String>,
+    pub verification: Option<String>,
+    pub context: Option<String>,
+    /// Comma separated tags
+    pub tags: Option<String>,
+    /// Code block language, e.g. "rust"
+    pub language: Option<String>,
+}
+
+impl EntryTemplate {
+    /// Skeleton text of a field
+    pub fn field(&self, field: InputField) -> &str {
+        let text = match field {
+            InputField::Error => &self.error,
+            InputField::Problem => &self.problem,
+            InputField::Solution => &self.solution,
+            InputField::Code => &self.code,
+            InputField::Verification => &self.verification,
+            InputField::Context => &self.context,
+            InputField::Tags => &self.tags,
+        };
+        text.as_deref().unwrap_or_default()
+    }
+
+    /// First line of the Error skeleton, shown in the picker
+    pub fn summary(&self) -> &str {
+        self.field(InputField::Error).lines().next().unwrap_or_default()
+    }
+}
+
+/// Templates available without any config; ones defined in the config
+/// with the same name replace them
+pub fn builtin() -> BTreeMap<String, EntryTemplate> {
+    let text = |text: &str| Some(text.to_string());
+    let templates = [
+        (
+            "panic",
+            EntryTemplate {
+                error: text("thread '{{thread}}' panicked at {{file:line}}:\n{{message}}"),
+                problem: text("{{what was unwrapped or indexed}} was {{unexpected value}} because {{cause}}"),
+                solution: text("{{fix}}"),
+                verification: text("Run {{test or command}} and confirm it no longer panics"),
+                ..Default::default()
+            },
+        ),
+        (
+            "ci-failure",
+            EntryTemplate {
+                error: text("CI job {{job}} failed on {{branch}}"),
+                problem: text("Step {{failing step}} failed: {{cause}}"),
+                solution: text("{{fix}}"),
+                verification: text("Re-run {{job}} and confirm it passes"),
+                context: text("pipeline: {{pipeline url}}\ncommit: {{sha}}"),
+                ..Default::default()
+            },
+        ),
+        (
+            "prod-incident",
+            EntryTemplate {
+                error: text("{{service}}: {{symptom}} in production"),
+                problem: text("Impact: {{who or what was affected}}\nRoot cause: {{cause}}"),
+                solution: text("Mitigation: {{mitigation}}\nFollow-up: {{follow-up}}"),
+                verification: text("{{dashboard or check}} shows recovery"),
+                context: text("started: {{time}}\nseverity: {{severity}}"),
+                ..Default::default()
+            },
+        ),
+    ];
+    templates.into_iter().map(|(name, template)| (name.to_string(), template)).collect()
+}
+
+/// Char range of the first `{{placeholder}}` starting at or after char
+/// `from`, braces included
+pub fn find_placeholder(text: &str, from: usize) -> Option<(usize, usize)> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut start = from;
+    while start + 1 < chars.len() {
+        if chars[start] == '{' && chars[start + 1] == '{' {
+            let close = (start + 2..chars.len().saturating_sub(1)).find(|&i| chars[i] == '}' && chars[i + 1] == '}')?;
+            return Some((start, close + 2));
+        }
+        start += 1;
+    }
+    None
+}
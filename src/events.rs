@@ -1,32 +1,149 @@
+use std::collections::VecDeque;
+use std::fs;
 use std::io;
-use std::time::Duration;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
 
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use tokio::sync::{Semaphore, mpsc};
+use tokio::task::JoinHandle;
+use tracing::instrument;
 
-use crate::app::AppState;
-use crate::notion::client::{NotionClient, create_entry, FaultLogEntry};
+use crate::app::{
+    AppState, FaultLogEntry, InFlightSubmission, LastSubmission, PageInfo, PendingAction, RowPick, Screen, Severity,
+    SubmissionTask, TargetKind,
+};
+use crate::app_event::AppEvent;
+use crate::attachments::{self, AttachSource};
+use crate::audit::{AuditAction, AuditRecord};
+use crate::buffer::pop_grapheme;
+use crate::clipboard;
+use crate::context::GitContext;
+use crate::export;
+use crate::keys::Action;
+use crate::links;
+use crate::markdown;
+use crate::markup::validate_mermaid;
+use crate::notion::client::{
+    AppendedBlocks, Attachment, NotionClient, NotionError, create_entry_with_fallback, create_error_block, create_page,
+    delete_block, sha256_hex, upload_file, upload_text_file,
+};
+use crate::notion::database::{fetch_database, query_rows, relation_properties, update_row_status};
+use crate::notion::entries::fetch_entries;
+use crate::notion::preview::{fetch_page_content, preview_lines};
+use crate::notion::limits::{OVERFLOW_FILENAME, PayloadReport, split_overflow};
+use crate::notion::rate_limit::{BatchProgress, MAX_IN_FLIGHT, RateLimiter};
+use crate::notion::template::BlockTemplate;
+use crate::notify::{self, Notice};
+use crate::page_loader::PageLoad;
+use crate::queue::{QueueStatus, QueuedSubmission, status_for_error, unix_now};
+use crate::sink::{FaultSink, Receipt, Sink};
+use crate::storage::EntryStatus;
+use crate::submit;
 
-/// Handle all input events for the application
-/// Returns Ok(()) on success, Err on event reading failure
-pub async fn handle_events(app: &mut AppState, notion_client: Option<&NotionClient>) -> io::Result<()> {
-    // Poll for events with a small timeout (100ms)
-    // This allows the UI to remain responsive
-    if event::poll(Duration::from_millis(100))? {
-        // Read the event
-        if let Event::Key(key_event) = event::read()? {
-            // Only handle key press events (not release)
-            if key_event.kind == KeyEventKind::Press {
-                handle_key_event(app, key_event, notion_client).await;
+/// Apply one event to the app state; the main loop redraws after each.
+/// Returns Err only when reading the terminal failed.
+pub async fn handle_event(app: &mut AppState, event: AppEvent, notion_client: Option<&NotionClient>) -> io::Result<()> {
+    match event {
+        AppEvent::Key(key_event) => handle_key_event(app, key_event, notion_client).await,
+        AppEvent::Paste(text) => handle_paste(app, &text),
+        AppEvent::Mouse(mouse_event) => handle_mouse_event(app, mouse_event),
+        AppEvent::Resize => app.handle_resize(),
+        AppEvent::PagesLoaded(load) => apply_page_load(app, load, notion_client).await,
+        AppEvent::SubmissionFinished => complete_submission(app).await,
+        AppEvent::RetryFinished => complete_retry(app).await,
+        // Flush the queue as soon as connectivity comes back
+        AppEvent::Health(health) => {
+            if app.record_health(health) {
+                if health.reachable {
+                    flush_queue(app, notion_client).await;
+                } else {
+                    app.set_error("Network unavailable. Submissions will be queued");
+                }
             }
         }
+        // Say why a request is taking longer: it is waiting to retry
+        AppEvent::Retrying(notice) => app.set_loading(format!("⏳ {}", notice.message())),
+        AppEvent::ApiCall(call) => app.log_api_call(&call),
+        AppEvent::InputFailed(e) => return Err(e),
+        // Let old messages fade and retry queued submissions whose
+        // backoff has elapsed
+        AppEvent::Tick => {
+            app.expire_status(Instant::now());
+            retry_due(app, notion_client).await
+        }
     }
-
     Ok(())
 }
 
+/// Rows scrolled per notch of the mouse wheel
+const WHEEL_ROWS: isize = 3;
+
+/// Clicks focus the page list or a field and the wheel scrolls them; only
+/// on the main screen with no picker open
+pub fn handle_mouse_event(app: &mut AppState, mouse: MouseEvent) {
+    if app.screen != Screen::Main || app.has_modal() {
+        return;
+    }
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => app.click(mouse.column, mouse.row),
+        MouseEventKind::ScrollUp => app.scroll_at(mouse.column, mouse.row, -WHEEL_ROWS),
+        MouseEventKind::ScrollDown => app.scroll_at(mouse.column, mouse.row, WHEEL_ROWS),
+        _ => {}
+    }
+}
+
 /// Handle a specific key event based on current app mode
-async fn handle_key_event(app: &mut AppState, key: KeyEvent, notion_client: Option<&NotionClient>) {
-    if app.is_editing() {
+pub async fn handle_key_event(app: &mut AppState, key: KeyEvent, notion_client: Option<&NotionClient>) {
+    // Raw mode delivers Ctrl+Z as a key; the main loop suspends the process.
+    // While editing it undoes instead.
+    let undo_key = app.is_editing() && app.config.keys.editing_action(&key) == Some(Action::UndoEdit);
+    if key.code == KeyCode::Char('z') && key.modifiers.contains(KeyModifiers::CONTROL) && !undo_key {
+        app.suspend_requested = true;
+    } else if key.code == KeyCode::F(12) {
+        // Works on every screen; the main loop saves the next frame
+        app.screenshot_requested = true;
+    } else if app.pending_action.is_some() {
+        // Confirmation dialog - answering may submit
+        handle_confirmation_mode(app, key, notion_client).await;
+    } else if app.is_queue_screen() {
+        // Queue inspector - retrying needs async
+        handle_queue_mode(app, key, notion_client).await;
+    } else if app.is_history_screen() {
+        // History browser - local only
+        handle_history_mode(app, key, notion_client).await;
+    } else if app.is_entries_screen() {
+        // Entry reader - reloading needs async
+        handle_entries_mode(app, key, notion_client).await;
+    } else if app.is_preview_screen() {
+        // Page preview - reloading needs async
+        handle_preview_mode(app, key, notion_client).await;
+    } else if app.is_rows_screen() {
+        // Database row picker - querying needs async
+        handle_rows_mode(app, key, notion_client).await;
+    } else if app.is_relations_screen() {
+        // Relation properties - picking a page queries its database
+        handle_relations_mode(app, key, notion_client).await;
+    } else if app.is_page_settings_screen() {
+        // Page settings - saved locally
+        handle_page_settings_mode(app, key);
+    } else if app.new_page.is_some() {
+        // New page prompt - creating the page needs async
+        handle_new_page_mode(app, key, notion_client).await;
+    } else if app.attach_input.is_some() {
+        // Attach prompt - typing a path or link
+        handle_attach_prompt_mode(app, key);
+    } else if app.language_picking {
+        // Language picker - typing narrows the list
+        handle_language_picker_mode(app, key);
+    } else if app.template_picking {
+        // Entry template picker - typing narrows the list
+        handle_template_picker_mode(app, key);
+    } else if app.page_filtering {
+        // Page filter - typing narrows the page list
+        handle_page_filter_mode(app, key);
+    } else if app.is_editing() {
         // Editing mode - no async needed
         handle_editing_mode(app, key);
     } else {
@@ -37,52 +154,226 @@ async fn handle_key_event(app: &mut AppState, key: KeyEvent, notion_client: Opti
 
 /// Handle key events in normal (navigation) mode
 async fn handle_normal_mode(app: &mut AppState, key: KeyEvent, notion_client: Option<&NotionClient>) {
-    match key.code {
+    let Some(action) = app.config.keys.normal_action(&key) else {
+        return;
+    };
+    match action {
         // Application Control
-        KeyCode::Char('q') | KeyCode::Char('Q') => {
-            app.quit();
-        }
+        Action::Quit => app.quit(),
 
         // Focus Navigation
-        KeyCode::Tab => {
-            app.toggle_focus();
-        }
+        Action::ToggleFocus => app.toggle_focus(),
 
         // Up/Down Navigation
-        KeyCode::Up | KeyCode::Char('k') => {
-            app.handle_up();
+        Action::Up => app.handle_up(),
+        Action::Down => app.handle_down(),
+
+        // Fold and unfold subpages in the page tree
+        Action::Left => app.handle_left(),
+        Action::Right => app.handle_right(),
+
+        // Jump through long page lists, or scroll the focused field
+        Action::PageUp => app.handle_page_up(),
+        Action::PageDown => app.handle_page_down(),
+        Action::First => app.select_first_page(),
+        Action::Last => app.select_last_page(),
+
+        // Narrow the page list by typing part of a title
+        Action::FilterPages => app.start_page_filter(),
+
+        // Check the selected page to also receive the next entry
+        Action::CheckPage => app.toggle_page_check(),
+
+        // Enter Edit Mode
+        Action::EditMode => app.enter_edit_mode(),
+
+        // Submit to Notion after confirming; entries that cannot be sent
+        // yet, or whose overflow warning was just shown, skip the dialog
+        Action::Submit => {
+            if app.can_submit() && !app.overflow_confirmed && app.submission.is_none() {
+                app.request_confirmation(PendingAction::Submit);
+            } else {
+                submit_to_notion(app, notion_client).await;
+            }
         }
-        KeyCode::Down | KeyCode::Char('j') => {
-            app.handle_down();
+
+        // Toggle Mermaid diagram mode for the Code field
+        Action::ToggleMermaid => {
+            app.toggle_mermaid();
+            app.set_status(format!("Code language: {}", app.code_language));
         }
 
-        // Enter Edit Mode
-        KeyCode::Char('e') | KeyCode::Char('i') => {
-            app.enter_edit_mode();
+        // Toggle diff mode for the Code field
+        Action::ToggleDiff => {
+            app.toggle_diff();
+            app.set_status(format!("Code language: {}", app.code_language));
         }
 
-        // Submit to Notion
-        KeyCode::Enter => {
-            submit_to_notion(app, notion_client).await;
+        // Choose the code block's language from Notion's list
+        Action::PickLanguage => app.start_language_picker(),
+
+        // Start from a pre-filled entry template
+        Action::PickTemplate => app.start_template_picker(),
+
+        // Step the status new database rows start with
+        Action::CycleStatus => {
+            app.fault_status = app.fault_status.next();
+            app.set_status(format!("New rows start as {}", app.fault_status.name()));
+        }
+
+        // Mark how bad the fault is: Critical, High, Medium, Low or unset
+        Action::CycleSeverity => {
+            app.severity = Severity::cycle(app.severity);
+            match app.severity {
+                Some(severity) => app.set_status(format!("Severity: {}", severity.name())),
+                None => app.set_status("Severity cleared"),
+            }
         }
 
+        // Undo the last submission within the undo window
+        Action::Undo => undo_last_submission(app, notion_client).await,
+
+        // Open the offline queue inspector
+        Action::OpenQueue => app.open_queue(),
+
+        // Retry everything in the offline queue now
+        Action::RetryQueue => retry_queue(app, notion_client).await,
+
+        // Browse past entries from the local history
+        Action::History => app.open_history(),
+
+        // Read the entries already on the selected page
+        Action::ViewPage => view_page_entries(app, notion_client).await,
+
+        // Skim everything on the selected page, read-only
+        Action::PreviewPage => preview_page(app, notion_client).await,
+
+        // Choose the row of the selected database that entries go to
+        Action::PickRow => pick_row(app, notion_client).await,
+
+        // Choose pages that new database rows link to
+        Action::Relations => open_relations(app, notion_client).await,
+
+        // Change the formatting remembered for the selected page
+        Action::PageSettings => app.open_page_settings(),
+
+        // Copy the focused field, or the whole entry as Markdown
+        Action::CopyField => {
+            let field = app.active_field();
+            let label = app.config.template.labels.label(field).to_string();
+            let text = app.field(field).to_string();
+            if text.trim().is_empty() {
+                app.set_error(format!("{} is empty; nothing to copy", label));
+            } else {
+                copy_to_clipboard(app, &text, &label);
+            }
+        }
+        Action::CopyEntry => {
+            if app.has_input() {
+                let markdown = app.entry_markdown();
+                copy_to_clipboard(app, &markdown, "the entry as Markdown");
+            } else {
+                app.set_error("The entry is empty; nothing to copy");
+            }
+        }
+
+        // Edit the focused field in $EDITOR; the main loop hands it the
+        // terminal
+        Action::ExternalEditor => app.editor_requested = true,
+
+        // Stack the draft under the next entry submitted, as one group
+        Action::AddSubEntry => app.add_sub_entry(),
+
+        // Shrink to the status and the active field, or expand again
+        Action::ToggleMini => app.toggle_mini(),
+
+        // Show or hide the earlier status messages
+        Action::StatusLog => app.toggle_status_log(),
+
+        // Write in the active field alone, without the rest of the UI
+        Action::ToggleZen => app.toggle_zen(),
+
+        // Ask for a screenshot, log file or link to embed in the entry
+        Action::Attach => app.open_attach_prompt(),
+
+        // Create a page in Notion from the page list
+        Action::NewPage if app.is_page_list_focused() => app.open_new_page_prompt(),
+        Action::NewPage => app.set_status("Switch to the page list (Tab) to create a page"),
+
+        // Links found in the Error and Code fields
+        Action::NextLink => app.next_link(),
+        Action::PreviousLink => app.previous_link(),
+        Action::OpenLink => match app.selected_link() {
+            Some(link) => match links::open(&link) {
+                Ok(()) => app.set_status(format!("Opened {}", link)),
+                Err(e) => app.set_error(e),
+            },
+            None => app.set_error("No links in the Error or Code field"),
+        },
+        Action::CopyLink => match app.selected_link() {
+            Some(link) => copy_to_clipboard(app, &link, "the link"),
+            None => app.set_error("No links in the Error or Code field"),
+        },
+
+        // Look up earlier entries with the first trace ID in the entry
+        Action::FindTrace => app.find_trace(),
+
         // Clear All Inputs
-        KeyCode::Char('c') => {
-            app.clear_inputs();
-            app.set_status("Inputs cleared");
+        Action::ClearInputs => {
+            if app.has_input() {
+                app.request_confirmation(PendingAction::ClearInputs);
+            } else {
+                app.clear_inputs();
+                app.set_status("Inputs cleared");
+            }
         }
 
         // Clear Status Message
-        KeyCode::Esc => {
-            app.clear_status();
-        }
+        Action::ClearStatus => app.clear_status(),
 
-        _ => {}
+        // Fetch the page list again; the main loop runs it in the background
+        Action::RefreshPages => app.refresh_requested = true,
+
+        // Editing actions never come from the normal-mode map
+        Action::ExitEdit
+        | Action::NextField
+        | Action::FieldUp
+        | Action::FieldDown
+        | Action::Paste
+        | Action::UndoEdit
+        | Action::RedoEdit => {}
+    }
+}
+
+/// Put `text` on the system clipboard and report it as `what`
+fn copy_to_clipboard(app: &mut AppState, text: &str, what: &str) {
+    match clipboard::write_text(text) {
+        Ok(()) => app.set_success(format!("Copied {} to the clipboard", what)),
+        Err(e) => app.set_error(e),
     }
 }
 
 /// Submit the fault log entry to Notion
-async fn submit_to_notion(app: &mut AppState, notion_client: Option<&NotionClient>) {
+#[instrument(skip_all)]
+pub async fn submit_to_notion(app: &mut AppState, notion_client: Option<&NotionClient>) {
+    // One request at a time keeps undo and the queue unambiguous
+    if app.submission.is_some() {
+        app.set_error("A submission is already in progress");
+        return;
+    }
+
+    // `[export] mode = "instead"`: the Markdown file is the whole submission
+    if app.config.export.replaces_notion() {
+        export_only(app);
+        return;
+    }
+
+    // Another sink replaces Notion for the whole session
+    if let Some(sink) = app.sink.clone() {
+        submit_to_sink(app, &sink);
+        return;
+    }
+
     // Check if we can submit
     if !app.can_submit() {
         app.set_error("Fill in Error, Problem, and Solution fields first");
@@ -98,8 +389,17 @@ async fn submit_to_notion(app: &mut AppState, notion_client: Option<&NotionClien
         }
     };
 
+    // Refuse early when the token cannot write at all
+    if let Some(blocker) = app.capabilities.as_ref().and_then(|c| c.submit_blocker()) {
+        app.set_error(blocker);
+        return;
+    }
+
+    // Record the commit the fix was made against, not the one at startup
+    app.git_context = GitContext::current();
+
     // Get the submission data
-    let (page_id, entry_data) = match app.get_submission_data() {
+    let (page_id, mut entry) = match app.get_submission_data() {
         Some(data) => data,
         None => {
             app.set_error("Failed to prepare submission data");
@@ -107,75 +407,1602 @@ async fn submit_to_notion(app: &mut AppState, notion_client: Option<&NotionClien
         }
     };
 
+    // Catch broken diagrams before Notion renders them blank
+    if let (true, Some(code)) = (app.is_mermaid(), entry.code.as_deref()) {
+        if let Err(e) = validate_mermaid(code) {
+            app.set_error(format!("Mermaid: {}", e));
+            return;
+        }
+    }
+
     // Check if this is a demo page
-    if page_id.starts_with("demo-") {
+    let targets = app.submission_targets();
+    if targets.iter().any(|target| target.id.starts_with("demo-")) {
         app.set_error("Cannot submit to demo pages. Connect to Notion API first.");
         return;
     }
 
-    // Create the FaultLogEntry for the API
-    let entry = FaultLogEntry {
-        error: entry_data.error,
-        problem: entry_data.problem,
-        solution: entry_data.solution,
-        code: entry_data.code,
+    // Warn before submitting entries Notion would reject, then move the
+    // overflow into an attached text file if the user submits again
+    let preview = create_error_block(&entry, entry.language.as_deref(), &app.submission_template(&page_id, &entry));
+    let report = PayloadReport::of(&preview);
+    let split = if report.exceeds_limits() {
+        let Some(split) = split_overflow(&entry) else {
+            app.set_error(report.describe());
+            return;
+        };
+        if !app.overflow_confirmed {
+            app.overflow_confirmed = true;
+            app.set_error(format!(
+                "{}. Press Enter again to attach the overflow as {}",
+                report.describe(),
+                OVERFLOW_FILENAME
+            ));
+            return;
+        }
+        Some(split)
+    } else {
+        None
     };
 
-    // Show loading status
+    // Keep the whole entry locally once the checks pass; a file that cannot
+    // be written stops the submission rather than going missing from the
+    // archive
+    if let Err(e) = export_once(app, &entry, &targets) {
+        app.set_error(format!("Export: {}", e));
+        return;
+    }
+
+    if let Some((trimmed, overflow)) = split {
+        app.start_loading();
+        let payload_sha256 = sha256_hex(overflow.as_bytes());
+        match upload_text_file(client, OVERFLOW_FILENAME, overflow).await {
+            Ok(upload_id) => {
+                let mut record = AuditRecord::now(AuditAction::UploadFile, payload_sha256);
+                record.created_ids.push(upload_id.clone());
+                audit(app, &record);
+                entry = trimmed;
+                entry.attachments.push(Attachment {
+                    name: OVERFLOW_FILENAME.to_string(),
+                    upload_id,
+                    url: None,
+                });
+            }
+            Err(e) => {
+                app.set_error(format!("Failed to upload overflow: {}", e));
+                return;
+            }
+        }
+    }
+    app.overflow_confirmed = false;
+
+    // Upload attached files first; one that fails keeps the entry in the
+    // editor with its files still attached
+    if !entry.files.is_empty() {
+        app.start_loading();
+        if let Err(e) = upload_attachments(app, client, &mut entry).await {
+            app.set_error(e);
+            return;
+        }
+    }
+
+    // Run the requests on background tasks so the UI keeps drawing, one
+    // per target so checked pages are written concurrently; the main loop
+    // hears when all are done through `SubmissionFinished`
+    let requests: Vec<_> = targets
+        .iter()
+        .map(|target| {
+            let client = client.clone();
+            let template = app.submission_template(&target.id, &entry);
+            let preferred = app.token_map.get(&target.id).map(str::to_string);
+            let (page_id, kind, entry) = (target.id.clone(), target.kind, entry.clone());
+            tokio::spawn(async move {
+                create_entry_with_fallback(&client, &page_id, kind, &entry, &template, preferred.as_deref()).await
+            })
+        })
+        .collect();
+    let events = app.events.clone();
+    let task = tokio::spawn(async move {
+        let mut results = Vec::new();
+        for request in requests {
+            // A request that panicked fails the whole submission, which
+            // puts the entry back in the editor
+            results.push(request.await.unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic())));
+        }
+        if let Some(events) = events {
+            let _ = events.send(AppEvent::SubmissionFinished);
+        }
+        results
+    });
+    app.submission = Some(InFlightSubmission {
+        targets,
+        entry,
+        task: SubmissionTask::Notion(task),
+    });
     app.start_loading();
+    // The entry now lives in the task; the editor is free for the next one
+    app.clear_inputs();
+}
 
-    // Make the API call
-    match create_entry(client, &page_id, &entry).await {
-        Ok(()) => {
-            app.set_success("Error logged to Notion successfully! ✓");
-            app.clear_inputs();
+/// Log the entry to every target of a sink other than Notion on a
+/// background task; the main loop hears when it is done through
+/// `SubmissionFinished`. Such sinks have no offline queue or undo, so the
+/// entry stays in the editor until every target took it.
+fn submit_to_sink(app: &mut AppState, sink: &Sink) {
+    if !app.can_submit() {
+        app.set_error("Fill in Error, Problem, and Solution fields first");
+        return;
+    }
+    app.git_context = GitContext::current();
+    let entry = app.current_entry();
+    let targets = app.submission_targets();
+    if let Err(e) = export_once(app, &entry, &targets) {
+        app.set_error(format!("Export: {}", e));
+        return;
+    }
+
+    let requests: Vec<(PageInfo, BlockTemplate)> = targets
+        .iter()
+        .map(|target| (target.clone(), app.submission_template(&target.id, &entry)))
+        .collect();
+    let (sink, submitted, events) = (sink.clone(), entry.clone(), app.events.clone());
+    let task = tokio::spawn(async move {
+        let mut results = Vec::new();
+        for (target, template) in requests {
+            results.push(sink.submit_entry(&target, &submitted, &template).await);
         }
+        if let Some(events) = events {
+            let _ = events.send(AppEvent::SubmissionFinished);
+        }
+        results
+    });
+    app.submission = Some(InFlightSubmission {
+        targets,
+        entry,
+        task: SubmissionTask::Sink(task),
+    });
+    app.start_loading();
+}
+
+/// Apply the results of a sink submission. Targets that failed stay
+/// checked, the others are unchecked, so submitting again retries only
+/// those.
+async fn complete_sink_submission(
+    app: &mut AppState,
+    targets: Vec<PageInfo>,
+    entry: FaultLogEntry,
+    task: JoinHandle<Vec<Result<Receipt, String>>>,
+) {
+    let name = app.sink.as_ref().map_or("the sink", |sink| sink.name());
+    let results = match task.await {
+        Ok(results) => results,
         Err(e) => {
-            app.set_error(format!("Failed to submit: {}", e));
+            app.set_error(format!("Submission task failed: {}", e));
+            return;
+        }
+    };
+
+    let mut logged = Vec::new();
+    let mut failed = Vec::new();
+    let mut notify_error = None;
+    for (target, result) in targets.iter().zip(results) {
+        match result {
+            Ok(receipt) => {
+                remember(app, &target.id, &target.title, &entry, EntryStatus::Submitted);
+                if let Err(e) = notify_logged(app, &target.id, &target.title, &entry, &receipt.url).await {
+                    notify_error = Some(e);
+                }
+                logged.push(receipt.url);
+            }
+            Err(e) => failed.push((target, e)),
+        }
+    }
+    if failed.is_empty() {
+        // The same entry logged again later is a new export
+        app.exported = None;
+        // Leave the editor alone if the user already started the next entry
+        if app.current_entry().content_hash() == entry.content_hash() {
+            app.clear_inputs();
         }
+        match notify_error {
+            Some(e) => app.set_error(format!("Logged to {}, but the notification failed: {}", name, e)),
+            None => app.set_success(format!("Logged to {}: {}", name, logged.join(", "))),
+        }
+        if app.capture {
+            app.quit();
+        }
+        return;
+    }
+
+    let failed_ids: Vec<String> = failed.iter().map(|(target, _)| target.id.clone()).collect();
+    app.keep_checked_targets(&failed_ids);
+    let failures: Vec<String> = failed.iter().map(|(target, e)| format!("{} ({})", target.title, e)).collect();
+    if logged.is_empty() {
+        app.set_error(format!("{} failed on {}", name, failures.join(", ")));
+    } else {
+        app.set_error(format!(
+            "Logged to {}, but failed on {}; submit again to retry those",
+            logged.join(", "),
+            failures.join(", ")
+        ));
     }
 }
 
-/// Handle key events in editing mode
-fn handle_editing_mode(app: &mut AppState, key: KeyEvent) {
-    match key.code {
-        // Exit Edit Mode
-        KeyCode::Esc => {
-            app.exit_edit_mode();
+/// Post the entry to the `[notify]` webhook, if one is set. A failed
+/// notification is returned for the status line but never fails the entry.
+async fn notify_logged(app: &AppState, page_id: &str, page_title: &str, entry: &FaultLogEntry, url: &str) -> Result<(), String> {
+    let Some(webhook) = app.config.notify.webhook_url() else {
+        return Ok(());
+    };
+    let template = app.submission_template(page_id, entry);
+    let notice = Notice {
+        title: markdown::entry_title(entry, &template),
+        page: page_title,
+        url,
+        entry,
+    };
+    let result = match webhook {
+        Ok(webhook) => notify::send(&webhook, app.config.notify.format, &notice).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = &result {
+        tracing::warn!(error = %e, "notification failed");
+    }
+    result
+}
+
+/// Upload the entry's attached files and turn its links into external
+/// files, moving them from `files` to `attachments`
+async fn upload_attachments(app: &mut AppState, client: &NotionClient, entry: &mut FaultLogEntry) -> Result<(), String> {
+    for source in std::mem::take(&mut entry.files) {
+        let name = attachments::display_name(&source);
+        let attachment = match attachments::resolve(&source).map_err(|e| format!("Attach: {}", e))? {
+            AttachSource::Link(url) => Attachment {
+                name,
+                upload_id: String::new(),
+                url: Some(url),
+            },
+            AttachSource::File(path) => {
+                let content = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+                let payload_sha256 = sha256_hex(&content);
+                let upload_id = upload_file(client, &name, attachments::content_type(&name), content)
+                    .await
+                    .map_err(|e| format!("Failed to upload {}: {}", name, e))?;
+                let mut record = AuditRecord::now(AuditAction::UploadFile, payload_sha256);
+                record.created_ids.push(upload_id.clone());
+                audit(app, &record);
+                Attachment {
+                    name,
+                    upload_id,
+                    url: None,
+                }
+            }
+        };
+        entry.attachments.push(attachment);
+    }
+    Ok(())
+}
+
+/// Write the entry as Markdown into the export directory, if one is set
+fn export_entry(app: &AppState, entry: &FaultLogEntry, targets: &[PageInfo]) -> Result<Option<PathBuf>, String> {
+    let Some(dir) = app.config.export.dir() else {
+        return Ok(None);
+    };
+    let page_id = targets.first().map(|page| page.id.as_str()).unwrap_or_default();
+    let template = app.submission_template(page_id, entry);
+    let titles: Vec<String> = targets.iter().map(|page| page.title.clone()).collect();
+    export::write(&dir, entry, &template, &titles, chrono::Local::now().date_naive()).map(Some)
+}
+
+/// `export_entry`, skipped when the same entry already went to the same
+/// targets and only the submission failed
+fn export_once(app: &mut AppState, entry: &FaultLogEntry, targets: &[PageInfo]) -> Result<(), String> {
+    let ids: Vec<&str> = targets.iter().map(|page| page.id.as_str()).collect();
+    let key = sha256_hex(serde_json::to_string(&(entry, ids)).unwrap_or_default().as_bytes());
+    if app.exported.as_deref() == Some(key.as_str()) {
+        return Ok(());
+    }
+    export_entry(app, entry, targets)?;
+    app.exported = Some(key);
+    Ok(())
+}
+
+/// Save the entry as a Markdown file without sending it to Notion; a
+/// selected page still names the page and picks its template
+fn export_only(app: &mut AppState) {
+    if app.error_input.is_blank() || app.problem_input.is_blank() || app.solution_input.is_blank() {
+        app.set_error("Fill in Error, Problem, and Solution fields first");
+        return;
+    }
+    app.git_context = GitContext::current();
+    let entry = app.current_entry();
+    let targets: Vec<PageInfo> = app
+        .submission_targets()
+        .into_iter()
+        .filter(|page| !page.id.starts_with("demo-"))
+        .collect();
+    match export_entry(app, &entry, &targets) {
+        Ok(Some(path)) => {
+            app.set_success(format!("Saved to {}", path.display()));
+            app.clear_inputs();
         }
+        Ok(None) => app.set_error("Set [export] dir to save entries as Markdown"),
+        Err(e) => app.set_error(format!("Export: {}", e)),
+    }
+}
 
-        // Text Input
-        KeyCode::Char(c) => {
-            app.add_char(c);
+/// Show the result of a background page fetch. The first one of a session
+/// also reports what the integration may do and retries entries left in
+/// the queue by earlier sessions.
+pub async fn apply_page_load(app: &mut AppState, load: PageLoad, notion_client: Option<&NotionClient>) {
+    app.pages_loading = false;
+    match load.pages {
+        Ok(pages) if pages.is_empty() => {
+            app.replace_pages(pages);
+            app.set_status("No pages found. Create a page in Notion first.");
         }
+        Ok(pages) => {
+            app.set_success(format!("Loaded {} pages from Notion", pages.len()));
+            app.replace_pages(pages);
+        }
+        Err(e) => app.set_error(format!("Failed to fetch pages: {}", e)),
+    }
+    app.select_wanted_page();
 
-        // Backspace
-        KeyCode::Backspace => {
-            app.delete_char();
+    match load.capabilities {
+        Some(Ok(capabilities)) => {
+            if let Some(blocker) = capabilities.submit_blocker() {
+                app.set_error(blocker);
+            }
+            app.capabilities = Some(capabilities);
         }
+        Some(Err(e)) => app.set_error(format!("Failed to check integration capabilities: {}", e)),
+        None => {}
+    }
 
-        // New Line
-        KeyCode::Enter => {
-            // In editing mode, Enter adds a newline
-            // Use Esc to exit, then Enter to submit
-            app.add_newline();
+    if load.initial && !app.queue.pending_indices().is_empty() {
+        retry_queue(app, notion_client).await;
+    }
+}
+
+/// Wait for the background submission, if any, and apply its result
+pub async fn complete_submission(app: &mut AppState) {
+    let Some(submission) = app.submission.take() else {
+        return;
+    };
+    let InFlightSubmission { targets, entry, task } = submission;
+    let task = match task {
+        SubmissionTask::Notion(task) => task,
+        SubmissionTask::Sink(task) => {
+            complete_sink_submission(app, targets, entry, task).await;
+            return;
+        }
+    };
+    let first_page = targets.first().map(|target| target.id.clone()).unwrap_or_default();
+
+    let results = match task.await {
+        Ok(results) => results,
+        Err(e) => {
+            // The task panicked or was cancelled; keep the entry unless the
+            // user already started a new one
+            if !app.has_input() {
+                app.load_entry(&first_page, &entry);
+            }
+            app.set_error(format!("Submission task failed: {}", e));
+            return;
+        }
+    };
+
+    let count = targets.len();
+    let mut logged = Vec::new();
+    let mut failed = Vec::new();
+    let mut notify_error = None;
+    for (target, result) in targets.into_iter().zip(results) {
+        match result {
+            Ok((token, appended)) => {
+                record_submission(app, &target.id, target.kind, &token, &appended);
+                let history_id = remember(app, &target.id, &target.title, &entry, EntryStatus::Submitted);
+                remember_row(app, history_id, target.kind, &appended.block_ids);
+                let url = submit::entry_url(&target.id, target.kind, &appended.block_ids);
+                if let Err(e) = notify_logged(app, &target.id, &target.title, &entry, &url).await {
+                    notify_error = Some(e);
+                }
+                logged.push(target.title);
+                // Undo takes back a submission to a single page only
+                app.last_submission = (count == 1).then(|| LastSubmission {
+                    page_id: target.id,
+                    entry: entry.clone(),
+                    block_ids: appended.block_ids,
+                    token,
+                    submitted_at: Instant::now(),
+                    history_id,
+                });
+            }
+            Err(e) => {
+                // Keep the entry in the offline queue instead of losing it
+                let history_id = remember(app, &target.id, &target.title, &entry, EntryStatus::Queued);
+                app.queue
+                    .push(&target.id, &target.title, entry.clone(), status_for_error(&e), e.to_string());
+                if let Some(item) = app.queue.last_mut() {
+                    item.kind = target.kind;
+                    item.history_id = history_id;
+                    item.http_status = e.status();
+                }
+                failed.push((target.title, e));
+            }
+        }
+    }
+
+    if failed.is_empty() {
+        // The same entry logged again later is a new export
+        app.exported = None;
+        if let Some(e) = notify_error {
+            app.set_error(format!("Logged to Notion, but the notification failed: {}", e));
+        } else if count == 1 {
+            app.set_success("Error logged to Notion successfully! (u to undo)");
+        } else {
+            app.set_success(format!("Logged to {} pages: {}", count, logged.join(", ")));
+        }
+        // The quick-capture window has done its job
+        if app.capture {
+            app.quit();
+        }
+        return;
+    }
+    let failure = match failed.as_slice() {
+        [(_, e)] if count == 1 => format!("Failed to submit: {}", e),
+        _ => {
+            let pages: Vec<String> = failed.iter().map(|(title, e)| format!("{} ({})", title, e)).collect();
+            format!("Logged to {} of {} pages; failed on {}", logged.len(), count, pages.join(", "))
         }
+    };
+    match app.queue.save() {
+        Ok(()) => app.set_error(format!("{}. Saved to queue (o to inspect)", failure)),
+        Err(save_error) => {
+            // Put the entry back unless the user already started a new one
+            if !app.has_input() {
+                app.load_entry(&first_page, &entry);
+            }
+            app.set_error(format!("{}. {}", failure, save_error));
+        }
+    }
+}
+
+/// Create the entry, trying every configured token, and remember which
+/// token worked for the page so it is tried first next time
+#[instrument(skip_all, fields(page_id = %page_id), err)]
+async fn submit_entry(
+    app: &mut AppState,
+    client: &NotionClient,
+    page_id: &str,
+    kind: TargetKind,
+    entry: &FaultLogEntry,
+) -> Result<(String, Vec<String>), NotionError> {
+    let preferred = app.token_map.get(page_id).map(str::to_string);
+    let (token, appended) = create_entry_with_fallback(
+        client,
+        page_id,
+        kind,
+        entry,
+        &app.submission_template(page_id, entry),
+        preferred.as_deref(),
+    )
+    .await?;
+
+    record_submission(app, page_id, kind, &token, &appended);
+    Ok((token, appended.block_ids))
+}
+
+/// Audit a successful append and remember which token worked for the page
+pub(crate) fn record_submission(app: &mut AppState, page_id: &str, kind: TargetKind, token: &str, appended: &AppendedBlocks) {
+    if let Some(anchor_id) = &appended.created_anchor {
+        let mut record = AuditRecord::now(AuditAction::AppendBlocks, sha256_hex(anchor_id.as_bytes()));
+        record.page_id = Some(page_id.to_string());
+        record.created_ids = vec![anchor_id.clone()];
+        record.token = Some(token.to_string());
+        audit(app, &record);
+    }
+
+    let action = match kind {
+        TargetKind::Page => AuditAction::AppendBlocks,
+        TargetKind::Database => AuditAction::CreatePage,
+    };
+    let mut record = AuditRecord::now(action, appended.payload_sha256.clone());
+    record.page_id = Some(page_id.to_string());
+    record.created_ids = appended.block_ids.clone();
+    record.token = Some(token.to_string());
+    audit(app, &record);
+
+    // Failing to persist the preference must not fail the submission
+    let _ = app.token_map.record(page_id, token);
+}
+
+/// Delete the blocks created by the last submission and put its content
+/// back into the editor
+#[instrument(skip_all)]
+async fn undo_last_submission(app: &mut AppState, notion_client: Option<&NotionClient>) {
+    if !app.can_undo() {
+        app.last_submission = None;
+        app.set_error("Nothing to undo");
+        return;
+    }
+    let Some(client) = notion_client else {
+        app.set_error("Notion API not connected. Check your API_KEY in .env");
+        return;
+    };
+    // Kept until every block is gone, so a failed undo can be retried
+    let Some(mut last) = app.last_submission.clone() else {
+        return;
+    };
+    let token_client = client.with_token(&last.token).unwrap_or_else(|| client.clone());
+
+    app.start_loading();
+    let mut deleted = Vec::new();
+    let mut failure = None;
+    for block_id in &last.block_ids {
+        if let Err(e) = delete_block(&token_client, block_id).await {
+            failure = Some(e);
+            break;
+        }
+        deleted.push(block_id.clone());
+    }
+
+    if deleted.is_empty() {
+        if let Some(e) = failure {
+            app.set_error(format!("Undo failed: {}", e));
+        }
+        return;
+    }
+    let mut record = AuditRecord::now(AuditAction::DeleteBlocks, sha256_hex(deleted.join(",").as_bytes()));
+    record.page_id = Some(last.page_id.clone());
+    record.created_ids = deleted.clone();
+    record.token = Some(last.token.clone());
+    audit(app, &record);
+
+    // Part of the entry left the page; its content belongs in the editor,
+    // unless the user started a draft there since. The history keeps it then.
+    let restored = if app.has_input() {
+        app.current_entry().content_hash() == last.entry.content_hash()
+    } else {
+        app.load_entry(&last.page_id, &last.entry);
+        true
+    };
+    if !restored && last.history_id.is_none() {
+        let title = app.notion_pages.iter().find(|page| page.id == last.page_id).map(|page| page.title.clone());
+        let title = title.unwrap_or_else(|| last.page_id.clone());
+        last.history_id = remember(app, &last.page_id, &title, &last.entry, EntryStatus::Submitted);
+    }
+    let whereabouts = match (restored, last.history_id) {
+        (true, _) => "Content restored to the editor",
+        (false, Some(_)) => "Your draft was kept; the undone entry is in the history (h, then e to copy it back)",
+        (false, None) => "Your draft was kept, but the undone entry could not be saved to the history",
+    };
+    last.block_ids.retain(|id| !deleted.contains(id));
+    match failure {
+        None => {
+            app.last_submission = None;
+            app.set_success(format!("Submission undone. {}", whereabouts));
+            mark_history(app, last.history_id, EntryStatus::Undone);
+        }
+        Some(e) => {
+            let message = format!(
+                "Undo removed {} of {} blocks: {}. {}; press {} to remove the rest",
+                deleted.len(),
+                deleted.len() + last.block_ids.len(),
+                e,
+                whereabouts,
+                app.config.keys.label(Action::Undo)
+            );
+            app.last_submission = Some(last);
+            app.set_error(message);
+        }
+    }
+}
 
-        // Navigate to Next Input (while editing)
-        KeyCode::Tab => {
-            app.exit_edit_mode();
-            app.next_input();
-            app.enter_edit_mode();
+/// Add an entry to the local history; a failure is reported but does not
+/// affect the submission
+fn remember(
+    app: &mut AppState,
+    page_id: &str,
+    page_title: &str,
+    entry: &FaultLogEntry,
+    status: EntryStatus,
+) -> Option<i64> {
+    match app.history.record(page_id, page_title, entry, status) {
+        Ok(id) => id,
+        Err(e) => {
+            app.set_error(format!("History: {}", e));
+            None
         }
+    }
+}
+
+/// Update the status of a history row, if the entry has one
+fn mark_history(app: &mut AppState, history_id: Option<i64>, status: EntryStatus) {
+    let Some(id) = history_id else {
+        return;
+    };
+    if let Err(e) = app.history.set_status(id, status) {
+        app.set_error(format!("History: {}", e));
+    }
+}
+
+/// Note the database row an entry became, so its status can change later
+fn remember_row(app: &mut AppState, history_id: Option<i64>, kind: TargetKind, block_ids: &[String]) {
+    let (Some(id), TargetKind::Database, Some(row_id)) = (history_id, kind, block_ids.first()) else {
+        return;
+    };
+    if let Err(e) = app.history.set_row_id(id, row_id) {
+        app.set_error(format!("History: {}", e));
+    }
+}
+
+/// Step the selected history entry's fault status and update its row
+#[instrument(skip_all)]
+async fn advance_history_status(app: &mut AppState, notion_client: Option<&NotionClient>) {
+    let Some(item) = app.selected_history_entry().cloned() else {
+        return;
+    };
+    let (Some(row_id), EntryStatus::Submitted) = (item.row_id.as_deref(), item.status) else {
+        app.set_error("Only entries submitted as database rows have a status");
+        return;
+    };
+    let Some(client) = notion_client else {
+        app.set_error("Notion API not connected. Check your API_KEY in .env");
+        return;
+    };
+    let token_client = app
+        .token_map
+        .get(&item.page_id)
+        .and_then(|token| client.with_token(token))
+        .unwrap_or_else(|| client.clone());
+
+    let status = item.entry.status.next();
+    app.start_loading();
+    match update_row_status(&token_client, row_id, status, &app.template_for(&item.page_id)).await {
+        Ok(Some((property, payload_sha256))) => {
+            let mut record = AuditRecord::now(AuditAction::UpdatePage, payload_sha256);
+            record.page_id = Some(row_id.to_string());
+            record.token = app.token_map.get(&item.page_id).map(str::to_string);
+            audit(app, &record);
 
-        // Navigate Up/Down Between Inputs
-        KeyCode::Up => {
-            app.exit_edit_mode();
-            app.previous_input();
+            let mut entry = item.entry;
+            entry.status = status;
+            if let Err(e) = app.history.update_entry(item.id, &entry) {
+                app.set_error(format!("History: {}", e));
+                return;
+            }
+            app.refresh_history();
+            app.set_success(format!("{} set to {}", property, status.name()));
         }
-        KeyCode::Down => {
-            app.exit_edit_mode();
-            app.next_input();
+        Ok(None) => app.set_error(format!("{} has no Status select property", item.page_title)),
+        Err(e) => app.set_error(format!("Failed to update status: {}", e)),
+    }
+}
+
+/// Write an audit record; a failure is reported but does not undo the write
+fn audit(app: &mut AppState, record: &AuditRecord) {
+    if let Err(e) = app.audit_log.append(record) {
+        app.set_error(format!("Audit log: {}", e));
+    }
+}
+
+/// Handle key events on the queue inspector screen
+async fn handle_queue_mode(app: &mut AppState, key: KeyEvent, notion_client: Option<&NotionClient>) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('o') => app.close_queue(),
+        KeyCode::Char('q') | KeyCode::Char('Q') => app.quit(),
+        KeyCode::Up | KeyCode::Char('k') => app.previous_queue_item(),
+        KeyCode::Down | KeyCode::Char('j') => app.next_queue_item(),
+
+        // Retry the selected submission now
+        KeyCode::Char('r') | KeyCode::Enter => {
+            retry_selected(app, notion_client).await;
+        }
+
+        // Retry every queued submission now, ignoring the backoff schedule
+        KeyCode::Char('R') => {
+            let indices: Vec<usize> = (0..app.queue.len()).collect();
+            retry_many(app, notion_client, indices).await;
+        }
+
+        // Move the entry back into the editor and drop it from the queue
+        KeyCode::Char('e') => {
+            if let Some(item) = app.queue.remove(app.selected_queue_index) {
+                mark_history(app, item.history_id, EntryStatus::Discarded);
+                app.load_entry(&item.page_id, &item.entry);
+                app.clamp_queue_selection();
+                save_queue(app, "Queued entry moved to the editor");
+                app.close_queue();
+            }
+        }
+
+        // Point the submission at the next page in the sidebar
+        KeyCode::Char('p') => {
+            let index = app.selected_queue_index;
+            let Some(item) = app.queue.get(index) else {
+                return;
+            };
+            if app.notion_pages.is_empty() {
+                app.set_error("No pages loaded to choose from");
+                return;
+            }
+            let current = app.notion_pages.iter().position(|p| p.id == item.page_id);
+            let next = current.map_or(0, |idx| (idx + 1) % app.notion_pages.len());
+            let page = app.notion_pages[next].clone();
+            if let Some(item) = app.queue.get_mut(index) {
+                item.page_id = page.id;
+                item.kind = page.kind;
+                item.page_title = page.title.clone();
+                item.status = QueueStatus::Pending;
+                item.next_retry_at = Some(unix_now());
+            }
+            save_queue(app, format!("Target changed to {}", page.title));
+        }
+
+        // Discard the selected submission, once confirmed
+        KeyCode::Char('x') | KeyCode::Delete if app.queue.get(app.selected_queue_index).is_some() => {
+            app.request_confirmation(PendingAction::DiscardQueued);
+        }
+
+        _ => {}
+    }
+}
+
+/// Keys in the confirmation dialog: y or n answer at once, arrows and Tab
+/// move between Yes and No, Enter picks the highlighted one, Esc says No
+async fn handle_confirmation_mode(app: &mut AppState, key: KeyEvent, notion_client: Option<&NotionClient>) {
+    let confirmed = match key.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') => true,
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => false,
+        KeyCode::Enter => app.confirm_yes,
+        KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab | KeyCode::Char('h') | KeyCode::Char('l') => {
+            app.confirm_yes = !app.confirm_yes;
+            return;
+        }
+        _ => return,
+    };
+    let Some(action) = app.pending_action.take() else {
+        return;
+    };
+    if !confirmed {
+        app.set_status("Cancelled");
+        return;
+    }
+    match action {
+        PendingAction::Submit => submit_to_notion(app, notion_client).await,
+        PendingAction::ClearInputs => {
+            app.clear_inputs();
+            app.set_status("Inputs cleared");
+        }
+        PendingAction::DiscardQueued => discard_queued(app),
+    }
+}
+
+/// Handle key events on the history screen; while searching, keys edit
+/// the filter and the list updates as you type
+async fn handle_history_mode(app: &mut AppState, key: KeyEvent, notion_client: Option<&NotionClient>) {
+    if app.history_searching {
+        match key.code {
+            KeyCode::Esc | KeyCode::Enter => app.history_searching = false,
+            KeyCode::Backspace => {
+                pop_grapheme(&mut app.history_query);
+                app.refresh_history();
+            }
+            KeyCode::Char(c) => {
+                app.history_query.push(c);
+                app.refresh_history();
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('h') => app.close_history(),
+        KeyCode::Char('q') | KeyCode::Char('Q') => app.quit(),
+        KeyCode::Up | KeyCode::Char('k') => app.previous_history_item(),
+        KeyCode::Down | KeyCode::Char('j') => app.next_history_item(),
+        KeyCode::Char('/') => app.history_searching = true,
+
+        // Start a new entry from a past one
+        KeyCode::Char('e') => {
+            if let Some(item) = app.selected_history_entry().cloned() {
+                app.load_entry(&item.page_id, &item.entry);
+                app.close_history();
+                app.set_status("Past entry copied to the editor");
+            }
+        }
+
+        // Move the fault along Open → Investigating → Fixed in Notion
+        KeyCode::Char('s') => advance_history_status(app, notion_client).await,
+
+        _ => {}
+    }
+}
+
+/// Handle key events while filtering the page list
+fn handle_page_filter_mode(app: &mut AppState, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter => app.accept_page_filter(),
+        KeyCode::Esc => app.cancel_page_filter(),
+        KeyCode::Up => app.previous_match(),
+        KeyCode::Down => app.next_match(),
+        KeyCode::Backspace => {
+            pop_grapheme(&mut app.page_filter);
+            app.refresh_page_filter();
+        }
+        KeyCode::Char(c) => {
+            app.page_filter.push(c);
+            app.refresh_page_filter();
+        }
+        _ => {}
+    }
+}
+
+/// Pasted text goes into the field being edited, or the filter being typed
+fn handle_paste(app: &mut AppState, text: &str) {
+    // Filters are one line; keep what was pasted before any line break
+    let first_line = text.lines().next().unwrap_or_default();
+    if app.is_editing() {
+        app.paste(text);
+    } else if let Some(prompt) = app.new_page.as_mut() {
+        prompt.title.push_str(first_line);
+    } else if let Some(input) = app.attach_input.as_mut() {
+        // Dropping a file on the terminal pastes its path
+        input.push_str(first_line);
+    } else if app.language_picking {
+        app.language_filter.push_str(first_line);
+        app.refresh_language_filter();
+    } else if app.template_picking {
+        app.template_filter.push_str(first_line);
+        app.refresh_template_filter();
+    } else if app.page_filtering {
+        app.page_filter.push_str(first_line);
+        app.refresh_page_filter();
+    } else {
+        app.set_status("Press e to edit a field, then paste");
+    }
+}
+
+/// Handle key events in the language picker popup
+fn handle_language_picker_mode(app: &mut AppState, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter => app.accept_language(),
+        KeyCode::Esc => app.cancel_language_picker(),
+        KeyCode::Up => app.previous_language(),
+        KeyCode::Down => app.next_language(),
+        KeyCode::Backspace => {
+            pop_grapheme(&mut app.language_filter);
+            app.refresh_language_filter();
+        }
+        KeyCode::Char(c) => {
+            app.language_filter.push(c);
+            app.refresh_language_filter();
+        }
+        _ => {}
+    }
+}
+
+/// Handle key events in the attach prompt
+fn handle_attach_prompt_mode(app: &mut AppState, key: KeyEvent) {
+    let Some(input) = app.attach_input.as_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Enter => app.commit_attach_input(),
+        KeyCode::Esc => app.attach_input = None,
+        KeyCode::Delete => app.remove_last_attachment(),
+        KeyCode::Backspace => pop_grapheme(input),
+        KeyCode::Char(c) => input.push(c),
+        _ => {}
+    }
+}
+
+/// Handle key events in the new page prompt
+async fn handle_new_page_mode(app: &mut AppState, key: KeyEvent, notion_client: Option<&NotionClient>) {
+    let Some(prompt) = app.new_page.as_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Enter => create_new_page(app, notion_client).await,
+        KeyCode::Esc => app.new_page = None,
+        KeyCode::Up | KeyCode::BackTab => app.cycle_new_page_parent(false),
+        KeyCode::Down | KeyCode::Tab => app.cycle_new_page_parent(true),
+        KeyCode::Backspace => pop_grapheme(&mut prompt.title),
+        KeyCode::Char(c) => prompt.title.push(c),
+        _ => {}
+    }
+}
+
+/// Create the page typed into the prompt and select it; a failure keeps
+/// the prompt open to try again
+async fn create_new_page(app: &mut AppState, notion_client: Option<&NotionClient>) {
+    let Some(client) = notion_client else {
+        app.set_error("Creating pages needs a Notion connection (API_KEY)");
+        return;
+    };
+    let title = app.new_page.as_ref().map(|prompt| prompt.title.trim().to_string()).unwrap_or_default();
+    if title.is_empty() {
+        app.set_error("Type a title for the new page");
+        return;
+    }
+    let Some(parent) = app.new_page_parent().cloned() else {
+        app.set_error("Choose a page to create the new one under");
+        return;
+    };
+    app.set_loading(format!("Creating {}…", title));
+    match create_page(client, &parent.id, &title).await {
+        Ok((page, payload_sha256)) => {
+            let mut record = AuditRecord::now(AuditAction::CreatePage, payload_sha256);
+            record.page_id = Some(parent.id.clone());
+            record.created_ids.push(page.id.clone());
+            audit(app, &record);
+            app.insert_created_page(page);
+            app.set_success(format!("Created {} under {}", title, parent.title));
+        }
+        Err(e) => app.set_error(format!("Failed to create {}: {}", title, e)),
+    }
+}
+
+/// Handle key events in the entry template picker popup
+fn handle_template_picker_mode(app: &mut AppState, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter => app.accept_template(),
+        KeyCode::Esc => app.cancel_template_picker(),
+        KeyCode::Up => app.previous_template(),
+        KeyCode::Down => app.next_template(),
+        KeyCode::Backspace => {
+            pop_grapheme(&mut app.template_filter);
+            app.refresh_template_filter();
+        }
+        KeyCode::Char(c) => {
+            app.template_filter.push(c);
+            app.refresh_template_filter();
+        }
+        _ => {}
+    }
+}
+
+/// Handle key events on the page settings screen
+fn handle_page_settings_mode(app: &mut AppState, key: KeyEvent) {
+    if let Some(input) = app.setting_input.as_mut() {
+        match key.code {
+            KeyCode::Enter => app.commit_setting_input(),
+            KeyCode::Esc => app.setting_input = None,
+            KeyCode::Backspace => {
+                pop_grapheme(input);
+            }
+            KeyCode::Char(c) => input.push(c),
+            _ => {}
+        }
+        return;
+    }
+
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('p') => app.close_page_settings(),
+        KeyCode::Char('q') | KeyCode::Char('Q') => app.quit(),
+        KeyCode::Up | KeyCode::Char('k') => app.previous_setting(),
+        KeyCode::Down | KeyCode::Char('j') => app.next_setting(),
+        KeyCode::Enter | KeyCode::Char(' ') => app.change_setting(),
+        KeyCode::Char('x') | KeyCode::Delete | KeyCode::Backspace => app.reset_setting(),
+        _ => {}
+    }
+}
+
+/// The page entries go to, or an error explaining what to select first
+fn target_or_error(app: &mut AppState) -> Option<PageInfo> {
+    match app.submission_target() {
+        Some(database) if database.is_database() => {
+            app.set_error(format!("Pick a row of {} first", database.title));
+            None
+        }
+        Some(page) => Some(page),
+        None => {
+            app.set_error("Select a page first");
+            None
+        }
+    }
+}
+
+/// Load the rows being picked from into the row picker, optionally only
+/// those whose title contains `query`
+#[instrument(skip_all)]
+async fn load_rows(app: &mut AppState, notion_client: Option<&NotionClient>, query: &str) -> bool {
+    let Some(client) = notion_client else {
+        app.set_error("Notion API not connected. Check your API_KEY in .env");
+        return false;
+    };
+    let Some(database_id) = app.row_database_id() else {
+        app.set_error("Select a database to pick a row from");
+        return false;
+    };
+    let source = match &app.row_pick {
+        RowPick::Target => app.get_selected_page().map(|p| p.title.clone()).unwrap_or_default(),
+        RowPick::Relation { property, .. } => property.clone(),
+    };
+
+    app.start_loading();
+    match query_rows(client, &database_id, query).await {
+        Ok(rows) => {
+            app.set_success(format!("{} rows in {}", rows.len(), source));
+            app.open_rows(rows);
+            true
+        }
+        Err(e) => {
+            app.set_error(format!("Failed to query {}: {}", source, e));
+            false
+        }
+    }
+}
+
+/// Open the row picker for the selected database
+async fn pick_row(app: &mut AppState, notion_client: Option<&NotionClient>) {
+    app.row_pick = RowPick::Target;
+    app.row_query.clear();
+    app.row_searching = false;
+    if load_rows(app, notion_client, "").await {
+        app.set_status("Pick the row to add entries to");
+    }
+}
+
+/// Handle key events in the database row picker
+async fn handle_rows_mode(app: &mut AppState, key: KeyEvent, notion_client: Option<&NotionClient>) {
+    if app.row_searching {
+        match key.code {
+            KeyCode::Esc => app.row_searching = false,
+            // Ask Notion too, for rows beyond the ones loaded
+            KeyCode::Enter => {
+                app.row_searching = false;
+                let query = app.row_query.clone();
+                load_rows(app, notion_client, &query).await;
+            }
+            KeyCode::Backspace => {
+                pop_grapheme(&mut app.row_query);
+                app.refresh_row_filter();
+            }
+            KeyCode::Char(c) => {
+                app.row_query.push(c);
+                app.refresh_row_filter();
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('b') => app.close_rows(),
+        KeyCode::Char('q') | KeyCode::Char('Q') => app.quit(),
+        KeyCode::Up | KeyCode::Char('k') => app.previous_row(),
+        KeyCode::Down | KeyCode::Char('j') => app.next_row(),
+        KeyCode::Char('/') => app.row_searching = true,
+        KeyCode::Char('r') => {
+            app.row_query.clear();
+            load_rows(app, notion_client, "").await;
+        }
+        KeyCode::Char('n') if app.row_pick == RowPick::Target => app.clear_row(),
+        KeyCode::Enter => app.choose_row(),
+        _ => {}
+    }
+}
+
+/// Open the relation properties of the database new rows go to
+#[instrument(skip_all)]
+async fn open_relations(app: &mut AppState, notion_client: Option<&NotionClient>) {
+    let Some(client) = notion_client else {
+        app.set_error("Notion API not connected. Check your API_KEY in .env");
+        return;
+    };
+    let Some(database) = app.submission_target().filter(|p| p.is_database()) else {
+        app.set_error("Relations apply to new rows; select a database without a picked row");
+        return;
+    };
+
+    app.start_loading();
+    match fetch_database(client, &database.id).await {
+        Ok(schema) => {
+            let properties = relation_properties(&schema);
+            if properties.is_empty() {
+                app.set_error(format!("{} has no relation properties", database.title));
+            } else {
+                app.set_status("Pick pages that new rows link to");
+                app.open_relations(properties);
+            }
+        }
+        Err(e) => app.set_error(format!("Failed to fetch {}: {}", database.title, e)),
+    }
+}
+
+/// Handle key events on the relation properties screen
+async fn handle_relations_mode(app: &mut AppState, key: KeyEvent, notion_client: Option<&NotionClient>) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('l') => app.close_relations(),
+        KeyCode::Char('q') | KeyCode::Char('Q') => app.quit(),
+        KeyCode::Up | KeyCode::Char('k') => app.previous_relation(),
+        KeyCode::Down | KeyCode::Char('j') => app.next_relation(),
+        KeyCode::Char('x') | KeyCode::Delete | KeyCode::Backspace => app.clear_relation(),
+        KeyCode::Enter => {
+            let Some(property) = app.selected_relation_property().cloned() else {
+                return;
+            };
+            app.row_pick = RowPick::Relation {
+                property: property.name.clone(),
+                database_id: property.database_id,
+            };
+            app.row_query.clear();
+            app.row_searching = false;
+            if load_rows(app, notion_client, "").await {
+                app.set_status(format!("Pick a page to link through {}", property.name));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Fetch the selected page's entries and open the reader
+#[instrument(skip_all)]
+async fn view_page_entries(app: &mut AppState, notion_client: Option<&NotionClient>) {
+    let Some(client) = notion_client else {
+        app.set_error("Notion API not connected. Check your API_KEY in .env");
+        return;
+    };
+    let Some(page) = target_or_error(app) else {
+        return;
+    };
+    if page.id.starts_with("demo-") {
+        app.set_error("Cannot read demo pages. Connect to Notion API first.");
+        return;
+    }
+
+    app.start_loading();
+    let template = app.template_for(&page.id);
+    match fetch_entries(client, &page.id, &template).await {
+        Ok(entries) => {
+            app.set_success(format!("{} entries on {}", entries.len(), page.title));
+            app.open_entries(entries);
+        }
+        Err(e) => app.set_error(format!("Failed to read {}: {}", page.title, e)),
+    }
+}
+
+/// Handle key events in the entry reader
+async fn handle_entries_mode(app: &mut AppState, key: KeyEvent, notion_client: Option<&NotionClient>) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('v') => app.close_entries(),
+        KeyCode::Char('q') | KeyCode::Char('Q') => app.quit(),
+        KeyCode::Up | KeyCode::Char('k') => app.previous_page_entry(),
+        KeyCode::Down | KeyCode::Char('j') => app.next_page_entry(),
+        KeyCode::PageUp => app.scroll_entry(-10),
+        KeyCode::PageDown => app.scroll_entry(10),
+        KeyCode::Char('r') => view_page_entries(app, notion_client).await,
+
+        // Start a new entry from one on the page
+        KeyCode::Char('e') => {
+            if let Some(item) = app.selected_logged_entry().cloned() {
+                let page_id = app.submission_target().map(|p| p.id).unwrap_or_default();
+                app.load_entry(&page_id, &item.entry);
+                app.close_entries();
+                app.set_status("Entry copied to the editor");
+            }
+        }
+
+        _ => {}
+    }
+}
+
+/// Fetch the selected page's content and open the preview
+#[instrument(skip_all)]
+async fn preview_page(app: &mut AppState, notion_client: Option<&NotionClient>) {
+    let Some(client) = notion_client else {
+        app.set_error("Notion API not connected. Check your API_KEY in .env");
+        return;
+    };
+    let Some(page) = target_or_error(app) else {
+        return;
+    };
+    if page.id.starts_with("demo-") {
+        app.set_error("Cannot read demo pages. Connect to Notion API first.");
+        return;
+    }
+
+    app.start_loading();
+    match fetch_page_content(client, &page.id).await {
+        Ok(blocks) => {
+            app.set_success(format!("{} blocks on {}", blocks.len(), page.title));
+            app.open_preview(&page.title, preview_lines(&blocks));
+        }
+        Err(e) => app.set_error(format!("Failed to read {}: {}", page.title, e)),
+    }
+}
+
+/// Handle key events in the page preview
+async fn handle_preview_mode(app: &mut AppState, key: KeyEvent, notion_client: Option<&NotionClient>) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('P') => app.close_preview(),
+        KeyCode::Char('q') | KeyCode::Char('Q') => app.quit(),
+        KeyCode::Up | KeyCode::Char('k') => app.scroll_preview(-1),
+        KeyCode::Down | KeyCode::Char('j') => app.scroll_preview(1),
+        KeyCode::PageUp => app.scroll_preview(-10),
+        KeyCode::PageDown => app.scroll_preview(10),
+        KeyCode::Home => app.preview_scroll = 0,
+        KeyCode::End => app.scroll_preview(i32::from(u16::MAX)),
+        KeyCode::Char('r') => preview_page(app, notion_client).await,
+        _ => {}
+    }
+}
+
+/// Retry the selected queued submission
+async fn retry_selected(app: &mut AppState, notion_client: Option<&NotionClient>) {
+    let Some(client) = notion_client else {
+        app.set_error("Notion API not connected. Check your API_KEY in .env");
+        return;
+    };
+    // The entry may be part of the batch already on its way
+    if app.retry.is_some() {
+        app.set_error("A retry is already in progress");
+        return;
+    }
+    let index = app.selected_queue_index;
+    let Some(page_title) = app.queue.get(index).map(|item| item.page_title.clone()) else {
+        return;
+    };
+
+    app.start_loading();
+    let result = retry_queued(app, client, index).await;
+    app.clamp_queue_selection();
+    match (result, app.queue.save()) {
+        (Ok(()), Ok(())) => app.set_success(format!("Queued entry submitted to {}", page_title)),
+        (Err(e), Ok(())) => app.set_error(format!("Retry failed: {}", e)),
+        (_, Err(save_error)) => app.set_error(save_error),
+    }
+}
+
+/// Retry queued submissions whose backoff delay has elapsed.
+/// Called from the main loop on every iteration.
+pub async fn retry_due(app: &mut AppState, notion_client: Option<&NotionClient>) {
+    if notion_client.is_none() || app.is_loading || app.retry.is_some() {
+        return;
+    }
+    let due = app.queue.due_indices(unix_now());
+    if !due.is_empty() {
+        retry_many(app, notion_client, due).await;
+    }
+}
+
+/// Submit every pending queued entry, e.g. when the network comes back
+#[instrument(skip_all)]
+pub async fn flush_queue(app: &mut AppState, notion_client: Option<&NotionClient>) {
+    // A retry already on its way reports when it is done
+    if app.retry.is_some() {
+        return;
+    }
+    let pending = app.queue.pending_indices();
+    if pending.is_empty() {
+        app.set_success("Back online");
+    } else {
+        retry_many(app, notion_client, pending).await;
+    }
+}
+
+/// Retry every pending queued submission now, ignoring the backoff
+/// schedule; also run once at launch for entries left by earlier sessions
+pub async fn retry_queue(app: &mut AppState, notion_client: Option<&NotionClient>) {
+    let pending = app.queue.pending_indices();
+    if pending.is_empty() {
+        app.set_status("Nothing to retry");
+    } else {
+        retry_many(app, notion_client, pending).await;
+    }
+}
+
+/// Retry several queued submissions on a background task; the main loop
+/// hears when they are done through `RetryFinished`
+#[instrument(skip_all, fields(count = indices.len()))]
+async fn retry_many(app: &mut AppState, notion_client: Option<&NotionClient>, indices: Vec<usize>) {
+    let Some(client) = notion_client else {
+        app.set_error("Notion API not connected. Check your API_KEY in .env");
+        return;
+    };
+    if indices.is_empty() {
+        return;
+    }
+    if app.retry.is_some() {
+        app.set_error("A retry is already in progress");
+        return;
+    }
+
+    app.start_loading();
+    let (_, mut receiver) = spawn_retries(app, client, &indices);
+    let events = app.events.clone();
+    app.retry = Some(tokio::spawn(async move {
+        let mut results = Vec::new();
+        while let Some(result) = receiver.recv().await {
+            results.push(result);
+        }
+        if let Some(events) = events {
+            let _ = events.send(AppEvent::RetryFinished);
+        }
+        results
+    }));
+    // Outside the TUI nobody hears `RetryFinished`; wait for it here
+    if app.events.is_none() {
+        complete_retry(app).await;
+    }
+}
+
+/// Apply the results of the background retry to the queue and history,
+/// and report a summary
+pub async fn complete_retry(app: &mut AppState) {
+    let Some(task) = app.retry.take() else {
+        return;
+    };
+    let results = match task.await {
+        Ok(results) => results,
+        // The entries were not touched; they stay queued for the next try
+        Err(e) => {
+            app.set_error(format!("Retry task failed: {}", e));
+            return;
+        }
+    };
+
+    let (mut submitted, mut failed) = (0, 0);
+    for (item, result) in results {
+        let retried = apply_retry(app, item, result).await;
+        tracing::debug!(id = retried.item.id, ok = retried.error.is_none());
+        match retried.error {
+            Some(_) => failed += 1,
+            None => submitted += 1,
+        }
+    }
+    app.clamp_queue_selection();
+
+    if let Err(e) = app.queue.save() {
+        app.set_error(e);
+    } else if failed == 0 {
+        app.set_success(format!("{} queued entries submitted", submitted));
+    } else {
+        app.set_error(format!("{} queued entries submitted, {} still failing", submitted, failed));
+    }
+}
+
+/// Submit one queued item; removes it on success, reschedules it on failure
+async fn retry_queued(app: &mut AppState, client: &NotionClient, index: usize) -> Result<(), String> {
+    let Some(item) = app.queue.get(index).cloned() else {
+        return Ok(());
+    };
+    let result = submit_entry(app, client, &item.page_id, item.kind, &item.entry).await;
+    let block_ids = result.as_ref().map(|(_, block_ids)| block_ids.clone()).unwrap_or_default();
+    settle_retry(app, &item, result).map_err(|e| e.to_string())?;
+    let url = submit::entry_url(&item.page_id, item.kind, &block_ids);
+    // The entry went through; a failed notification is only logged
+    let _ = notify_logged(app, &item.page_id, &item.page_title, &item.entry, &url).await;
+    Ok(())
+}
+
+/// A queued entry sent again by `retry_batch`
+#[derive(Debug)]
+pub struct Retried {
+    /// The entry as it was queued before this attempt
+    pub item: QueuedSubmission,
+    /// Why it failed again; `None` once it went through
+    pub error: Option<NotionError>,
+}
+
+/// A queued entry and the outcome of sending it again: the token that
+/// succeeded and the created blocks
+type RetryResult = (QueuedSubmission, Result<(String, AppendedBlocks), NotionError>);
+
+/// Retry several queued entries and wait for them; `on_retried` sees each
+/// outcome as it arrives
+#[instrument(skip_all, fields(count = indices.len()))]
+pub async fn retry_batch(
+    app: &mut AppState,
+    client: &NotionClient,
+    indices: Vec<usize>,
+    mut on_retried: impl FnMut(&Retried, &BatchProgress),
+) -> Vec<Retried> {
+    let (total, mut receiver) = spawn_retries(app, client, &indices);
+    let mut progress = BatchProgress::new(total, Instant::now());
+    let mut retried = Vec::with_capacity(total);
+    while let Some((item, result)) = receiver.recv().await {
+        let outcome = apply_retry(app, item, result).await;
+        progress.done += 1;
+        on_retried(&outcome, &progress);
+        retried.push(outcome);
+    }
+    retried
+}
+
+/// Send several queued entries again on background tasks, paced to Notion's
+/// rate limit. Entries for different pages are sent in parallel; entries
+/// for the same page go one after another in queue order, so they land on
+/// it in the order they were written. Returns how many are sent and the
+/// receiver their outcomes arrive on, which closes after the last.
+fn spawn_retries(
+    app: &AppState,
+    client: &NotionClient,
+    indices: &[usize],
+) -> (usize, mpsc::UnboundedReceiver<RetryResult>) {
+    let mut lanes: Vec<VecDeque<(QueuedSubmission, BlockTemplate, Option<String>)>> = Vec::new();
+    for item in indices.iter().filter_map(|&index| app.queue.get(index).cloned()) {
+        let template = app.submission_template(&item.page_id, &item.entry);
+        let preferred = app.token_map.get(&item.page_id).map(str::to_string);
+        match lanes.iter_mut().find(|lane| lane.front().is_some_and(|(first, ..)| first.page_id == item.page_id)) {
+            Some(lane) => lane.push_back((item, template, preferred)),
+            None => lanes.push(VecDeque::from([(item, template, preferred)])),
+        }
+    }
+
+    let total = lanes.iter().map(VecDeque::len).sum();
+    // Every request of the batch takes a token, fallbacks and retries too
+    let client = client.paced(RateLimiter::notion());
+    let slots = Arc::new(Semaphore::new(MAX_IN_FLIGHT));
+    let (sender, receiver) = mpsc::unbounded_channel();
+    for lane in lanes {
+        let (client, slots, sender) = (client.clone(), slots.clone(), sender.clone());
+        tokio::spawn(async move {
+            for (item, template, preferred) in lane {
+                let Ok(_slot) = slots.acquire().await else {
+                    return;
+                };
+                let result = create_entry_with_fallback(
+                    &client,
+                    &item.page_id,
+                    item.kind,
+                    &item.entry,
+                    &template,
+                    preferred.as_deref(),
+                )
+                .await;
+                if sender.send((item, result)).is_err() {
+                    return;
+                }
+            }
+        });
+    }
+    // The receiver closes once every lane has finished and dropped its sender
+    drop(sender);
+    (total, receiver)
+}
+
+/// Apply the outcome of sending `item` again, and announce it once it
+/// went through
+async fn apply_retry(
+    app: &mut AppState,
+    item: QueuedSubmission,
+    result: Result<(String, AppendedBlocks), NotionError>,
+) -> Retried {
+    let result = result.map(|(token, appended)| {
+        record_submission(app, &item.page_id, item.kind, &token, &appended);
+        (token, appended.block_ids)
+    });
+    let url = result.as_ref().ok().map(|(_, block_ids)| submit::entry_url(&item.page_id, item.kind, block_ids));
+    let error = settle_retry(app, &item, result).err();
+    if let Some(url) = url {
+        let _ = notify_logged(app, &item.page_id, &item.page_title, &item.entry, &url).await;
+    }
+    Retried { item, error }
+}
+
+/// Update the queue and history with the outcome of sending `item` again:
+/// drop it once it went through, else record the failure for the backoff
+fn settle_retry(
+    app: &mut AppState,
+    item: &QueuedSubmission,
+    result: Result<(String, Vec<String>), NotionError>,
+) -> Result<(), NotionError> {
+    match result {
+        Ok((_, block_ids)) => {
+            if let Some(index) = app.queue.index_of(item.id) {
+                app.queue.remove(index);
+            }
+            // Entries queued before the history existed get a row now
+            let history_id = match item.history_id {
+                Some(_) => {
+                    mark_history(app, item.history_id, EntryStatus::Submitted);
+                    item.history_id
+                }
+                None => remember(app, &item.page_id, &item.page_title, &item.entry, EntryStatus::Submitted),
+            };
+            remember_row(app, history_id, item.kind, &block_ids);
+            Ok(())
+        }
+        Err(e) => {
+            if let Some(queued) = app.queue.index_of(item.id).and_then(|index| app.queue.get_mut(index)) {
+                queued.record_failure(status_for_error(&e), e.to_string(), unix_now());
+                queued.http_status = e.status();
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Drop the selected submission from the queue
+fn discard_queued(app: &mut AppState) {
+    if let Some(item) = app.queue.remove(app.selected_queue_index) {
+        mark_history(app, item.history_id, EntryStatus::Discarded);
+        app.clamp_queue_selection();
+        save_queue(app, "Queued entry discarded");
+    }
+}
+
+/// Persist the queue and report either the success message or the save error
+fn save_queue(app: &mut AppState, success: impl Into<String>) {
+    match app.queue.save() {
+        Ok(()) => app.set_success(success),
+        Err(e) => app.set_error(e),
+    }
+}
+
+/// Handle key events in editing mode. Bound keys run their action; the
+/// rest edit the text, so bindings here should avoid printable characters.
+fn handle_editing_mode(app: &mut AppState, key: KeyEvent) {
+    if let Some(action) = app.config.keys.editing_action(&key) {
+        match action {
+            // Exit Edit Mode
+            Action::ExitEdit => app.exit_edit_mode(),
+
+            // Jump to the next template placeholder, else the next input
+            Action::NextField => {
+                if app.next_placeholder() {
+                    return;
+                }
+                app.exit_edit_mode();
+                app.next_input();
+                app.enter_edit_mode();
+            }
+
+            // Move between wrapped rows, then step short fields through
+            // their earlier values, then Navigate Up/Down Between Inputs
+            Action::FieldUp if !app.move_cursor_up() && !app.recall_previous() => {
+                app.exit_edit_mode();
+                app.previous_input();
+            }
+            Action::FieldDown if !app.move_cursor_down() && !app.recall_next() => {
+                app.exit_edit_mode();
+                app.next_input();
+            }
+
+            // Insert the system clipboard at the cursor
+            Action::Paste => match clipboard::read_text() {
+                Ok(text) => app.paste(&text),
+                Err(e) => app.set_error(e),
+            },
+
+            // Step back and forth through this field's changes
+            Action::UndoEdit => app.undo_edit(),
+            Action::RedoEdit => app.redo_edit(),
+
+            _ => {}
+        }
+        return;
+    }
+
+    match key.code {
+        // Text Input
+        KeyCode::Char(c) => {
+            app.add_char(c);
+        }
+
+        // Backspace / Delete around the cursor
+        KeyCode::Backspace => {
+            app.delete_char();
+        }
+        KeyCode::Delete => {
+            app.delete_char_forward();
+        }
+
+        // Move the cursor within the field
+        KeyCode::Left => app.move_cursor_left(),
+        KeyCode::Right => app.move_cursor_right(),
+        KeyCode::Home => app.move_cursor_line_start(),
+        KeyCode::End => app.move_cursor_line_end(),
+
+        // New Line
+        KeyCode::Enter => {
+            // In editing mode, Enter adds a newline
+            // Use Esc to exit, then Enter to submit
+            app.add_newline();
         }
 
         _ => {}
@@ -0,0 +1,129 @@
+// Markdown export: each entry also written as a file, e.g. into a notes
+// repository, alongside or instead of the Notion submission
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::app::FaultLogEntry;
+use crate::config::expand_home;
+use crate::markdown::{entry_markdown, entry_title};
+use crate::notion::template::BlockTemplate;
+
+/// Longest slug taken from the error, in characters
+const SLUG_LENGTH: usize = 50;
+
+/// `[export]` in the config; nothing is exported without a `dir`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExportConfig {
+    /// Directory the Markdown files go to; a leading `~/` is the home
+    /// directory
+    pub dir: Option<String>,
+    pub mode: ExportMode,
+}
+
+/// Whether entries still go to Notion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportMode {
+    /// Write the file and submit to Notion
+    #[default]
+    Alongside,
+    /// Only write the file
+    Instead,
+}
+
+impl ExportConfig {
+    /// The export directory, if exporting is on
+    pub fn dir(&self) -> Option<PathBuf> {
+        let dir = self.dir.as_deref().map(str::trim).filter(|dir| !dir.is_empty())?;
+        Some(expand_home(dir))
+    }
+
+    /// Entries are written to files and not sent to Notion
+    pub fn replaces_notion(&self) -> bool {
+        self.mode == ExportMode::Instead && self.dir().is_some()
+    }
+}
+
+/// File name for an entry: `YYYY-MM-DD-slug.md`, the slug taken from the
+/// first line of the error
+pub fn file_name(entry: &FaultLogEntry, date: NaiveDate) -> String {
+    format!("{}-{}.md", date.format("%Y-%m-%d"), slug(entry.error.lines().next().unwrap_or_default()))
+}
+
+/// Lowercase words joined by dashes, e.g. "Connection refused (db)" →
+/// "connection-refused-db"
+pub fn slug(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.chars().count() >= SLUG_LENGTH {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() { "entry".to_string() } else { slug.to_string() }
+}
+
+/// The file's content: YAML frontmatter with the date, pages, status,
+/// severity and tags, then the entry as Markdown
+pub fn document(entry: &FaultLogEntry, template: &BlockTemplate, pages: &[String], date: NaiveDate) -> String {
+    let mut frontmatter = vec![
+        format!("title: {}", quoted(entry_title(entry, template))),
+        format!("date: {}", date.format("%Y-%m-%d")),
+    ];
+    match pages {
+        [] => {}
+        [page] => frontmatter.push(format!("page: {}", quoted(page))),
+        pages => frontmatter.push(format!("page: [{}]", list(pages))),
+    }
+    frontmatter.push(format!("status: {}", entry.status.name().to_lowercase()));
+    if let Some(severity) = entry.severity {
+        frontmatter.push(format!("severity: {}", severity.name().to_lowercase()));
+    }
+    frontmatter.push(format!("tags: [{}]", list(&entry.tags)));
+    format!("---\n{}\n---\n\n{}", frontmatter.join("\n"), entry_markdown(entry, template))
+}
+
+/// Write the entry into `dir`, creating it if needed. A file of the same
+/// name is never replaced; the new one gets a `-2`, `-3`, … suffix.
+pub fn write(dir: &Path, entry: &FaultLogEntry, template: &BlockTemplate, pages: &[String], date: NaiveDate) -> Result<PathBuf, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let name = file_name(entry, date);
+    let stem = name.trim_end_matches(".md");
+    let content = document(entry, template, pages, date);
+    let mut attempt = 1;
+    loop {
+        let path = match attempt {
+            1 => dir.join(&name),
+            n => dir.join(format!("{}-{}.md", stem, n)),
+        };
+        attempt += 1;
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(content.as_bytes())
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(format!("Failed to write {}: {}", path.display(), e)),
+        }
+    }
+}
+
+/// A YAML string; JSON string syntax is valid YAML and escapes everything
+fn quoted(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+fn list(items: &[String]) -> String {
+    items.iter().map(|item| quoted(item)).collect::<Vec<_>>().join(", ")
+}
@@ -0,0 +1,98 @@
+// Editing a field in the user's own editor: its text goes to a temp file,
+// `$VISUAL` or `$EDITOR` opens it, and the saved file becomes the field
+use std::collections::hash_map::RandomState;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::clipboard::normalize_newlines;
+
+/// Names tried before giving up on creating the temp file
+const TEMP_ATTEMPTS: usize = 8;
+
+/// Used when neither `$VISUAL` nor `$EDITOR` is set
+#[cfg(unix)]
+pub const FALLBACK_EDITOR: &str = "vi";
+#[cfg(not(unix))]
+pub const FALLBACK_EDITOR: &str = "notepad";
+
+/// The editor command: `$VISUAL`, else `$EDITOR`, else the fallback
+pub fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|command| !command.trim().is_empty())
+        .unwrap_or_else(|| FALLBACK_EDITOR.to_string())
+}
+
+/// Open `text` in `editor` and wait for it to exit. `editor` may carry
+/// arguments, e.g. `code --wait`; the file is passed last. `name` ends the
+/// temp file's name so editors pick a mode from its extension. Returns
+/// `None` when the editor exits with an error, which cancels the edit.
+pub fn edit(text: &str, name: &str, editor: &str) -> Result<Option<String>, String> {
+    let (path, mut file) = create_temp(name)?;
+    let written = file.write_all(text.as_bytes());
+    drop(file);
+    if let Err(e) = written {
+        let _ = fs::remove_file(&path);
+        return Err(format!("Failed to write {}: {}", path.display(), e));
+    }
+    let result = run(editor, &path).and_then(|succeeded| {
+        if !succeeded {
+            return Ok(None);
+        }
+        let edited = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Ok(Some(trim_final_newline(normalize_newlines(&edited))))
+    });
+    let _ = fs::remove_file(&path);
+    result
+}
+
+/// A new temp file only the user can read, under a random name so nobody
+/// can plant a file or link there first
+fn create_temp(name: &str) -> Result<(PathBuf, File), String> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut last_error = None;
+    for _ in 0..TEMP_ATTEMPTS {
+        let random = RandomState::new().build_hasher().finish();
+        let path = env::temp_dir().join(format!("faultnote-{:016x}-{}", random, name));
+        match options.open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => last_error = Some(e),
+            Err(e) => return Err(format!("Failed to create {}: {}", path.display(), e)),
+        }
+    }
+    Err(format!(
+        "Failed to create a temp file for the editor: {}",
+        last_error.map_or_else(|| "no name was free".to_string(), |e| e.to_string())
+    ))
+}
+
+/// Run the editor on `path`; whether it exited successfully
+fn run(editor: &str, path: &Path) -> Result<bool, String> {
+    let mut words = editor.split_whitespace();
+    let program = words.next().ok_or("No editor configured; set $EDITOR")?;
+    let status = Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .map_err(|e| format!("Failed to start {}: {}", program, e))?;
+    Ok(status.success())
+}
+
+/// Editors end files with a newline the field did not have
+fn trim_final_newline(mut text: String) -> String {
+    if text.ends_with('\n') {
+        text.pop();
+    }
+    text
+}
@@ -0,0 +1,111 @@
+// Regex extractors from `[[extractors]]`: values in pasted or piped text,
+// such as a request id or an environment, filled into fields, tags or
+// Context lines
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+
+use crate::fields::InputField;
+
+/// `[[extractors]]` in the config
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Extractor {
+    /// Key of the Context line a `metadata` value goes on; defaults to the
+    /// pattern's first named group
+    #[serde(default)]
+    pub name: Option<String>,
+    pub pattern: Pattern,
+    pub into: ExtractTarget,
+    /// What is filled in, with `$1` or `${group}` for groups; defaults to
+    /// the first group, else the whole match
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
+/// A regular expression, checked when the config is loaded
+#[derive(Debug, Clone)]
+pub struct Pattern(pub Regex);
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Regex::new(&pattern).map(Pattern).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Where an extracted value goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtractTarget {
+    Error,
+    Problem,
+    Solution,
+    Code,
+    Verification,
+    Context,
+    /// A tag on the entry; every match becomes one
+    Tag,
+    /// A `name: value` line in the Context field
+    Metadata,
+}
+
+impl ExtractTarget {
+    /// The field a value fills, for targets that are one
+    pub fn field(self) -> Option<InputField> {
+        match self {
+            ExtractTarget::Error => Some(InputField::Error),
+            ExtractTarget::Problem => Some(InputField::Problem),
+            ExtractTarget::Solution => Some(InputField::Solution),
+            ExtractTarget::Code => Some(InputField::Code),
+            ExtractTarget::Verification => Some(InputField::Verification),
+            ExtractTarget::Context => Some(InputField::Context),
+            ExtractTarget::Tag | ExtractTarget::Metadata => None,
+        }
+    }
+}
+
+/// One value found by an extractor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Extracted {
+    pub target: ExtractTarget,
+    /// Key for `metadata` values
+    pub name: String,
+    pub value: String,
+}
+
+impl Extractor {
+    /// Values this extractor finds in `text`: the first match, or every
+    /// match for tags. Blank values are left out.
+    pub fn extract(&self, text: &str) -> Vec<Extracted> {
+        let regex = &self.pattern.0;
+        let template = self.value.clone().unwrap_or_else(|| {
+            if regex.captures_len() > 1 { "${1}".to_string() } else { "${0}".to_string() }
+        });
+        let name = self
+            .name
+            .clone()
+            .or_else(|| regex.capture_names().flatten().next().map(str::to_string))
+            .unwrap_or_else(|| "extracted".to_string());
+        let limit = if self.into == ExtractTarget::Tag { usize::MAX } else { 1 };
+        let mut found: Vec<Extracted> = Vec::new();
+        for captures in regex.captures_iter(text).take(limit) {
+            let mut value = String::new();
+            captures.expand(&template, &mut value);
+            let value = value.trim();
+            if value.is_empty() || found.iter().any(|known| known.value == value) {
+                continue;
+            }
+            found.push(Extracted {
+                target: self.into,
+                name: name.clone(),
+                value: value.to_string(),
+            });
+        }
+        found
+    }
+}
+
+/// Everything the extractors find in `text`, in config order
+pub fn extract_all(extractors: &[Extractor], text: &str) -> Vec<Extracted> {
+    extractors.iter().flat_map(|extractor| extractor.extract(text)).collect()
+}
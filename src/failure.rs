@@ -0,0 +1,114 @@
+// Classes of failure for subcommands, each with its own exit status so
+// wrapper scripts can tell a bad token from a flaky network
+use std::fmt;
+
+use crate::notion::client::NotionError;
+
+/// What kind of thing went wrong. Exit statuses are part of the CLI's
+/// contract; 2 belongs to argument errors reported by clap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureClass {
+    /// Anything not covered below, such as a local file that cannot be written
+    General,
+    /// The input was rejected: an invalid config, an unknown page, an entry
+    /// Notion refused
+    Validation,
+    /// No token, or the token cannot reach the page
+    Auth,
+    /// Notion could not be reached, rate limited the request or failed on
+    /// its side; trying again later may work
+    Network,
+    /// A command handling several entries finished some of them
+    Partial,
+}
+
+impl FailureClass {
+    pub const ALL: [FailureClass; 5] = [
+        FailureClass::General,
+        FailureClass::Validation,
+        FailureClass::Auth,
+        FailureClass::Network,
+        FailureClass::Partial,
+    ];
+
+    pub fn exit_code(self) -> i32 {
+        match self {
+            FailureClass::General => 1,
+            FailureClass::Validation => 3,
+            FailureClass::Auth => 4,
+            FailureClass::Network => 5,
+            FailureClass::Partial => 6,
+        }
+    }
+
+    /// Name used in JSON output
+    pub fn name(self) -> &'static str {
+        match self {
+            FailureClass::General => "general",
+            FailureClass::Validation => "validation",
+            FailureClass::Auth => "auth",
+            FailureClass::Network => "network",
+            FailureClass::Partial => "partial",
+        }
+    }
+
+    /// Class of a failed Notion request from its HTTP status, `None` when
+    /// no response arrived. Notion answers 404 for pages not shared with
+    /// the integration, so it counts as an access problem.
+    pub fn for_http_status(status: Option<u16>) -> Self {
+        match status {
+            None | Some(429) => FailureClass::Network,
+            Some(status) if status >= 500 => FailureClass::Network,
+            Some(401 | 403 | 404) => FailureClass::Auth,
+            Some(_) => FailureClass::Validation,
+        }
+    }
+
+    /// Class of a failed Notion request
+    pub fn for_request(error: &NotionError) -> Self {
+        Self::for_http_status(error.status())
+    }
+}
+
+/// A failed subcommand: its message and class
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub class: FailureClass,
+    pub message: String,
+}
+
+impl Failure {
+    pub fn new(class: FailureClass, message: impl Into<String>) -> Self {
+        Self {
+            class,
+            message: message.into(),
+        }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(FailureClass::Validation, message)
+    }
+
+    pub fn auth(message: impl Into<String>) -> Self {
+        Self::new(FailureClass::Auth, message)
+    }
+}
+
+/// Errors without a class are general failures
+impl From<String> for Failure {
+    fn from(message: String) -> Self {
+        Self::new(FailureClass::General, message)
+    }
+}
+
+impl From<&str> for Failure {
+    fn from(message: &str) -> Self {
+        Self::new(FailureClass::General, message)
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
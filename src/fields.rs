@@ -0,0 +1,147 @@
+// Field schema: the single source of truth for input fields, their TUI
+// titles and the labels/headings emitted to Notion
+use serde::Deserialize;
+
+/// The input fields, in on-screen order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputField {
+    Error,
+    Problem,
+    Solution,
+    Code,
+    Verification,
+    Context,
+    Tags,
+}
+
+impl InputField {
+    pub const ALL: [InputField; 7] = [
+        InputField::Error,
+        InputField::Problem,
+        InputField::Solution,
+        InputField::Code,
+        InputField::Verification,
+        InputField::Context,
+        InputField::Tags,
+    ];
+
+    /// Fields written as sections of an entry; tags are listed after them
+    pub const SECTIONS: [InputField; 6] = [
+        InputField::Error,
+        InputField::Problem,
+        InputField::Solution,
+        InputField::Code,
+        InputField::Verification,
+        InputField::Context,
+    ];
+
+    /// Field at a position in `ALL`, if any
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|field| *field == self).unwrap_or_default()
+    }
+
+    /// Built-in short label
+    pub fn default_label(self) -> &'static str {
+        match self {
+            InputField::Error => "Error",
+            InputField::Problem => "Problem",
+            InputField::Solution => "Solution",
+            InputField::Code => "Code",
+            InputField::Verification => "Verification",
+            InputField::Context => "Context",
+            InputField::Tags => "Tags",
+        }
+    }
+
+    /// Field named by its built-in label, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|field| field.default_label().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Built-in Notion heading for sections that have one
+    pub fn default_heading(self) -> &'static str {
+        match self {
+            InputField::Error => "Error",
+            InputField::Problem => "What was the problem?",
+            InputField::Solution => "How did you fix it?",
+            InputField::Code => "Code Reference",
+            InputField::Verification => "How to verify?",
+            InputField::Context => "Context",
+            InputField::Tags => "Tags",
+        }
+    }
+
+    pub fn emoji(self) -> &'static str {
+        match self {
+            InputField::Error => "🔴",
+            InputField::Problem => "🟡",
+            InputField::Solution => "🟢",
+            InputField::Code => "💻",
+            InputField::Verification => "☑️",
+            InputField::Context => "🧭",
+            InputField::Tags => "🏷️",
+        }
+    }
+
+    /// Short, often repeated fields, where Up/Down while editing step
+    /// through earlier values before moving to another field
+    pub fn recalls_values(self) -> bool {
+        matches!(self, InputField::Error)
+    }
+
+    /// Input hint shown after the title in the TUI
+    pub fn hint(self) -> Option<&'static str> {
+        match self {
+            InputField::Error | InputField::Problem | InputField::Solution => None,
+            InputField::Code => Some("optional"),
+            InputField::Verification => Some("one step per line, optional"),
+            InputField::Context => Some("key: value per line, optional"),
+            InputField::Tags => Some("comma separated, optional"),
+        }
+    }
+}
+
+/// Renamed labels from `[template.labels]`; unset fields keep the defaults.
+/// A renamed field uses its label as the Notion heading too, so the TUI and
+/// the page always agree.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FieldLabels {
+    pub error: Option<String>,
+    pub problem: Option<String>,
+    pub solution: Option<String>,
+    pub code: Option<String>,
+    pub verification: Option<String>,
+    pub context: Option<String>,
+    pub tags: Option<String>,
+}
+
+impl FieldLabels {
+    /// The renamed label of a field, if any
+    pub fn custom(&self, field: InputField) -> Option<&str> {
+        let label = match field {
+            InputField::Error => &self.error,
+            InputField::Problem => &self.problem,
+            InputField::Solution => &self.solution,
+            InputField::Code => &self.code,
+            InputField::Verification => &self.verification,
+            InputField::Context => &self.context,
+            InputField::Tags => &self.tags,
+        };
+        label.as_deref()
+    }
+
+    /// Label used for TUI titles and plain-style section labels
+    pub fn label(&self, field: InputField) -> &str {
+        self.custom(field).unwrap_or(field.default_label())
+    }
+
+    /// Heading emitted above a section in Notion
+    pub fn heading(&self, field: InputField) -> &str {
+        self.custom(field).unwrap_or(field.default_heading())
+    }
+}
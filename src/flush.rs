@@ -0,0 +1,114 @@
+// `faultnote queue flush`: retry the offline queue from the shell, with
+// progress for people or one line per entry for other tools
+use std::io::{self, IsTerminal};
+use std::time::Instant;
+
+use crate::events::{self, Retried};
+use crate::failure::{Failure, FailureClass};
+use crate::notion::rate_limit::BatchProgress;
+use crate::submit::{self, load_app};
+
+/// How a flush reports each entry while it runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FlushOutput {
+    /// A line per entry on stderr with the time left, only when it is a
+    /// terminal
+    #[default]
+    Progress,
+    /// Nothing per entry
+    Quiet,
+    /// `<status> <id>` per entry on stdout, in a stable format
+    Porcelain,
+}
+
+/// What happened to one queued entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flushed {
+    /// Queue id, as shown in the queue inspector
+    pub id: u64,
+    pub page_title: String,
+    /// Why it failed again; `None` once it went through
+    pub error: Option<String>,
+    /// HTTP status of the failure, when Notion answered
+    pub http_status: Option<u16>,
+}
+
+impl Flushed {
+    fn new(retried: &Retried) -> Self {
+        Self {
+            id: retried.item.id,
+            page_title: retried.item.page_title.clone(),
+            error: retried.error.as_ref().map(ToString::to_string),
+            http_status: retried.error.as_ref().and_then(|error| error.status()),
+        }
+    }
+
+    pub fn status(&self) -> &'static str {
+        if self.error.is_some() {
+            "failed"
+        } else {
+            "sent"
+        }
+    }
+
+    /// `sent 12` or `failed 13`
+    pub fn porcelain(&self) -> String {
+        format!("{} {}", self.status(), self.id)
+    }
+
+    fn progress(&self) -> String {
+        match &self.error {
+            Some(error) => format!("✗ #{} {}: {}", self.id, self.page_title, error),
+            None => format!("✓ #{} {}", self.id, self.page_title),
+        }
+    }
+}
+
+/// Retry every pending entry in the queue, paced to Notion's rate limit,
+/// and save it
+pub async fn run(output: FlushOutput) -> Result<Vec<Flushed>, Failure> {
+    let mut app = load_app()?;
+    let pending = app.queue.pending_indices();
+    if pending.is_empty() {
+        return Ok(Vec::new());
+    }
+    let client = submit::connect(&app)?;
+    let progress = output == FlushOutput::Progress && io::stderr().is_terminal();
+    if progress {
+        let estimate = BatchProgress::new(pending.len(), Instant::now()).eta(Instant::now());
+        eprintln!("Sending {} queued entries, about {}s", pending.len(), estimate.as_secs_f64().ceil() as u64);
+    }
+
+    let mut flushed = Vec::new();
+    events::retry_batch(&mut app, &client, pending, |retried, batch| {
+        let result = Flushed::new(retried);
+        match output {
+            FlushOutput::Porcelain => println!("{}", result.porcelain()),
+            _ if progress => eprintln!("{} ({})", result.progress(), batch.label(Instant::now())),
+            _ => {}
+        }
+        flushed.push(result);
+    })
+    .await;
+    app.queue.save()?;
+    Ok(flushed)
+}
+
+/// A flush where anything failed: partial when some entries went through,
+/// else classed by the first failure
+pub fn failure(flushed: &[Flushed]) -> Option<Failure> {
+    let failed: Vec<&Flushed> = flushed.iter().filter(|entry| entry.error.is_some()).collect();
+    let first = failed.first()?;
+    let class = if failed.len() < flushed.len() {
+        FailureClass::Partial
+    } else {
+        FailureClass::for_http_status(first.http_status)
+    };
+    let message = format!(
+        "{} of {} queued entries still failing (first: {})",
+        failed.len(),
+        flushed.len(),
+        first.error.as_deref().unwrap_or_default()
+    );
+    Some(Failure::new(class, message))
+}
@@ -0,0 +1,282 @@
+// `faultnote fsck`: check the files in the data directory, repair what an
+// interrupted write left behind, and move records that cannot be read to
+// `quarantine/` so the app starts instead of failing on them
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::audit::AuditRecord;
+use crate::import::ImportProgress;
+use crate::page_settings::PageSettings;
+use crate::queue::QueuedSubmission;
+use crate::storage::History;
+
+/// Directory in the data directory holding quarantined records
+pub const QUARANTINE_DIR: &str = "quarantine";
+
+/// Where a save writes before replacing `path`, so an interrupted write
+/// leaves the old file intact and the new content next to it
+pub fn journal_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+/// Replace the file at `path` with `contents` through its journal file
+pub fn write_atomic(path: &Path, contents: &str) -> Result<(), String> {
+    let journal = journal_path(path);
+    fs::write(&journal, contents).map_err(|e| format!("Failed to write {}: {}", journal.display(), e))?;
+    fs::rename(&journal, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+/// Something wrong with a local file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// Path relative to the data directory
+    pub file: String,
+    pub problem: String,
+    /// What was done about it, or would be without `repair`
+    pub fix: String,
+}
+
+/// How the records of a file are laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    /// A JSON array of records
+    List,
+    /// A JSON object of records by key
+    Map,
+    /// One JSON record per line
+    Lines,
+    /// The whole file is one record
+    Whole,
+}
+
+/// Check every local file under `data_dir`. With `repair`, fix what can
+/// be fixed; otherwise only report.
+pub fn check(data_dir: &Path, repair: bool, now: DateTime<Utc>) -> Result<Vec<Finding>, String> {
+    let quarantine = Quarantine {
+        dir: data_dir.join(QUARANTINE_DIR),
+        stamp: now.format("%Y%m%dT%H%M%S").to_string(),
+        repair,
+    };
+    let mut findings = Vec::new();
+    let mut check_file = |name: &str, check: fn(&str, Layout) -> Option<Split>, layout: Layout| {
+        check_records(data_dir, name, layout, check, &quarantine).map(|found| findings.extend(found))
+    };
+    check_file("queue.json", split::<QueuedSubmission>, Layout::List)?;
+    check_file("tokens.json", split::<String>, Layout::Map)?;
+    check_file("page_settings.json", split::<PageSettings>, Layout::Map)?;
+    check_file("audit.jsonl", split::<AuditRecord>, Layout::Lines)?;
+    if let Ok(imports) = fs::read_dir(data_dir.join("imports")) {
+        let mut names: Vec<String> = imports
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter(|name| name.ends_with(".json"))
+            .collect();
+        names.sort();
+        for name in names {
+            check_file(&format!("imports/{}", name), split::<ImportProgress>, Layout::Whole)?;
+        }
+    }
+    findings.extend(check_history(data_dir, &quarantine)?);
+    Ok(findings)
+}
+
+/// Moves unreadable data aside instead of deleting it
+struct Quarantine {
+    dir: PathBuf,
+    /// Time of the check, prefixed to quarantined file names
+    stamp: String,
+    repair: bool,
+}
+
+impl Quarantine {
+    /// Keep `contents` taken from `name`; returns where it went, relative
+    /// to the data directory
+    fn keep(&self, name: &str, contents: &str) -> Result<String, String> {
+        let file = format!("{}-{}", self.stamp, name.replace('/', "-"));
+        if self.repair {
+            fs::create_dir_all(&self.dir).map_err(|e| format!("Failed to create {}: {}", self.dir.display(), e))?;
+            let path = self.dir.join(&file);
+            fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        }
+        Ok(format!("{}/{}", QUARANTINE_DIR, file))
+    }
+}
+
+/// Readable and unreadable records of a file
+struct Split {
+    /// The file rewritten with only the readable records
+    readable: String,
+    unreadable: Vec<String>,
+}
+
+/// Sort the records of `text` into those that decode as `T` and those
+/// that do not; `None` when the text is not in `layout` at all
+fn split<T: DeserializeOwned>(text: &str, layout: Layout) -> Option<Split> {
+    let readable = |value: &Value| serde_json::from_value::<T>(value.clone()).is_ok();
+    match layout {
+        Layout::List => {
+            let values: Vec<Value> = serde_json::from_str(text).ok()?;
+            let (good, bad): (Vec<Value>, Vec<Value>) = values.into_iter().partition(readable);
+            Some(Split {
+                readable: serde_json::to_string_pretty(&good).ok()?,
+                unreadable: bad.iter().map(Value::to_string).collect(),
+            })
+        }
+        Layout::Map => {
+            let values: serde_json::Map<String, Value> = serde_json::from_str(text).ok()?;
+            let (good, bad): (serde_json::Map<String, Value>, serde_json::Map<String, Value>) =
+                values.into_iter().partition(|(_, value)| readable(value));
+            Some(Split {
+                readable: serde_json::to_string_pretty(&good).ok()?,
+                unreadable: bad.into_iter().map(|(key, value)| serde_json::json!({ key: value }).to_string()).collect(),
+            })
+        }
+        Layout::Lines => {
+            let (good, bad): (Vec<&str>, Vec<&str>) = text
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .partition(|line| serde_json::from_str::<T>(line).is_ok());
+            Some(Split {
+                readable: good.iter().map(|line| format!("{}\n", line)).collect(),
+                unreadable: bad.into_iter().map(str::to_string).collect(),
+            })
+        }
+        Layout::Whole => serde_json::from_str::<T>(text).ok().map(|_| Split {
+            readable: text.to_string(),
+            unreadable: Vec::new(),
+        }),
+    }
+}
+
+/// Check one JSON file, preferring a complete journal over a damaged file
+/// and quarantining records neither can supply
+fn check_records(
+    data_dir: &Path,
+    name: &str,
+    layout: Layout,
+    split: fn(&str, Layout) -> Option<Split>,
+    quarantine: &Quarantine,
+) -> Result<Vec<Finding>, String> {
+    let path = data_dir.join(name);
+    let journal = journal_path(&path);
+    let read = |path: &Path| fs::read_to_string(path).ok();
+    let (text, journal_text) = (read(&path), read(&journal));
+    let complete = |text: &Option<String>| {
+        text.as_deref()
+            .and_then(|text| split(text, layout))
+            .is_some_and(|split| split.unreadable.is_empty())
+    };
+    let finding = |problem: String, fix: String| Finding {
+        file: name.to_string(),
+        problem,
+        fix,
+    };
+
+    if complete(&text) || (text.is_none() && journal_text.is_none()) {
+        if journal_text.is_none() {
+            return Ok(Vec::new());
+        }
+        if quarantine.repair {
+            fs::remove_file(&journal).map_err(|e| format!("Failed to remove {}: {}", journal.display(), e))?;
+        }
+        let problem = "an interrupted write left an unused copy".to_string();
+        return Ok(vec![finding(problem, "removed the copy".to_string())]);
+    }
+    if complete(&journal_text) {
+        if quarantine.repair {
+            if let Some(text) = &text {
+                quarantine.keep(name, text)?;
+            }
+            fs::rename(&journal, &path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))?;
+        }
+        let problem = match text {
+            Some(_) => "damaged by an interrupted write",
+            None => "missing after an interrupted write",
+        };
+        return Ok(vec![finding(problem.to_string(), "restored from the complete copy".to_string())]);
+    }
+
+    let mut findings = Vec::new();
+    if journal_text.is_some() {
+        if quarantine.repair {
+            fs::remove_file(&journal).map_err(|e| format!("Failed to remove {}: {}", journal.display(), e))?;
+        }
+        let problem = "an interrupted write left an incomplete copy".to_string();
+        findings.push(finding(problem, "removed the copy".to_string()));
+    }
+    let Some(text) = text else {
+        return Ok(findings);
+    };
+    match split(&text, layout) {
+        Some(Split { readable, unreadable }) => {
+            let kept = quarantine.keep(name, &unreadable.join("\n"))?;
+            if quarantine.repair {
+                write_atomic(&path, &readable)?;
+            }
+            let problem = format!("{} unreadable records", unreadable.len());
+            findings.push(finding(problem, format!("moved them to {}", kept)));
+        }
+        None => {
+            let kept = quarantine.keep(name, &text)?;
+            if quarantine.repair {
+                fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+            }
+            let problem = "cannot be read at all".to_string();
+            findings.push(finding(problem, format!("moved it to {}; FaultNote starts it afresh", kept)));
+        }
+    }
+    Ok(findings)
+}
+
+/// Check the history database. SQLite rolls interrupted transactions back
+/// from its own journal when the file is opened; what is left is damage
+/// to the file itself, or entries that cannot be decoded.
+fn check_history(data_dir: &Path, quarantine: &Quarantine) -> Result<Vec<Finding>, String> {
+    let name = "history.sqlite3";
+    let path = data_dir.join(name);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let damaged = match History::open(path.clone()) {
+        Ok(history) => match history.integrity_errors() {
+            Ok(errors) if errors.is_empty() => None,
+            Ok(errors) => Some(errors.join("; ")),
+            Err(e) => Some(e),
+        },
+        Err(e) => Some(e),
+    };
+    if let Some(problem) = damaged {
+        let kept = format!("{}/{}-{}", QUARANTINE_DIR, quarantine.stamp, name);
+        if quarantine.repair {
+            fs::create_dir_all(&quarantine.dir)
+                .map_err(|e| format!("Failed to create {}: {}", quarantine.dir.display(), e))?;
+            fs::rename(&path, data_dir.join(&kept)).map_err(|e| format!("Failed to move {}: {}", path.display(), e))?;
+        }
+        return Ok(vec![Finding {
+            file: name.to_string(),
+            problem,
+            fix: format!("moved it to {}; FaultNote starts a new history", kept),
+        }]);
+    }
+
+    let history = History::open(path)?;
+    let unreadable = history.unreadable()?;
+    if unreadable.is_empty() {
+        return Ok(Vec::new());
+    }
+    let lines: Vec<String> = unreadable.iter().filter_map(|row| serde_json::to_string(row).ok()).collect();
+    let kept = quarantine.keep("history.jsonl", &lines.join("\n"))?;
+    if quarantine.repair {
+        history.delete(&unreadable.iter().map(|row| row.id).collect::<Vec<_>>())?;
+    }
+    Ok(vec![Finding {
+        file: name.to_string(),
+        problem: format!("{} unreadable entries", unreadable.len()),
+        fix: format!("moved them to {}", kept),
+    }])
+}
@@ -0,0 +1,72 @@
+// Fuzzy matching for filtering lists by typed text
+
+/// How well `query` matches `text`, ignoring case; lower is better.
+/// Substrings beat scattered matches, and earlier or tighter ones rank
+/// higher. `None` when the characters do not all appear in order.
+pub fn score(query: &str, text: &str) -> Option<usize> {
+    let query = query.to_lowercase();
+    let text = text.to_lowercase();
+    if query.is_empty() {
+        return Some(0);
+    }
+    if let Some(pos) = text.find(&query) {
+        return Some(text[..pos].chars().count());
+    }
+
+    // Every query character in order; score by the span they cover
+    let mut wanted = query.chars().peekable();
+    let mut first = None;
+    let mut last = 0;
+    for (idx, c) in text.chars().enumerate() {
+        if wanted.peek() == Some(&c) {
+            wanted.next();
+            first.get_or_insert(idx);
+            last = idx;
+        }
+    }
+    if wanted.peek().is_some() {
+        return None;
+    }
+    let start = first.unwrap_or_default();
+    // Past any substring score so scattered matches always rank lower
+    Some(text.chars().count() + start + (last - start))
+}
+
+/// Indices of the texts matching `query`, best first; ties keep their order
+pub fn rank<'a>(query: &str, texts: impl IntoIterator<Item = &'a str>) -> Vec<usize> {
+    let mut scored: Vec<(usize, usize)> = texts
+        .into_iter()
+        .enumerate()
+        .filter_map(|(idx, text)| score(query, text).map(|score| (score, idx)))
+        .collect();
+    scored.sort();
+    scored.into_iter().map(|(_, idx)| idx).collect()
+}
+
+/// The candidate closest to a misspelled `word`, when one is within a few
+/// edits of it, ignoring case
+pub fn closest<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let word = word.to_lowercase();
+    let limit = (word.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&word, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = previous[j] + usize::from(ca != *cb);
+            current.push(substitute.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
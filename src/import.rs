@@ -0,0 +1,322 @@
+// `faultnote import`: log many entries from a JSON Lines file. The outcome
+// of every entry is saved as it happens, so an import stopped by a crash or
+// Ctrl+C can be resumed without sending anything twice. Entries already in
+// the local history, or on the target page, are skipped as duplicates.
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::{AppState, FaultLogEntry, PageInfo};
+use crate::config::data_dir;
+use crate::fsck::write_atomic;
+use crate::failure::{Failure, FailureClass};
+use crate::notion::client::{NotionClient, fetch_pages, sha256_hex};
+use crate::notion::entries::fetch_entries;
+use crate::notion::rate_limit::{BatchProgress, RateLimiter};
+use crate::submit::{self, SubmitRequest, connect, find_page, load_app};
+
+/// What happened to one entry of an import file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStatus {
+    /// Not tried yet, or refused before sending; tried again on resume
+    #[default]
+    Pending,
+    Sent,
+    /// Sending failed and the entry went to the offline queue, which
+    /// retries it; skipped on resume
+    Queued,
+    /// Logged to the page before, so not sent; sent with `--force`
+    Duplicate,
+}
+
+/// Outcome of one entry of the import file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryProgress {
+    pub line: usize,
+    /// Hash of the line, so an entry keeps its outcome when lines around
+    /// it are edited, and an edited entry counts as new
+    pub sha256: String,
+    pub status: ImportStatus,
+    /// Link to the entry once sent
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Why the last attempt failed
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Per-entry progress of importing one file, saved in the data directory
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportProgress {
+    pub source: PathBuf,
+    pub entries: Vec<EntryProgress>,
+}
+
+impl ImportProgress {
+    /// Where progress of importing the file at `source` is kept
+    pub fn path_for(source: &Path) -> Option<PathBuf> {
+        let source = source.canonicalize().unwrap_or_else(|_| source.to_path_buf());
+        let key: String = sha256_hex(source.to_string_lossy().as_bytes()).chars().take(16).collect();
+        data_dir().map(|dir| dir.join("imports").join(format!("{}.json", key)))
+    }
+
+    /// Progress saved at `path`, `None` when there is none
+    pub fn load(path: &Path) -> Result<Option<Self>, String> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&text)
+            .map(Some)
+            .map_err(|e| format!("Corrupt import progress {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let json =
+            serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize import progress: {}", e))?;
+        write_atomic(path, &json)
+    }
+
+    /// Progress for the entries of `lines`, carrying over the outcome of
+    /// identical entries from `saved`
+    pub fn new(source: PathBuf, lines: &[ImportLine], saved: Option<&ImportProgress>) -> Self {
+        let mut previous: Vec<&EntryProgress> = saved.map(|saved| saved.entries.iter().collect()).unwrap_or_default();
+        let entries = lines
+            .iter()
+            .map(|line| {
+                let carried = previous
+                    .iter()
+                    .position(|entry| entry.sha256 == line.sha256)
+                    .map(|index| previous.remove(index));
+                EntryProgress {
+                    line: line.number,
+                    sha256: line.sha256.clone(),
+                    ..carried.cloned().unwrap_or_default()
+                }
+            })
+            .collect();
+        Self { source, entries }
+    }
+
+    pub fn count(&self, status: ImportStatus) -> usize {
+        self.entries.iter().filter(|entry| entry.status == status).count()
+    }
+
+    /// Whether any entry left the machine, so starting over would
+    /// duplicate it
+    pub fn has_started(&self) -> bool {
+        self.count(ImportStatus::Pending) < self.entries.len()
+    }
+}
+
+/// An entry read from an import file
+#[derive(Debug, Clone)]
+pub struct ImportLine {
+    pub number: usize,
+    pub sha256: String,
+    pub request: SubmitRequest,
+}
+
+/// Entries of an import file: one JSON object per line with the fields of
+/// `faultnote submit`. Blank lines and lines starting with `#` are skipped.
+pub fn parse(text: &str) -> Result<Vec<ImportLine>, String> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| {
+            let request: SubmitRequest =
+                serde_json::from_str(line).map_err(|e| format!("Line {}: {}", number, e))?;
+            if request.error.trim().is_empty() {
+                return Err(format!("Line {}: \"error\" is empty", number));
+            }
+            Ok(ImportLine {
+                number,
+                sha256: sha256_hex(line.as_bytes()),
+                request,
+            })
+        })
+        .collect()
+}
+
+/// Summary of an import run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Imported {
+    pub progress: ImportProgress,
+    /// Entries already done by an earlier run and skipped
+    pub skipped: usize,
+}
+
+/// How `run` treats an import file
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// Page or database for lines without their own `page`
+    pub page: Option<String>,
+    /// Continue an interrupted import instead of refusing it
+    pub resume: bool,
+    /// Also compare with the entries already on each target page, not only
+    /// the local history
+    pub check_page: bool,
+    /// Send entries even when they look like duplicates
+    pub force: bool,
+}
+
+/// Import the entries of `file`. Without `resume`, a file already partly
+/// imported is refused rather than sent twice.
+pub async fn run(file: &Path, options: &ImportOptions) -> Result<Imported, Failure> {
+    let text = fs::read_to_string(file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+    let lines = parse(&text).map_err(Failure::validation)?;
+    let progress_path = ImportProgress::path_for(file).ok_or("No data directory available")?;
+    let saved = ImportProgress::load(&progress_path)?;
+    if let Some(saved) = saved.as_ref().filter(|saved| saved.has_started() && !options.resume) {
+        return Err(Failure::validation(format!(
+            "{} was imported before ({} of {} entries sent); use --resume to send only the rest",
+            file.display(),
+            saved.count(ImportStatus::Sent) + saved.count(ImportStatus::Queued),
+            saved.entries.len()
+        )));
+    }
+
+    let mut progress = ImportProgress::new(file.to_path_buf(), &lines, saved.as_ref());
+    let todo: Vec<usize> = (0..lines.len())
+        .filter(|&index| match progress.entries[index].status {
+            ImportStatus::Pending => true,
+            ImportStatus::Duplicate => options.force,
+            ImportStatus::Sent | ImportStatus::Queued => false,
+        })
+        .collect();
+    let skipped = lines.len() - todo.len();
+    if todo.is_empty() {
+        return Ok(Imported { progress, skipped });
+    }
+
+    let mut app = load_app()?;
+    // Duplicate checks, fallbacks and retries are paced along with entries
+    let client = connect(&app)?.paced(RateLimiter::notion());
+    let pages = fetch_pages(&client)
+        .await
+        .map_err(|e| Failure::new(FailureClass::for_request(&e), format!("Failed to fetch pages: {}", e)))?;
+    let show_progress = io::stderr().is_terminal();
+    let mut batch = BatchProgress::new(todo.len(), Instant::now());
+    let mut on_page = HashMap::new();
+
+    for index in todo {
+        let ImportLine { number, request, .. } = &lines[index];
+        let wanted = request.page.as_deref().or(options.page.as_deref());
+        let entry = &mut progress.entries[index];
+        let page = wanted.and_then(|wanted| find_page(&pages, wanted));
+        let duplicate = match &page {
+            Some(page) if !options.force => {
+                duplicate_of(&app, &client, page, &request.entry(), options.check_page, &mut on_page).await?
+            }
+            _ => None,
+        };
+        match (page, duplicate) {
+            (Some(_), Some(reason)) => {
+                entry.status = ImportStatus::Duplicate;
+                entry.error = Some(reason);
+            }
+            (Some(page), None) => {
+                let hash = request.entry().content_hash();
+                if let Some(hashes) = on_page.get_mut(&page.id) {
+                    hashes.insert(hash);
+                }
+                let queued = app.queue.len();
+                match submit::submit(&mut app, &client, page, request.clone()).await {
+                    Ok(submitted) => {
+                        entry.status = ImportStatus::Sent;
+                        entry.url = Some(submitted.url());
+                        entry.error = None;
+                    }
+                    Err(e) => {
+                        if app.queue.len() > queued {
+                            entry.status = ImportStatus::Queued;
+                        }
+                        entry.error = Some(e.message);
+                    }
+                }
+            }
+            (None, _) => {
+                entry.error = Some(match wanted {
+                    Some(wanted) => format!("No page or database matches \"{}\"", wanted),
+                    None => "No page given; add \"page\" to the line or pass --page".to_string(),
+                });
+            }
+        }
+        batch.done += 1;
+        if show_progress {
+            let mark = match (entry.status, &entry.error) {
+                (ImportStatus::Sent, _) => "✓".to_string(),
+                (ImportStatus::Duplicate, Some(reason)) => format!("skipped, {}", reason),
+                (_, Some(error)) => format!("✗ {}", error),
+                (_, None) => "✗".to_string(),
+            };
+            eprintln!("line {}: {} ({})", number, mark, batch.label(Instant::now()));
+        }
+        progress.save(&progress_path)?;
+    }
+    Ok(Imported { progress, skipped })
+}
+
+/// Why `entry` would repeat one already logged to `page`: a match in the
+/// local history, or with `check_page` among the entries on the page
+/// (fetched once per page into `on_page`). `None` when it is new.
+async fn duplicate_of(
+    app: &AppState,
+    client: &NotionClient,
+    page: &PageInfo,
+    entry: &FaultLogEntry,
+    check_page: bool,
+    on_page: &mut HashMap<String, HashSet<String>>,
+) -> Result<Option<String>, Failure> {
+    let hash = entry.content_hash();
+    if app.history.contains(&page.id, &hash)? {
+        return Ok(Some("already in the local history".to_string()));
+    }
+    // Database rows are read back as properties, not entries
+    if !check_page || page.is_database() {
+        return Ok(None);
+    }
+    if !on_page.contains_key(&page.id) {
+        let logged = fetch_entries(client, &page.id, &app.template_for(&page.id))
+            .await
+            .map_err(|e| Failure::new(FailureClass::for_request(&e), format!("Failed to read {}: {}", page.title, e)))?;
+        let hashes = logged.iter().map(|logged| logged.entry.content_hash()).collect();
+        on_page.insert(page.id.clone(), hashes);
+    }
+    let found = on_page.get(&page.id).is_some_and(|hashes| hashes.contains(&hash));
+    Ok(found.then(|| format!("already on {}", page.title)))
+}
+
+/// An import where entries are left: partial when others went through,
+/// else a validation failure
+pub fn failure(imported: &Imported) -> Option<Failure> {
+    let progress = &imported.progress;
+    let pending = progress.count(ImportStatus::Pending);
+    if pending == 0 {
+        return None;
+    }
+    let first = progress
+        .entries
+        .iter()
+        .find(|entry| entry.status == ImportStatus::Pending)
+        .and_then(|entry| Some(format!("line {}: {}", entry.line, entry.error.as_deref()?)))
+        .unwrap_or_default();
+    let class = if pending < progress.entries.len() {
+        FailureClass::Partial
+    } else {
+        FailureClass::Validation
+    };
+    Some(Failure::new(
+        class,
+        format!("{} of {} entries not imported; fix them and rerun with --resume ({})", pending, progress.entries.len(), first),
+    ))
+}
@@ -0,0 +1,367 @@
+// Key bindings: actions mapped to keys, overridable from `[keys]` in the config
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// Something a key can do on the main screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    // Normal mode
+    Quit,
+    ToggleFocus,
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    FilterPages,
+    CheckPage,
+    EditMode,
+    Submit,
+    ToggleMermaid,
+    ToggleDiff,
+    PickLanguage,
+    PickTemplate,
+    CycleStatus,
+    CycleSeverity,
+    Undo,
+    OpenQueue,
+    RetryQueue,
+    History,
+    ViewPage,
+    PreviewPage,
+    PickRow,
+    Relations,
+    PageSettings,
+    CopyField,
+    CopyEntry,
+    ExternalEditor,
+    AddSubEntry,
+    NextLink,
+    PreviousLink,
+    OpenLink,
+    CopyLink,
+    FindTrace,
+    ClearInputs,
+    ClearStatus,
+    RefreshPages,
+    ToggleMini,
+    StatusLog,
+    ToggleZen,
+    Attach,
+    NewPage,
+    // Editing mode
+    ExitEdit,
+    NextField,
+    FieldUp,
+    FieldDown,
+    Paste,
+    UndoEdit,
+    RedoEdit,
+}
+
+impl Action {
+    /// Actions that apply while typing in a field; everything else is normal mode
+    pub fn is_editing(self) -> bool {
+        matches!(
+            self,
+            Action::ExitEdit
+                | Action::NextField
+                | Action::FieldUp
+                | Action::FieldDown
+                | Action::Paste
+                | Action::UndoEdit
+                | Action::RedoEdit
+        )
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::ToggleFocus => "toggle_focus",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::FilterPages => "filter_pages",
+            Action::CheckPage => "check_page",
+            Action::EditMode => "edit_mode",
+            Action::Submit => "submit",
+            Action::ToggleMermaid => "toggle_mermaid",
+            Action::ToggleDiff => "toggle_diff",
+            Action::PickLanguage => "pick_language",
+            Action::PickTemplate => "pick_template",
+            Action::CycleStatus => "cycle_status",
+            Action::CycleSeverity => "cycle_severity",
+            Action::Undo => "undo",
+            Action::OpenQueue => "open_queue",
+            Action::RetryQueue => "retry_queue",
+            Action::History => "history",
+            Action::ViewPage => "view_page",
+            Action::PreviewPage => "preview_page",
+            Action::PickRow => "pick_row",
+            Action::Relations => "relations",
+            Action::PageSettings => "page_settings",
+            Action::CopyField => "copy_field",
+            Action::CopyEntry => "copy_entry",
+            Action::ExternalEditor => "external_editor",
+            Action::AddSubEntry => "add_sub_entry",
+            Action::NextLink => "next_link",
+            Action::PreviousLink => "previous_link",
+            Action::OpenLink => "open_link",
+            Action::CopyLink => "copy_link",
+            Action::FindTrace => "find_trace",
+            Action::ClearInputs => "clear_inputs",
+            Action::ClearStatus => "clear_status",
+            Action::RefreshPages => "refresh_pages",
+            Action::ToggleMini => "toggle_mini",
+            Action::StatusLog => "status_log",
+            Action::ToggleZen => "toggle_zen",
+            Action::Attach => "attach",
+            Action::NewPage => "new_page",
+            Action::ExitEdit => "exit_edit",
+            Action::NextField => "next_field",
+            Action::FieldUp => "field_up",
+            Action::FieldDown => "field_down",
+            Action::Paste => "paste",
+            Action::UndoEdit => "undo_edit",
+            Action::RedoEdit => "redo_edit",
+        }
+    }
+}
+
+/// Built-in bindings, in the order shown in the command bar
+const DEFAULT_BINDINGS: &[(Action, &[&str])] = &[
+    (Action::Quit, &["q", "Q"]),
+    (Action::ToggleFocus, &["Tab"]),
+    (Action::Up, &["Up", "k"]),
+    (Action::Down, &["Down", "j"]),
+    (Action::Left, &["Left"]),
+    (Action::Right, &["Right"]),
+    (Action::PageUp, &["PageUp"]),
+    (Action::PageDown, &["PageDown"]),
+    (Action::First, &["Home"]),
+    (Action::Last, &["End"]),
+    (Action::FilterPages, &["/"]),
+    (Action::CheckPage, &["Space"]),
+    (Action::EditMode, &["e", "i"]),
+    (Action::Submit, &["Enter"]),
+    (Action::ToggleMermaid, &["m"]),
+    (Action::ToggleDiff, &["d"]),
+    (Action::PickLanguage, &["L"]),
+    (Action::PickTemplate, &["t"]),
+    (Action::CycleStatus, &["s"]),
+    (Action::CycleSeverity, &["!"]),
+    (Action::Undo, &["u"]),
+    (Action::OpenQueue, &["o"]),
+    (Action::RetryQueue, &["R"]),
+    (Action::History, &["h"]),
+    (Action::ViewPage, &["v"]),
+    (Action::PreviewPage, &["P"]),
+    (Action::PickRow, &["b"]),
+    (Action::Relations, &["l"]),
+    (Action::PageSettings, &["p"]),
+    (Action::CopyField, &["y"]),
+    (Action::CopyEntry, &["Y"]),
+    (Action::ExternalEditor, &["E"]),
+    (Action::AddSubEntry, &["A"]),
+    (Action::NextLink, &["]"]),
+    (Action::PreviousLink, &["["]),
+    (Action::OpenLink, &["g"]),
+    (Action::CopyLink, &["G"]),
+    (Action::FindTrace, &["f"]),
+    (Action::ClearInputs, &["c"]),
+    (Action::ClearStatus, &["Esc"]),
+    (Action::RefreshPages, &["r"]),
+    (Action::ToggleMini, &["M"]),
+    (Action::StatusLog, &["S"]),
+    (Action::ToggleZen, &["Z"]),
+    (Action::Attach, &["@"]),
+    (Action::NewPage, &["n"]),
+    (Action::ExitEdit, &["Esc"]),
+    (Action::NextField, &["Tab"]),
+    (Action::FieldUp, &["Up"]),
+    (Action::FieldDown, &["Down"]),
+    (Action::Paste, &["Ctrl+v"]),
+    (Action::UndoEdit, &["Ctrl+z"]),
+    (Action::RedoEdit, &["Ctrl+y"]),
+];
+
+/// One key or several for an action in the config file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KeySpec {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeySpec {
+    fn names(&self) -> &[String] {
+        match self {
+            KeySpec::One(name) => std::slice::from_ref(name),
+            KeySpec::Many(names) => names,
+        }
+    }
+}
+
+/// Which action each key triggers, per mode
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "HashMap<Action, KeySpec>")]
+pub struct KeyBindings {
+    keys: HashMap<Action, Vec<KeyEvent>>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self::with_overrides(&HashMap::new()).expect("default key bindings are valid")
+    }
+}
+
+impl TryFrom<HashMap<Action, KeySpec>> for KeyBindings {
+    type Error = String;
+
+    fn try_from(overrides: HashMap<Action, KeySpec>) -> Result<Self, String> {
+        Self::with_overrides(&overrides)
+    }
+}
+
+impl KeyBindings {
+    /// The defaults with some actions rebound; a rebound action loses its
+    /// default keys. Two actions of the same mode may not share a key.
+    pub fn with_overrides(overrides: &HashMap<Action, KeySpec>) -> Result<Self, String> {
+        let mut keys = HashMap::new();
+        for (action, defaults) in DEFAULT_BINDINGS {
+            let parsed = match overrides.get(action) {
+                Some(spec) => spec
+                    .names()
+                    .iter()
+                    .map(|name| parse_key(name).map_err(|e| format!("keys.{}: {}", action.name(), e)))
+                    .collect::<Result<Vec<_>, _>>()?,
+                None => defaults.iter().map(|name| parse_key(name).expect("valid default key")).collect(),
+            };
+            keys.insert(*action, parsed);
+        }
+
+        let bindings = Self { keys };
+        bindings.check_conflicts()?;
+        Ok(bindings)
+    }
+
+    fn check_conflicts(&self) -> Result<(), String> {
+        let mut seen: HashMap<(bool, (KeyCode, KeyModifiers)), Action> = HashMap::new();
+        for (action, _) in DEFAULT_BINDINGS {
+            for key in &self.keys[action] {
+                if let Some(other) = seen.insert((action.is_editing(), normalize(*key)), *action) {
+                    return Err(format!(
+                        "Key '{}' is bound to both {} and {}",
+                        key_label(key),
+                        other.name(),
+                        action.name()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The normal-mode action bound to a key press
+    pub fn normal_action(&self, key: &KeyEvent) -> Option<Action> {
+        self.find(key, false)
+    }
+
+    /// The editing-mode action bound to a key press; unbound keys type text
+    pub fn editing_action(&self, key: &KeyEvent) -> Option<Action> {
+        self.find(key, true)
+    }
+
+    fn find(&self, key: &KeyEvent, editing: bool) -> Option<Action> {
+        let pressed = normalize(*key);
+        DEFAULT_BINDINGS
+            .iter()
+            .map(|(action, _)| *action)
+            .filter(|action| action.is_editing() == editing)
+            .find(|action| self.keys[action].iter().any(|bound| normalize(*bound) == pressed))
+    }
+
+    /// First key bound to an action, as shown in the command bar
+    pub fn label(&self, action: Action) -> String {
+        self.keys[&action].first().map(key_label).unwrap_or_default()
+    }
+}
+
+/// Compare keys by code and modifiers only; Shift is implied by the
+/// character for letters and symbols
+fn normalize(key: KeyEvent) -> (KeyCode, KeyModifiers) {
+    let mut modifiers = key.modifiers;
+    if matches!(key.code, KeyCode::Char(_)) {
+        modifiers.remove(KeyModifiers::SHIFT);
+    }
+    (key.code, modifiers)
+}
+
+/// Short display name of a key, e.g. "q", "Ctrl+s", "↑", "Enter"
+pub fn key_label(key: &KeyEvent) -> String {
+    let name = match key.code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        other => format!("{:?}", other),
+    };
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        format!("Ctrl+{}", name)
+    } else if key.modifiers.contains(KeyModifiers::ALT) {
+        format!("Alt+{}", name)
+    } else {
+        name
+    }
+}
+
+/// Parse a key name such as `Enter`, `Ctrl+z`, `PageDown` or `j`
+pub fn parse_key(name: &str) -> Result<KeyEvent, String> {
+    let (modifiers, key) = match name.split_once('+') {
+        Some((modifier, key)) if modifier.eq_ignore_ascii_case("ctrl") => (KeyModifiers::CONTROL, key),
+        Some((modifier, key)) if modifier.eq_ignore_ascii_case("alt") => (KeyModifiers::ALT, key),
+        _ => (KeyModifiers::NONE, name),
+    };
+
+    let code = match key {
+        "Enter" => KeyCode::Enter,
+        "Esc" => KeyCode::Esc,
+        "Tab" => KeyCode::Tab,
+        "BackTab" => KeyCode::BackTab,
+        "Backspace" => KeyCode::Backspace,
+        "Delete" => KeyCode::Delete,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "Space" => KeyCode::Char(' '),
+        _ => {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => return Err(format!("Unknown key '{}'", name)),
+            }
+        }
+    };
+    Ok(KeyEvent::new(code, modifiers))
+}
@@ -1,7 +1,51 @@
 // FaultNote Library
-// Exports modules for use in tests and as a library
+// Exports modules for the binary, tests, and library use
 
 pub mod app;
+pub mod app_event;
+pub mod attachments;
+pub mod audit;
+pub mod backup;
+pub mod buffer;
+pub mod capture;
+pub mod cli;
+pub mod clipboard;
+pub mod config;
+pub mod config_file;
+pub mod context;
+pub mod entry_templates;
 pub mod events;
+pub mod export;
+pub mod external_editor;
+pub mod extractors;
+pub mod failure;
+pub mod flush;
+pub mod fields;
+pub mod fsck;
+pub mod fuzzy;
+pub mod import;
+pub mod keys;
+pub mod links;
+pub mod markdown;
+pub mod markup;
+pub mod network;
+pub mod notify;
 pub mod notion;
+pub mod page_loader;
+pub mod page_settings;
+pub mod page_tree;
+pub mod picker;
+pub mod piped;
+pub mod queue;
+pub mod sample;
+pub mod screenshot;
+pub mod script;
+pub mod sink;
+pub mod storage;
+pub mod tags;
+pub mod submit;
+pub mod telemetry;
+pub mod trace_ids;
 pub mod ui;
+pub mod vars;
+pub mod wrap;
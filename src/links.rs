@@ -0,0 +1,105 @@
+// Links found in pasted logs: dashboards, traces and CI runs, listed in a
+// side panel and optionally written to the entry as bookmarks
+use std::process::{Command, Stdio};
+
+use crate::app::FaultLogEntry;
+
+/// Characters that end a sentence or a quote rather than a URL
+const TRAILING: &[char] = &['.', ',', ';', ':', '!', '?', '\'', '"', '>', '*', '`'];
+
+/// Every `http://` and `https://` URL in `text`, in order, without
+/// duplicates. Punctuation after a URL and a closing bracket without an
+/// opening one in the URL are left out.
+pub fn find_links(text: &str) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    let mut rest = text;
+    while let Some(start) = find_scheme(rest) {
+        let candidate = &rest[start..];
+        let end = candidate
+            .find(|c: char| c.is_whitespace() || c.is_control() || matches!(c, '<' | '"' | '`'))
+            .unwrap_or(candidate.len());
+        let link = trim_link(&candidate[..end]);
+        rest = &candidate[end..];
+        // A bare scheme is not a link
+        if link.len() > "https://".len() && !links.iter().any(|known| known == link) {
+            links.push(link.to_string());
+        }
+    }
+    links
+}
+
+/// Links in the Error field, then in the Code field
+pub fn entry_links(entry: &FaultLogEntry) -> Vec<String> {
+    links_in([entry.error.as_str(), entry.code.as_deref().unwrap_or_default()])
+}
+
+/// Links in several texts, in order, without duplicates
+pub fn links_in<'a>(texts: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    for link in texts.into_iter().flat_map(find_links) {
+        if !links.contains(&link) {
+            links.push(link);
+        }
+    }
+    links
+}
+
+/// Open `url` in the default browser
+pub fn open(url: &str) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let mut command = Command::new("xdg-open");
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|mut child| {
+            // Reap the opener once it hands the URL over
+            std::thread::spawn(move || child.wait());
+        })
+        .map_err(|e| format!("Failed to open {}: {}", url, e))
+}
+
+fn find_scheme(text: &str) -> Option<usize> {
+    let http = text.find("http://");
+    let https = text.find("https://");
+    match (http, https) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn trim_link(mut link: &str) -> &str {
+    loop {
+        let trimmed = link.trim_end_matches(TRAILING);
+        let trimmed = match trimmed.chars().last() {
+            Some(close @ (')' | ']' | '}')) => {
+                let open = match close {
+                    ')' => '(',
+                    ']' => '[',
+                    _ => '{',
+                };
+                // Keep brackets that belong to the URL, as in wiki links
+                if trimmed.matches(open).count() < trimmed.matches(close).count() {
+                    &trimmed[..trimmed.len() - 1]
+                } else {
+                    trimmed
+                }
+            }
+            _ => trimmed,
+        };
+        if trimmed == link {
+            return link;
+        }
+        link = trimmed;
+    }
+}
@@ -3,81 +3,258 @@ use std::io;
 
 use crossterm::{
     execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use ratatui::{Terminal, backend::CrosstermBackend};
+use chrono::Utc;
+use clap::Parser;
+use ratatui::{Terminal, TerminalOptions, Viewport, backend::CrosstermBackend};
+use tracing::Instrument;
 
-mod app;
-mod events;
-mod notion;
-mod ui;
+use faultnote::app::{AppState, FocusArea, PageInfo, TargetKind};
+use faultnote::app_event::{AppEvent, EventBus, spawn_input};
+use faultnote::audit::AuditLog;
+use faultnote::cli::{self, Cli};
+use faultnote::config::Config;
+use faultnote::context::GitContext;
+use faultnote::network::ConnectivityMonitor;
+use faultnote::notion::client::{NotionClient, create_notion_client};
+use faultnote::notion::tokens::TokenMap;
+use faultnote::page_loader::PageLoader;
+use faultnote::page_settings::PageSettingsStore;
+use faultnote::piped;
+use faultnote::queue::SubmissionQueue;
+use faultnote::script::{Script, run_step};
+use faultnote::sink::{FaultSink, Sink, SinkKind};
+use faultnote::storage::History;
+use faultnote::submit;
+use faultnote::telemetry;
+use faultnote::vars::detect_project;
+use faultnote::{events, external_editor, screenshot, ui};
 
-use app::{AppState, PageInfo};
-use notion::client::{NotionClient, create_notion_client, fetch_pages};
+/// Rows used when rendering inline without an alternate screen
+const INLINE_HEIGHT: u16 = 30;
 
 #[tokio::main]
 async fn main() -> io::Result<()> {
+    // Subcommands run without the TUI
+    let args = Cli::parse();
+    let telemetry_guard = telemetry::init(args.trace.as_deref()).map_err(io::Error::other)?;
+    if let Some(command) = args.command {
+        let result = cli::run(command, args.format).await;
+        // Flush spans before exiting with the failure's status
+        drop(telemetry_guard);
+        if let Err(failure) = result {
+            eprintln!("Error: {}", failure);
+            std::process::exit(failure.class.exit_code());
+        }
+        return Ok(());
+    }
+
+    // Load the replay script up front so a typo fails before the TUI starts
+    let mut script = match &args.script {
+        Some(path) => Some(Script::load(path).map_err(io::Error::other)?),
+        None => None,
+    };
+
+    // Output piped in (`cargo build 2>&1 | faultnote`) is read before the
+    // TUI starts; scripted runs keep stdin to themselves
+    let piped = match args.script {
+        Some(_) => None,
+        None => piped::read_stdin()?,
+    };
+
     // Initialize the application
     let mut app = AppState::new();
+    if let Some(path) = AuditLog::default_path() {
+        app.audit_log = AuditLog::new(path);
+    }
+    app.git_context = GitContext::current();
 
-    // Try to create Notion client and fetch pages
-    let notion_client = match create_notion_client() {
-        Ok(client) => {
-            app.set_status("Connected to Notion API");
-            Some(client)
+    // Load user configuration, falling back to defaults on error
+    let config_error = match Config::load() {
+        Ok(config) => {
+            app.config = config;
+            None
         }
-        Err(e) => {
-            app.set_error(format!("Notion API error: {}. Using demo pages.", e));
-            // Add demo pages as fallback
-            app.set_pages(vec![
-                PageInfo {
-                    id: "demo-1".to_string(),
-                    title: "Demo: Project Errors".to_string(),
-                },
-                PageInfo {
-                    id: "demo-2".to_string(),
-                    title: "Demo: Bug Tracker".to_string(),
-                },
-            ]);
+        Err(e) => Some(e),
+    };
+
+    // Restore submissions that failed in earlier sessions
+    let queue_error = match SubmissionQueue::default_path().map(SubmissionQueue::load) {
+        Some(Ok(queue)) => {
+            app.queue = queue;
             None
         }
+        Some(Err(e)) => Some(e),
+        None => None,
     };
 
-    // Fetch actual pages from Notion if client is available
-    if let Some(ref client) = notion_client {
-        app.set_status("Fetching pages from Notion...");
-        match fetch_pages(client).await {
-            Ok(pages) => {
-                if pages.is_empty() {
-                    app.set_status("No pages found. Create a page in Notion first.");
-                } else {
-                    app.set_success(format!("Loaded {} pages from Notion", pages.len()));
-                    app.set_pages(pages);
-                }
+    // Keep a searchable local copy of every entry
+    let history_error = match History::default_path().map(History::open) {
+        Some(Ok(history)) => {
+            app.history = history;
+            // Drop what is beyond `[history]` retention
+            app.history.prune(&app.config.history, Utc::now(), false).err()
+        }
+        Some(Err(e)) => Some(e),
+        None => None,
+    };
+
+    // Remember which token works for which page across sessions
+    let token_map_error = match TokenMap::default_path().map(TokenMap::load) {
+        Some(Ok(token_map)) => {
+            app.token_map = token_map;
+            None
+        }
+        Some(Err(e)) => Some(e),
+        None => None,
+    };
+
+    // Formatting remembered per page, applied when a page is selected
+    let page_settings_error = match PageSettingsStore::default_path().map(PageSettingsStore::load) {
+        Some(Ok(page_settings)) => {
+            app.page_settings = page_settings;
+            None
+        }
+        Some(Err(e)) => Some(e),
+        None => None,
+    };
+
+    if let Some(kind) = args.sink {
+        app.config.sink.kind = kind;
+    }
+    app.wanted_page = args.page.clone();
+    // A link already names the target
+    if app.wanted_page.is_none() && args.target.is_none() {
+        app.wanted_page = app.config.default_page.clone();
+    }
+
+    // Try to create Notion client and fetch pages; another sink lists its
+    // targets instead
+    let mut token_warnings = Vec::new();
+    let notion_client = if app.config.sink.kind != SinkKind::Notion {
+        connect_sink(&mut app).await;
+        None
+    } else {
+        match create_notion_client(&app.config.tokens) {
+            Ok((client, skipped)) => {
+                app.set_status("Connected to Notion API");
+                token_warnings = skipped;
+                Some(client)
+            }
+            Err(e) => {
+                app.set_error(format!("Notion API error: {}. Using demo pages.", e));
+                // Add demo pages as fallback
+                app.set_pages(vec![
+                    PageInfo {
+                        id: "demo-1".to_string(),
+                        title: "Demo: Project Errors".to_string(),
+                        kind: TargetKind::Page,
+                        parent: None,
+                    },
+                    PageInfo {
+                        id: "demo-2".to_string(),
+                        title: "Demo: Bug Tracker".to_string(),
+                        kind: TargetKind::Page,
+                        parent: None,
+                    },
+                ]);
+                None
             }
+        }
+    };
+
+    // Input, network results and ticks all reach the main loop here
+    let mut bus = EventBus::new();
+    app.events = Some(bus.sender());
+
+    // Fetch pages from Notion in the background so the TUI opens at once;
+    // the integration's capabilities are checked and entries left in the
+    // queue are retried once they arrive
+    let mut page_loader = notion_client.clone().map(|client| PageLoader::new(client, bus.sender()));
+    // A link names the target itself; the page list waits for `r`
+    let target = match (&args.target, &notion_client) {
+        (Some(link), Some(client)) => match submit::resolve_target(client, link).await {
+            Ok(page) => Some(page),
             Err(e) => {
-                app.set_error(format!("Failed to fetch pages: {}", e));
+                app.set_error(e);
+                None
             }
+        },
+        (Some(_), None) => {
+            app.set_error("--target needs a Notion connection");
+            None
         }
+        (None, _) => None,
+    };
+    if let Some(page) = target {
+        app.set_status(format!("Logging to {} (r loads the other pages)", page.title));
+        app.set_pages(vec![page]);
+        app.current_focus = FocusArea::InputSection;
+    } else if let Some(loader) = page_loader.as_mut() {
+        loader.refresh();
+        app.pages_loading = true;
+        app.set_loading("Loading pages from Notion…");
+    }
+
+    if let Some(output) = &piped {
+        piped::prefill(&mut app, output, args.error_only);
+    }
+    for (field, value) in &args.fields {
+        *app.field_mut(*field) = value.as_str().into();
+    }
+    app.mini = args.mini;
+    app.inline = args.inline || std::env::var("TERM").is_ok_and(|term| term == "dumb");
+    if args.capture {
+        app.start_capture();
+    }
+
+    // Surface config problems last so they are not overwritten
+    for warning in app.config.warnings.clone().into_iter().chain(token_warnings) {
+        app.set_status(warning);
+    }
+    if let Some(e) = config_error {
+        app.set_error(format!("{}. Using default settings.", e));
+    }
+    if let Some(e) = queue_error.or(token_map_error).or(history_error).or(page_settings_error) {
+        app.set_error(format!("{} (run `faultnote fsck` to repair)", e));
     }
 
     // Setup terminal
     enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let mut terminal = setup_terminal(&mut app)?;
 
     // Clear the terminal
     terminal.clear()?;
 
+    // Ping the API so the queue is flushed when the network returns, and
+    // say in the status line when a request waits to retry; every request
+    // goes to the activity log
+    if let Some(client) = &notion_client {
+        ConnectivityMonitor::spawn(client.clone()).forward(bus.sender());
+        client.retry_watcher().forward(bus.sender());
+        client.activity.forward(bus.sender());
+    }
+
+    // Scripted runs replay their keys instead of reading the terminal
+    if script.is_none() {
+        spawn_input(bus.sender(), bus.input_pause());
+    }
+
     // Main application loop
-    let result = run_app(&mut terminal, &mut app, notion_client.as_ref()).await;
+    let result = run_app(
+        &mut terminal,
+        &mut app,
+        notion_client.as_ref(),
+        &mut bus,
+        page_loader.as_mut(),
+        script.as_mut(),
+    )
+    .await;
 
     // Restore terminal on exit
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+    restore_terminal(&mut terminal, &app)?;
 
     // Handle any errors from the app
     if let Err(err) = result {
@@ -89,21 +266,207 @@ async fn main() -> io::Result<()> {
     Ok(())
 }
 
+/// Enter the alternate screen, or fall back to an inline viewport when
+/// `--inline` asked for it or the terminal lacks one (some IDE consoles)
+fn setup_terminal(app: &mut AppState) -> io::Result<Terminal<CrosstermBackend<io::Stdout>>> {
+    let mut stdout = io::stdout();
+    // Pastes arrive as one event instead of a key per character; terminals
+    // without bracketed paste keep sending keys
+    let _ = execute!(stdout, EnableBracketedPaste);
+    // Clicks and the wheel reach the app; Shift+drag still selects text in
+    // most terminals
+    let _ = execute!(stdout, EnableMouseCapture);
+    if !app.inline && execute!(stdout, EnterAlternateScreen).is_ok() {
+        return Terminal::new(CrosstermBackend::new(stdout));
+    }
+
+    app.inline = true;
+    let (_, rows) = terminal::size().unwrap_or((80, INLINE_HEIGHT));
+    Terminal::with_options(
+        CrosstermBackend::new(stdout),
+        TerminalOptions {
+            viewport: Viewport::Inline(rows.min(INLINE_HEIGHT)),
+        },
+    )
+}
+
+/// Log to the configured sink: list its repositories or notes as the pages
+async fn connect_sink(app: &mut AppState) {
+    let project = app.config.project.clone().unwrap_or_else(detect_project);
+    let sink = match Sink::from_config(&app.config.sink, &project) {
+        Some(Ok(sink)) => sink,
+        Some(Err(e)) => {
+            app.set_error(format!("{}: {}", app.config.sink.kind.name(), e));
+            return;
+        }
+        None => return,
+    };
+    match sink.list_targets().await {
+        Ok(targets) => {
+            let noun = match sink {
+                Sink::GitHub(_) => "repositories",
+                Sink::Obsidian(_) => "notes",
+            };
+            app.set_status(format!("Connected to {} ({} {})", sink.name(), targets.len(), noun));
+            app.set_pages(targets);
+            app.select_wanted_page();
+        }
+        Err(e) => app.set_error(format!("{}: {}", sink.name(), e)),
+    }
+    app.sink = Some(sink);
+}
+
+/// Give the terminal back to the shell
+fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &AppState) -> io::Result<()> {
+    let _ = execute!(terminal.backend_mut(), DisableBracketedPaste, DisableMouseCapture);
+    disable_raw_mode()?;
+    if app.inline {
+        terminal.clear()?;
+    } else {
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    }
+    terminal.show_cursor()
+}
+
+/// Restore the terminal and stop like any job on Ctrl+Z; after `fg`
+/// (SIGCONT) re-enter raw mode and force a full redraw
+#[cfg(unix)]
+fn suspend(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut AppState) -> io::Result<()> {
+    restore_terminal(terminal, app)?;
+    // SAFETY: raise only sends a signal to this process; SIGTSTP stops it
+    // and execution continues here once the shell sends SIGCONT
+    unsafe {
+        libc::raise(libc::SIGTSTP);
+    }
+    resume_terminal(terminal, app)
+}
+
+/// Take the terminal back after `restore_terminal` and redraw it all
+fn resume_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &AppState) -> io::Result<()> {
+    enable_raw_mode()?;
+    let _ = execute!(terminal.backend_mut(), EnableBracketedPaste, EnableMouseCapture);
+    if !app.inline {
+        execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    }
+    terminal.clear()
+}
+
+#[cfg(not(unix))]
+fn suspend(_terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut AppState) -> io::Result<()> {
+    app.set_error("Suspend is not supported on this platform");
+    Ok(())
+}
+
+/// Open the active field in the user's editor with the terminal handed
+/// over, then load what was saved
+fn edit_externally(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut AppState,
+    bus: &EventBus,
+) -> io::Result<()> {
+    let field = app.active_field();
+    let text = app.field(field).to_string();
+    let name = format!("{}.md", field.default_label().to_lowercase());
+    let editor = external_editor::editor_command();
+
+    // The input thread would otherwise read keys meant for the editor
+    let pause = bus.input_pause();
+    pause.pause();
+    restore_terminal(terminal, app)?;
+    let edited = external_editor::edit(&text, &name, &editor);
+    let resumed = resume_terminal(terminal, app);
+    pause.resume();
+    resumed?;
+
+    match edited {
+        Ok(Some(text)) => app.apply_external_edit(&text),
+        Ok(None) => app.set_status(format!("{} exited with an error; the field is unchanged", editor)),
+        Err(e) => app.set_error(e),
+    }
+    Ok(())
+}
+
 /// Main application loop
 async fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     app: &mut AppState,
     notion_client: Option<&NotionClient>,
+    bus: &mut EventBus,
+    mut page_loader: Option<&mut PageLoader>,
+    mut script: Option<&mut Script>,
 ) -> io::Result<()> {
     while app.is_running() {
         // Draw the UI
-        terminal.draw(|frame| {
+        let draw_span = tracing::trace_span!("draw").entered();
+        let frame = terminal.draw(|frame| {
             ui::render(frame, app);
         })?;
+        drop(draw_span);
+
+        // F12: dump exactly what is on screen for bug reports
+        if app.screenshot_requested {
+            app.screenshot_requested = false;
+            match screenshot::save(frame.buffer) {
+                Ok(path) => app.set_success(format!("Screenshot saved to {}", path.display())),
+                Err(e) => app.set_error(e),
+            }
+        }
+
+        // Wait for the next event, or replay the next scripted step once
+        // the events already sent are handled, quitting when it runs out
+        let event = match script.as_mut() {
+            Some(script) => match bus.try_next() {
+                Some(event) => event,
+                None => {
+                    match script.next_step() {
+                        Some((line, step)) => run_step(app, notion_client, line, step)
+                            .await
+                            .map_err(io::Error::other)?,
+                        None => app.quit(),
+                    }
+                    AppEvent::Tick
+                }
+            },
+            None => bus.next().await,
+        };
+        events::handle_event(app, event, notion_client)
+            .instrument(tracing::trace_span!("handle_event"))
+            .await?;
+
+        // Ctrl+Z: hand the terminal back and stop until resumed
+        if app.suspend_requested {
+            app.suspend_requested = false;
+            suspend(terminal, app)?;
+        }
+
+        // E: edit the focused field in $EDITOR
+        if app.editor_requested {
+            app.editor_requested = false;
+            if script.is_some() {
+                app.set_error("The external editor is not available in scripted runs");
+            } else {
+                edit_externally(terminal, app, bus)?;
+            }
+        }
 
-        // Handle input events (including submission)
-        events::handle_events(app, notion_client).await?;
+        // Refresh the page list on request; the result arrives as an event
+        if app.refresh_requested {
+            app.refresh_requested = false;
+            match page_loader.as_mut().map(|loader| loader.refresh()) {
+                Some(true) => {
+                    app.pages_loading = true;
+                    app.set_loading("Refreshing pages…");
+                }
+                Some(false) => app.set_status("Pages are already loading"),
+                None if app.sink.is_some() => connect_sink(app).await,
+                None => app.set_error("Notion API not connected. Check your API_KEY in .env"),
+            }
+        }
     }
 
+    // Don't drop a request that is still in flight when quitting
+    events::complete_submission(app).await;
+    events::complete_retry(app).await;
+
     Ok(())
 }
@@ -0,0 +1,90 @@
+// Entries as Markdown, for pasting into chats, issues and PR descriptions
+use crate::app::FaultLogEntry;
+use crate::fields::InputField;
+use crate::links::entry_links;
+use crate::notion::client::{context_pairs, verification_steps};
+use crate::notion::template::BlockTemplate;
+
+/// An entry as Markdown: a heading like the one in Notion, then each
+/// filled-in field under its section heading
+pub fn entry_markdown(entry: &FaultLogEntry, template: &BlockTemplate) -> String {
+    format!("## {}\n\n{}", entry_title(entry, template), entry_sections(entry, template))
+}
+
+/// The entry's heading: the template's title, else the error's first line
+pub fn entry_title<'a>(entry: &'a FaultLogEntry, template: &'a BlockTemplate) -> &'a str {
+    let title = template
+        .title
+        .as_deref()
+        .unwrap_or_else(|| entry.error.lines().next().unwrap_or_default());
+    title.trim()
+}
+
+/// The sections of `entry_markdown` without the heading, for places that
+/// show the title on their own, like an issue. Grouped entries follow
+/// under numbered headings, their sections one level down.
+pub fn entry_sections(entry: &FaultLogEntry, template: &BlockTemplate) -> String {
+    let mut sections = field_sections(entry, template, "###");
+    for (number, sub_entry) in entry.sub_entries.iter().enumerate() {
+        let title = sub_entry.error.lines().next().unwrap_or_default().trim();
+        sections.push(format!("---\n\n### {}. {}", number + 1, title));
+        sections.extend(field_sections(sub_entry, template, "####"));
+    }
+    sections.join("\n\n") + "\n"
+}
+
+fn field_sections(entry: &FaultLogEntry, template: &BlockTemplate, level: &str) -> Vec<String> {
+    let mut sections = Vec::new();
+    for field in InputField::SECTIONS {
+        if let Some(body) = field_markdown(entry, field) {
+            sections.push(format!("{} {}\n\n{}", level, template.heading(field), body));
+        }
+    }
+    let links = if template.related_links { entry_links(entry) } else { Vec::new() };
+    if !links.is_empty() {
+        let list: Vec<String> = links.iter().map(|link| format!("- <{}>", link)).collect();
+        sections.push(format!("{} {}\n\n{}", level, template.locale.related_links_heading(), list.join("\n")));
+    }
+    if !entry.tags.is_empty() {
+        sections.push(format!("**{}** {}", template.tags_prefix().trim_end(), entry.tags.join(", ")));
+    }
+    sections
+}
+
+/// A field as Markdown, or `None` when it is empty. Code keeps its
+/// language, verification steps become a checklist and context a list.
+pub fn field_markdown(entry: &FaultLogEntry, field: InputField) -> Option<String> {
+    let text = entry.field(field);
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let body = match field {
+        InputField::Code => {
+            let language = entry.language.as_deref().filter(|language| *language != "plain text");
+            format!("```{}\n{}\n```", language.unwrap_or_default(), text)
+        }
+        InputField::Verification => {
+            let steps: Vec<String> = verification_steps(text)
+                .iter()
+                .map(|(step, checked)| format!("- [{}] {}", if *checked { 'x' } else { ' ' }, step))
+                .collect();
+            steps.join("\n")
+        }
+        InputField::Context => {
+            let pairs: Vec<String> = context_pairs(text)
+                .iter()
+                .map(|(key, value)| {
+                    if value.is_empty() {
+                        format!("- {}", key)
+                    } else {
+                        format!("- **{}**: {}", key, value)
+                    }
+                })
+                .collect();
+            pairs.join("\n")
+        }
+        _ => text.to_string(),
+    };
+    Some(body)
+}
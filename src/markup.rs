@@ -0,0 +1,165 @@
+// Helpers for special content inside entry fields (diagrams, equations, diffs)
+
+/// A piece of field text: plain text or a `$$…$$` equation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'a> {
+    Text(&'a str),
+    Equation(&'a str),
+}
+
+/// Split text on `$$…$$` delimiters. An unterminated `$$` is kept as text.
+/// Returns a single empty text segment for empty input so callers always
+/// have something to render.
+pub fn split_equations(text: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("$$") {
+        let after = &rest[start + 2..];
+        let Some(len) = after.find("$$") else {
+            break;
+        };
+        if start > 0 {
+            segments.push(Segment::Text(&rest[..start]));
+        }
+        let expression = after[..len].trim();
+        if !expression.is_empty() {
+            segments.push(Segment::Equation(expression));
+        }
+        rest = &after[len + 2..];
+    }
+
+    if !rest.is_empty() || segments.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    segments
+}
+
+/// If a line holds nothing but a single `$$…$$` equation, return its expression
+pub fn display_equation(line: &str) -> Option<&str> {
+    let inner = line.trim().strip_prefix("$$")?.strip_suffix("$$")?.trim();
+    if inner.is_empty() || inner.contains("$$") {
+        None
+    } else {
+        Some(inner)
+    }
+}
+
+/// Kind of a line in a unified diff, used for coloring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine {
+    Added,
+    Removed,
+    Hunk,
+    Meta,
+    Context,
+}
+
+/// Classify one line of a unified diff
+pub fn classify_diff_line(line: &str) -> DiffLine {
+    if line.starts_with("+++") || line.starts_with("---") || line.starts_with("diff ") {
+        DiffLine::Meta
+    } else if line.starts_with("@@") {
+        DiffLine::Hunk
+    } else if line.starts_with('+') {
+        DiffLine::Added
+    } else if line.starts_with('-') {
+        DiffLine::Removed
+    } else {
+        DiffLine::Context
+    }
+}
+
+/// Heuristic check for unified diff content (e.g. `git diff` output)
+pub fn looks_like_diff(code: &str) -> bool {
+    let mut lines = code.lines().filter(|line| !line.trim().is_empty());
+    let Some(first) = lines.next() else {
+        return false;
+    };
+    first.starts_with("diff --git")
+        || first.starts_with("--- ")
+        || (first.starts_with("@@") && first[2..].contains("@@"))
+}
+
+/// Diagram types Mermaid accepts as the first statement
+const MERMAID_DIAGRAMS: &[&str] = &[
+    "graph",
+    "flowchart",
+    "sequenceDiagram",
+    "classDiagram",
+    "stateDiagram",
+    "stateDiagram-v2",
+    "erDiagram",
+    "journey",
+    "gantt",
+    "pie",
+    "gitGraph",
+    "mindmap",
+    "timeline",
+    "quadrantChart",
+    "requirementDiagram",
+    "C4Context",
+    "sankey-beta",
+    "xychart-beta",
+    "block-beta",
+];
+
+/// Keywords that open a section closed by `end`
+const MERMAID_BLOCK_OPENERS: &[&str] = &[
+    "subgraph", "loop", "alt", "opt", "par", "critical", "break", "rect", "box",
+];
+
+/// Run basic local checks on a Mermaid diagram before it is sent to Notion.
+/// This is not a full parser: it catches the mistakes that make Notion show
+/// a blank diagram (unknown diagram type, unbalanced brackets or `end`s).
+pub fn validate_mermaid(source: &str) -> Result<(), String> {
+    let mut lines = source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("%%"));
+
+    let header = lines.next().ok_or("Mermaid diagram is empty")?;
+    let kind = header.split_whitespace().next().unwrap_or_default();
+    if !MERMAID_DIAGRAMS.contains(&kind) {
+        return Err(format!("Unknown Mermaid diagram type '{}'", kind));
+    }
+
+    let mut open_sections = 0usize;
+    for (number, line) in source.lines().enumerate() {
+        let line = line.trim();
+        let first_word = line.split_whitespace().next().unwrap_or_default();
+        if MERMAID_BLOCK_OPENERS.contains(&first_word) {
+            open_sections += 1;
+        } else if first_word == "end" {
+            open_sections = open_sections
+                .checked_sub(1)
+                .ok_or_else(|| format!("Unexpected 'end' on line {}", number + 1))?;
+        }
+    }
+    if open_sections > 0 {
+        return Err(format!("{} section(s) missing 'end'", open_sections));
+    }
+
+    let mut stack = Vec::new();
+    for c in source.chars() {
+        match c {
+            '(' | '[' | '{' => stack.push(c),
+            ')' | ']' | '}' => {
+                let expected = match c {
+                    ')' => '(',
+                    ']' => '[',
+                    _ => '{',
+                };
+                if stack.pop() != Some(expected) {
+                    return Err(format!("Unbalanced '{}' in Mermaid diagram", c));
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(c) = stack.pop() {
+        return Err(format!("Unclosed '{}' in Mermaid diagram", c));
+    }
+
+    Ok(())
+}
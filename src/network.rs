@@ -0,0 +1,103 @@
+// Connectivity monitoring and health pings against the Notion API, used to
+// flush the offline queue when the network returns
+use std::time::{Duration, Instant};
+
+use tokio::sync::{mpsc, watch};
+use tokio::time;
+use tracing::instrument;
+
+use crate::app_event::AppEvent;
+use crate::notion::client::NotionClient;
+
+/// How often the API is pinged
+const PROBE_INTERVAL: Duration = Duration::from_secs(10);
+/// How long a single ping may take before the network counts as down
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Round trips slower than this mark the connection as degraded
+pub const DEGRADED_LATENCY: Duration = Duration::from_millis(1500);
+
+/// Result of the latest health ping
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Health {
+    pub reachable: bool,
+    /// Round-trip time of the ping, when it got an answer
+    pub latency: Option<Duration>,
+}
+
+impl Health {
+    pub fn online(latency: Duration) -> Self {
+        Self { reachable: true, latency: Some(latency) }
+    }
+
+    pub fn offline() -> Self {
+        Self { reachable: false, latency: None }
+    }
+
+    /// Reachable, but slow enough that submissions may time out and queue
+    pub fn is_degraded(&self) -> bool {
+        self.reachable && self.latency.is_some_and(|latency| latency > DEGRADED_LATENCY)
+    }
+
+    /// Short label for the diagnostics panel
+    pub fn label(&self) -> &'static str {
+        if !self.reachable {
+            "offline"
+        } else if self.is_degraded() {
+            "degraded"
+        } else {
+            "healthy"
+        }
+    }
+}
+
+/// Receives health updates from the background ping task
+pub struct ConnectivityMonitor {
+    receiver: watch::Receiver<Health>,
+}
+
+impl ConnectivityMonitor {
+    /// Spawn a background task that pings the API periodically. The ping
+    /// goes through the client's own connection pool, so DNS and TLS are
+    /// already resolved when the user submits.
+    pub fn spawn(client: NotionClient) -> Self {
+        let (sender, receiver) = watch::channel(Health::online(Duration::ZERO));
+        tokio::spawn(async move {
+            let mut interval = time::interval(PROBE_INTERVAL);
+            loop {
+                interval.tick().await;
+                let health = ping(&client).await;
+                if sender.send(health).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { receiver }
+    }
+
+    /// Send each new ping result to the main loop as `AppEvent::Health`
+    pub fn forward(mut self, events: mpsc::UnboundedSender<AppEvent>) {
+        tokio::spawn(async move {
+            while self.receiver.changed().await.is_ok() {
+                let health = *self.receiver.borrow_and_update();
+                if events.send(AppEvent::Health(health)).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// Time one request to the API. Any HTTP response counts as reachable;
+/// only transport errors and timeouts mean the API is down.
+#[instrument(skip_all, ret)]
+pub async fn ping(client: &NotionClient) -> Health {
+    let started = Instant::now();
+    let request = client
+        .http_client
+        .get(format!("{}/v1/users/me", client.base_url))
+        .send();
+    match time::timeout(PROBE_TIMEOUT, request).await {
+        Ok(Ok(_)) => Health::online(started.elapsed()),
+        _ => Health::offline(),
+    }
+}
@@ -0,0 +1,119 @@
+// Webhook notifications: a short summary of every logged entry, with a
+// link to it, posted to Slack or any service taking JSON
+use std::env;
+use std::time::Duration;
+
+use dotenv::dotenv;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::app::FaultLogEntry;
+
+/// How long a webhook may take before the notification is given up
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `[notify]` in the config; nothing is sent without `url_env`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotifyConfig {
+    /// Environment variable (or `.env` entry) holding the webhook URL, so
+    /// profiles never carry the URL itself
+    pub url_env: Option<String>,
+    pub format: NotifyFormat,
+}
+
+/// Body posted to the webhook
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyFormat {
+    /// `{"text": …}` in Slack's markup, which Mattermost and others accept too
+    #[default]
+    Slack,
+    /// The entry's title, page, link and fields as a JSON object
+    Json,
+}
+
+impl NotifyConfig {
+    /// The webhook to post to, once `url_env` is set. Errors when the
+    /// variable is missing.
+    pub fn webhook_url(&self) -> Option<Result<String, String>> {
+        let name = self.url_env.as_deref().map(str::trim).filter(|name| !name.is_empty())?;
+        dotenv().ok();
+        Some(
+            env::var(name)
+                .ok()
+                .filter(|url| !url.trim().is_empty())
+                .ok_or_else(|| format!("{} not found in environment variables", name)),
+        )
+    }
+}
+
+/// A logged entry as announced
+#[derive(Debug, Clone, Copy)]
+pub struct Notice<'a> {
+    pub title: &'a str,
+    /// Title of the page, database or other target it went to
+    pub page: &'a str,
+    /// Link to the entry; empty when there is none
+    pub url: &'a str,
+    pub entry: &'a FaultLogEntry,
+}
+
+/// The body posted for `notice`
+pub fn payload(format: NotifyFormat, notice: &Notice) -> Value {
+    let entry = notice.entry;
+    match format {
+        NotifyFormat::Slack => {
+            let emoji = entry.severity.map_or("🔴", |severity| severity.emoji());
+            let target = if notice.url.is_empty() {
+                format!("*{}*", escape(notice.page))
+            } else {
+                format!("<{}|{}>", notice.url, escape(notice.page))
+            };
+            let mut text = format!("{} *{}* logged to {}", emoji, escape(notice.title), target);
+            for (label, value) in [("Problem", &entry.problem), ("Fix", &entry.solution)] {
+                if let Some(line) = value.lines().map(str::trim).find(|line| !line.is_empty()) {
+                    text.push_str(&format!("\n*{}:* {}", label, escape(line)));
+                }
+            }
+            json!({ "text": text })
+        }
+        NotifyFormat::Json => json!({
+            "title": notice.title,
+            "page": notice.page,
+            "url": notice.url,
+            "error": entry.error,
+            "problem": entry.problem,
+            "solution": entry.solution,
+            "status": entry.status.name().to_lowercase(),
+            "severity": entry.severity.map(|severity| severity.name().to_lowercase()),
+            "tags": entry.tags,
+        }),
+    }
+}
+
+/// Post `notice` to `url`
+pub async fn send(url: &str, format: NotifyFormat, notice: &Notice<'_>) -> Result<(), String> {
+    let client = Client::builder()
+        .timeout(TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let response = client
+        .post(url)
+        .json(&payload(format, notice))
+        .send()
+        .await
+        .map_err(|e| format!("Webhook request failed: {}", e.without_url()))?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(format!("Webhook returned {}: {}", status.as_u16(), body.trim()))
+}
+
+/// Slack treats `&`, `<` and `>` as markup
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
@@ -0,0 +1,108 @@
+// Notion API calls as they settle, streamed to the activity log so a setup
+// that misbehaves can be followed request by request
+use std::time::Duration;
+
+use reqwest::{Method, Url};
+use tokio::sync::{broadcast, mpsc};
+
+use crate::app_event::AppEvent;
+
+/// Calls kept for a listener that falls behind; older ones are dropped
+const FEED_CAPACITY: usize = 64;
+
+/// A request sent to Notion, reported once its last attempt is answered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiCall {
+    pub method: Method,
+    /// Path of the URL with long ids shortened
+    pub path: String,
+    /// `None` when no response came
+    pub status: Option<u16>,
+    /// Attempts made, retries included
+    pub attempts: u32,
+    /// From the first attempt to the last answer, waits included
+    pub elapsed: Duration,
+}
+
+impl ApiCall {
+    pub fn new(method: Method, url: &Url, status: Option<u16>, attempts: u32, elapsed: Duration) -> Self {
+        Self {
+            method,
+            path: short_path(url.path()),
+            status,
+            attempts,
+            elapsed,
+        }
+    }
+
+    /// Whether Notion refused the request or did not answer
+    pub fn failed(&self) -> bool {
+        self.status.is_none_or(|status| status >= 400)
+    }
+
+    /// e.g. "PATCH /v1/blocks/1a2b3c4d…/children → 200 in 312ms (2 attempts)"
+    pub fn message(&self) -> String {
+        let status = self.status.map_or_else(|| "no response".to_string(), |status| status.to_string());
+        let mut message = format!("{} {} → {} in {}ms", self.method, self.path, status, self.elapsed.as_millis());
+        if self.attempts > 1 {
+            message.push_str(&format!(" ({} attempts)", self.attempts));
+        }
+        message
+    }
+}
+
+/// `path` with page, block and database ids cut to their first 8
+/// characters, which tell them apart in a log
+pub fn short_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            let is_id = segment.len() >= 32 && segment.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+            if is_id { format!("{}…", &segment[..8]) } else { segment.to_string() }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Where every copy of a client reports its calls; calls made while
+/// nobody listens are dropped
+#[derive(Debug, Clone)]
+pub struct ActivityFeed {
+    sender: broadcast::Sender<ApiCall>,
+}
+
+impl Default for ActivityFeed {
+    fn default() -> Self {
+        Self {
+            sender: broadcast::channel(FEED_CAPACITY).0,
+        }
+    }
+}
+
+impl ActivityFeed {
+    pub fn report(&self, call: ApiCall) {
+        let _ = self.sender.send(call);
+    }
+
+    /// Calls reported from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<ApiCall> {
+        self.sender.subscribe()
+    }
+
+    /// Send each call to the main loop as `AppEvent::ApiCall`
+    pub fn forward(&self, events: mpsc::UnboundedSender<AppEvent>) {
+        let mut receiver = self.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(call) => {
+                        if events.send(AppEvent::ApiCall(call)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,86 @@
+// Anchor heading that entries are inserted under on mixed-content pages
+use serde_json::{Value, json};
+use tracing::instrument;
+
+use crate::notion::client::{CheckStatus, NotionClient, NotionError, created_block_ids};
+use crate::notion::entries::{fetch_children, plain_text};
+use crate::notion::template::HeadingLevel;
+
+/// A heading such as "## Fault Log", split into level and text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Anchor {
+    pub level: HeadingLevel,
+    pub text: String,
+}
+
+impl Anchor {
+    /// Parse Markdown-style heading markers; without one the anchor is an h2
+    pub fn parse(spec: &str) -> Self {
+        let spec = spec.trim();
+        let hashes = spec.chars().take_while(|c| *c == '#').count();
+        let level = match hashes {
+            1 => HeadingLevel::H1,
+            3 => HeadingLevel::H3,
+            _ => HeadingLevel::H2,
+        };
+        Self {
+            level,
+            text: spec[hashes..].trim().to_string(),
+        }
+    }
+
+    /// Id of the first heading among `blocks` whose text matches, at any level
+    pub fn find(&self, blocks: &[Value]) -> Option<String> {
+        blocks
+            .iter()
+            .find(|block| {
+                let kind = block.get("type").and_then(Value::as_str).unwrap_or_default();
+                matches!(kind, "heading_1" | "heading_2" | "heading_3")
+                    && plain_text(&block[kind]["rich_text"]).trim() == self.text
+            })
+            .and_then(|block| block.get("id").and_then(Value::as_str))
+            .map(str::to_string)
+    }
+
+    /// The heading block appended when the page has no anchor yet
+    pub fn block(&self) -> Value {
+        let kind = self.level.block_type();
+        json!({
+            "object": "block",
+            "type": kind,
+            kind: {
+                "rich_text": [{ "type": "text", "text": { "content": self.text } }]
+            }
+        })
+    }
+}
+
+/// Id of the anchor heading on a page, appending it at the bottom when
+/// missing; the flag is true when the heading was created
+#[instrument(skip(client), fields(anchor = %anchor.text), err)]
+pub async fn find_or_create_anchor(
+    client: &NotionClient,
+    page_id: &str,
+    anchor: &Anchor,
+) -> Result<(String, bool), NotionError> {
+    let blocks = fetch_children(client, page_id).await?;
+    if let Some(id) = anchor.find(&blocks) {
+        return Ok((id, false));
+    }
+
+    let url = format!("{}/v1/blocks/{}/children", client.base_url, page_id);
+    let response: Value = client
+        .send(client.http_client.patch(&url).json(&json!({ "children": [anchor.block()] })))
+        .await?
+        .check_status()
+        .await?
+        .json()
+        .await?;
+    let id = created_block_ids(&response)
+        .into_iter()
+        .next()
+        .ok_or_else(|| NotionError::Unexpected {
+            message: "the created anchor heading has no id".to_string(),
+        })?;
+    Ok((id, true))
+}
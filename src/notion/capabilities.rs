@@ -0,0 +1,104 @@
+// Integration capability introspection
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+use tracing::instrument;
+
+use crate::failure::FailureClass;
+use crate::notion::client::{CheckStatus, NotionClient, NotionError};
+
+/// Pages tried before giving up on finding one shared with the integration
+pub const PROBE_PAGES: usize = 3;
+
+/// What the integration token is allowed to do, as far as Notion reveals it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    pub bot_name: Option<String>,
+    pub workspace: Option<String>,
+    pub read_content: bool,
+    pub insert_content: bool,
+    pub read_comments: bool,
+}
+
+impl Capabilities {
+    /// Explanation for why submission cannot work, if it cannot
+    pub fn submit_blocker(&self) -> Option<&'static str> {
+        if !self.insert_content {
+            Some(
+                "Integration lacks the 'Insert content' capability. \
+                 Enable it at notion.so/my-integrations → Capabilities",
+            )
+        } else {
+            None
+        }
+    }
+}
+
+/// Pull the bot and workspace names out of a `/v1/users/me` response
+pub fn parse_bot_info(user: &Value) -> (Option<String>, Option<String>) {
+    let name = user.get("name").and_then(Value::as_str).map(str::to_string);
+    let workspace = user
+        .pointer("/bot/workspace_name")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    (name, workspace)
+}
+
+/// Notion answers 403 when a capability is missing and 404 for a page not
+/// shared with the integration, the access problems
+/// `FailureClass::for_http_status` sees; any other status (including
+/// validation errors) means the request got past the check
+pub fn probe_allows(status: StatusCode) -> bool {
+    FailureClass::for_http_status(Some(status.as_u16())) != FailureClass::Auth
+}
+
+/// Query the bot user and probe each capability against the first of
+/// `page_ids` shared with the integration, trying up to `PROBE_PAGES`.
+/// The insert probe appends an empty children list, which writes nothing.
+/// When none of them is shared, nothing is allowed.
+#[instrument(skip(client), err)]
+pub async fn fetch_capabilities(
+    client: &NotionClient,
+    page_ids: &[String],
+) -> Result<Capabilities, NotionError> {
+    let user: Value = client
+        .send(client.http_client.get(format!("{}/v1/users/me", client.base_url)))
+        .await?
+        .check_status()
+        .await?
+        .json()
+        .await?;
+    let (bot_name, workspace) = parse_bot_info(&user);
+    let mut capabilities = Capabilities {
+        bot_name,
+        workspace,
+        ..Default::default()
+    };
+
+    for page_id in page_ids.iter().take(PROBE_PAGES) {
+        let read = client
+            .send(client.http_client.get(format!("{}/v1/blocks/{}/children?page_size=1", client.base_url, page_id)))
+            .await?
+            .status();
+        // Not shared with the integration; another page may be
+        if read == StatusCode::NOT_FOUND {
+            continue;
+        }
+
+        let empty_append = client
+            .http_client
+            .patch(format!("{}/v1/blocks/{}/children", client.base_url, page_id))
+            .json(&json!({ "children": [] }));
+        let insert = client.send(empty_append).await?.status();
+
+        let comments = client
+            .send(client.http_client.get(format!("{}/v1/comments?block_id={}&page_size=1", client.base_url, page_id)))
+            .await?
+            .status();
+
+        capabilities.read_content = probe_allows(read);
+        capabilities.insert_content = probe_allows(insert);
+        capabilities.read_comments = probe_allows(comments);
+        break;
+    }
+    Ok(capabilities)
+}
@@ -1,18 +1,75 @@
 use dotenv::dotenv;
 use reqwest::{
-    Client,
-    header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderValue},
+    Client, RequestBuilder, Response,
+    header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderValue, RETRY_AFTER},
+    multipart::{Form, Part},
 };
 use serde::Deserialize;
 use serde_json::{Value, json};
-use std::{env, time::Duration};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, env, fmt, sync::Arc, time::{Duration, Instant}};
+use tokio::sync::watch;
+use tracing::instrument;
 
-use crate::app::PageInfo;
+pub use crate::app::{Attachment, FaultLogEntry};
+use crate::app::{PageInfo, TargetKind};
+use crate::attachments::is_image;
+use crate::config::TokenConfig;
+use crate::fields::InputField;
+use crate::links::entry_links;
+use crate::markup::{Segment, display_equation, split_equations};
+use crate::notion::activity::{ActivityFeed, ApiCall};
+use crate::notion::anchor::{Anchor, find_or_create_anchor};
+use crate::notion::database::create_row;
+use crate::notion::entries::plain_text;
+use crate::notion::languages::DEFAULT_LANGUAGE;
+use crate::notion::limits::MAX_TEXT_LENGTH;
+use crate::notion::rate_limit::RateLimiter;
+use crate::notion::retry::{MAX_ATTEMPTS, RetryNotice, RetryWatcher, jitter, retry_delay, retry_reason};
+use crate::notion::template::{AppendPosition, BlockTemplate};
+use crate::tags;
+
+/// Most blocks walked up from a page nested in blocks to the page holding
+/// them
+const MAX_BLOCK_DEPTH: usize = 8;
 
 /// Notion API client
+#[derive(Clone)]
 pub struct NotionClient {
     pub base_url: String,
+    /// HTTP client authenticated with the primary token
     pub http_client: Client,
+    /// Every configured token, primary first
+    pub tokens: Vec<ApiToken>,
+    /// Retries waiting on a rate limit or a server error, shared by every
+    /// copy of the client; `None` once the request is settled
+    pub retries: Arc<watch::Sender<Option<RetryNotice>>>,
+    /// Every settled request, for the activity log
+    pub activity: ActivityFeed,
+    /// Paces every attempt, retries included, when set by `paced`
+    pub limiter: Option<RateLimiter>,
+}
+
+/// An HTTP client authenticated with one integration token
+#[derive(Clone)]
+pub struct ApiToken {
+    pub name: String,
+    pub http_client: Client,
+}
+
+/// Blocks created by appending an entry to a page
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AppendedBlocks {
+    pub block_ids: Vec<String>,
+    pub payload_sha256: String,
+    /// Anchor heading created on the page before the entry, if any
+    pub created_anchor: Option<String>,
+}
+
+/// Response from Notion file upload API
+#[derive(Debug, Deserialize)]
+struct FileUploadResponse {
+    id: String,
 }
 
 /// Response from Notion search API
@@ -23,33 +80,142 @@ struct SearchResponse {
     has_more: bool,
 }
 
-/// Data to be sent to Notion when submitting a fault log
-#[derive(Debug, Clone)]
-pub struct FaultLogEntry {
-    pub error: String,
-    pub problem: String,
-    pub solution: String,
-    pub code: Option<String>,
-}
-
 impl NotionClient {
-    fn new(base_url: String, http_client: Client) -> Self {
+    fn new(base_url: String, tokens: Vec<ApiToken>) -> Self {
+        let http_client = tokens[0].http_client.clone();
         Self {
             base_url,
             http_client,
+            tokens,
+            retries: Arc::default(),
+            activity: ActivityFeed::default(),
+            limiter: None,
+        }
+    }
+
+    /// A copy of this client that sends requests with the named token
+    pub fn with_token(&self, name: &str) -> Option<NotionClient> {
+        let token = self.tokens.iter().find(|t| t.name == name)?;
+        Some(Self {
+            base_url: self.base_url.clone(),
+            http_client: token.http_client.clone(),
+            tokens: self.tokens.clone(),
+            retries: self.retries.clone(),
+            activity: self.activity.clone(),
+            limiter: self.limiter.clone(),
+        })
+    }
+
+    /// A copy of this client whose requests, and every copy's, wait for
+    /// `limiter`; for batches that would otherwise outrun Notion's rate limit
+    pub fn paced(&self, limiter: RateLimiter) -> NotionClient {
+        Self {
+            limiter: Some(limiter),
+            ..self.clone()
         }
     }
+
+    /// Follow the retries of requests sent through this client
+    pub fn retry_watcher(&self) -> RetryWatcher {
+        RetryWatcher::new(self.retries.subscribe())
+    }
+
+    /// Send a request, retrying rate limits (after `Retry-After`), server
+    /// errors and dropped connections with backoff, each attempt waiting
+    /// for the limiter of a paced client. Returns the last
+    /// response whatever its status; see `CheckStatus` for turning an
+    /// error status into a `NotionError`.
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, NotionError> {
+        let (http_client, request) = request.build_split();
+        let mut request = request?;
+        let mut attempt = 1;
+        let started = Instant::now();
+        loop {
+            if let Some(limiter) = &self.limiter {
+                limiter.acquire().await;
+            }
+            let method = request.method().clone();
+            let url = request.url().clone();
+            let next = request.try_clone();
+            let result = http_client.execute(request).await;
+            let (status, connected) = match &result {
+                Ok(response) => (Some(response.status().as_u16()), true),
+                Err(e) => (None, !e.is_connect()),
+            };
+            let retry_after = result
+                .as_ref()
+                .ok()
+                .and_then(|response| response.headers().get(RETRY_AFTER))
+                .and_then(|value| value.to_str().ok());
+            let retry = retry_reason(&method, status, connected)
+                .filter(|_| attempt < MAX_ATTEMPTS)
+                .zip(retry_delay(attempt + 1, retry_after, jitter()))
+                .zip(next);
+            let Some(((reason, wait), next)) = retry else {
+                self.retries.send_replace(None);
+                self.activity.report(ApiCall::new(method, &url, status, attempt, started.elapsed()));
+                return Ok(result?);
+            };
+
+            attempt += 1;
+            tracing::warn!(?reason, attempt, ?wait, "retrying Notion request");
+            self.retries.send_replace(Some(RetryNotice { reason, attempt, wait }));
+            tokio::time::sleep(wait).await;
+            request = next;
+        }
+    }
+
+    /// Token names in the order they should be tried, `preferred` first
+    pub fn token_order(&self, preferred: Option<&str>) -> Vec<&str> {
+        let mut names: Vec<&str> = self.tokens.iter().map(|t| t.name.as_str()).collect();
+        if let Some(idx) = preferred.and_then(|p| names.iter().position(|n| *n == p)) {
+            let name = names.remove(idx);
+            names.insert(0, name);
+        }
+        names
+    }
 }
 
-/// Create and configure a NotionClient from environment variables
-pub fn create_notion_client() -> Result<NotionClient, String> {
+/// Create and configure a NotionClient from the `API_KEY` environment
+/// variable plus any tokens listed in the config. A configured token that
+/// is unusable is skipped rather than failing the others; each comes back
+/// as a warning next to the client.
+pub fn create_notion_client(configured: &[TokenConfig]) -> Result<(NotionClient, Vec<String>), String> {
     dotenv().ok();
 
-    let api_key = env::var("API_KEY")
-        .map_err(|_| "API_KEY not found in environment variables".to_string())?;
+    let mut keys: Vec<(String, String)> = Vec::new();
+    if let Ok(api_key) = env::var("API_KEY") {
+        keys.push(("default".to_string(), api_key));
+    }
+    let mut warnings = Vec::new();
+    for token in configured {
+        match token.resolve() {
+            Some(key) => keys.push((token.name.clone(), key)),
+            None => warnings.push(format!("Skipped token '{}': it has no key or its env var is unset", token.name)),
+        }
+    }
 
-    let base_url = "https://api.notion.com".to_string();
+    let mut tokens = Vec::new();
+    for (name, key) in keys {
+        match build_http_client(&key) {
+            Ok(http_client) => tokens.push(ApiToken { name, http_client }),
+            Err(e) if name == "default" => return Err(e),
+            Err(e) => warnings.push(format!("Skipped token '{}': {}", name, e)),
+        }
+    }
+    if tokens.is_empty() {
+        let mut message = "API_KEY not found in environment variables".to_string();
+        for warning in &warnings {
+            message.push_str(&format!("; {}", warning));
+        }
+        return Err(message);
+    }
 
+    Ok((NotionClient::new("https://api.notion.com".to_string(), tokens), warnings))
+}
+
+/// Build an HTTP client that authenticates every request with `api_key`
+fn build_http_client(api_key: &str) -> Result<Client, String> {
     let mut headers = HeaderMap::new();
     headers.insert(
         AUTHORIZATION,
@@ -62,23 +228,166 @@ pub fn create_notion_client() -> Result<NotionClient, String> {
         HeaderValue::from_static("2022-06-28"),
     );
 
-    let client = Client::builder()
+    Client::builder()
         .timeout(Duration::from_secs(30))
         .default_headers(headers)
         .build()
-        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
 
-    Ok(NotionClient::new(base_url, client))
+/// A failed Notion request, classified from the HTTP status and the
+/// `code` and `message` Notion sends with every error
+#[derive(Debug)]
+pub enum NotionError {
+    /// 401: the token is invalid or was revoked
+    Unauthorized { message: String },
+    /// 403: the integration lacks a capability, e.g. inserting content
+    Forbidden { message: String },
+    /// 404: the page does not exist or is not shared with the integration
+    NotFound { message: String },
+    /// 429: too many requests
+    RateLimited { message: String },
+    /// Any other 4xx, such as `validation_error` for a malformed block
+    Rejected { status: u16, code: String, message: String },
+    /// 5xx: Notion failed on its side
+    Server { status: u16, code: String, message: String },
+    /// No usable response: connection, timeout or an unreadable body
+    Request(reqwest::Error),
+    /// A successful response that lacks what the request should return
+    Unexpected { message: String },
 }
 
-/// Create a toggleable error block for Notion with professional styling
-pub fn create_error_block(
-    error: &str,
-    problem: &str,
-    solution: &str,
-    code: Option<&str>,
-    language: Option<&str>,
-) -> Value {
+impl NotionError {
+    /// Error for a response with a failing status and this body
+    pub fn from_response(status: u16, body: &str) -> Self {
+        let parsed: Value = serde_json::from_str(body).unwrap_or_default();
+        let code = parsed["code"].as_str().unwrap_or_default().to_string();
+        let message = parsed["message"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| body.trim().chars().take(200).collect());
+        match status {
+            401 => NotionError::Unauthorized { message },
+            403 => NotionError::Forbidden { message },
+            404 => NotionError::NotFound { message },
+            429 => NotionError::RateLimited { message },
+            500.. => NotionError::Server { status, code, message },
+            _ => NotionError::Rejected { status, code, message },
+        }
+    }
+
+    /// HTTP status Notion answered with; `None` when no response came
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            NotionError::Unauthorized { .. } => Some(401),
+            NotionError::Forbidden { .. } => Some(403),
+            NotionError::NotFound { .. } => Some(404),
+            NotionError::RateLimited { .. } => Some(429),
+            NotionError::Rejected { status, .. } | NotionError::Server { status, .. } => Some(*status),
+            NotionError::Request(error) => error.status().map(|status| status.as_u16()),
+            NotionError::Unexpected { .. } => None,
+        }
+    }
+
+    /// Whether this token cannot reach the page, so another token might
+    /// succeed (revoked token, missing access, page not shared)
+    pub fn is_access_error(&self) -> bool {
+        matches!(
+            self,
+            NotionError::Unauthorized { .. } | NotionError::Forbidden { .. } | NotionError::NotFound { .. }
+        )
+    }
+}
+
+impl fmt::Display for NotionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotionError::Unauthorized { .. } => {
+                write!(f, "Notion rejected the API token — check API_KEY or create a new integration secret")
+            }
+            NotionError::Forbidden { message } => write!(
+                f,
+                "integration is not allowed to do this — enable its capabilities at notion.so/my-integrations ({})",
+                message
+            ),
+            NotionError::NotFound { .. } => {
+                write!(f, "integration lacks access to this page — share it with the integration (••• → Connections)")
+            }
+            NotionError::RateLimited { .. } => write!(f, "Notion is rate limiting requests — try again in a moment"),
+            NotionError::Rejected { code, message, .. } if code.is_empty() => write!(f, "Notion rejected the request: {}", message),
+            NotionError::Rejected { code, message, .. } => write!(f, "Notion rejected the request ({}): {}", code, message),
+            NotionError::Server { status, message, .. } => write!(f, "Notion had a server error ({}): {}", status, message),
+            NotionError::Request(error) if error.is_timeout() => write!(f, "Notion did not answer in time"),
+            NotionError::Request(error) if error.is_decode() => write!(f, "unexpected response from Notion: {}", error),
+            NotionError::Request(error) => write!(f, "could not reach Notion: {}", error),
+            NotionError::Unexpected { message } => write!(f, "unexpected response from Notion: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for NotionError {}
+
+impl From<reqwest::Error> for NotionError {
+    fn from(error: reqwest::Error) -> Self {
+        NotionError::Request(error)
+    }
+}
+
+/// Turn a failing response into a `NotionError` carrying Notion's message
+pub(crate) trait CheckStatus: Sized {
+    async fn check_status(self) -> Result<Self, NotionError>;
+}
+
+impl CheckStatus for Response {
+    async fn check_status(self) -> Result<Self, NotionError> {
+        let status = self.status();
+        if status.is_success() {
+            return Ok(self);
+        }
+        let body = self.text().await.unwrap_or_default();
+        Err(NotionError::from_response(status.as_u16(), &body))
+    }
+}
+
+/// Create an entry, falling back through the configured tokens when one
+/// lacks access. Returns the name of the token that succeeded.
+#[instrument(skip_all, fields(page_id = %page_id), err)]
+pub async fn create_entry_with_fallback(
+    client: &NotionClient,
+    page_id: &str,
+    kind: TargetKind,
+    entry: &FaultLogEntry,
+    template: &BlockTemplate,
+    preferred_token: Option<&str>,
+) -> Result<(String, AppendedBlocks), NotionError> {
+    let mut last_error = None;
+    for name in client.token_order(preferred_token) {
+        let Some(token_client) = client.with_token(name) else {
+            continue;
+        };
+        let created = match kind {
+            TargetKind::Page => create_entry(&token_client, page_id, entry, template).await,
+            TargetKind::Database => create_row(&token_client, page_id, entry, template).await,
+        };
+        match created {
+            Ok(appended) => return Ok((name.to_string(), appended)),
+            Err(e) if e.is_access_error() => last_error = Some(e),
+            Err(e) => return Err(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| NotionError::Unauthorized {
+        message: "no integration token is configured".to_string(),
+    }))
+}
+
+/// The sections of an entry between its opening and closing dividers,
+/// without the heading; also the body of a new database row
+pub fn entry_sections(entry: &FaultLogEntry, language: Option<&str>, template: &BlockTemplate) -> Vec<Value> {
+    let error = entry.error.as_str();
+    let problem = entry.problem.as_str();
+    let solution = entry.solution.as_str();
+    let code = entry.code.as_deref();
+
     let mut children: Vec<Value> = vec![
         // Divider for visual separation
         json!({
@@ -86,72 +395,118 @@ pub fn create_error_block(
             "type": "divider",
             "divider": {}
         }),
-        // ERROR Section - Red callout
-        json!({
-            "object": "block",
-            "type": "callout",
-            "callout": {
-                "rich_text": [{
-                    "type": "text",
-                    "text": { "content": error }
-                }],
-                "icon": { "type": "emoji", "emoji": "🔴" },
-                "color": "red_background"
-            }
-        }),
-        // Heading for Error label
-        json!({
+    ];
+
+    // When and where the entry was logged, from the template
+    if let Some(metadata) = template.metadata.as_deref().filter(|m| !m.trim().is_empty()) {
+        children.push(json!({
             "object": "block",
-            "type": "heading_3",
-            "heading_3": {
-                "rich_text": [{
-                    "type": "text",
-                    "text": { "content": "What was the problem?" },
-                    "annotations": { "bold": true }
-                }],
-                "color": "orange"
+            "type": "paragraph",
+            "paragraph": {
+                "rich_text": plain_rich_text(metadata),
+                "color": "gray"
             }
-        }),
-        // PROBLEM Section - Yellow/Orange callout
-        json!({
-            "object": "block",
-            "type": "callout",
-            "callout": {
-                "rich_text": [{
-                    "type": "text",
-                    "text": { "content": problem }
-                }],
-                "icon": { "type": "emoji", "emoji": "🟡" },
-                "color": "yellow_background"
+        }));
+    }
+
+    // ERROR Section - Red callout
+    children.extend(create_section_blocks(error, template.label(InputField::Error), "🔴", "red_background", template));
+
+    // Heading for Error label
+    children.push(json!({
+        "object": "block",
+        "type": "heading_3",
+        "heading_3": {
+            "rich_text": [{
+                "type": "text",
+                "text": { "content": template.heading(InputField::Problem) },
+                "annotations": { "bold": true }
+            }],
+            "color": "orange"
+        }
+    }));
+
+    // PROBLEM Section - Yellow/Orange callout
+    children.extend(create_section_blocks(problem, template.label(InputField::Problem), "🟡", "yellow_background", template));
+
+    // Heading for Solution label
+    children.push(json!({
+        "object": "block",
+        "type": "heading_3",
+        "heading_3": {
+            "rich_text": [{
+                "type": "text",
+                "text": { "content": template.heading(InputField::Solution) },
+                "annotations": { "bold": true }
+            }],
+            "color": "green"
+        }
+    }));
+
+    // SOLUTION Section - Green callout
+    children.extend(create_section_blocks(solution, template.label(InputField::Solution), "✅", "green_background", template));
+
+    // Add verification checklist if provided
+    if let Some(verification) = entry.verification.as_deref() {
+        let steps = verification_steps(verification);
+        if !steps.is_empty() {
+            children.push(json!({
+                "object": "block",
+                "type": "heading_3",
+                "heading_3": {
+                    "rich_text": [{
+                        "type": "text",
+                        "text": { "content": template.heading(InputField::Verification) },
+                        "annotations": { "bold": true }
+                    }],
+                    "color": "blue"
+                }
+            }));
+            for (step, checked) in steps {
+                children.push(json!({
+                    "object": "block",
+                    "type": "to_do",
+                    "to_do": {
+                        "rich_text": [{
+                            "type": "text",
+                            "text": { "content": step }
+                        }],
+                        "checked": checked
+                    }
+                }));
             }
-        }),
-        // Heading for Solution label
-        json!({
+        }
+    }
+
+    // Add environment context table and git state if provided
+    let pairs = entry.context.as_deref().map(context_pairs).unwrap_or_default();
+    if !pairs.is_empty() || entry.git.is_some() {
+        children.push(json!({
             "object": "block",
             "type": "heading_3",
             "heading_3": {
                 "rich_text": [{
                     "type": "text",
-                    "text": { "content": "How did you fix it?" },
+                    "text": { "content": template.heading(InputField::Context) },
                     "annotations": { "bold": true }
                 }],
-                "color": "green"
+                "color": "gray"
             }
-        }),
-        // SOLUTION Section - Green callout
-        json!({
-            "object": "block",
-            "type": "callout",
-            "callout": {
-                "rich_text": [{
-                    "type": "text",
-                    "text": { "content": solution }
-                }],
-                "icon": { "type": "emoji", "emoji": "✅" },
-                "color": "green_background"
-            }
-        }),
-    ];
+        }));
+        if !pairs.is_empty() {
+            children.push(create_context_table(&pairs));
+        }
+        if let Some(git) = &entry.git {
+            children.push(json!({
+                "object": "block",
+                "type": "paragraph",
+                "paragraph": {
+                    "rich_text": plain_rich_text(&git.describe()),
+                    "color": "gray"
+                }
+            }));
+        }
+    }
 
     // Add code block if provided
     if let Some(code_content) = code {
@@ -162,7 +517,7 @@ pub fn create_error_block(
                 "heading_3": {
                     "rich_text": [{
                         "type": "text",
-                        "text": { "content": "Code Reference" },
+                        "text": { "content": template.heading(InputField::Code) },
                         "annotations": { "bold": true }
                     }],
                     "color": "purple"
@@ -173,59 +528,401 @@ pub fn create_error_block(
                 "type": "code",
                 "code": {
                     "caption": [],
-                    "rich_text": [{
-                        "type": "text",
-                        "text": { "content": code_content }
-                    }],
+                    "rich_text": plain_rich_text(code_content),
                     "language": language.unwrap_or("plain text")
                 }
             }));
         }
     }
 
+    // Embed attached files; images show inline
+    for attachment in &entry.attachments {
+        let mut file = match &attachment.url {
+            Some(url) => json!({ "type": "external", "external": { "url": url } }),
+            None => json!({ "type": "file_upload", "file_upload": { "id": attachment.upload_id } }),
+        };
+        file["caption"] = json!([]);
+        let kind = if is_image(&attachment.name) {
+            "image"
+        } else {
+            file["name"] = json!(attachment.name);
+            "file"
+        };
+        children.push(json!({
+            "object": "block",
+            "type": kind,
+            kind: file
+        }));
+    }
+
+    // Bookmark the links found in pasted logs
+    let links = if template.related_links { entry_links(entry) } else { Vec::new() };
+    if !links.is_empty() {
+        children.push(json!({
+            "object": "block",
+            "type": "heading_3",
+            "heading_3": {
+                "rich_text": [{
+                    "type": "text",
+                    "text": { "content": template.locale.related_links_heading() },
+                    "annotations": { "bold": true }
+                }],
+                "color": "gray"
+            }
+        }));
+        for url in links {
+            children.push(json!({
+                "object": "block",
+                "type": "bookmark",
+                "bookmark": { "url": url, "caption": [] }
+            }));
+        }
+    }
+
+    // List the tags as colored chips
+    if !entry.tags.is_empty() {
+        let mut rich_text = vec![json!({
+            "type": "text",
+            "text": { "content": template.tags_prefix() },
+            "annotations": { "bold": true }
+        })];
+        rich_text.extend(tag_chips(&entry.tags));
+        children.push(json!({
+            "object": "block",
+            "type": "paragraph",
+            "paragraph": {
+                "rich_text": rich_text,
+                "color": "gray"
+            }
+        }));
+    }
+
+    // Grouped entries follow the summary, each under a numbered heading
+    for (number, sub_entry) in entry.sub_entries.iter().enumerate() {
+        children.extend(sub_entry_blocks(number + 1, sub_entry, template));
+    }
+
     // Add closing divider
     children.push(json!({
         "object": "block",
         "type": "divider",
         "divider": {}
     }));
+    children
+}
 
-    // The main toggleable heading with timestamp
-    json!([{
+/// A grouped entry inside its parent: a flat numbered heading and its
+/// sections, without the dividers around them. Flat, so the blocks stay
+/// within the two levels of nesting Notion accepts in one request.
+fn sub_entry_blocks(number: usize, entry: &FaultLogEntry, template: &BlockTemplate) -> Vec<Value> {
+    let icon = match (template.is_plain(), entry.severity) {
+        (true, _) => String::new(),
+        (false, Some(severity)) => format!("{} ", severity.emoji()),
+        (false, None) => "🐛 ".to_string(),
+    };
+    let title: String = entry.error.lines().next().unwrap_or_default().trim().chars().take(50).collect();
+    let color = entry.severity.map_or("default", |severity| severity.color());
+    // The parent's title and metadata line are its own
+    let mut template = template.clone();
+    template.title = None;
+    template.metadata = None;
+    let language = entry.language.as_deref().or(Some(DEFAULT_LANGUAGE));
+    let mut sections = entry_sections(entry, language, &template);
+    sections.pop();
+    sections.remove(0);
+
+    let mut blocks = vec![json!({
         "object": "block",
-        "type": "heading_2",
-        "heading_2": {
+        "type": "heading_3",
+        "heading_3": {
             "rich_text": [
                 {
                     "type": "text",
-                    "text": { "content": "🐛 " }
+                    "text": { "content": format!("{}. {}", number, icon) }
                 },
                 {
                     "type": "text",
-                    "text": { "content": &error[..std::cmp::min(error.len(), 50)] },
+                    "text": { "content": title },
                     "annotations": { "bold": true }
-                },
-                {
-                    "type": "text",
-                    "text": { "content": if error.len() > 50 { "..." } else { "" } }
                 }
             ],
-            "color": "red",
-            "is_toggleable": true,
-            "children": children
+            "color": color
+        }
+    })];
+    blocks.extend(sections);
+    blocks
+}
+
+/// Create the blocks for one entry: a heading with the sections nested
+/// under it, or followed by them when the heading is not toggleable
+pub fn create_error_block(
+    entry: &FaultLogEntry,
+    language: Option<&str>,
+    template: &BlockTemplate,
+) -> Value {
+    let title = template.title.as_deref().unwrap_or(entry.error.as_str());
+    let truncated: String = title.chars().take(50).collect();
+    let mut children = entry_sections(entry, language, template);
+
+    // The main heading; a toggleable one holds the sections, a flat one is followed by them
+    let icon = match (template.is_plain(), entry.severity) {
+        (true, _) => String::new(),
+        (false, Some(severity)) => format!("{} ", severity.emoji()),
+        (false, None) => "🐛 ".to_string(),
+    };
+    let style = &template.heading;
+    let color = entry.severity.map_or(style.color.as_str(), |severity| severity.color());
+    let mut heading_body = json!({
+        "rich_text": [
+            {
+                "type": "text",
+                "text": { "content": icon }
+            },
+            {
+                "type": "text",
+                "text": { "content": truncated },
+                "annotations": { "bold": true }
+            },
+            {
+                "type": "text",
+                "text": { "content": if title.chars().count() > 50 { "..." } else { "" } }
+            }
+        ],
+        "color": color,
+        "is_toggleable": style.toggleable
+    });
+    if style.toggleable {
+        heading_body["children"] = Value::Array(std::mem::take(&mut children));
+    }
+    let block_type = style.level.block_type();
+    let heading = json!({
+        "object": "block",
+        "type": block_type,
+        block_type: heading_body
+    });
+
+    let mut blocks = vec![heading];
+    blocks.extend(children);
+    blocks.extend(template.separator_blocks());
+    Value::Array(blocks)
+}
+
+/// Background colors tags are shown in, picked by tag so a tag keeps its
+/// color across entries
+const TAG_COLORS: [&str; 8] = [
+    "blue_background",
+    "green_background",
+    "orange_background",
+    "pink_background",
+    "purple_background",
+    "red_background",
+    "yellow_background",
+    "brown_background",
+];
+
+/// Notion color of a tag's chip; tags that are the same tag get the same
+/// color
+pub fn tag_color(tag: &str) -> &'static str {
+    let sum = tags::key(tag).bytes().fold(0usize, |sum, byte| sum.wrapping_mul(31).wrapping_add(byte as usize));
+    TAG_COLORS[sum % TAG_COLORS.len()]
+}
+
+/// Tags as inline code chips, one space apart
+pub fn tag_chips(tags: &[String]) -> Vec<Value> {
+    let mut chips = Vec::new();
+    for (index, tag) in tags.iter().enumerate() {
+        if index > 0 {
+            chips.push(json!({ "type": "text", "text": { "content": " " } }));
+        }
+        chips.push(json!({
+            "type": "text",
+            "text": { "content": tag },
+            "annotations": { "code": true, "color": tag_color(tag) }
+        }));
+    }
+    chips
+}
+
+/// Create a section callout. Lines holding only `$$…$$` become equation
+/// blocks after the callout; inline `$$…$$` spans become inline equations.
+/// The plain style uses a paragraph led by a bold label instead, since a
+/// callout without an icon gets Notion's default emoji.
+fn create_section_blocks(content: &str, label: &str, emoji: &str, color: &str, template: &BlockTemplate) -> Vec<Value> {
+    let mut text_lines = Vec::new();
+    let mut equations = Vec::new();
+    for line in content.lines() {
+        match display_equation(line) {
+            Some(expression) => equations.push(expression),
+            None => text_lines.push(line),
         }
-    }])
+    }
+
+    let text = rich_text(&text_lines.join("\n"));
+    let section = if template.is_plain() {
+        let mut runs = vec![json!({
+            "type": "text",
+            "text": { "content": format!("{}: ", label) },
+            "annotations": { "bold": true }
+        })];
+        runs.extend(text.as_array().cloned().unwrap_or_default());
+        json!({
+            "object": "block",
+            "type": "paragraph",
+            "paragraph": {
+                "rich_text": runs,
+                "color": color
+            }
+        })
+    } else {
+        json!({
+            "object": "block",
+            "type": "callout",
+            "callout": {
+                "rich_text": text,
+                "icon": { "type": "emoji", "emoji": emoji },
+                "color": color
+            }
+        })
+    };
+
+    let mut blocks = vec![section];
+    blocks.extend(equations.into_iter().map(|expression| {
+        json!({
+            "object": "block",
+            "type": "equation",
+            "equation": { "expression": expression }
+        })
+    }));
+    blocks
+}
+
+/// Build Notion rich text, turning `$$…$$` spans into inline equations
+pub fn rich_text(content: &str) -> Value {
+    let runs: Vec<Value> = split_equations(content)
+        .into_iter()
+        .flat_map(|segment| match segment {
+            Segment::Text(text) => text_runs(text),
+            Segment::Equation(expression) => {
+                vec![json!({ "type": "equation", "equation": { "expression": expression } })]
+            }
+        })
+        .collect();
+    Value::Array(runs)
+}
+
+/// Build Notion rich text without any markup processing
+pub fn plain_rich_text(content: &str) -> Value {
+    Value::Array(text_runs(content))
+}
+
+/// Split text into runs no longer than Notion's per-object character limit
+fn text_runs(content: &str) -> Vec<Value> {
+    if content.is_empty() {
+        return vec![json!({ "type": "text", "text": { "content": "" } })];
+    }
+
+    let mut runs = Vec::new();
+    let mut rest = content;
+    while !rest.is_empty() {
+        let end = rest
+            .char_indices()
+            .nth(MAX_TEXT_LENGTH)
+            .map(|(idx, _)| idx)
+            .unwrap_or(rest.len());
+        runs.push(json!({ "type": "text", "text": { "content": &rest[..end] } }));
+        rest = &rest[end..];
+    }
+    runs
+}
+
+/// Split the verification field into checklist steps, one per non-empty line,
+/// each with whether it is already done. Leading list markers (`-`, `*`,
+/// `- [ ]`, `- [x]`) are stripped so pasted checklists work.
+pub fn verification_steps(verification: &str) -> Vec<(&str, bool)> {
+    verification
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix('-').or_else(|| line.strip_prefix('*')).unwrap_or(line).trim_start();
+            let (line, checked) = match line.get(..3) {
+                Some("[ ]") => (&line[3..], false),
+                Some("[x]" | "[X]") => (&line[3..], true),
+                _ => (line, false),
+            };
+            let line = line.trim();
+            (!line.is_empty()).then_some((line, checked))
+        })
+        .collect()
 }
 
-fn extract_page_info(result: &Value) -> Option<PageInfo> {
+/// Parse the context field into `(key, value)` rows.
+/// Lines without a colon are kept as a key with an empty value.
+pub fn context_pairs(context: &str) -> Vec<(&str, &str)> {
+    context
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.split_once(':') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => (line, ""),
+        })
+        .collect()
+}
+
+/// Build a two-column Notion table block with a header row
+fn create_context_table(pairs: &[(&str, &str)]) -> Value {
+    let cell = |content: &str| json!([{ "type": "text", "text": { "content": content } }]);
+
+    let mut rows = vec![json!({
+        "object": "block",
+        "type": "table_row",
+        "table_row": { "cells": [cell("Key"), cell("Value")] }
+    })];
+    rows.extend(pairs.iter().map(|(key, value)| {
+        json!({
+            "object": "block",
+            "type": "table_row",
+            "table_row": { "cells": [cell(key), cell(value)] }
+        })
+    }));
+
+    json!({
+        "object": "block",
+        "type": "table",
+        "table": {
+            "table_width": 2,
+            "has_column_header": true,
+            "has_row_header": false,
+            "children": rows
+        }
+    })
+}
+
+/// Id, title and kind of a page or database from a search or query result
+pub fn extract_page_info(result: &Value) -> Option<PageInfo> {
     let id = result.get("id")?.as_str()?.to_string();
 
+    if result.get("object").and_then(Value::as_str) == Some("database") {
+        let title = Some(plain_text(&result["title"]))
+            .filter(|title| !title.is_empty())
+            .unwrap_or_else(|| "Untitled".to_string());
+        return Some(PageInfo {
+            id,
+            title,
+            kind: TargetKind::Database,
+            parent: parent_id(result),
+        });
+    }
+
+    // Database rows name their title property freely; plain pages call it "title"
     let title = result
         .get("properties")
+        .and_then(Value::as_object)
         .and_then(|props| {
             props.get("title")
                 .or_else(|| props.get("Name"))
                 .or_else(|| props.get("Title"))
+                .or_else(|| props.values().find(|prop| prop["type"] == "title"))
         })
         .and_then(|title_prop| title_prop.get("title"))
         .and_then(|title_array| title_array.as_array())
@@ -235,22 +932,80 @@ fn extract_page_info(result: &Value) -> Option<PageInfo> {
         .map(|s| s.to_string())
         .unwrap_or_else(|| "Untitled".to_string());
 
-    Some(PageInfo { id, title })
+    Some(PageInfo {
+        id,
+        title,
+        kind: TargetKind::Page,
+        parent: parent_id(result),
+    })
 }
 
-/// Fetch all pages from Notion
-pub async fn fetch_pages(client: &NotionClient) -> Result<Vec<PageInfo>, reqwest::Error> {
+/// Id of the page, database or block a search result is nested in; none
+/// at the top of the workspace
+pub fn parent_id(result: &Value) -> Option<String> {
+    let parent = result.get("parent")?;
+    let kind = parent.get("type")?.as_str()?;
+    parent.get(kind)?.as_str().map(str::to_string)
+}
+
+/// The page or database holding a block, walking up through nested blocks
+/// such as columns and toggles
+#[instrument(skip(client), err)]
+async fn block_owner(client: &NotionClient, block_id: &str) -> Result<Option<String>, NotionError> {
+    let mut id = block_id.to_string();
+    for _ in 0..MAX_BLOCK_DEPTH {
+        let url = format!("{}/v1/blocks/{}", client.base_url, id);
+        let block: Value = client
+            .send(client.http_client.get(&url))
+            .await?
+            .check_status()
+            .await?
+            .json()
+            .await?;
+        match block["parent"]["type"].as_str() {
+            Some("block_id") => id = block["parent"]["block_id"].as_str().unwrap_or_default().to_string(),
+            Some(_) => return Ok(parent_id(&block)),
+            None => return Ok(None),
+        }
+    }
+    Ok(None)
+}
+
+/// One page or database by id, for a target given as a link rather than
+/// picked from the list
+#[instrument(skip(client), err)]
+pub async fn fetch_target(client: &NotionClient, id: &str) -> Result<PageInfo, NotionError> {
+    let page_url = format!("{}/v1/pages/{}", client.base_url, id);
+    let response = client.send(client.http_client.get(&page_url)).await?.check_status().await;
+    let response = match response {
+        Ok(response) => response,
+        // Database links look the same as page links
+        Err(NotionError::NotFound { .. } | NotionError::Rejected { .. }) => {
+            let database_url = format!("{}/v1/databases/{}", client.base_url, id);
+            client.send(client.http_client.get(&database_url)).await?.check_status().await?
+        }
+        Err(e) => return Err(e),
+    };
+    let result: Value = response.json().await?;
+    Ok(extract_page_info(&result).unwrap_or_else(|| PageInfo {
+        id: id.to_string(),
+        title: "Untitled".to_string(),
+        ..Default::default()
+    }))
+}
+
+/// Fetch all pages and databases shared with the integration
+#[instrument(skip_all, err)]
+pub async fn fetch_pages(client: &NotionClient) -> Result<Vec<PageInfo>, NotionError> {
     let main_url = format!("{}/v1/search", client.base_url);
 
     let mut all_pages: Vec<PageInfo> = Vec::new();
     let mut start_cursor: Option<String> = None;
+    // Pages placed in a column or toggle name that block as their parent
+    let mut in_blocks = Vec::new();
 
     loop {
         let mut body = json!({
-            "filter": {
-                "property": "object",
-                "value": "page"
-            },
             "page_size": 100
         });
 
@@ -259,16 +1014,18 @@ pub async fn fetch_pages(client: &NotionClient) -> Result<Vec<PageInfo>, reqwest
         }
 
         let response: SearchResponse = client
-            .http_client
-            .post(&main_url)
-            .json(&body)
-            .send()
+            .send(client.http_client.post(&main_url).json(&body))
+            .await?
+            .check_status()
             .await?
             .json()
             .await?;
 
         for result in &response.results {
             if let Some(page_info) = extract_page_info(result) {
+                if result["parent"]["type"] == "block_id" {
+                    in_blocks.push(all_pages.len());
+                }
                 all_pages.push(page_info);
             }
         }
@@ -280,34 +1037,201 @@ pub async fn fetch_pages(client: &NotionClient) -> Result<Vec<PageInfo>, reqwest
         }
     }
 
+    // Nest those under the page holding the block; one that cannot be read
+    // leaves its pages at the top of the tree
+    let mut owners: HashMap<String, Option<String>> = HashMap::new();
+    for index in in_blocks {
+        let Some(block_id) = all_pages[index].parent.clone() else {
+            continue;
+        };
+        if !owners.contains_key(&block_id) {
+            let owner = block_owner(client, &block_id).await.unwrap_or_default();
+            owners.insert(block_id.clone(), owner);
+        }
+        all_pages[index].parent = owners[&block_id].clone();
+    }
+
     Ok(all_pages)
 }
 
+/// Body of a request creating an empty page titled `title` under another
+/// page
+pub fn new_page_body(parent_id: &str, title: &str) -> Value {
+    json!({
+        "parent": { "page_id": parent_id },
+        "properties": {
+            "title": { "title": [{ "type": "text", "text": { "content": title } }] }
+        }
+    })
+}
+
+/// Create an empty page under `parent_id`, returning it as listed in the
+/// sidebar along with the hash of the request body
+#[instrument(skip(client), err)]
+pub async fn create_page(client: &NotionClient, parent_id: &str, title: &str) -> Result<(PageInfo, String), NotionError> {
+    let url = format!("{}/v1/pages", client.base_url);
+    let body = new_page_body(parent_id, title);
+    let payload_sha256 = sha256_hex(body.to_string().as_bytes());
+    let page: Value = client
+        .send(client.http_client.post(&url).json(&body))
+        .await?
+        .check_status()
+        .await?
+        .json()
+        .await?;
+    let id = page.get("id").and_then(Value::as_str).ok_or_else(|| NotionError::Unexpected {
+        message: "the created page has no id".to_string(),
+    })?;
+    let page = PageInfo {
+        id: id.to_string(),
+        title: title.to_string(),
+        kind: TargetKind::Page,
+        parent: Some(parent_id.to_string()),
+    };
+    Ok((page, payload_sha256))
+}
+
+/// Upload a text file through Notion's file upload API and return its upload id
+pub async fn upload_text_file(
+    client: &NotionClient,
+    filename: &str,
+    content: String,
+) -> Result<String, NotionError> {
+    upload_file(client, filename, "text/plain", content.into_bytes()).await
+}
+
+/// Upload a file of any type through Notion's file upload API and return
+/// its upload id
+#[instrument(skip(client, content), fields(bytes = content.len()), err)]
+pub async fn upload_file(
+    client: &NotionClient,
+    filename: &str,
+    content_type: &str,
+    content: Vec<u8>,
+) -> Result<String, NotionError> {
+    let create_url = format!("{}/v1/file_uploads", client.base_url);
+    let create = client.http_client.post(&create_url).json(&json!({
+        "mode": "single_part",
+        "filename": filename,
+        "content_type": content_type
+    }));
+    let upload: FileUploadResponse = client
+        .send(create)
+        .await?
+        .check_status()
+        .await?
+        .json()
+        .await?;
+
+    let part = Part::bytes(content)
+        .file_name(filename.to_string())
+        .mime_str(content_type)?;
+    let send_url = format!("{}/v1/file_uploads/{}/send", client.base_url, upload.id);
+    // Multipart bodies cannot be cloned, so this request is sent only once
+    client
+        .send(client.http_client.post(&send_url).multipart(Form::new().part("file", part)))
+        .await?
+        .check_status()
+        .await?;
+
+    Ok(upload.id)
+}
+
 /// Create a fault log entry on a Notion page
+#[instrument(skip_all, fields(page_id = %page_id), err)]
 pub async fn create_entry(
     client: &NotionClient,
     page_id: &str,
     entry: &FaultLogEntry,
-) -> Result<(), reqwest::Error> {
+    template: &BlockTemplate,
+) -> Result<AppendedBlocks, NotionError> {
     let main_url = format!("{}/v1/blocks/{}/children", client.base_url, page_id);
 
-    let block = create_error_block(
-        &entry.error,
-        &entry.problem,
-        &entry.solution,
-        entry.code.as_deref(),
-        Some("rust"),
-    );
+    let language = entry.language.as_deref().or(Some(DEFAULT_LANGUAGE));
+    let block = create_error_block(entry, language, template);
+
+    let mut created_anchor = None;
+    let after = match (&template.after, &template.anchor, template.position) {
+        (Some(block_id), _, _) => Some(block_id.clone()),
+        (None, Some(anchor), _) => {
+            let (anchor_id, created) = find_or_create_anchor(client, page_id, &Anchor::parse(anchor)).await?;
+            if created {
+                created_anchor = Some(anchor_id.clone());
+            }
+            Some(anchor_id)
+        }
+        (None, None, AppendPosition::Top) => first_child_id(client, page_id).await?,
+        (None, None, AppendPosition::Bottom) => None,
+    };
+    let body = append_body(block, after.as_deref());
+    let payload_sha256 = sha256_hex(body.to_string().as_bytes());
 
-    let body = json!({ "children": block });
+    let response: Value = client
+        .send(client.http_client.patch(&main_url).json(&body))
+        .await?
+        .check_status()
+        .await?
+        .json()
+        .await?;
 
-    client
-        .http_client
-        .patch(&main_url)
-        .json(&body)
-        .send()
+    Ok(AppendedBlocks {
+        block_ids: created_block_ids(&response),
+        payload_sha256,
+        created_anchor,
+    })
+}
+
+/// Body of an append-children request; with `after`, Notion inserts the
+/// blocks directly below that block instead of at the end
+pub fn append_body(blocks: Value, after: Option<&str>) -> Value {
+    let mut body = json!({ "children": blocks });
+    if let Some(after) = after {
+        body["after"] = json!(after);
+    }
+    body
+}
+
+/// Id of the first block on a page, or `None` for an empty page
+#[instrument(skip(client), err)]
+async fn first_child_id(client: &NotionClient, page_id: &str) -> Result<Option<String>, NotionError> {
+    let url = format!("{}/v1/blocks/{}/children?page_size=1", client.base_url, page_id);
+    let response: Value = client
+        .send(client.http_client.get(&url))
         .await?
-        .error_for_status()?;
+        .check_status()
+        .await?
+        .json()
+        .await?;
+    Ok(created_block_ids(&response).into_iter().next())
+}
 
+/// Delete (archive) a block and everything nested under it
+#[instrument(skip(client), err)]
+pub async fn delete_block(client: &NotionClient, block_id: &str) -> Result<(), NotionError> {
+    client
+        .send(client.http_client.delete(format!("{}/v1/blocks/{}", client.base_url, block_id)))
+        .await?
+        .check_status()
+        .await?;
     Ok(())
 }
+
+/// Ids of the top-level blocks in an append- or list-children response
+pub fn created_block_ids(response: &Value) -> Vec<String> {
+    response
+        .get("results")
+        .and_then(Value::as_array)
+        .map(|results| {
+            results
+                .iter()
+                .filter_map(|block| block.get("id").and_then(Value::as_str))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Lowercase hex SHA-256 of a request payload
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
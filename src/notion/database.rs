@@ -0,0 +1,344 @@
+// Databases as targets: new rows per entry, or entries appended to a row
+use serde_json::{Map, Value, json};
+use tracing::instrument;
+
+use crate::app::{FaultLogEntry, FaultStatus, PageInfo, RelationProperty};
+use crate::fields::InputField;
+use crate::notion::client::{
+    AppendedBlocks, CheckStatus, NotionClient, NotionError, entry_sections, extract_page_info, plain_rich_text,
+    sha256_hex,
+};
+use crate::notion::languages::DEFAULT_LANGUAGE;
+use crate::notion::template::{BlockTemplate, EntryField, PropertySource};
+
+/// Most rows loaded into the row picker at once
+pub const ROW_LIMIT: usize = 500;
+
+/// The database object, including its property schema
+#[instrument(skip(client), err)]
+pub async fn fetch_database(client: &NotionClient, database_id: &str) -> Result<Value, NotionError> {
+    let url = format!("{}/v1/databases/{}", client.base_url, database_id);
+    client
+        .send(client.http_client.get(&url))
+        .await?
+        .check_status()
+        .await?
+        .json()
+        .await
+        .map_err(NotionError::from)
+}
+
+/// Name of the database's title property, e.g. "Name" or "Service"
+pub async fn title_property(client: &NotionClient, database_id: &str) -> Result<String, NotionError> {
+    let database = fetch_database(client, database_id).await?;
+    Ok(find_title_property(&database).unwrap_or_else(|| "Name".to_string()))
+}
+
+/// The title property among a database's properties
+pub fn find_title_property(database: &Value) -> Option<String> {
+    database
+        .get("properties")?
+        .as_object()?
+        .iter()
+        .find(|(_, prop)| prop["type"] == "title")
+        .map(|(name, _)| name.clone())
+}
+
+/// Relation properties of a database and the databases they point to
+pub fn relation_properties(database: &Value) -> Vec<RelationProperty> {
+    let Some(schema) = database.get("properties").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let mut properties: Vec<RelationProperty> = schema
+        .iter()
+        .filter(|(_, prop)| prop["type"] == "relation")
+        .filter_map(|(name, prop)| {
+            Some(RelationProperty {
+                name: name.clone(),
+                database_id: prop["relation"]["database_id"].as_str()?.to_string(),
+            })
+        })
+        .collect();
+    properties.sort_by(|a, b| a.name.cmp(&b.name));
+    properties
+}
+
+/// Query body returning recently edited rows first, only those whose title
+/// contains `query` when one is given
+pub fn query_body(title_property: Option<&str>, query: &str, start_cursor: Option<&str>) -> Value {
+    let mut body = json!({
+        "page_size": 100,
+        "sorts": [{ "timestamp": "last_edited_time", "direction": "descending" }]
+    });
+    if let (Some(property), false) = (title_property, query.is_empty()) {
+        body["filter"] = json!({ "property": property, "title": { "contains": query } });
+    }
+    if let Some(cursor) = start_cursor {
+        body["start_cursor"] = json!(cursor);
+    }
+    body
+}
+
+/// Rows of a database, newest first, up to `ROW_LIMIT`. A non-empty
+/// `query` is matched against row titles by Notion, so rows beyond the
+/// limit can still be found.
+#[instrument(skip(client), err)]
+pub async fn query_rows(client: &NotionClient, database_id: &str, query: &str) -> Result<Vec<PageInfo>, NotionError> {
+    let property = if query.is_empty() {
+        None
+    } else {
+        Some(title_property(client, database_id).await?)
+    };
+
+    let url = format!("{}/v1/databases/{}/query", client.base_url, database_id);
+    let mut rows = Vec::new();
+    let mut start_cursor: Option<String> = None;
+    loop {
+        let body = query_body(property.as_deref(), query, start_cursor.as_deref());
+        let response: Value = client
+            .send(client.http_client.post(&url).json(&body))
+            .await?
+            .check_status()
+            .await?
+            .json()
+            .await?;
+
+        if let Some(results) = response.get("results").and_then(Value::as_array) {
+            rows.extend(results.iter().filter_map(extract_page_info));
+        }
+        let has_more = response.get("has_more").and_then(Value::as_bool).unwrap_or(false);
+        start_cursor = response.get("next_cursor").and_then(Value::as_str).map(str::to_string);
+        if !has_more || start_cursor.is_none() || rows.len() >= ROW_LIMIT {
+            break;
+        }
+    }
+    rows.truncate(ROW_LIMIT);
+    Ok(rows)
+}
+
+/// Add an entry to a database as a new row: its fields fill the row's
+/// properties and its sections become the row's page content
+#[instrument(skip_all, fields(database_id = %database_id), err)]
+pub async fn create_row(
+    client: &NotionClient,
+    database_id: &str,
+    entry: &FaultLogEntry,
+    template: &BlockTemplate,
+) -> Result<AppendedBlocks, NotionError> {
+    let database = fetch_database(client, database_id).await?;
+    let language = entry.language.as_deref().or(Some(DEFAULT_LANGUAGE));
+    let mut body = json!({
+        "parent": { "database_id": database_id },
+        "properties": row_properties(&database, entry, template),
+        "children": entry_sections(entry, language, template)
+    });
+    if let Some(icon) = status_icon(entry.status, template) {
+        body["icon"] = icon;
+    }
+    if let Some(cover) = template.cover_value() {
+        body["cover"] = cover;
+    }
+    let payload_sha256 = sha256_hex(body.to_string().as_bytes());
+
+    let url = format!("{}/v1/pages", client.base_url);
+    let page: Value = client
+        .send(client.http_client.post(&url).json(&body))
+        .await?
+        .check_status()
+        .await?
+        .json()
+        .await?;
+
+    Ok(AppendedBlocks {
+        block_ids: page.get("id").and_then(Value::as_str).map(str::to_string).into_iter().collect(),
+        payload_sha256,
+        created_anchor: None,
+    })
+}
+
+/// Emoji icon for a row in the given state; none for the plain style
+pub fn status_icon(status: FaultStatus, template: &BlockTemplate) -> Option<Value> {
+    if template.is_plain() {
+        return None;
+    }
+    Some(json!({ "type": "emoji", "emoji": status.emoji() }))
+}
+
+/// The row property holding the fault status and its type: one mapped to
+/// the `status` field in the config, else one named "Status". Only select
+/// and status properties qualify.
+pub fn status_property(row: &Value, template: &BlockTemplate) -> Option<(String, String)> {
+    let properties = row.get("properties")?.as_object()?;
+    let configured = template
+        .properties
+        .iter()
+        .filter(|(_, source)| **source == PropertySource::Field(EntryField::Status))
+        .map(|(name, _)| name.as_str());
+    let named = properties.keys().map(String::as_str).filter(|name| name.eq_ignore_ascii_case("status"));
+    configured.chain(named).find_map(|name| {
+        let kind = properties.get(name)?.get("type")?.as_str()?;
+        matches!(kind, "select" | "status").then(|| (name.to_string(), kind.to_string()))
+    })
+}
+
+/// Set the status and icon of a row created from an entry; returns the property
+/// changed and the payload's SHA-256, or `None` when the row has no
+/// status property
+#[instrument(skip(client, template), err)]
+pub async fn update_row_status(
+    client: &NotionClient,
+    row_id: &str,
+    status: FaultStatus,
+    template: &BlockTemplate,
+) -> Result<Option<(String, String)>, NotionError> {
+    let url = format!("{}/v1/pages/{}", client.base_url, row_id);
+    let row: Value = client
+        .send(client.http_client.get(&url))
+        .await?
+        .check_status()
+        .await?
+        .json()
+        .await?;
+    let Some((name, kind)) = status_property(&row, template) else {
+        return Ok(None);
+    };
+    let Some(value) = property_value(&kind, status.name()) else {
+        return Ok(None);
+    };
+
+    let mut body = json!({ "properties": { name.clone(): value } });
+    if let Some(icon) = status_icon(status, template) {
+        body["icon"] = icon;
+    }
+    client
+        .send(client.http_client.patch(&url).json(&body))
+        .await?
+        .check_status()
+        .await?;
+    Ok(Some((name, sha256_hex(body.to_string().as_bytes()))))
+}
+
+/// Property values for a new row. Relations picked for the entry win;
+/// configured properties come from the template; otherwise the title gets
+/// the error and a property named like a field (e.g. "Problem" or "Tags")
+/// gets that field.
+pub fn row_properties(database: &Value, entry: &FaultLogEntry, template: &BlockTemplate) -> Value {
+    let mut properties = Map::new();
+    let Some(schema) = database.get("properties").and_then(Value::as_object) else {
+        return Value::Object(properties);
+    };
+    for (name, property) in schema {
+        let kind = property.get("type").and_then(Value::as_str).unwrap_or_default();
+        if let Some(ids) = entry.relations.get(name).filter(|ids| !ids.is_empty()) {
+            if let Some(value) = property_value(kind, &ids.join(",")) {
+                properties.insert(name.clone(), value);
+                continue;
+            }
+        }
+        let source = template
+            .properties
+            .get(name)
+            .cloned()
+            .or_else(|| default_source(name, kind, template));
+        let text = match source {
+            Some(PropertySource::Field(field)) => field_text(field, entry),
+            Some(PropertySource::Value { value }) => Some(value),
+            None => None,
+        };
+        if let Some(value) = text.and_then(|text| property_value(kind, &text)) {
+            properties.insert(name.clone(), value);
+        }
+    }
+    Value::Object(properties)
+}
+
+fn default_source(name: &str, kind: &str, template: &BlockTemplate) -> Option<PropertySource> {
+    if kind == "title" {
+        return Some(match &template.title {
+            Some(title) => PropertySource::Value { value: title.clone() },
+            None => PropertySource::Field(EntryField::Error),
+        });
+    }
+    let field = match name.to_lowercase().as_str() {
+        "tags" => EntryField::Tags,
+        "language" => EntryField::Language,
+        "status" => EntryField::Status,
+        "severity" => EntryField::Severity,
+        _ => InputField::ALL
+            .into_iter()
+            .find(|field| {
+                template.label(*field).eq_ignore_ascii_case(name)
+                    || template.labels.label(*field).eq_ignore_ascii_case(name)
+            })
+            .map(entry_field)?,
+    };
+    Some(PropertySource::Field(field))
+}
+
+fn entry_field(field: InputField) -> EntryField {
+    match field {
+        InputField::Error => EntryField::Error,
+        InputField::Problem => EntryField::Problem,
+        InputField::Solution => EntryField::Solution,
+        InputField::Code => EntryField::Code,
+        InputField::Verification => EntryField::Verification,
+        InputField::Context => EntryField::Context,
+        InputField::Tags => EntryField::Tags,
+    }
+}
+
+/// An entry field as text; tags are comma separated
+fn field_text(field: EntryField, entry: &FaultLogEntry) -> Option<String> {
+    let text = match field {
+        EntryField::Error => Some(entry.error.clone()),
+        EntryField::Problem => Some(entry.problem.clone()),
+        EntryField::Solution => Some(entry.solution.clone()),
+        EntryField::Code => entry.code.clone(),
+        EntryField::Verification => entry.verification.clone(),
+        EntryField::Context => entry.context.clone(),
+        EntryField::Language => entry.language.clone(),
+        EntryField::Tags => Some(entry.tags.join(", ")),
+        EntryField::Status => Some(entry.status.name().to_string()),
+        EntryField::Severity => entry.severity.map(|severity| severity.name().to_string()),
+    };
+    text.filter(|text| !text.trim().is_empty())
+}
+
+/// A property value of the given Notion type; `None` for types that text
+/// cannot fill (people, formulas) or text that does not parse. Relations
+/// take comma separated page ids.
+pub fn property_value(kind: &str, text: &str) -> Option<Value> {
+    let value = match kind {
+        "title" => json!({ "title": plain_rich_text(text) }),
+        "rich_text" => json!({ "rich_text": plain_rich_text(text) }),
+        "select" => json!({ "select": { "name": text.trim() } }),
+        "status" => json!({ "status": { "name": text.trim() } }),
+        "multi_select" => {
+            let options: Vec<Value> = text
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(|name| json!({ "name": name }))
+                .collect();
+            json!({ "multi_select": options })
+        }
+        "number" => json!({ "number": text.trim().parse::<f64>().ok()? }),
+        "checkbox" => json!({ "checkbox": matches!(text.trim().to_lowercase().as_str(), "true" | "yes" | "1") }),
+        "url" | "email" | "phone_number" => json!({ kind: text.trim() }),
+        "date" => json!({ "date": { "start": text.trim() } }),
+        "relation" => {
+            let pages: Vec<Value> = text
+                .split(',')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(|id| json!({ "id": id }))
+                .collect();
+            if pages.is_empty() {
+                return None;
+            }
+            json!({ "relation": pages })
+        }
+        _ => return None,
+    };
+    Some(value)
+}
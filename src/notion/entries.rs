@@ -0,0 +1,290 @@
+// Reading fault log entries back from a Notion page
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+use tracing::instrument;
+
+use crate::app::{FaultLogEntry, FaultStatus, Severity};
+use crate::fields::InputField;
+use crate::notion::client::{CheckStatus, NotionClient, NotionError};
+use crate::notion::locale::Locale;
+use crate::notion::template::BlockTemplate;
+
+/// A fault log found on a Notion page
+#[derive(Debug, Clone, Default)]
+pub struct LoggedEntry {
+    /// Id of the entry heading
+    pub block_id: String,
+    /// Heading text without the icon
+    pub title: String,
+    pub entry: FaultLogEntry,
+}
+
+/// Fetch every child block of a page or block, following pagination
+#[instrument(skip(client), err)]
+pub async fn fetch_children(client: &NotionClient, block_id: &str) -> Result<Vec<Value>, NotionError> {
+    let mut blocks = Vec::new();
+    let mut start_cursor: Option<String> = None;
+    loop {
+        let mut url = format!("{}/v1/blocks/{}/children?page_size=100", client.base_url, block_id);
+        if let Some(cursor) = &start_cursor {
+            url.push_str("&start_cursor=");
+            url.push_str(cursor);
+        }
+        let response: Value = client
+            .send(client.http_client.get(&url))
+            .await?
+            .check_status()
+            .await?
+            .json()
+            .await?;
+
+        if let Some(results) = response.get("results").and_then(Value::as_array) {
+            blocks.extend(results.iter().cloned());
+        }
+        let has_more = response.get("has_more").and_then(Value::as_bool).unwrap_or(false);
+        start_cursor = response.get("next_cursor").and_then(Value::as_str).map(str::to_string);
+        if !has_more || start_cursor.is_none() {
+            break;
+        }
+    }
+    Ok(blocks)
+}
+
+/// Fetch a page and read back the entries FaultNote wrote to it.
+/// Nested blocks are fetched and stored under `children`, the shape
+/// `create_error_block` produces, so `parse_entries` handles both.
+#[instrument(skip(client, template), err)]
+pub async fn fetch_entries(
+    client: &NotionClient,
+    page_id: &str,
+    template: &BlockTemplate,
+) -> Result<Vec<LoggedEntry>, NotionError> {
+    let mut blocks = fetch_children(client, page_id).await?;
+    for block in blocks.iter_mut() {
+        let expand = has_children(block) && (is_table(block) || heading_body(block).is_some());
+        if !expand {
+            continue;
+        }
+        let mut children = fetch_children(client, &block_id(block)).await?;
+        for child in children.iter_mut().filter(|child| is_table(child) && has_children(child)) {
+            let rows = fetch_children(client, &block_id(child)).await?;
+            set_children(child, rows);
+        }
+        set_children(block, children);
+    }
+    Ok(parse_entries(&blocks, template))
+}
+
+/// Find entry headings among a page's blocks. A toggleable entry holds its
+/// sections as children; a flat one is followed by them. Either way the
+/// sections open and close with a divider. Headings and labels match in
+/// the template's locale or in English, so older entries still parse.
+pub fn parse_entries(blocks: &[Value], template: &BlockTemplate) -> Vec<LoggedEntry> {
+    let mut entries = Vec::new();
+    let mut index = 0;
+    while index < blocks.len() {
+        let block = &blocks[index];
+        index += 1;
+        let Some(heading) = heading_body(block) else {
+            continue;
+        };
+
+        let sections = match heading.get("children").and_then(Value::as_array) {
+            Some(children) if children.first().is_some_and(is_divider) => children.as_slice(),
+            Some(_) => continue,
+            None => {
+                if !blocks.get(index).is_some_and(is_divider) {
+                    continue;
+                }
+                let end = blocks[index + 1..]
+                    .iter()
+                    .position(is_divider)
+                    .map_or(blocks.len(), |offset| index + offset + 2);
+                let sections = &blocks[index..end];
+                index = end;
+                sections
+            }
+        };
+
+        let title = plain_text(&heading["rich_text"]);
+        let title = title.trim();
+        let severity = Severity::ALL.into_iter().find(|severity| title.starts_with(severity.emoji()));
+        let title = severity.map_or(title, |severity| &title[severity.emoji().len()..]);
+        let mut entry = parse_sections(sections, template);
+        entry.severity = severity;
+        entries.push(LoggedEntry {
+            block_id: block_id(block),
+            title: title.trim_start_matches('🐛').trim().to_string(),
+            entry,
+        });
+    }
+    entries
+}
+
+/// Rebuild an entry from its section blocks. Text belongs to the Error
+/// field until a section heading matching one of the labels switches field.
+fn parse_sections(blocks: &[Value], template: &BlockTemplate) -> FaultLogEntry {
+    let labels = &template.labels;
+    let mut texts: [Vec<String>; InputField::ALL.len()] = Default::default();
+    let mut field = InputField::Error;
+    let mut language = None;
+    let mut tags = Vec::new();
+
+    for block in blocks {
+        let kind = block.get("type").and_then(Value::as_str).unwrap_or_default();
+        let body = &block[kind];
+        let text = match kind {
+            "heading_3" => {
+                let heading = plain_text(&body["rich_text"]);
+                let heading = heading.trim();
+                let matches = |f: &InputField| template.heading(*f) == heading || labels.heading(*f) == heading;
+                if let Some(next) = InputField::SECTIONS.into_iter().find(matches) {
+                    field = next;
+                }
+                continue;
+            }
+            "callout" | "to_do" => plain_text(&body["rich_text"]),
+            "paragraph" => {
+                let text = plain_text(&body["rich_text"]);
+                let list = text
+                    .strip_prefix(template.tags_prefix().as_str())
+                    .or_else(|| text.strip_prefix(Locale::En.tags_prefix()));
+                if let Some(list) = list {
+                    tags = chip_texts(&body["rich_text"]);
+                    // Entries logged before tags were chips list them
+                    // comma separated
+                    if tags.is_empty() {
+                        tags = list.split(", ").map(str::to_string).collect();
+                    }
+                    continue;
+                }
+                // Gray notes such as the metadata line belong to no field
+                if body["color"] == "gray" {
+                    continue;
+                }
+                // The plain style leads with a bold "Label: " run
+                let prefix = format!("{}: ", template.label(field));
+                let english = format!("{}: ", labels.label(field));
+                text.strip_prefix(&prefix)
+                    .or_else(|| text.strip_prefix(&english))
+                    .map(str::to_string)
+                    .unwrap_or(text)
+            }
+            "equation" => format!("$${}$$", body["expression"].as_str().unwrap_or_default()),
+            "code" => {
+                language = body["language"].as_str().map(str::to_string);
+                plain_text(&body["rich_text"])
+            }
+            "table" => table_text(body),
+            _ => continue,
+        };
+        if !text.is_empty() {
+            texts[field.index()].push(text);
+        }
+    }
+
+    let take = |field: InputField| texts[field.index()].join("\n");
+    let optional = |field: InputField| Some(take(field)).filter(|text| !text.is_empty());
+    FaultLogEntry {
+        error: take(InputField::Error),
+        problem: take(InputField::Problem),
+        solution: take(InputField::Solution),
+        code: optional(InputField::Code),
+        verification: optional(InputField::Verification),
+        context: optional(InputField::Context),
+        language,
+        attachments: Vec::new(),
+        files: Vec::new(),
+        tags,
+        relations: BTreeMap::new(),
+        status: FaultStatus::Open,
+        severity: None,
+        git: None,
+        sub_entries: Vec::new(),
+    }
+}
+
+/// Context table rows as `key: value` lines, skipping the header row
+fn table_text(table: &Value) -> String {
+    let Some(rows) = table.get("children").and_then(Value::as_array) else {
+        return String::new();
+    };
+    let skip = usize::from(table["has_column_header"].as_bool().unwrap_or(false));
+    rows.iter()
+        .skip(skip)
+        .filter_map(|row| row["table_row"]["cells"].as_array())
+        .map(|cells| {
+            let cell = |idx: usize| cells.get(idx).map(plain_text).unwrap_or_default();
+            let (key, value) = (cell(0), cell(1));
+            if value.is_empty() {
+                key
+            } else {
+                format!("{}: {}", key, value)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Text of a rich text array; inline equations come back as `$$…$$`
+pub fn plain_text(runs: &Value) -> String {
+    let Some(runs) = runs.as_array() else {
+        return String::new();
+    };
+    runs.iter()
+        .map(|run| match run.get("type").and_then(Value::as_str) {
+            Some("equation") => format!("$${}$$", run["equation"]["expression"].as_str().unwrap_or_default()),
+            _ => run
+                .get("plain_text")
+                .or_else(|| run["text"].get("content"))
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+        })
+        .collect()
+}
+
+/// Text of the inline code runs of a rich text array, one per tag chip
+fn chip_texts(runs: &Value) -> Vec<String> {
+    runs.as_array()
+        .into_iter()
+        .flatten()
+        .filter(|run| run["annotations"]["code"] == true)
+        .map(|run| plain_text(&Value::Array(vec![run.clone()])))
+        .filter(|text| !text.trim().is_empty())
+        .collect()
+}
+
+/// The type-specific body of a heading block
+fn heading_body(block: &Value) -> Option<&Value> {
+    let kind = block.get("type").and_then(Value::as_str)?;
+    if matches!(kind, "heading_1" | "heading_2" | "heading_3") {
+        block.get(kind)
+    } else {
+        None
+    }
+}
+
+pub(crate) fn set_children(block: &mut Value, children: Vec<Value>) {
+    let kind = block.get("type").and_then(Value::as_str).unwrap_or_default().to_string();
+    if let Some(body) = block.get_mut(&kind).and_then(Value::as_object_mut) {
+        body.insert("children".to_string(), Value::Array(children));
+    }
+}
+
+pub(crate) fn block_id(block: &Value) -> String {
+    block.get("id").and_then(Value::as_str).unwrap_or_default().to_string()
+}
+
+pub(crate) fn has_children(block: &Value) -> bool {
+    block.get("has_children").and_then(Value::as_bool).unwrap_or(false)
+}
+
+fn is_divider(block: &Value) -> bool {
+    block.get("type").and_then(Value::as_str) == Some("divider")
+}
+
+fn is_table(block: &Value) -> bool {
+    block.get("type").and_then(Value::as_str) == Some("table")
+}
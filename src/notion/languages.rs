@@ -0,0 +1,111 @@
+// Code block languages Notion can highlight
+
+/// Language used when an entry does not name one
+pub const DEFAULT_LANGUAGE: &str = "rust";
+
+/// Every language Notion accepts for a code block, as the API spells them
+pub const LANGUAGES: &[&str] = &[
+    "abap",
+    "arduino",
+    "bash",
+    "basic",
+    "c",
+    "c#",
+    "c++",
+    "clojure",
+    "coffeescript",
+    "css",
+    "dart",
+    "diff",
+    "docker",
+    "elixir",
+    "elm",
+    "erlang",
+    "f#",
+    "flow",
+    "fortran",
+    "gherkin",
+    "glsl",
+    "go",
+    "graphql",
+    "groovy",
+    "haskell",
+    "html",
+    "java",
+    "java/c/c++/c#",
+    "javascript",
+    "json",
+    "julia",
+    "kotlin",
+    "latex",
+    "less",
+    "lisp",
+    "livescript",
+    "lua",
+    "makefile",
+    "markdown",
+    "markup",
+    "matlab",
+    "mermaid",
+    "nix",
+    "objective-c",
+    "ocaml",
+    "pascal",
+    "perl",
+    "php",
+    "plain text",
+    "powershell",
+    "prolog",
+    "protobuf",
+    "python",
+    "r",
+    "reason",
+    "ruby",
+    "rust",
+    "sass",
+    "scala",
+    "scheme",
+    "scss",
+    "shell",
+    "sql",
+    "swift",
+    "typescript",
+    "vb.net",
+    "verilog",
+    "vhdl",
+    "visual basic",
+    "webassembly",
+    "xml",
+    "yaml",
+];
+
+/// Short names people type for languages
+const ALIASES: &[(&str, &str)] = &[
+    ("cpp", "c++"),
+    ("csharp", "c#"),
+    ("dockerfile", "docker"),
+    ("golang", "go"),
+    ("js", "javascript"),
+    ("kt", "kotlin"),
+    ("md", "markdown"),
+    ("plain", "plain text"),
+    ("plaintext", "plain text"),
+    ("py", "python"),
+    ("rb", "ruby"),
+    ("rs", "rust"),
+    ("sh", "shell"),
+    ("text", "plain text"),
+    ("ts", "typescript"),
+    ("yml", "yaml"),
+    ("zsh", "shell"),
+];
+
+/// The Notion spelling of a language name or common alias, ignoring case
+pub fn normalize(name: &str) -> Option<&'static str> {
+    let name = name.trim().to_lowercase();
+    LANGUAGES
+        .iter()
+        .copied()
+        .find(|language| *language == name)
+        .or_else(|| ALIASES.iter().find(|(alias, _)| *alias == name).map(|(_, language)| *language))
+}
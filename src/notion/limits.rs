@@ -0,0 +1,142 @@
+// Notion request limits and the overflow fallback for oversized entries
+use serde_json::Value;
+
+use crate::app::FaultLogEntry;
+
+/// Maximum characters in a single rich text object
+pub const MAX_TEXT_LENGTH: usize = 2000;
+/// Maximum elements in any array (children, rich text, table rows)
+pub const MAX_ARRAY_ITEMS: usize = 100;
+/// Maximum blocks in a single request
+pub const MAX_BLOCK_ELEMENTS: usize = 1000;
+/// Maximum size of a request body
+pub const MAX_PAYLOAD_BYTES: usize = 500 * 1024;
+
+/// Characters of each oversized field kept inline when the rest is attached
+const PREVIEW_CHARS: usize = 1500;
+/// Checklist steps or context rows kept inline when the rest is attached
+const PREVIEW_ITEMS: usize = 40;
+
+/// Name of the text file holding content that did not fit in blocks
+pub const OVERFLOW_FILENAME: &str = "faultnote-overflow.txt";
+
+/// Size of a block payload measured against Notion's limits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PayloadReport {
+    pub blocks: usize,
+    pub largest_array: usize,
+    pub bytes: usize,
+}
+
+impl PayloadReport {
+    /// Measure a JSON array of blocks as built by `create_error_block`
+    pub fn of(blocks: &Value) -> Self {
+        let mut report = Self {
+            bytes: serde_json::to_vec(blocks).map(|b| b.len()).unwrap_or(0),
+            ..Self::default()
+        };
+        report.walk(blocks);
+        report
+    }
+
+    fn walk(&mut self, value: &Value) {
+        match value {
+            Value::Array(items) => {
+                self.largest_array = self.largest_array.max(items.len());
+                items.iter().for_each(|item| self.walk(item));
+            }
+            Value::Object(map) => {
+                if map.get("object").and_then(Value::as_str) == Some("block") {
+                    self.blocks += 1;
+                }
+                map.values().for_each(|item| self.walk(item));
+            }
+            _ => {}
+        }
+    }
+
+    pub fn exceeds_limits(&self) -> bool {
+        self.blocks > MAX_BLOCK_ELEMENTS
+            || self.largest_array > MAX_ARRAY_ITEMS
+            || self.bytes > MAX_PAYLOAD_BYTES
+    }
+
+    /// Human readable summary for the status bar
+    pub fn describe(&self) -> String {
+        format!(
+            "Entry too large for Notion ({} blocks, {} items in one list, {} KB)",
+            self.blocks,
+            self.largest_array,
+            self.bytes / 1024
+        )
+    }
+}
+
+/// Shorten oversized fields so the entry fits in blocks.
+/// Returns the trimmed entry and the full text of every shortened field,
+/// or `None` when nothing needed trimming.
+pub fn split_overflow(entry: &FaultLogEntry) -> Option<(FaultLogEntry, String)> {
+    let mut trimmed = entry.clone();
+    let mut overflow = String::new();
+
+    let text_fields = [
+        ("Error", &mut trimmed.error),
+        ("Problem", &mut trimmed.problem),
+        ("Solution", &mut trimmed.solution),
+    ];
+    for (label, field) in text_fields {
+        if let Some(preview) = preview_chars(field) {
+            push_section(&mut overflow, label, field);
+            *field = preview;
+        }
+    }
+
+    let optional_fields = [
+        ("Code", &mut trimmed.code, false),
+        ("Verification", &mut trimmed.verification, true),
+        ("Context", &mut trimmed.context, true),
+    ];
+    for (label, field, by_line) in optional_fields {
+        let Some(text) = field.as_mut() else {
+            continue;
+        };
+        let preview = if by_line {
+            preview_lines(text)
+        } else {
+            preview_chars(text)
+        };
+        if let Some(preview) = preview {
+            push_section(&mut overflow, label, text);
+            *text = preview;
+        }
+    }
+
+    if overflow.is_empty() {
+        None
+    } else {
+        Some((trimmed, overflow))
+    }
+}
+
+fn preview_chars(text: &str) -> Option<String> {
+    if text.chars().count() <= PREVIEW_CHARS {
+        return None;
+    }
+    let head: String = text.chars().take(PREVIEW_CHARS).collect();
+    Some(format!("{}\n… (truncated, full text in {})", head, OVERFLOW_FILENAME))
+}
+
+fn preview_lines(text: &str) -> Option<String> {
+    if text.lines().count() <= PREVIEW_ITEMS {
+        return preview_chars(text);
+    }
+    let head: Vec<&str> = text.lines().take(PREVIEW_ITEMS).collect();
+    Some(head.join("\n"))
+}
+
+fn push_section(out: &mut String, label: &str, text: &str) {
+    if !out.is_empty() {
+        out.push_str("\n\n");
+    }
+    out.push_str(&format!("===== {} =====\n{}", label, text));
+}
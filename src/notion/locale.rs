@@ -0,0 +1,186 @@
+// Language of the text FaultNote writes to Notion, independent of the TUI
+use serde::Deserialize;
+
+use crate::fields::InputField;
+
+/// Locale of emitted section labels, headings and dates. Written as a
+/// language code such as "de" or "pt-BR"; only the language part counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Fr,
+    Es,
+    Pt,
+    It,
+    Nl,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 7] = [
+        Locale::En,
+        Locale::De,
+        Locale::Fr,
+        Locale::Es,
+        Locale::Pt,
+        Locale::It,
+        Locale::Nl,
+    ];
+
+    /// The locale for a code like "de", "de_DE" or "pt-BR", ignoring case
+    pub fn parse(code: &str) -> Option<Self> {
+        let language = code.trim().split(['-', '_', '.']).next()?.to_lowercase();
+        Self::ALL.into_iter().find(|locale| locale.code() == language)
+    }
+
+    /// Two-letter language code
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::De => "de",
+            Locale::Fr => "fr",
+            Locale::Es => "es",
+            Locale::Pt => "pt",
+            Locale::It => "it",
+            Locale::Nl => "nl",
+        }
+    }
+
+    /// Short section label, as in plain-style "Label: " runs
+    pub fn label(self, field: InputField) -> &'static str {
+        use InputField::*;
+        match (self, field) {
+            (Locale::En, _) => field.default_label(),
+            (Locale::De, Error) => "Fehler",
+            (Locale::De, Problem) => "Problem",
+            (Locale::De, Solution) => "Lösung",
+            (Locale::De, Code) => "Code",
+            (Locale::De, Verification) => "Überprüfung",
+            (Locale::De, Context) => "Kontext",
+            (Locale::De, Tags) => "Schlagwörter",
+            (Locale::Fr, Error) => "Erreur",
+            (Locale::Fr, Problem) => "Problème",
+            (Locale::Fr, Solution) => "Solution",
+            (Locale::Fr, Code) => "Code",
+            (Locale::Fr, Verification) => "Vérification",
+            (Locale::Fr, Context) => "Contexte",
+            (Locale::Fr, Tags) => "Étiquettes",
+            (Locale::Es, Error) => "Error",
+            (Locale::Es, Problem) => "Problema",
+            (Locale::Es, Solution) => "Solución",
+            (Locale::Es, Code) => "Código",
+            (Locale::Es, Verification) => "Verificación",
+            (Locale::Es, Context) => "Contexto",
+            (Locale::Es, Tags) => "Etiquetas",
+            (Locale::Pt, Error) => "Erro",
+            (Locale::Pt, Problem) => "Problema",
+            (Locale::Pt, Solution) => "Solução",
+            (Locale::Pt, Code) => "Código",
+            (Locale::Pt, Verification) => "Verificação",
+            (Locale::Pt, Context) => "Contexto",
+            (Locale::Pt, Tags) => "Etiquetas",
+            (Locale::It, Error) => "Errore",
+            (Locale::It, Problem) => "Problema",
+            (Locale::It, Solution) => "Soluzione",
+            (Locale::It, Code) => "Codice",
+            (Locale::It, Verification) => "Verifica",
+            (Locale::It, Context) => "Contesto",
+            (Locale::It, Tags) => "Etichette",
+            (Locale::Nl, Error) => "Fout",
+            (Locale::Nl, Problem) => "Probleem",
+            (Locale::Nl, Solution) => "Oplossing",
+            (Locale::Nl, Code) => "Code",
+            (Locale::Nl, Verification) => "Verificatie",
+            (Locale::Nl, Context) => "Context",
+            (Locale::Nl, Tags) => "Tags",
+        }
+    }
+
+    /// Heading above a section
+    pub fn heading(self, field: InputField) -> &'static str {
+        use InputField::*;
+        match (self, field) {
+            (Locale::En, _) => field.default_heading(),
+            (_, Error | Context | Tags) => self.label(field),
+            (Locale::De, Problem) => "Was war das Problem?",
+            (Locale::De, Solution) => "Wie wurde es behoben?",
+            (Locale::De, Code) => "Codeverweis",
+            (Locale::De, Verification) => "Wie lässt es sich prüfen?",
+            (Locale::Fr, Problem) => "Quel était le problème ?",
+            (Locale::Fr, Solution) => "Comment l'avez-vous corrigé ?",
+            (Locale::Fr, Code) => "Référence de code",
+            (Locale::Fr, Verification) => "Comment vérifier ?",
+            (Locale::Es, Problem) => "¿Cuál era el problema?",
+            (Locale::Es, Solution) => "¿Cómo se solucionó?",
+            (Locale::Es, Code) => "Referencia de código",
+            (Locale::Es, Verification) => "¿Cómo verificarlo?",
+            (Locale::Pt, Problem) => "Qual era o problema?",
+            (Locale::Pt, Solution) => "Como foi corrigido?",
+            (Locale::Pt, Code) => "Referência de código",
+            (Locale::Pt, Verification) => "Como verificar?",
+            (Locale::It, Problem) => "Qual era il problema?",
+            (Locale::It, Solution) => "Come è stato risolto?",
+            (Locale::It, Code) => "Riferimento al codice",
+            (Locale::It, Verification) => "Come verificare?",
+            (Locale::Nl, Problem) => "Wat was het probleem?",
+            (Locale::Nl, Solution) => "Hoe is het opgelost?",
+            (Locale::Nl, Code) => "Codeverwijzing",
+            (Locale::Nl, Verification) => "Hoe controleren?",
+        }
+    }
+
+    /// Heading above the bookmarks of links found in the entry
+    pub fn related_links_heading(self) -> &'static str {
+        match self {
+            Locale::En => "Related links",
+            Locale::De => "Weiterführende Links",
+            Locale::Fr => "Liens associés",
+            Locale::Es => "Enlaces relacionados",
+            Locale::Pt => "Links relacionados",
+            Locale::It => "Link correlati",
+            Locale::Nl => "Gerelateerde links",
+        }
+    }
+
+    /// Leads the paragraph listing an entry's tags
+    pub fn tags_prefix(self) -> &'static str {
+        match self {
+            Locale::En | Locale::Nl => "Tags: ",
+            Locale::De => "Schlagwörter: ",
+            Locale::Fr => "Étiquettes : ",
+            Locale::Es | Locale::Pt => "Etiquetas: ",
+            Locale::It => "Etichette: ",
+        }
+    }
+
+    /// chrono format of `{date}`
+    pub fn date_format(self) -> &'static str {
+        match self {
+            Locale::En => "%Y-%m-%d",
+            Locale::De => "%d.%m.%Y",
+            Locale::Fr | Locale::Es | Locale::Pt | Locale::It => "%d/%m/%Y",
+            Locale::Nl => "%d-%m-%Y",
+        }
+    }
+
+    /// chrono format of `{time}`
+    pub fn time_format(self) -> &'static str {
+        match self {
+            Locale::Fr => "%Hh%M",
+            _ => "%H:%M",
+        }
+    }
+}
+
+impl TryFrom<String> for Locale {
+    type Error = String;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        Self::parse(&code).ok_or_else(|| {
+            let known: Vec<&str> = Self::ALL.iter().map(|locale| locale.code()).collect();
+            format!("unknown locale \"{}\", expected one of {}", code, known.join(", "))
+        })
+    }
+}
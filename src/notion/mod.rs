@@ -1 +1,14 @@
+pub mod activity;
+pub mod anchor;
+pub mod capabilities;
 pub mod client;
+pub mod database;
+pub mod entries;
+pub mod languages;
+pub mod locale;
+pub mod limits;
+pub mod preview;
+pub mod rate_limit;
+pub mod retry;
+pub mod template;
+pub mod tokens;
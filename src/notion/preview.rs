@@ -0,0 +1,151 @@
+// Read-only preview of a whole Notion page, to skim its notes and
+// conventions before logging to it
+use std::future::Future;
+use std::pin::Pin;
+
+use serde_json::Value;
+use tracing::instrument;
+use unicode_width::UnicodeWidthStr;
+
+use crate::notion::client::{NotionClient, NotionError};
+use crate::notion::entries::{block_id, fetch_children, has_children, plain_text, set_children};
+
+/// Levels of nested blocks fetched below the page; deeper ones are left out
+pub const MAX_DEPTH: usize = 3;
+
+/// How a preview line is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewStyle {
+    Heading,
+    Text,
+    Code,
+    Quote,
+    /// Dividers, code languages and blocks shown only by their type
+    Muted,
+}
+
+/// One line of the preview
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewLine {
+    pub style: PreviewStyle,
+    pub text: String,
+}
+
+impl PreviewLine {
+    fn new(style: PreviewStyle, text: impl Into<String>) -> Self {
+        Self {
+            style,
+            text: text.into(),
+        }
+    }
+}
+
+/// Fetch a page's blocks with their children, `MAX_DEPTH` levels deep.
+/// Children are stored under `children`, like `fetch_entries` does.
+#[instrument(skip(client), err)]
+pub async fn fetch_page_content(client: &NotionClient, page_id: &str) -> Result<Vec<Value>, NotionError> {
+    fetch_tree(client, page_id.to_string(), 0).await
+}
+
+fn fetch_tree(
+    client: &NotionClient,
+    parent: String,
+    depth: usize,
+) -> Pin<Box<dyn Future<Output = Result<Vec<Value>, NotionError>> + '_>> {
+    Box::pin(async move {
+        let mut blocks = fetch_children(client, &parent).await?;
+        if depth < MAX_DEPTH {
+            for block in blocks.iter_mut().filter(|block| expands(block)) {
+                let children = fetch_tree(client, block_id(block), depth + 1).await?;
+                set_children(block, children);
+            }
+        }
+        Ok(blocks)
+    })
+}
+
+/// Nested blocks are part of the page; subpages are pages of their own
+fn expands(block: &Value) -> bool {
+    let kind = block.get("type").and_then(Value::as_str).unwrap_or_default();
+    has_children(block) && !matches!(kind, "child_page" | "child_database")
+}
+
+/// The page as text: headings, paragraphs, lists, quotes and code, nested
+/// blocks indented under their parent
+pub fn preview_lines(blocks: &[Value]) -> Vec<PreviewLine> {
+    let mut lines = Vec::new();
+    push_blocks(&mut lines, blocks, 0);
+    lines
+}
+
+fn push_blocks(lines: &mut Vec<PreviewLine>, blocks: &[Value], depth: usize) {
+    let indent = "  ".repeat(depth);
+    let mut number = 0;
+    for block in blocks {
+        let kind = block.get("type").and_then(Value::as_str).unwrap_or_default();
+        let body = &block[kind];
+        let text = plain_text(&body["rich_text"]);
+        number = if kind == "numbered_list_item" { number + 1 } else { 0 };
+        match kind {
+            "paragraph" => push_text(lines, PreviewStyle::Text, &indent, "", &text),
+            "heading_1" | "heading_2" | "heading_3" => {
+                let level = usize::from(kind.as_bytes()[kind.len() - 1] - b'0');
+                push_text(lines, PreviewStyle::Heading, &indent, &format!("{} ", "#".repeat(level)), &text);
+            }
+            "bulleted_list_item" => push_text(lines, PreviewStyle::Text, &indent, "• ", &text),
+            "numbered_list_item" => push_text(lines, PreviewStyle::Text, &indent, &format!("{}. ", number), &text),
+            "to_do" => {
+                let checked = body["checked"].as_bool().unwrap_or(false);
+                push_text(lines, PreviewStyle::Text, &indent, if checked { "☑ " } else { "☐ " }, &text);
+            }
+            "toggle" => push_text(lines, PreviewStyle::Text, &indent, "▸ ", &text),
+            "quote" => push_text(lines, PreviewStyle::Quote, &indent, "│ ", &text),
+            "callout" => {
+                let icon = body["icon"]["emoji"].as_str().unwrap_or("💡");
+                push_text(lines, PreviewStyle::Quote, &indent, &format!("{} ", icon), &text);
+            }
+            "code" => {
+                let language = body["language"].as_str().unwrap_or_default();
+                lines.push(PreviewLine::new(PreviewStyle::Muted, format!("{}```{}", indent, language)));
+                for line in text.lines() {
+                    lines.push(PreviewLine::new(PreviewStyle::Code, format!("{}{}", indent, line)));
+                }
+                lines.push(PreviewLine::new(PreviewStyle::Muted, format!("{}```", indent)));
+            }
+            "equation" => {
+                let expression = body["expression"].as_str().unwrap_or_default();
+                lines.push(PreviewLine::new(PreviewStyle::Text, format!("{}$${}$$", indent, expression)));
+            }
+            "divider" => lines.push(PreviewLine::new(PreviewStyle::Muted, format!("{}────────", indent))),
+            "table_row" => {
+                let cells: Vec<String> = body["cells"].as_array().into_iter().flatten().map(plain_text).collect();
+                lines.push(PreviewLine::new(PreviewStyle::Text, format!("{}│ {} │", indent, cells.join(" │ "))));
+            }
+            // A table is only its rows
+            "table" => {}
+            "child_page" | "child_database" => {
+                let title = body["title"].as_str().unwrap_or_default();
+                lines.push(PreviewLine::new(PreviewStyle::Muted, format!("{}📄 {}", indent, title)));
+            }
+            "" => continue,
+            other => lines.push(PreviewLine::new(PreviewStyle::Muted, format!("{}[{}]", indent, other.replace('_', " ")))),
+        }
+        if let Some(children) = body.get("children").and_then(Value::as_array) {
+            // Table rows line up with their table
+            let depth = if kind == "table" { depth } else { depth + 1 };
+            push_blocks(lines, children, depth);
+        }
+    }
+}
+
+/// Text over one or more lines, the first after `marker` and the rest
+/// lined up under it. An empty paragraph stays a blank line.
+fn push_text(lines: &mut Vec<PreviewLine>, style: PreviewStyle, indent: &str, marker: &str, text: &str) {
+    let hanging = " ".repeat(marker.width());
+    let mut text_lines = text.lines();
+    let first = text_lines.next().unwrap_or_default();
+    lines.push(PreviewLine::new(style, format!("{}{}{}", indent, marker, first).trim_end().to_string()));
+    for line in text_lines {
+        lines.push(PreviewLine::new(style, format!("{}{}{}", indent, hanging, line)));
+    }
+}
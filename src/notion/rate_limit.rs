@@ -0,0 +1,106 @@
+// Pacing for batches of requests, so retrying a long queue stays under
+// Notion's average limit of three requests per second instead of hitting 429s
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+
+/// Notion's documented average request rate per integration
+pub const REQUESTS_PER_SECOND: f64 = 3.0;
+/// Most requests of a batch in flight at once
+pub const MAX_IN_FLIGHT: usize = 3;
+
+/// Token bucket: `capacity` requests may go at once, then one more every
+/// `1 / rate` seconds
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    capacity: f64,
+    rate: f64,
+    /// Tokens left; negative when requests are already waiting for refills
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    /// A full bucket
+    pub fn new(capacity: f64, rate: f64, now: Instant) -> Self {
+        Self {
+            capacity,
+            rate,
+            tokens: capacity,
+            refilled_at: now,
+        }
+    }
+
+    /// Take a token, returning how long to wait before sending. Waiting
+    /// requests are served in the order they reserved.
+    pub fn reserve(&mut self, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.refilled_at = now;
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+/// A token bucket shared by the tasks of a batch
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    bucket: Arc<Mutex<TokenBucket>>,
+}
+
+impl RateLimiter {
+    /// Limiter at Notion's request rate, allowing a burst of `MAX_IN_FLIGHT`
+    pub fn notion() -> Self {
+        let bucket = TokenBucket::new(MAX_IN_FLIGHT as f64, REQUESTS_PER_SECOND, Instant::now());
+        Self {
+            bucket: Arc::new(Mutex::new(bucket)),
+        }
+    }
+
+    /// Wait until the next request may be sent
+    pub async fn acquire(&self) {
+        let wait = self.bucket.lock().await.reserve(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// How far a batch has got, for progress lines with an ETA
+#[derive(Debug, Clone, Copy)]
+pub struct BatchProgress {
+    pub done: usize,
+    pub total: usize,
+    started: Instant,
+}
+
+impl BatchProgress {
+    pub fn new(total: usize, now: Instant) -> Self {
+        Self { done: 0, total, started: now }
+    }
+
+    /// Time the rest should take: at the observed pace, but never faster
+    /// than the rate limit allows
+    pub fn eta(&self, now: Instant) -> Duration {
+        let remaining = self.total.saturating_sub(self.done) as f64;
+        let limited = 1.0 / REQUESTS_PER_SECOND;
+        let observed = match self.done {
+            0 => 0.0,
+            done => now.saturating_duration_since(self.started).as_secs_f64() / done as f64,
+        };
+        Duration::from_secs_f64(remaining * limited.max(observed))
+    }
+
+    /// e.g. "12/40, about 10s left"
+    pub fn label(&self, now: Instant) -> String {
+        if self.done >= self.total {
+            return format!("{}/{}", self.done, self.total);
+        }
+        format!("{}/{}, about {}s left", self.done, self.total, self.eta(now).as_secs_f64().ceil() as u64)
+    }
+}
@@ -0,0 +1,115 @@
+// Retrying Notion requests that failed for a passing reason: rate limits
+// (429 with `Retry-After`), overloaded servers and dropped connections
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use reqwest::Method;
+use tokio::sync::{mpsc, watch};
+
+use crate::app_event::AppEvent;
+
+/// Attempts per request, the first included
+pub const MAX_ATTEMPTS: u32 = 4;
+/// Delay before the first retry when Notion gives no `Retry-After`;
+/// doubled on each further attempt
+pub const BASE_DELAY: Duration = Duration::from_millis(500);
+/// Longest `Retry-After` honored; a longer one fails the request instead
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Why a request is being retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryReason {
+    RateLimited,
+    ServerError(u16),
+    Unreachable,
+}
+
+/// A retry about to happen, shown in the status line while it waits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryNotice {
+    pub reason: RetryReason,
+    /// The attempt that is about to be made
+    pub attempt: u32,
+    pub wait: Duration,
+}
+
+impl RetryNotice {
+    /// e.g. "Notion is rate limiting; retrying in 2s (attempt 2 of 4)"
+    pub fn message(&self) -> String {
+        let reason = match self.reason {
+            RetryReason::RateLimited => "Notion is rate limiting".to_string(),
+            RetryReason::ServerError(status) => format!("Notion answered {}", status),
+            RetryReason::Unreachable => "Notion did not answer".to_string(),
+        };
+        format!(
+            "{}; retrying in {}s (attempt {} of {})",
+            reason,
+            self.wait.as_secs_f64().ceil() as u64,
+            self.attempt,
+            MAX_ATTEMPTS
+        )
+    }
+}
+
+/// Whether a failed attempt is worth repeating. `status` is `None` when no
+/// response came; `connected` tells whether the request may have reached
+/// Notion. Requests that create or change content are only repeated when
+/// Notion certainly did not act on them, so a retry cannot duplicate an
+/// entry.
+pub fn retry_reason(method: &Method, status: Option<u16>, connected: bool) -> Option<RetryReason> {
+    let idempotent = matches!(*method, Method::GET | Method::HEAD | Method::DELETE);
+    match status {
+        Some(429) => Some(RetryReason::RateLimited),
+        Some(503) => Some(RetryReason::ServerError(503)),
+        Some(status @ (500 | 502 | 504)) if idempotent => Some(RetryReason::ServerError(status)),
+        Some(_) => None,
+        None if idempotent || !connected => Some(RetryReason::Unreachable),
+        None => None,
+    }
+}
+
+/// Delay before `attempt` (2 for the first retry): the `Retry-After`
+/// seconds when given, else exponential backoff scaled by `jitter` in
+/// [0, 1) so clients that failed together do not retry together. `None`
+/// when Notion asks to wait longer than `MAX_RETRY_AFTER`.
+pub fn retry_delay(attempt: u32, retry_after: Option<&str>, jitter: f64) -> Option<Duration> {
+    if let Some(seconds) = retry_after.and_then(|value| value.trim().parse::<u64>().ok()) {
+        let wait = Duration::from_secs(seconds);
+        return (wait <= MAX_RETRY_AFTER).then_some(wait);
+    }
+    let backoff = BASE_DELAY * 2u32.pow(attempt.saturating_sub(2).min(6));
+    Some(backoff.mul_f64(0.5 + jitter.clamp(0.0, 1.0)))
+}
+
+/// A number in [0, 1) that differs between calls
+pub fn jitter() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u8(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Receives notices from every copy of a client, for the status line
+pub struct RetryWatcher {
+    receiver: watch::Receiver<Option<RetryNotice>>,
+}
+
+impl RetryWatcher {
+    pub fn new(receiver: watch::Receiver<Option<RetryNotice>>) -> Self {
+        Self { receiver }
+    }
+
+    /// Send each retry to the main loop as `AppEvent::Retrying`
+    pub fn forward(mut self, events: mpsc::UnboundedSender<AppEvent>) {
+        tokio::spawn(async move {
+            while self.receiver.changed().await.is_ok() {
+                let notice = self.receiver.borrow_and_update().clone();
+                if let Some(notice) = notice {
+                    if events.send(AppEvent::Retrying(notice)).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+    }
+}
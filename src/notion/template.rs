@@ -0,0 +1,217 @@
+// Block template: layout options applied when building Notion blocks
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::fields::{FieldLabels, InputField};
+use crate::notion::locale::Locale;
+use crate::vars::Vars;
+
+/// Visual style of section labels
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockStyle {
+    /// Colored callouts with emoji icons
+    #[default]
+    Emoji,
+    /// Bold text labels only, for workspaces that ban emoji
+    Plain,
+}
+
+/// Where new entries go on the page
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AppendPosition {
+    /// After everything else, Notion's default
+    #[default]
+    Bottom,
+    /// Newest first, directly below the page's first block (its header)
+    Top,
+}
+
+/// Size of the heading that opens each entry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeadingLevel {
+    H1,
+    #[default]
+    H2,
+    H3,
+}
+
+impl HeadingLevel {
+    /// Notion block type for this level
+    pub fn block_type(self) -> &'static str {
+        match self {
+            HeadingLevel::H1 => "heading_1",
+            HeadingLevel::H2 => "heading_2",
+            HeadingLevel::H3 => "heading_3",
+        }
+    }
+}
+
+/// Look of the heading that opens each entry
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HeadingTemplate {
+    pub level: HeadingLevel,
+    /// Collapse the sections under the heading; when false they follow it
+    /// flat on the page, which prints and exports better
+    pub toggleable: bool,
+    /// Notion color name, e.g. "red", "gray_background" or "default"
+    pub color: String,
+}
+
+impl Default for HeadingTemplate {
+    fn default() -> Self {
+        Self {
+            level: HeadingLevel::default(),
+            toggleable: true,
+            color: "red".to_string(),
+        }
+    }
+}
+
+/// Entry field copied into a database property
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryField {
+    Error,
+    Problem,
+    Solution,
+    Code,
+    Verification,
+    Context,
+    Language,
+    Tags,
+    Status,
+    Severity,
+}
+
+/// What fills a database property: an entry field such as `"problem"`,
+/// or a fixed `{ value = "Open" }`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum PropertySource {
+    Field(EntryField),
+    Value { value: String },
+}
+
+/// Options controlling how an entry is laid out on the Notion page
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BlockTemplate {
+    /// Emoji callouts or plain bold labels; also applies to the TUI
+    pub style: BlockStyle,
+    /// Insert a divider block after each entry
+    pub divider_between_entries: bool,
+    /// Insert an empty paragraph after each entry
+    pub blank_line_between_entries: bool,
+    /// Renamed section labels, shared with the TUI titles
+    pub labels: FieldLabels,
+    /// Language of the labels, headings and dates written to Notion;
+    /// the TUI stays in English
+    pub locale: Locale,
+    /// Level, color and collapsibility of the entry heading
+    pub heading: HeadingTemplate,
+    /// Append at the bottom of the page or prepend below its header
+    pub position: AppendPosition,
+    /// Insert entries directly below this block id; overrides `position`
+    pub after: Option<String>,
+    /// Heading such as "## Fault Log" to insert entries under, created at
+    /// the bottom of the page when missing; overrides `position`
+    pub anchor: Option<String>,
+    /// Database row properties by name, for entries submitted as new rows
+    pub properties: BTreeMap<String, PropertySource>,
+    /// Entry heading, e.g. "{date} {error}"; defaults to the Error field
+    pub title: Option<String>,
+    /// Gray line opening each entry, e.g. "Logged {date} on {hostname}"
+    pub metadata: Option<String>,
+    /// Cover of each new row's page: a Notion color ("red", "yellow",
+    /// "blue", "beige") or an image URL
+    pub cover: Option<String>,
+    /// Add the URLs found in the Error and Code fields as a "Related
+    /// links" list of bookmarks
+    pub related_links: bool,
+}
+
+impl BlockTemplate {
+    pub fn is_plain(&self) -> bool {
+        self.style == BlockStyle::Plain
+    }
+
+    /// Section label written to Notion: the renamed one, else the locale's
+    pub fn label(&self, field: InputField) -> &str {
+        self.labels.custom(field).unwrap_or(self.locale.label(field))
+    }
+
+    /// Section heading written to Notion: the renamed label, else the
+    /// locale's heading
+    pub fn heading(&self, field: InputField) -> &str {
+        self.labels.custom(field).unwrap_or(self.locale.heading(field))
+    }
+
+    /// Leads the paragraph listing the tags: the renamed Tags label, else
+    /// the locale's
+    pub fn tags_prefix(&self) -> String {
+        match self.labels.custom(InputField::Tags) {
+            Some(label) => format!("{}: ", label),
+            None => self.locale.tags_prefix().to_string(),
+        }
+    }
+
+    /// This template with `{variables}` in the title, metadata line and
+    /// labels filled in, ready to emit
+    pub fn resolve(&self, vars: &Vars) -> BlockTemplate {
+        let render = |text: &Option<String>| text.as_deref().map(|text| vars.render(text));
+        let labels = &self.labels;
+        BlockTemplate {
+            title: render(&self.title),
+            metadata: render(&self.metadata),
+            labels: FieldLabels {
+                error: render(&labels.error),
+                problem: render(&labels.problem),
+                solution: render(&labels.solution),
+                code: render(&labels.code),
+                verification: render(&labels.verification),
+                context: render(&labels.context),
+                tags: render(&labels.tags),
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Page cover for new rows, when one is configured
+    pub fn cover_value(&self) -> Option<Value> {
+        let cover = self.cover.as_deref()?.trim();
+        let url = match cover.to_lowercase().as_str() {
+            "" => return None,
+            color @ ("red" | "yellow" | "blue" | "beige") => {
+                format!("https://www.notion.so/images/page-cover/solid_{}.png", color)
+            }
+            _ => cover.to_string(),
+        };
+        Some(json!({ "type": "external", "external": { "url": url } }))
+    }
+
+    /// Top-level blocks appended after an entry to separate it from the next one
+    pub fn separator_blocks(&self) -> Vec<Value> {
+        let mut blocks = Vec::new();
+        if self.divider_between_entries {
+            blocks.push(json!({
+                "object": "block",
+                "type": "divider",
+                "divider": {}
+            }));
+        }
+        if self.blank_line_between_entries {
+            blocks.push(json!({
+                "object": "block",
+                "type": "paragraph",
+                "paragraph": { "rich_text": [] }
+            }));
+        }
+        blocks
+    }
+}
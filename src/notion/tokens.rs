@@ -0,0 +1,64 @@
+// Remembers which integration token last succeeded for each page
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use tracing::instrument;
+
+use crate::config::data_dir;
+use crate::fsck::write_atomic;
+
+/// Page id → name of the token that last wrote to it, saved as JSON
+#[derive(Debug, Default)]
+pub struct TokenMap {
+    pages: HashMap<String, String>,
+    path: Option<PathBuf>,
+}
+
+impl TokenMap {
+    /// Default location of the token map file
+    pub fn default_path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("tokens.json"))
+    }
+
+    /// Load the map from `path`; a missing file is an empty map
+    pub fn load(path: PathBuf) -> Result<Self, String> {
+        let pages = if path.exists() {
+            let text = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            serde_json::from_str(&text)
+                .map_err(|e| format!("Corrupt token map {}: {}", path.display(), e))?
+        } else {
+            HashMap::new()
+        };
+        Ok(Self {
+            pages,
+            path: Some(path),
+        })
+    }
+
+    pub fn get(&self, page_id: &str) -> Option<&str> {
+        self.pages.get(page_id).map(String::as_str)
+    }
+
+    /// Record the token that worked for a page; saves only when it changed
+    #[instrument(skip(self), err)]
+    pub fn record(&mut self, page_id: &str, token: &str) -> Result<(), String> {
+        if self.get(page_id) == Some(token) {
+            return Ok(());
+        }
+        self.pages.insert(page_id.to_string(), token.to_string());
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let json = serde_json::to_string_pretty(&self.pages)
+            .map_err(|e| format!("Failed to serialize token map: {}", e))?;
+        write_atomic(path, &json)
+    }
+}
@@ -0,0 +1,69 @@
+// Loads the page list from Notion in the background, so the TUI opens at
+// once on slow networks and the list can be refreshed without freezing it
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tokio::sync::mpsc;
+
+use crate::app::PageInfo;
+use crate::app_event::AppEvent;
+use crate::notion::capabilities::{Capabilities, fetch_capabilities};
+use crate::notion::client::{NotionClient, fetch_pages};
+
+/// Result of one background load
+#[derive(Debug)]
+pub struct PageLoad {
+    /// The first load of the session
+    pub initial: bool,
+    pub pages: Result<Vec<PageInfo>, String>,
+    /// What the integration may do on the first page, checked once per
+    /// session; `None` when not checked
+    pub capabilities: Option<Result<Capabilities, String>>,
+}
+
+/// Runs page fetches on a tokio task, which sends each result to the main
+/// loop as `AppEvent::PagesLoaded`
+pub struct PageLoader {
+    client: NotionClient,
+    events: mpsc::UnboundedSender<AppEvent>,
+    loading: Arc<AtomicBool>,
+    loaded: Arc<AtomicBool>,
+}
+
+impl PageLoader {
+    pub fn new(client: NotionClient, events: mpsc::UnboundedSender<AppEvent>) -> Self {
+        Self {
+            client,
+            events,
+            loading: Arc::default(),
+            loaded: Arc::default(),
+        }
+    }
+
+    /// Start fetching the page list; `false` when a fetch is already running
+    pub fn refresh(&mut self) -> bool {
+        if self.loading.swap(true, Ordering::SeqCst) {
+            return false;
+        }
+        let initial = !self.loaded.load(Ordering::SeqCst);
+        let (client, events) = (self.client.clone(), self.events.clone());
+        let (loading, loaded) = (self.loading.clone(), self.loaded.clone());
+        tokio::spawn(async move {
+            let pages = fetch_pages(&client).await.map_err(|e| e.to_string());
+            let page_ids: Vec<String> = pages.iter().flatten().map(|page| page.id.clone()).collect();
+            let capabilities = if initial && !page_ids.is_empty() {
+                Some(fetch_capabilities(&client, &page_ids).await.map_err(|e| e.to_string()))
+            } else {
+                None
+            };
+            loaded.fetch_or(pages.is_ok(), Ordering::SeqCst);
+            loading.store(false, Ordering::SeqCst);
+            let _ = events.send(AppEvent::PagesLoaded(PageLoad {
+                initial,
+                pages,
+                capabilities,
+            }));
+        });
+        true
+    }
+}
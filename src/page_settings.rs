@@ -0,0 +1,219 @@
+// Per-page preferences remembered between sessions
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::config::data_dir;
+use crate::fsck::write_atomic;
+use crate::notion::languages;
+use crate::notion::template::{AppendPosition, BlockStyle, BlockTemplate};
+
+/// Formatting conventions for one page; unset values fall back to the config
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PageSettings {
+    pub style: Option<BlockStyle>,
+    pub position: Option<AppendPosition>,
+    /// Heading such as "## Fault Log" to insert entries under
+    pub anchor: Option<String>,
+    /// Language preselected for the Code field when the page is selected
+    pub code_language: Option<String>,
+    /// Attached to every entry submitted to the page
+    pub tags: Vec<String>,
+}
+
+impl PageSettings {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Layer these settings over a template from the config. A remembered
+    /// anchor replaces a configured `after` block, since it was chosen later.
+    pub fn apply(&self, template: &mut BlockTemplate) {
+        if let Some(style) = self.style {
+            template.style = style;
+        }
+        if let Some(position) = self.position {
+            template.position = position;
+        }
+        if self.anchor.is_some() {
+            template.anchor = self.anchor.clone();
+            template.after = None;
+        }
+    }
+
+    /// Current value of a setting as typed on the settings screen
+    pub fn text(&self, setting: PageSetting) -> String {
+        match setting {
+            PageSetting::Style => self.style.map(style_name).unwrap_or_default().to_string(),
+            PageSetting::Position => self.position.map(position_name).unwrap_or_default().to_string(),
+            PageSetting::Anchor => self.anchor.clone().unwrap_or_default(),
+            PageSetting::CodeLanguage => self.code_language.clone().unwrap_or_default(),
+            PageSetting::Tags => self.tags.join(", "),
+        }
+    }
+
+    /// Set a text setting from the settings screen; empty text unsets it
+    pub fn set_text(&mut self, setting: PageSetting, text: &str) {
+        let text = text.trim();
+        let value = Some(text.to_string()).filter(|t| !t.is_empty());
+        match setting {
+            PageSetting::Style | PageSetting::Position => {}
+            PageSetting::Anchor => self.anchor = value,
+            PageSetting::CodeLanguage => {
+                self.code_language = value.map(|v| languages::normalize(&v).map_or_else(|| v.to_lowercase(), str::to_string))
+            }
+            PageSetting::Tags => {
+                self.tags = text
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+        }
+    }
+
+    /// Step a choice setting to its next value, ending back at unset
+    pub fn cycle(&mut self, setting: PageSetting) {
+        match setting {
+            PageSetting::Style => {
+                self.style = match self.style {
+                    None => Some(BlockStyle::Emoji),
+                    Some(BlockStyle::Emoji) => Some(BlockStyle::Plain),
+                    Some(BlockStyle::Plain) => None,
+                }
+            }
+            PageSetting::Position => {
+                self.position = match self.position {
+                    None => Some(AppendPosition::Bottom),
+                    Some(AppendPosition::Bottom) => Some(AppendPosition::Top),
+                    Some(AppendPosition::Top) => None,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Forget one setting so the config applies again
+    pub fn reset(&mut self, setting: PageSetting) {
+        match setting {
+            PageSetting::Style => self.style = None,
+            PageSetting::Position => self.position = None,
+            PageSetting::Anchor => self.anchor = None,
+            PageSetting::CodeLanguage => self.code_language = None,
+            PageSetting::Tags => self.tags.clear(),
+        }
+    }
+}
+
+/// A row on the page settings screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageSetting {
+    Style,
+    Position,
+    Anchor,
+    CodeLanguage,
+    Tags,
+}
+
+impl PageSetting {
+    pub const ALL: [PageSetting; 5] = [
+        PageSetting::Style,
+        PageSetting::Position,
+        PageSetting::Anchor,
+        PageSetting::CodeLanguage,
+        PageSetting::Tags,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PageSetting::Style => "Block style",
+            PageSetting::Position => "Insert at",
+            PageSetting::Anchor => "Anchor heading",
+            PageSetting::CodeLanguage => "Code language",
+            PageSetting::Tags => "Default tags",
+        }
+    }
+
+    /// Choices are cycled; everything else is typed
+    pub fn is_choice(self) -> bool {
+        matches!(self, PageSetting::Style | PageSetting::Position)
+    }
+}
+
+pub fn style_name(style: BlockStyle) -> &'static str {
+    match style {
+        BlockStyle::Emoji => "emoji",
+        BlockStyle::Plain => "plain",
+    }
+}
+
+pub fn position_name(position: AppendPosition) -> &'static str {
+    match position {
+        AppendPosition::Bottom => "bottom",
+        AppendPosition::Top => "top",
+    }
+}
+
+/// Page id → remembered settings, saved as JSON
+#[derive(Debug, Default)]
+pub struct PageSettingsStore {
+    pages: HashMap<String, PageSettings>,
+    path: Option<PathBuf>,
+}
+
+impl PageSettingsStore {
+    /// Default location of the page settings file
+    pub fn default_path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("page_settings.json"))
+    }
+
+    /// Load the settings from `path`; a missing file remembers nothing
+    pub fn load(path: PathBuf) -> Result<Self, String> {
+        let pages = if path.exists() {
+            let text = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            serde_json::from_str(&text)
+                .map_err(|e| format!("Corrupt page settings {}: {}", path.display(), e))?
+        } else {
+            HashMap::new()
+        };
+        Ok(Self {
+            pages,
+            path: Some(path),
+        })
+    }
+
+    pub fn get(&self, page_id: &str) -> Option<&PageSettings> {
+        self.pages.get(page_id)
+    }
+
+    /// Remember a page's settings; saves only when they changed
+    #[instrument(skip(self, settings), err)]
+    pub fn set(&mut self, page_id: &str, settings: PageSettings) -> Result<(), String> {
+        if self.get(page_id).cloned().unwrap_or_default() == settings {
+            return Ok(());
+        }
+        if settings.is_empty() {
+            self.pages.remove(page_id);
+        } else {
+            self.pages.insert(page_id.to_string(), settings);
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let json = serde_json::to_string_pretty(&self.pages)
+            .map_err(|e| format!("Failed to serialize page settings: {}", e))?;
+        write_atomic(path, &json)
+    }
+}
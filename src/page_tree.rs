@@ -0,0 +1,43 @@
+// The page list as a tree: pages under the page or database they live in,
+// so pages with the same title in different places can be told apart
+use std::collections::{HashMap, HashSet};
+
+use crate::app::PageInfo;
+
+/// Pages in tree order with their depth: each page after its parent and
+/// the subpages of its earlier siblings. Siblings keep the order they were
+/// listed in; pages whose parent is not listed are at the top.
+pub fn tree_order(pages: Vec<PageInfo>) -> Vec<(PageInfo, usize)> {
+    let ids: HashSet<&str> = pages.iter().map(|page| page.id.as_str()).collect();
+    let mut children: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut roots = Vec::new();
+    for (index, page) in pages.iter().enumerate() {
+        match page.parent.as_deref().filter(|parent| ids.contains(parent) && *parent != page.id) {
+            Some(parent) => children.entry(parent).or_default().push(index),
+            None => roots.push(index),
+        }
+    }
+
+    // Pages caught in a parent cycle hang under no root; they start their
+    // own tree after the others
+    let mut order = Vec::with_capacity(pages.len());
+    let mut visited = vec![false; pages.len()];
+    for start in roots.into_iter().chain(0..pages.len()) {
+        let mut stack = vec![(start, 0)];
+        while let Some((index, depth)) = stack.pop() {
+            if std::mem::replace(&mut visited[index], true) {
+                continue;
+            }
+            order.push((index, depth));
+            if let Some(subpages) = children.get(pages[index].id.as_str()) {
+                stack.extend(subpages.iter().rev().map(|&subpage| (subpage, depth + 1)));
+            }
+        }
+    }
+
+    let mut slots: Vec<Option<PageInfo>> = pages.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|(index, depth)| slots[index].take().map(|page| (page, depth)))
+        .collect()
+}
@@ -0,0 +1,161 @@
+// Minimal fuzzy picker drawn inline on the terminal, like fzf, for
+// commands that need a choice but run outside the TUI
+use std::io::{self, Write};
+
+use crossterm::{
+    cursor::{MoveToColumn, MoveUp},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Attribute, Print, SetAttribute},
+    terminal::{self, Clear, ClearType},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::buffer::pop_grapheme;
+use crate::fuzzy;
+use crate::wrap::truncate_to_width;
+
+/// Most matches listed below the prompt
+pub const VISIBLE_MATCHES: usize = 10;
+
+/// What a key press did to the picker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickerOutcome {
+    /// Keep reading keys
+    Pending,
+    /// The item at this index was chosen
+    Picked(usize),
+    Cancelled,
+}
+
+/// Query and highlighted match of a picker over `items`
+#[derive(Debug, Clone)]
+pub struct Picker {
+    items: Vec<String>,
+    pub query: String,
+    /// Indices of the items matching the query, best first
+    pub matches: Vec<usize>,
+    pub selected: usize,
+}
+
+impl Picker {
+    pub fn new(items: Vec<String>) -> Self {
+        let mut picker = Self {
+            items,
+            query: String::new(),
+            matches: Vec::new(),
+            selected: 0,
+        };
+        picker.refresh();
+        picker
+    }
+
+    fn refresh(&mut self) {
+        self.matches = fuzzy::rank(&self.query, self.items.iter().map(String::as_str));
+        self.selected = 0;
+    }
+
+    /// Apply a key: typing filters, arrows move, Enter picks, Esc or
+    /// Ctrl+C cancels
+    pub fn handle_key(&mut self, key: KeyEvent) -> PickerOutcome {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Enter => {
+                return match self.matches.get(self.selected) {
+                    Some(&index) => PickerOutcome::Picked(index),
+                    None => PickerOutcome::Pending,
+                };
+            }
+            KeyCode::Esc => return PickerOutcome::Cancelled,
+            KeyCode::Char('c') if ctrl => return PickerOutcome::Cancelled,
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Char('p') if ctrl => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.select_next(),
+            KeyCode::Char('n') if ctrl => self.select_next(),
+            KeyCode::Backspace => {
+                pop_grapheme(&mut self.query);
+                self.refresh();
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.query.push(c);
+                self.refresh();
+            }
+            _ => {}
+        }
+        PickerOutcome::Pending
+    }
+
+    fn select_next(&mut self) {
+        let visible = self.matches.len().min(VISIBLE_MATCHES);
+        if self.selected + 1 < visible {
+            self.selected += 1;
+        }
+    }
+
+    /// Lines to draw: the prompt, then the best matches
+    pub fn lines(&self, prompt: &str) -> Vec<(String, bool)> {
+        let mut lines = vec![(format!("{} {}", prompt, self.query), false)];
+        lines.extend(self.matches.iter().take(VISIBLE_MATCHES).enumerate().map(|(row, &index)| {
+            let marker = if row == self.selected { "▶" } else { " " };
+            (format!("{} {}", marker, self.items[index]), row == self.selected)
+        }));
+        if self.matches.is_empty() {
+            lines.push(("  (no matches)".to_string(), false));
+        }
+        lines
+    }
+}
+
+/// Let the user choose one of `items` on the terminal; `None` when they
+/// cancel. Drawn on stderr so stdout stays free for the command's output.
+pub fn pick(items: Vec<String>, prompt: &str) -> io::Result<Option<usize>> {
+    let mut picker = Picker::new(items);
+    let mut stderr = io::stderr();
+    terminal::enable_raw_mode()?;
+    let result = (|| -> io::Result<Option<usize>> {
+        loop {
+            draw(&mut stderr, &picker.lines(prompt))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match picker.handle_key(key) {
+                PickerOutcome::Pending => {}
+                PickerOutcome::Picked(index) => break Ok(Some(index)),
+                PickerOutcome::Cancelled => break Ok(None),
+            }
+        }
+    })();
+    // Leave the terminal as it was, without the picker's lines
+    let _ = queue!(stderr, MoveToColumn(0), Clear(ClearType::FromCursorDown));
+    let _ = stderr.flush();
+    terminal::disable_raw_mode()?;
+    result
+}
+
+/// Redraw the picker from the prompt line, which the cursor is left on
+fn draw(out: &mut impl Write, lines: &[(String, bool)]) -> io::Result<()> {
+    queue!(out, MoveToColumn(0), Clear(ClearType::FromCursorDown))?;
+    let width = terminal::size().map(|(cols, _)| cols as usize).unwrap_or(80).max(1);
+    for (row, (line, highlighted)) in lines.iter().enumerate() {
+        if row > 0 {
+            queue!(out, Print("\r\n"))?;
+        }
+        let line = truncate_to_width(line, width - 1);
+        if *highlighted {
+            queue!(out, SetAttribute(Attribute::Reverse), Print(line), SetAttribute(Attribute::Reset))?;
+        } else {
+            queue!(out, Print(line))?;
+        }
+    }
+    // Park the cursor at the end of the prompt
+    let below = lines.len().saturating_sub(1);
+    if below > 0 {
+        queue!(out, MoveUp(below as u16))?;
+    }
+    let prompt_width = lines.first().map_or(0, |(line, _)| truncate_to_width(line, width - 1).width());
+    queue!(out, MoveToColumn(prompt_width as u16))?;
+    out.flush()
+}
@@ -0,0 +1,82 @@
+// Output piped into FaultNote, e.g. `cargo build 2>&1 | faultnote`
+use std::io::{self, IsTerminal, Read};
+
+use crate::app::AppState;
+use crate::clipboard::normalize_newlines;
+use crate::fields::InputField;
+
+/// Language for the Code field when it holds piped tool output
+pub const OUTPUT_LANGUAGE: &str = "plain text";
+
+/// Everything piped into stdin, or `None` when stdin is a terminal. The
+/// keyboard is still read afterwards: crossterm opens the controlling
+/// terminal itself when stdin is not one.
+pub fn read_stdin() -> io::Result<Option<String>> {
+    let mut stdin = io::stdin();
+    if stdin.is_terminal() {
+        return Ok(None);
+    }
+    let mut bytes = Vec::new();
+    stdin.read_to_end(&mut bytes)?;
+    let text = strip_ansi(&normalize_newlines(&String::from_utf8_lossy(&bytes)));
+    Ok(Some(text).filter(|text| !text.trim().is_empty()))
+}
+
+/// Remove terminal color and cursor escapes, which tools forced into
+/// color mode still emit when piped
+pub fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            plain.push(c);
+            continue;
+        }
+        // CSI sequences end at the first letter; other escapes are one char
+        if chars.next_if_eq(&'[').is_some() {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            chars.next();
+        }
+    }
+    plain
+}
+
+/// The line that best names the failure: the first that looks like an
+/// error message, else the first non-blank line
+pub fn error_line(output: &str) -> Option<&str> {
+    let lines = output.lines().map(str::trim).filter(|line| !line.is_empty());
+    lines.clone().find(|line| looks_like_error(line)).or_else(|| lines.clone().next())
+}
+
+fn looks_like_error(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    lower.starts_with("error")
+        || lower.starts_with("fatal")
+        || lower.contains("panicked at")
+        || lower.contains("exception")
+        || lower.contains("traceback")
+        || lower.contains("error:")
+        || lower.contains("error[")
+}
+
+/// Fill the Error field with the failing line and, unless `error_only`,
+/// the Code field with the whole output
+pub fn prefill(app: &mut AppState, output: &str, error_only: bool) {
+    let Some(error) = error_line(output) else {
+        return;
+    };
+    *app.field_mut(InputField::Error) = error.into();
+    if !error_only {
+        *app.field_mut(InputField::Code) = output.trim_end().into();
+        app.code_language = OUTPUT_LANGUAGE.to_string();
+    }
+    let lines = output.lines().count();
+    let filled = app.apply_extractors(output);
+    let extracted = if filled.is_empty() { String::new() } else { format!(", extracted {}", filled.join(", ")) };
+    app.set_status(format!("Read {} piped lines{}; press e to edit, Enter to submit", lines, extracted));
+}
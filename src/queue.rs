@@ -0,0 +1,238 @@
+// Offline submission queue persisted as JSON in the data directory
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::app::{FaultLogEntry, TargetKind};
+use crate::config::data_dir;
+use crate::fsck::write_atomic;
+use crate::notion::client::NotionError;
+
+/// Whether a queued submission is expected to succeed on retry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueueStatus {
+    /// Failed for a transient reason (network, rate limit, server error)
+    Pending,
+    /// Rejected by Notion; needs editing or a different target page
+    Failed,
+}
+
+/// A fault log that could not be submitted yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedSubmission {
+    pub id: u64,
+    pub page_id: String,
+    /// A database target gets a new row instead of appended blocks
+    #[serde(default)]
+    pub kind: TargetKind,
+    pub page_title: String,
+    pub entry: FaultLogEntry,
+    pub status: QueueStatus,
+    pub last_error: Option<String>,
+    pub attempts: u32,
+    /// Unix time of the next automatic retry; `None` means manual only
+    #[serde(default)]
+    pub next_retry_at: Option<u64>,
+    /// Row in the local history, updated when the entry goes through
+    #[serde(default)]
+    pub history_id: Option<i64>,
+    /// HTTP status of the last failed attempt; `None` when no response came
+    #[serde(default)]
+    pub http_status: Option<u16>,
+}
+
+impl QueuedSubmission {
+    /// Record a failed attempt and schedule the next retry with backoff
+    pub fn record_failure(&mut self, status: QueueStatus, error: impl Into<String>, now: u64) {
+        self.attempts += 1;
+        self.status = status;
+        self.last_error = Some(error.into());
+        self.next_retry_at = next_retry_at(status, self.attempts, now);
+    }
+
+    /// Whether an automatic retry is due
+    pub fn is_due(&self, now: u64) -> bool {
+        self.next_retry_at.is_some_and(|at| at <= now)
+    }
+
+    /// Time left until the next automatic retry
+    pub fn retry_in(&self, now: u64) -> Option<Duration> {
+        self.next_retry_at
+            .map(|at| Duration::from_secs(at.saturating_sub(now)))
+    }
+}
+
+/// First retry delay; doubles with every failed attempt
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(30);
+/// Upper bound for the retry delay
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+
+/// Exponential backoff delay after `attempts` failed attempts
+pub fn backoff_delay(attempts: u32) -> Duration {
+    let exponent = attempts.saturating_sub(1).min(16);
+    BASE_RETRY_DELAY
+        .saturating_mul(1 << exponent)
+        .min(MAX_RETRY_DELAY)
+}
+
+fn next_retry_at(status: QueueStatus, attempts: u32, now: u64) -> Option<u64> {
+    match status {
+        QueueStatus::Pending => Some(now + backoff_delay(attempts).as_secs()),
+        QueueStatus::Failed => None,
+    }
+}
+
+/// Current time as Unix seconds
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Failed submissions, saved to disk after every change
+#[derive(Debug, Default)]
+pub struct SubmissionQueue {
+    items: Vec<QueuedSubmission>,
+    path: Option<PathBuf>,
+}
+
+impl SubmissionQueue {
+    /// Default location of the queue file
+    pub fn default_path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("queue.json"))
+    }
+
+    /// Load the queue from `path`; a missing file is an empty queue
+    #[instrument(skip_all, err)]
+    pub fn load(path: PathBuf) -> Result<Self, String> {
+        let items = if path.exists() {
+            let text = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            serde_json::from_str(&text)
+                .map_err(|e| format!("Corrupt queue file {}: {}", path.display(), e))?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            items,
+            path: Some(path),
+        })
+    }
+
+    /// Write the queue to disk. In-memory queues (no path) are never saved.
+    #[instrument(skip_all, fields(items = self.items.len()), err)]
+    pub fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let json = serde_json::to_string_pretty(&self.items)
+            .map_err(|e| format!("Failed to serialize queue: {}", e))?;
+        write_atomic(path, &json)
+    }
+
+    pub fn items(&self) -> &[QueuedSubmission] {
+        &self.items
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&QueuedSubmission> {
+        self.items.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut QueuedSubmission> {
+        self.items.get_mut(index)
+    }
+
+    /// Current index of the item with this id
+    pub fn index_of(&self, id: u64) -> Option<usize> {
+        self.items.iter().position(|item| item.id == id)
+    }
+
+    /// The most recently added item
+    pub fn last_mut(&mut self) -> Option<&mut QueuedSubmission> {
+        self.items.last_mut()
+    }
+
+    /// Add a failed submission and return its id
+    pub fn push(
+        &mut self,
+        page_id: &str,
+        page_title: &str,
+        entry: FaultLogEntry,
+        status: QueueStatus,
+        error: impl Into<String>,
+    ) -> u64 {
+        let id = self.items.iter().map(|item| item.id).max().unwrap_or(0) + 1;
+        self.items.push(QueuedSubmission {
+            id,
+            page_id: page_id.to_string(),
+            kind: TargetKind::Page,
+            page_title: page_title.to_string(),
+            entry,
+            status,
+            last_error: Some(error.into()),
+            attempts: 1,
+            next_retry_at: next_retry_at(status, 1, unix_now()),
+            history_id: None,
+            http_status: None,
+        });
+        id
+    }
+
+    /// Indices of items waiting for a transient problem to clear
+    pub fn pending_indices(&self) -> Vec<usize> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.status == QueueStatus::Pending)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Indices of items whose automatic retry is due
+    pub fn due_indices(&self, now: u64) -> Vec<usize> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.is_due(now))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Remove and return the item at `index`
+    pub fn remove(&mut self, index: usize) -> Option<QueuedSubmission> {
+        if index < self.items.len() {
+            Some(self.items.remove(index))
+        } else {
+            None
+        }
+    }
+}
+
+/// Classify a request failure: network problems, rate limits and server
+/// errors are worth retrying, anything else needs user action
+pub fn status_for_error(error: &NotionError) -> QueueStatus {
+    match error.status() {
+        Some(status) if status == 429 || status >= 500 => QueueStatus::Pending,
+        Some(_) => QueueStatus::Failed,
+        None => QueueStatus::Pending,
+    }
+}
@@ -0,0 +1,118 @@
+// `faultnote sample`: log a clearly marked test entry to a page and read it
+// back, to confirm the tokens, page access and template all work before
+// the first real entry
+use chrono::{DateTime, Utc};
+
+use crate::app::{AppState, FaultLogEntry, PageInfo};
+use crate::audit::{AuditAction, AuditRecord};
+use crate::events;
+use crate::failure::{Failure, FailureClass};
+use crate::notion::client::{NotionClient, create_entry_with_fallback, delete_block, fetch_pages, sha256_hex};
+use crate::notion::entries::{LoggedEntry, fetch_children, fetch_entries};
+use crate::notion::template::BlockTemplate;
+use crate::submit::{Submitted, connect, find_page, load_app};
+
+/// Start of the error of every sample entry
+pub const SAMPLE_MARKER: &str = "FaultNote sample entry";
+
+/// The test entry; the time makes each one unique so it is found again
+/// among earlier samples
+pub fn sample_entry(now: DateTime<Utc>) -> FaultLogEntry {
+    FaultLogEntry {
+        error: format!("{} ({}), safe to delete", SAMPLE_MARKER, now.format("%Y-%m-%d %H:%M:%S UTC")),
+        problem: "Checking that FaultNote can write to this page".to_string(),
+        solution: "Nothing to do; logged by `faultnote sample`".to_string(),
+        verification: Some("Read back from the page after writing".to_string()),
+        ..Default::default()
+    }
+}
+
+/// Check that `sent` is among the entries read back from a page with its
+/// fields intact
+pub fn verify(sent: &FaultLogEntry, logged: &[LoggedEntry]) -> Result<(), String> {
+    let found = logged
+        .iter()
+        .find(|logged| logged.entry.error == sent.error)
+        .ok_or("the entry was written but is not on the page when read back")?;
+    let fields = [
+        ("problem", &sent.problem, &found.entry.problem),
+        ("solution", &sent.solution, &found.entry.solution),
+    ];
+    match fields.iter().find(|(_, sent, read)| sent.trim() != read.trim()) {
+        Some((name, sent, read)) => Err(format!("the {} reads back as \"{}\" instead of \"{}\"", name, read, sent)),
+        None => Ok(()),
+    }
+}
+
+/// Log a sample entry to `wanted` and read it back; with `remove`, delete
+/// it again once verified
+pub async fn run(wanted: &str, remove: bool) -> Result<Submitted, Failure> {
+    let mut app = load_app()?;
+    let client = connect(&app)?;
+    let request_failure = |what: &str, e| Failure::new(FailureClass::for_request(&e), format!("{}: {}", what, e));
+    let pages = fetch_pages(&client)
+        .await
+        .map_err(|e| request_failure("Failed to fetch pages", e))?;
+    let page = find_page(&pages, wanted)
+        .ok_or_else(|| Failure::validation(format!("No page or database matches \"{}\"", wanted)))?;
+
+    let entry = sample_entry(Utc::now());
+    let template = app.submission_template(&page.id, &entry);
+    let preferred = app.token_map.get(&page.id).map(str::to_string);
+    let (token, appended) =
+        create_entry_with_fallback(&client, &page.id, page.kind, &entry, &template, preferred.as_deref())
+            .await
+            .map_err(|e| request_failure("Failed to write the sample entry", e))?;
+    events::record_submission(&mut app, &page.id, page.kind, &token, &appended);
+    let submitted = Submitted {
+        page,
+        block_ids: appended.block_ids,
+    };
+
+    let token_client = client.with_token(&token).unwrap_or_else(|| client.clone());
+    read_back(&token_client, &submitted, &entry, &app.template_for(&submitted.page.id)).await?;
+    if remove {
+        delete(&mut app, &token_client, &submitted, &token).await?;
+    }
+    Ok(submitted)
+}
+
+/// Fetch the sample entry back: the page's entries, or the body of the
+/// new database row
+async fn read_back(
+    client: &NotionClient,
+    submitted: &Submitted,
+    entry: &FaultLogEntry,
+    template: &BlockTemplate,
+) -> Result<(), Failure> {
+    let read_failure = |e| Failure::new(FailureClass::for_request(&e), format!("Failed to read the sample entry back: {}", e));
+    let PageInfo { id, title, .. } = &submitted.page;
+    if submitted.page.is_database() {
+        let row = submitted.block_ids.first().ok_or("Notion returned no id for the new row")?;
+        if fetch_children(client, row).await.map_err(read_failure)?.is_empty() {
+            return Err(Failure::validation(format!("The sample row in {} has no content when read back", title)));
+        }
+        return Ok(());
+    }
+    let logged = fetch_entries(client, id, template).await.map_err(read_failure)?;
+    verify(entry, &logged).map_err(|e| Failure::validation(format!("Sample entry on {}: {}", title, e)))
+}
+
+/// Delete the sample entry's blocks, or its row
+async fn delete(
+    app: &mut AppState,
+    client: &NotionClient,
+    submitted: &Submitted,
+    token: &str,
+) -> Result<(), Failure> {
+    for block_id in &submitted.block_ids {
+        delete_block(client, block_id)
+            .await
+            .map_err(|e| Failure::new(FailureClass::for_request(&e), format!("Failed to remove the sample entry: {}", e)))?;
+    }
+    let mut record = AuditRecord::now(AuditAction::DeleteBlocks, sha256_hex(submitted.block_ids.join(",").as_bytes()));
+    record.page_id = Some(submitted.page.id.clone());
+    record.created_ids = submitted.block_ids.clone();
+    record.token = Some(token.to_string());
+    app.audit_log.append(&record).map_err(Failure::from)
+}
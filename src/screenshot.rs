@@ -0,0 +1,124 @@
+// Dump the rendered screen as plain text and ANSI for bug reports
+use std::{fs, path::PathBuf};
+
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier, Style},
+};
+
+use crate::config::data_dir;
+
+/// The buffer as plain text, one line per row with trailing spaces trimmed
+pub fn buffer_to_text(buffer: &Buffer) -> String {
+    rows(buffer)
+        .map(|row| {
+            let line: String = row.iter().map(|(_, symbol)| *symbol).collect();
+            line.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The buffer with ANSI colors, viewable with `cat` or `less -R`
+pub fn buffer_to_ansi(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for row in rows(buffer) {
+        let mut current = Style::default();
+        for (style, symbol) in row {
+            if style != current {
+                out.push_str(&sgr(style));
+                current = style;
+            }
+            out.push_str(symbol);
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+/// Cells of each row as (style, symbol), skipping the filler cells that
+/// follow wide characters
+fn rows(buffer: &Buffer) -> impl Iterator<Item = Vec<(Style, &str)>> {
+    let width = buffer.area.width as usize;
+    buffer.content().chunks(width.max(1)).map(|row| {
+        row.iter()
+            .scan(0usize, |hidden, cell| {
+                // A wide symbol covers the cells after it
+                if *hidden > 0 {
+                    *hidden -= 1;
+                    return Some(None);
+                }
+                *hidden = unicode_width::UnicodeWidthStr::width(cell.symbol()).saturating_sub(1);
+                Some(Some((cell.style(), cell.symbol())))
+            })
+            .flatten()
+            .collect()
+    })
+}
+
+/// Escape sequence switching to `style` from the default
+fn sgr(style: Style) -> String {
+    let mut codes = vec!["0".to_string()];
+    let modifiers = [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ];
+    for (modifier, code) in modifiers {
+        if style.add_modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    if let Some(code) = style.fg.and_then(|color| color_code(color, false)) {
+        codes.push(code);
+    }
+    if let Some(code) = style.bg.and_then(|color| color_code(color, true)) {
+        codes.push(code);
+    }
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let base = if background { 40 } else { 30 };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => format!("{}", base),
+        Color::Red => format!("{}", base + 1),
+        Color::Green => format!("{}", base + 2),
+        Color::Yellow => format!("{}", base + 3),
+        Color::Blue => format!("{}", base + 4),
+        Color::Magenta => format!("{}", base + 5),
+        Color::Cyan => format!("{}", base + 6),
+        Color::Gray => format!("{}", base + 7),
+        Color::DarkGray => format!("{}", base + 60),
+        Color::LightRed => format!("{}", base + 61),
+        Color::LightGreen => format!("{}", base + 62),
+        Color::LightYellow => format!("{}", base + 63),
+        Color::LightBlue => format!("{}", base + 64),
+        Color::LightMagenta => format!("{}", base + 65),
+        Color::LightCyan => format!("{}", base + 66),
+        Color::White => format!("{}", base + 67),
+        Color::Indexed(index) => format!("{};5;{}", base + 8, index),
+        Color::Rgb(r, g, b) => format!("{};2;{};{};{}", base + 8, r, g, b),
+    };
+    Some(code)
+}
+
+/// Write `<stem>.txt` and `<stem>.ansi` into the screenshots folder of the
+/// data directory and return the text file's path
+pub fn save(buffer: &Buffer) -> Result<PathBuf, String> {
+    let dir = data_dir()
+        .ok_or("Could not determine the data directory")?
+        .join("screenshots");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let stem = format!("faultnote-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let text_path = dir.join(format!("{}.txt", stem));
+    let ansi_path = dir.join(format!("{}.ansi", stem));
+    fs::write(&text_path, buffer_to_text(buffer)).map_err(|e| format!("Failed to write {}: {}", text_path.display(), e))?;
+    fs::write(&ansi_path, buffer_to_ansi(buffer)).map_err(|e| format!("Failed to write {}: {}", ansi_path.display(), e))?;
+    Ok(text_path)
+}
@@ -0,0 +1,95 @@
+// Scripted key replay (`--script`) for demos, bug reports and black-box tests
+use std::{collections::VecDeque, fs, path::Path, time::Duration};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::app::AppState;
+use crate::events;
+use crate::keys::parse_key;
+use crate::notion::client::NotionClient;
+
+/// One instruction in a script file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptStep {
+    /// `key Enter`, `key Ctrl+z`, `key j`
+    Key(KeyEvent),
+    /// `type some text` - one key press per character
+    Type(String),
+    /// `wait 500` - pause in milliseconds
+    Wait(Duration),
+    /// `expect-status Submitted` - the status message must contain the text
+    ExpectStatus(String),
+}
+
+/// A parsed script, consumed one step at a time
+#[derive(Debug, Default)]
+pub struct Script {
+    steps: VecDeque<(usize, ScriptStep)>,
+}
+
+impl Script {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&content)
+    }
+
+    /// Parse one step per line; blank lines and `#` comments are skipped
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut steps = VecDeque::new();
+        for (idx, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+            let step = match command {
+                "key" => ScriptStep::Key(parse_key(argument.trim()).map_err(|e| format!("Line {}: {}", idx + 1, e))?),
+                "type" => ScriptStep::Type(argument.to_string()),
+                "wait" => ScriptStep::Wait(Duration::from_millis(
+                    argument.trim().parse().map_err(|_| format!("Line {}: invalid wait '{}'", idx + 1, argument))?,
+                )),
+                "expect-status" => ScriptStep::ExpectStatus(argument.trim().to_string()),
+                other => return Err(format!("Line {}: unknown command '{}'", idx + 1, other)),
+            };
+            steps.push_back((idx + 1, step));
+        }
+        Ok(Self { steps })
+    }
+
+    /// Next step with its line number
+    pub fn next_step(&mut self) -> Option<(usize, ScriptStep)> {
+        self.steps.pop_front()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+/// Run one step against the app; a failed expectation is an error
+pub async fn run_step(
+    app: &mut AppState,
+    notion_client: Option<&NotionClient>,
+    line: usize,
+    step: ScriptStep,
+) -> Result<(), String> {
+    match step {
+        ScriptStep::Key(key) => events::handle_key_event(app, key, notion_client).await,
+        ScriptStep::Type(text) => {
+            for c in text.chars() {
+                events::handle_key_event(app, KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE), notion_client).await;
+            }
+        }
+        ScriptStep::Wait(delay) => tokio::time::sleep(delay).await,
+        ScriptStep::ExpectStatus(expected) => {
+            let actual = app.status_message.as_deref().unwrap_or("");
+            if !actual.contains(&expected) {
+                return Err(format!(
+                    "Script line {}: expected status containing '{}', got '{}'",
+                    line, expected, actual
+                ));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,154 @@
+// GitHub Issues as a sink: each entry becomes an issue with the entry's
+// sections as Markdown headings
+use std::env;
+
+use dotenv::dotenv;
+use reqwest::Client;
+use reqwest::header::{ACCEPT, AUTHORIZATION, HeaderMap, HeaderValue, USER_AGENT};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::app::{FaultLogEntry, PageInfo, TargetKind};
+use crate::markdown::{entry_sections, entry_title};
+use crate::notion::template::BlockTemplate;
+use crate::sink::{FaultSink, Receipt};
+
+/// `[sink.github]` in the config
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GitHubConfig {
+    /// `owner/name` of the repositories offered; all repositories the token
+    /// can open issues in when empty
+    pub repos: Vec<String>,
+    /// Labels on every issue, next to the entry's tags
+    pub labels: Vec<String>,
+    /// Environment variable holding the token
+    pub token_env: String,
+    /// API root, for GitHub Enterprise
+    pub api_url: String,
+}
+
+impl Default for GitHubConfig {
+    fn default() -> Self {
+        Self {
+            repos: Vec::new(),
+            labels: Vec::new(),
+            token_env: "GITHUB_TOKEN".to_string(),
+            api_url: "https://api.github.com".to_string(),
+        }
+    }
+}
+
+/// Client for the GitHub REST API
+#[derive(Debug, Clone)]
+pub struct GitHubSink {
+    http_client: Client,
+    config: GitHubConfig,
+}
+
+impl GitHubSink {
+    /// A client authenticated with the token from `token_env`
+    pub fn new(config: &GitHubConfig) -> Result<Self, String> {
+        dotenv().ok();
+        let token = env::var(&config.token_env)
+            .map_err(|_| format!("{} not found in environment variables", config.token_env))?;
+        Self::with_token(config, &token)
+    }
+
+    pub fn with_token(config: &GitHubConfig, token: &str) -> Result<Self, String> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", token)).map_err(|e| format!("Invalid GitHub token format: {}", e))?,
+        );
+        headers.insert(ACCEPT, HeaderValue::from_static("application/vnd.github+json"));
+        headers.insert("X-GitHub-Api-Version", HeaderValue::from_static("2022-11-28"));
+        // GitHub rejects requests without one
+        headers.insert(USER_AGENT, HeaderValue::from_static("faultnote"));
+        let http_client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+        Ok(Self {
+            http_client,
+            config: config.clone(),
+        })
+    }
+
+    /// Send a request and return the JSON body, or the API's message
+    async fn request(&self, request: reqwest::RequestBuilder) -> Result<Value, String> {
+        let response = request.send().await.map_err(|e| format!("Request failed: {}", e))?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or_default();
+        if status.is_success() {
+            return Ok(body);
+        }
+        let message = body["message"].as_str().unwrap_or("no message");
+        Err(format!("GitHub returned {}: {}", status.as_u16(), message))
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.config.api_url.trim_end_matches('/'), path)
+    }
+}
+
+/// The issue for an entry: the entry's title, its sections as the body and
+/// the configured labels plus the entry's tags
+pub fn issue(entry: &FaultLogEntry, template: &BlockTemplate, labels: &[String]) -> Value {
+    let mut all_labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+    for tag in &entry.tags {
+        if !all_labels.contains(&tag.as_str()) {
+            all_labels.push(tag);
+        }
+    }
+    json!({
+        "title": entry_title(entry, template),
+        "body": entry_sections(entry, template),
+        "labels": all_labels,
+    })
+}
+
+fn repository(name: &str) -> PageInfo {
+    PageInfo {
+        id: name.to_string(),
+        title: name.to_string(),
+        kind: TargetKind::Page,
+        parent: None,
+    }
+}
+
+impl FaultSink for GitHubSink {
+    type Error = String;
+
+    fn name(&self) -> &'static str {
+        "GitHub"
+    }
+
+    async fn list_targets(&self) -> Result<Vec<PageInfo>, String> {
+        if !self.config.repos.is_empty() {
+            return Ok(self.config.repos.iter().map(|name| repository(name)).collect());
+        }
+        let listed = self
+            .request(self.http_client.get(self.url("/user/repos?per_page=100&sort=pushed")))
+            .await?;
+        Ok(listed
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|repo| repo["has_issues"].as_bool().unwrap_or(true))
+            .filter_map(|repo| repo["full_name"].as_str())
+            .map(repository)
+            .collect())
+    }
+
+    async fn submit_entry(&self, target: &PageInfo, entry: &FaultLogEntry, template: &BlockTemplate) -> Result<Receipt, String> {
+        let url = self.url(&format!("/repos/{}/issues", target.id));
+        let created = self
+            .request(self.http_client.post(url).json(&issue(entry, template, &self.config.labels)))
+            .await?;
+        Ok(Receipt {
+            ids: created["number"].as_u64().map(|number| number.to_string()).into_iter().collect(),
+            url: created["html_url"].as_str().unwrap_or_default().to_string(),
+        })
+    }
+}
@@ -0,0 +1,120 @@
+// Where entries go: Notion, or another tracker such as GitHub Issues or
+// an Obsidian vault
+pub mod github;
+pub mod notion;
+pub mod obsidian;
+
+use std::fmt;
+use std::future::Future;
+
+use serde::Deserialize;
+
+use crate::app::{FaultLogEntry, PageInfo};
+use crate::notion::template::BlockTemplate;
+
+use self::github::{GitHubConfig, GitHubSink};
+use self::obsidian::{ObsidianConfig, ObsidianSink};
+
+/// A backend entries can be logged to. Targets are listed like Notion
+/// pages so the page list and checks work the same for every sink.
+pub trait FaultSink {
+    type Error: fmt::Display;
+
+    /// Name for status messages, e.g. "GitHub"
+    fn name(&self) -> &'static str;
+
+    /// Everything entries can go to, e.g. pages or repositories
+    fn list_targets(&self) -> impl Future<Output = Result<Vec<PageInfo>, Self::Error>> + Send;
+
+    /// Log one entry to `target`
+    fn submit_entry(
+        &self,
+        target: &PageInfo,
+        entry: &FaultLogEntry,
+        template: &BlockTemplate,
+    ) -> impl Future<Output = Result<Receipt, Self::Error>> + Send;
+}
+
+/// What a sink created for an entry
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Receipt {
+    /// Ids of what was created: blocks, a database row or an issue number
+    pub ids: Vec<String>,
+    /// Link to the entry
+    pub url: String,
+}
+
+/// `[sink]` in the config
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SinkConfig {
+    pub kind: SinkKind,
+    pub github: GitHubConfig,
+    pub obsidian: ObsidianConfig,
+}
+
+/// Which sink the TUI logs to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SinkKind {
+    #[default]
+    Notion,
+    #[serde(rename = "github")]
+    #[value(name = "github")]
+    GitHub,
+    Obsidian,
+}
+
+impl SinkKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SinkKind::Notion => "Notion",
+            SinkKind::GitHub => "GitHub",
+            SinkKind::Obsidian => "Obsidian",
+        }
+    }
+}
+
+/// The sink the TUI logs to when it is not Notion
+#[derive(Debug, Clone)]
+pub enum Sink {
+    GitHub(GitHubSink),
+    Obsidian(ObsidianSink),
+}
+
+impl Sink {
+    /// The sink `config.kind` names, or `None` for Notion. `project` names
+    /// the note offered first in a vault.
+    pub fn from_config(config: &SinkConfig, project: &str) -> Option<Result<Self, String>> {
+        match config.kind {
+            SinkKind::Notion => None,
+            SinkKind::GitHub => Some(GitHubSink::new(&config.github).map(Sink::GitHub)),
+            SinkKind::Obsidian => Some(ObsidianSink::new(&config.obsidian, project).map(Sink::Obsidian)),
+        }
+    }
+}
+
+impl FaultSink for Sink {
+    type Error = String;
+
+    fn name(&self) -> &'static str {
+        match self {
+            Sink::GitHub(sink) => sink.name(),
+            Sink::Obsidian(sink) => sink.name(),
+        }
+    }
+
+    async fn list_targets(&self) -> Result<Vec<PageInfo>, String> {
+        match self {
+            Sink::GitHub(sink) => sink.list_targets().await,
+            Sink::Obsidian(sink) => sink.list_targets().await,
+        }
+    }
+
+    async fn submit_entry(&self, target: &PageInfo, entry: &FaultLogEntry, template: &BlockTemplate) -> Result<Receipt, String> {
+        match self {
+            Sink::GitHub(sink) => sink.submit_entry(target, entry, template).await,
+            Sink::Obsidian(sink) => sink.submit_entry(target, entry, template).await,
+        }
+    }
+}
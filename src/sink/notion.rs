@@ -0,0 +1,35 @@
+// Notion as a sink: pages and databases shared with the integration
+use crate::app::{FaultLogEntry, PageInfo};
+use crate::notion::client::{NotionClient, NotionError, create_entry_with_fallback, fetch_pages};
+use crate::notion::template::BlockTemplate;
+use crate::sink::{FaultSink, Receipt};
+use crate::submit::Submitted;
+
+impl FaultSink for NotionClient {
+    type Error = NotionError;
+
+    fn name(&self) -> &'static str {
+        "Notion"
+    }
+
+    async fn list_targets(&self) -> Result<Vec<PageInfo>, NotionError> {
+        fetch_pages(self).await
+    }
+
+    async fn submit_entry(
+        &self,
+        target: &PageInfo,
+        entry: &FaultLogEntry,
+        template: &BlockTemplate,
+    ) -> Result<Receipt, NotionError> {
+        let (_, appended) = create_entry_with_fallback(self, &target.id, target.kind, entry, template, None).await?;
+        let submitted = Submitted {
+            page: target.clone(),
+            block_ids: appended.block_ids,
+        };
+        Ok(Receipt {
+            url: submitted.url(),
+            ids: submitted.block_ids,
+        })
+    }
+}
@@ -0,0 +1,191 @@
+// An Obsidian vault as a sink: entries are appended to Markdown notes in a
+// folder of the vault, one note per project, so logging works offline
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::app::{FaultLogEntry, PageInfo, TargetKind};
+use crate::config::expand_home;
+use crate::fields::InputField;
+use crate::markdown::{entry_title, field_markdown};
+use crate::notion::template::BlockTemplate;
+use crate::sink::{FaultSink, Receipt};
+
+/// `[sink.obsidian]` in the config
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ObsidianConfig {
+    /// The vault's directory; a leading `~/` is the home directory
+    pub vault: String,
+    /// Folder of the vault holding the notes
+    pub folder: String,
+}
+
+impl Default for ObsidianConfig {
+    fn default() -> Self {
+        Self {
+            vault: String::new(),
+            folder: "FaultNote".to_string(),
+        }
+    }
+}
+
+/// Notes in one folder of a vault
+#[derive(Debug, Clone)]
+pub struct ObsidianSink {
+    vault: PathBuf,
+    folder: String,
+    /// Note offered even before it exists, named after the project
+    project: String,
+}
+
+impl ObsidianSink {
+    pub fn new(config: &ObsidianConfig, project: &str) -> Result<Self, String> {
+        if config.vault.trim().is_empty() {
+            return Err("Set [sink.obsidian] vault to the vault's directory".to_string());
+        }
+        let vault = expand_home(config.vault.trim());
+        if !vault.is_dir() {
+            return Err(format!("Vault {} is not a directory", vault.display()));
+        }
+        Ok(Self {
+            vault,
+            folder: config.folder.trim_matches('/').to_string(),
+            project: note_name(project),
+        })
+    }
+
+    fn folder_path(&self) -> PathBuf {
+        self.vault.join(&self.folder)
+    }
+
+    /// `obsidian://` link opening a note in the app
+    fn note_url(&self, note: &str) -> String {
+        let vault = self.vault.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let file = if self.folder.is_empty() { note.to_string() } else { format!("{}/{}", self.folder, note) };
+        Url::parse_with_params("obsidian://open", [("vault", vault), ("file", file)])
+            .map(String::from)
+            .unwrap_or_default()
+    }
+}
+
+/// A note name without characters Obsidian does not allow in file names
+pub fn note_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']') { '-' } else { c })
+        .collect();
+    let cleaned = cleaned.trim().trim_start_matches('.');
+    if cleaned.is_empty() { "Fault Log".to_string() } else { cleaned.to_string() }
+}
+
+/// The entry as appended to a note: a heading, Error, Problem and Solution
+/// as callouts, the other sections under headings and the tags as
+/// Obsidian tags, then grouped entries under numbered headings, closed by
+/// a rule
+pub fn vault_entry(entry: &FaultLogEntry, template: &BlockTemplate) -> String {
+    let mut sections = vec![format!("## {}", entry_title(entry, template))];
+    if let Some(metadata) = template.metadata.as_deref().filter(|metadata| !metadata.trim().is_empty()) {
+        sections.push(format!("*{}*", metadata.trim()));
+    }
+    sections.extend(callout_sections(entry, template, "###"));
+    for (number, sub_entry) in entry.sub_entries.iter().enumerate() {
+        let title = sub_entry.error.lines().next().unwrap_or_default().trim();
+        sections.push(format!("### {}. {}", number + 1, title));
+        sections.extend(callout_sections(sub_entry, template, "####"));
+    }
+    sections.push("---".to_string());
+    sections.join("\n\n") + "\n"
+}
+
+fn callout_sections(entry: &FaultLogEntry, template: &BlockTemplate, level: &str) -> Vec<String> {
+    let mut sections = Vec::new();
+    for field in InputField::SECTIONS {
+        let Some(body) = field_markdown(entry, field) else {
+            continue;
+        };
+        let callout = match field {
+            InputField::Error => Some("bug"),
+            InputField::Problem => Some("question"),
+            InputField::Solution => Some("success"),
+            _ => None,
+        };
+        let section = match callout {
+            Some(kind) => {
+                let lines: Vec<String> = body.lines().map(|line| format!("> {}", line).trim_end().to_string()).collect();
+                format!("> [!{}] {}\n{}", kind, template.heading(field), lines.join("\n"))
+            }
+            None => format!("{} {}\n\n{}", level, template.heading(field), body),
+        };
+        sections.push(section);
+    }
+    if !entry.tags.is_empty() {
+        let tags: Vec<String> = entry.tags.iter().map(|tag| format!("#{}", tag.trim().replace(' ', "-"))).collect();
+        sections.push(tags.join(" "));
+    }
+    sections
+}
+
+impl FaultSink for ObsidianSink {
+    type Error = String;
+
+    fn name(&self) -> &'static str {
+        "Obsidian"
+    }
+
+    /// The notes in the folder, plus the project's note if it is new
+    async fn list_targets(&self) -> Result<Vec<PageInfo>, String> {
+        let mut notes = Vec::new();
+        match fs::read_dir(self.folder_path()) {
+            Ok(entries) => {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if path.extension().is_some_and(|extension| extension == "md") {
+                        if let Some(stem) = path.file_stem() {
+                            notes.push(stem.to_string_lossy().to_string());
+                        }
+                    }
+                }
+            }
+            // The folder is created with the first entry
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to read {}: {}", self.folder_path().display(), e)),
+        }
+        if !notes.contains(&self.project) {
+            notes.push(self.project.clone());
+        }
+        notes.sort_by_key(|note| note.to_lowercase());
+        Ok(notes
+            .into_iter()
+            .map(|note| PageInfo {
+                id: note.clone(),
+                title: note,
+                kind: TargetKind::Page,
+                parent: None,
+            })
+            .collect())
+    }
+
+    async fn submit_entry(&self, target: &PageInfo, entry: &FaultLogEntry, template: &BlockTemplate) -> Result<Receipt, String> {
+        let folder = self.folder_path();
+        fs::create_dir_all(&folder).map_err(|e| format!("Failed to create {}: {}", folder.display(), e))?;
+        let path = folder.join(format!("{}.md", note_name(&target.id)));
+        let existing = fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        // A blank line keeps the new heading apart from what came before
+        let separator = if existing > 0 { "\n" } else { "" };
+        file.write_all(format!("{}{}", separator, vault_entry(entry, template)).as_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(Receipt {
+            ids: vec![path.display().to_string()],
+            url: self.note_url(&note_name(&target.id)),
+        })
+    }
+}
@@ -0,0 +1,569 @@
+// Local SQLite history of every fault log FaultNote has sent or queued
+use std::{fs, path::PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::app::FaultLogEntry;
+use crate::config::data_dir;
+use crate::fields::InputField;
+use crate::tags;
+use crate::trace_ids::{as_trace_id, entry_trace_ids};
+
+/// Outcome of a submission as far as FaultNote knows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryStatus {
+    /// Appended to the Notion page
+    Submitted,
+    /// Waiting in the offline queue
+    Queued,
+    /// Removed from Notion again with undo
+    Undone,
+    /// Dropped from the offline queue without being sent
+    Discarded,
+}
+
+impl EntryStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EntryStatus::Submitted => "submitted",
+            EntryStatus::Queued => "queued",
+            EntryStatus::Undone => "undone",
+            EntryStatus::Discarded => "discarded",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "submitted" => Ok(EntryStatus::Submitted),
+            "queued" => Ok(EntryStatus::Queued),
+            "undone" => Ok(EntryStatus::Undone),
+            "discarded" => Ok(EntryStatus::Discarded),
+            other => Err(format!("Unknown history status '{}'", other)),
+        }
+    }
+}
+
+/// One row of the history
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub id: i64,
+    /// RFC 3339 UTC timestamp of the first attempt
+    pub submitted_at: String,
+    pub page_id: String,
+    pub page_title: String,
+    pub status: EntryStatus,
+    pub entry: FaultLogEntry,
+    /// Database row the entry became, so its status can be updated later
+    pub row_id: Option<String>,
+}
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    submitted_at TEXT NOT NULL,
+    page_id TEXT NOT NULL,
+    page_title TEXT NOT NULL,
+    status TEXT NOT NULL,
+    error TEXT NOT NULL,
+    problem TEXT NOT NULL,
+    solution TEXT NOT NULL,
+    code TEXT,
+    entry_json TEXT NOT NULL,
+    row_id TEXT,
+    content_hash TEXT
+);
+CREATE INDEX IF NOT EXISTS entries_submitted_at ON entries (submitted_at);
+";
+
+/// Trace and correlation IDs found in each entry's Error and Code fields
+const TRACE_ID_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS trace_ids (
+    trace_id TEXT NOT NULL,
+    entry_id INTEGER NOT NULL,
+    PRIMARY KEY (trace_id, entry_id)
+) WITHOUT ROWID;
+CREATE INDEX IF NOT EXISTS trace_ids_entry_id ON trace_ids (entry_id);
+";
+
+/// How much history is kept (`[history]` in the config); by default all
+/// of it. Entries still waiting in the offline queue are never pruned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Retention {
+    /// Drop entries older than this many days; 0 keeps them forever
+    pub max_age_days: u32,
+    /// Keep only this many of the newest entries; 0 keeps all
+    pub max_entries: usize,
+}
+
+/// Result of pruning the history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pruned {
+    pub removed: usize,
+    pub kept: usize,
+}
+
+/// A row whose status or entry cannot be decoded, as `faultnote fsck`
+/// quarantines it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnreadableRow {
+    pub id: i64,
+    pub status: String,
+    pub entry_json: String,
+    pub error: String,
+}
+
+/// Entries looked through for earlier values of a field
+const FIELD_VALUE_SCAN: usize = 500;
+
+const COLUMNS: &str = "id, submitted_at, page_id, page_title, status, entry_json, row_id";
+
+/// SQLite database of past entries. In-memory histories (no connection)
+/// drop records, like an `AuditLog` without a path.
+#[derive(Debug, Default)]
+pub struct History {
+    conn: Option<Connection>,
+}
+
+impl History {
+    /// Default location of the history database
+    pub fn default_path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("history.sqlite3"))
+    }
+
+    /// Open or create the database at `path`
+    #[instrument(skip_all, err)]
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let conn = Connection::open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        Self::with_connection(conn)
+    }
+
+    /// A throwaway database, for tests and scripted runs
+    pub fn open_in_memory() -> Result<Self, String> {
+        let conn = Connection::open_in_memory().map_err(|e| format!("Failed to open history: {}", e))?;
+        Self::with_connection(conn)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self, String> {
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| format!("Failed to prepare history: {}", e))?;
+        migrate(&conn).map_err(|e| format!("Failed to upgrade history: {}", e))?;
+        Ok(Self { conn: Some(conn) })
+    }
+
+    /// Add an entry and return its id, or `None` when records are dropped
+    #[instrument(skip_all, fields(status = status.as_str()), err)]
+    pub fn record(
+        &self,
+        page_id: &str,
+        page_title: &str,
+        entry: &FaultLogEntry,
+        status: EntryStatus,
+    ) -> Result<Option<i64>, String> {
+        let Some(conn) = &self.conn else {
+            return Ok(None);
+        };
+        let entry_json = serde_json::to_string(entry)
+            .map_err(|e| format!("Failed to serialize history entry: {}", e))?;
+        conn.execute(
+            "INSERT INTO entries (submitted_at, page_id, page_title, status, error, problem, solution, code, entry_json, content_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                Utc::now().to_rfc3339(),
+                page_id,
+                page_title,
+                status.as_str(),
+                entry.error,
+                entry.problem,
+                entry.solution,
+                entry.code,
+                entry_json,
+                entry.content_hash()
+            ],
+        )
+        .map_err(|e| format!("Failed to write history: {}", e))?;
+        let id = conn.last_insert_rowid();
+        index_trace_ids(conn, id, entry).map_err(|e| format!("Failed to write history: {}", e))?;
+        Ok(Some(id))
+    }
+
+    /// Change the status of an entry, e.g. when a queued one goes through
+    pub fn set_status(&self, id: i64, status: EntryStatus) -> Result<(), String> {
+        let Some(conn) = &self.conn else {
+            return Ok(());
+        };
+        conn.execute("UPDATE entries SET status = ?1 WHERE id = ?2", params![status.as_str(), id])
+            .map(|_| ())
+            .map_err(|e| format!("Failed to update history: {}", e))
+    }
+
+    /// Remember the database row an entry became
+    pub fn set_row_id(&self, id: i64, row_id: &str) -> Result<(), String> {
+        let Some(conn) = &self.conn else {
+            return Ok(());
+        };
+        conn.execute("UPDATE entries SET row_id = ?1 WHERE id = ?2", params![row_id, id])
+            .map(|_| ())
+            .map_err(|e| format!("Failed to update history: {}", e))
+    }
+
+    /// Replace the stored entry, e.g. after its fault status changed
+    pub fn update_entry(&self, id: i64, entry: &FaultLogEntry) -> Result<(), String> {
+        let Some(conn) = &self.conn else {
+            return Ok(());
+        };
+        let entry_json = serde_json::to_string(entry)
+            .map_err(|e| format!("Failed to serialize history entry: {}", e))?;
+        conn.execute(
+            "UPDATE entries SET entry_json = ?1, content_hash = ?2 WHERE id = ?3",
+            params![entry_json, entry.content_hash(), id],
+        )
+        .and_then(|_| index_trace_ids(conn, id, entry))
+        .map_err(|e| format!("Failed to update history: {}", e))
+    }
+
+    /// Whether an entry with this content was sent or queued for the page
+    /// before; undone and discarded entries do not count
+    pub fn contains(&self, page_id: &str, content_hash: &str) -> Result<bool, String> {
+        let Some(conn) = &self.conn else {
+            return Ok(false);
+        };
+        conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM entries
+             WHERE content_hash = ?1 AND replace(page_id, '-', '') = ?2 AND status IN ('submitted', 'queued'))",
+            params![content_hash, page_id.replace('-', "")],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to search history: {}", e))
+    }
+
+    /// Delete entries beyond `retention`, oldest first, and shrink the file
+    /// when any went. With `dry_run` only counts what would be deleted.
+    #[instrument(skip(self), err)]
+    pub fn prune(&self, retention: &Retention, now: DateTime<Utc>, dry_run: bool) -> Result<Pruned, String> {
+        let Some(conn) = &self.conn else {
+            return Ok(Pruned { removed: 0, kept: 0 });
+        };
+        let failed = |e: rusqlite::Error| format!("Failed to prune history: {}", e);
+        conn.execute_batch("BEGIN").map_err(failed)?;
+        let removed = prune_rows(conn, retention, now);
+        let kept = conn.query_row("SELECT COUNT(*) FROM entries", [], |row| row.get::<_, i64>(0));
+        let finish = if dry_run || removed.is_err() { "ROLLBACK" } else { "COMMIT" };
+        conn.execute_batch(finish).map_err(failed)?;
+        let (removed, kept) = (removed.map_err(failed)?, kept.map_err(failed)? as usize);
+        if removed > 0 && !dry_run {
+            conn.execute_batch("VACUUM").map_err(failed)?;
+        }
+        Ok(Pruned { removed, kept })
+    }
+
+    /// Problems SQLite finds in the database file; empty when it is sound
+    pub fn integrity_errors(&self) -> Result<Vec<String>, String> {
+        let Some(conn) = &self.conn else {
+            return Ok(Vec::new());
+        };
+        let failed = |e: rusqlite::Error| format!("Failed to check history: {}", e);
+        let mut statement = conn.prepare("PRAGMA integrity_check").map_err(failed)?;
+        let lines = statement
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(failed)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(failed)?;
+        Ok(lines.into_iter().filter(|line| line != "ok").collect())
+    }
+
+    /// Rows that would fail to load
+    pub fn unreadable(&self) -> Result<Vec<UnreadableRow>, String> {
+        let Some(conn) = &self.conn else {
+            return Ok(Vec::new());
+        };
+        let failed = |e: rusqlite::Error| format!("Failed to check history: {}", e);
+        let mut statement = conn.prepare("SELECT id, status, entry_json FROM entries").map_err(failed)?;
+        let rows = statement
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))
+            .map_err(failed)?;
+        let mut unreadable = Vec::new();
+        for row in rows {
+            let (id, status, entry_json) = row.map_err(failed)?;
+            let decoded = EntryStatus::parse(&status)
+                .and_then(|_| serde_json::from_str::<FaultLogEntry>(&entry_json).map_err(|e| e.to_string()));
+            if let Err(error) = decoded {
+                unreadable.push(UnreadableRow {
+                    id,
+                    status,
+                    entry_json,
+                    error,
+                });
+            }
+        }
+        Ok(unreadable)
+    }
+
+    /// Delete rows by id
+    pub fn delete(&self, ids: &[i64]) -> Result<(), String> {
+        let Some(conn) = &self.conn else {
+            return Ok(());
+        };
+        for id in ids {
+            conn.execute("DELETE FROM entries WHERE id = ?1", params![id])
+                .and_then(|_| conn.execute("DELETE FROM trace_ids WHERE entry_id = ?1", params![id]))
+                .map_err(|e| format!("Failed to update history: {}", e))?;
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, id: i64) -> Result<Option<HistoryEntry>, String> {
+        let Some(conn) = &self.conn else {
+            return Ok(None);
+        };
+        let sql = format!("SELECT {} FROM entries WHERE id = ?1", COLUMNS);
+        let row = conn
+            .query_row(&sql, params![id], read_row)
+            .optional()
+            .map_err(|e| format!("Failed to read history: {}", e))?;
+        row.transpose()
+    }
+
+    /// Distinct earlier values of `field`, most recently logged first
+    pub fn field_values(&self, field: InputField, limit: usize) -> Result<Vec<String>, String> {
+        let mut values: Vec<String> = Vec::new();
+        for logged in self.recent(FIELD_VALUE_SCAN)? {
+            let text = logged.entry.field(field);
+            let value = text.trim();
+            if value.is_empty() || values.iter().any(|known| known == value) {
+                continue;
+            }
+            values.push(value.to_string());
+            if values.len() == limit {
+                break;
+            }
+        }
+        Ok(values)
+    }
+
+    /// Newest entries first
+    pub fn recent(&self, limit: usize) -> Result<Vec<HistoryEntry>, String> {
+        self.search("", limit)
+    }
+
+    /// Newest entries whose page title or main fields contain `query`
+    /// (case-insensitive); an empty query matches everything and `#tag`
+    /// finds the entries with that tag. A query that is a trace or
+    /// correlation ID is looked up in the ID index instead.
+    #[instrument(skip(self), err)]
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<HistoryEntry>, String> {
+        if let Some(tag) = as_tag(query) {
+            return self.with_tag(tag, limit);
+        }
+        if let Some(trace_id) = as_trace_id(query) {
+            return self.with_trace_id(&trace_id, limit);
+        }
+        let Some(conn) = &self.conn else {
+            return Ok(Vec::new());
+        };
+        let sql = format!(
+            "SELECT {} FROM entries
+             WHERE ?1 = '' OR page_title LIKE ?2 ESCAPE '\\' OR error LIKE ?2 ESCAPE '\\'
+                OR problem LIKE ?2 ESCAPE '\\' OR solution LIKE ?2 ESCAPE '\\' OR code LIKE ?2 ESCAPE '\\'
+             ORDER BY id DESC LIMIT ?3",
+            COLUMNS
+        );
+        let pattern = format!("%{}%", escape_like(query));
+        let mut statement = conn
+            .prepare(&sql)
+            .map_err(|e| format!("Failed to search history: {}", e))?;
+        let rows = statement
+            .query_map(params![query, pattern, limit as i64], read_row)
+            .map_err(|e| format!("Failed to search history: {}", e))?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row.map_err(|e| format!("Failed to read history: {}", e))??);
+        }
+        Ok(entries)
+    }
+
+    /// Newest entries tagged `tag`, compared like tags are everywhere else
+    #[instrument(skip(self), err)]
+    pub fn with_tag(&self, tag: &str, limit: usize) -> Result<Vec<HistoryEntry>, String> {
+        let Some(conn) = &self.conn else {
+            return Ok(Vec::new());
+        };
+        let wanted = tags::key(tag);
+        let sql = format!("SELECT {} FROM entries ORDER BY id DESC", COLUMNS);
+        let mut statement = conn
+            .prepare(&sql)
+            .map_err(|e| format!("Failed to search history: {}", e))?;
+        let rows = statement
+            .query_map([], read_row)
+            .map_err(|e| format!("Failed to search history: {}", e))?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            let logged = row.map_err(|e| format!("Failed to read history: {}", e))??;
+            if logged.entry.tags.iter().any(|tag| tags::key(tag) == wanted) {
+                entries.push(logged);
+                if entries.len() == limit {
+                    break;
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Newest entries whose Error or Code field contains the trace or
+    /// correlation ID, given lowercase as `find_trace_ids` returns it
+    #[instrument(skip(self), err)]
+    pub fn with_trace_id(&self, trace_id: &str, limit: usize) -> Result<Vec<HistoryEntry>, String> {
+        let Some(conn) = &self.conn else {
+            return Ok(Vec::new());
+        };
+        let sql = format!(
+            "SELECT {} FROM entries
+             WHERE id IN (SELECT entry_id FROM trace_ids WHERE trace_id = ?1)
+             ORDER BY id DESC LIMIT ?2",
+            COLUMNS
+        );
+        let mut statement = conn
+            .prepare(&sql)
+            .map_err(|e| format!("Failed to search history: {}", e))?;
+        let rows = statement
+            .query_map(params![trace_id, limit as i64], read_row)
+            .map_err(|e| format!("Failed to search history: {}", e))?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row.map_err(|e| format!("Failed to read history: {}", e))??);
+        }
+        Ok(entries)
+    }
+}
+
+/// The tag a `#tag` query asks for
+fn as_tag(query: &str) -> Option<&str> {
+    let tag = query.trim().strip_prefix('#')?.trim();
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// Replace the IDs indexed for an entry with those in `entry`
+fn index_trace_ids(conn: &Connection, id: i64, entry: &FaultLogEntry) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM trace_ids WHERE entry_id = ?1", params![id])?;
+    for trace_id in entry_trace_ids(entry) {
+        conn.execute("INSERT OR IGNORE INTO trace_ids (trace_id, entry_id) VALUES (?1, ?2)", params![trace_id, id])?;
+    }
+    Ok(())
+}
+
+/// Delete the rows `prune` drops and return how many went
+fn prune_rows(conn: &Connection, retention: &Retention, now: DateTime<Utc>) -> rusqlite::Result<usize> {
+    let mut removed = 0;
+    if retention.max_age_days > 0 {
+        let cutoff = now - Duration::days(retention.max_age_days.into());
+        removed += conn.execute(
+            "DELETE FROM entries WHERE status != 'queued' AND submitted_at < ?1",
+            params![cutoff.to_rfc3339()],
+        )?;
+    }
+    if retention.max_entries > 0 {
+        removed += conn.execute(
+            "DELETE FROM entries WHERE status != 'queued'
+             AND id NOT IN (SELECT id FROM entries ORDER BY id DESC LIMIT ?1)",
+            params![retention.max_entries as i64],
+        )?;
+    }
+    conn.execute("DELETE FROM trace_ids WHERE entry_id NOT IN (SELECT id FROM entries)", [])?;
+    Ok(removed)
+}
+
+/// Add columns that histories created by older versions lack
+fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+    let mut statement = conn.prepare("SELECT name FROM pragma_table_info('entries')")?;
+    let columns = statement
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    if !columns.iter().any(|name| name == "row_id") {
+        conn.execute_batch("ALTER TABLE entries ADD COLUMN row_id TEXT")?;
+    }
+    if !columns.iter().any(|name| name == "content_hash") {
+        conn.execute_batch("ALTER TABLE entries ADD COLUMN content_hash TEXT")?;
+        // Hash older entries so they count when spotting duplicates
+        let mut rows = conn.prepare("SELECT id, entry_json FROM entries")?;
+        let hashes = rows
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+            .filter_map(|row| {
+                let (id, entry_json) = row.ok()?;
+                let entry: FaultLogEntry = serde_json::from_str(&entry_json).ok()?;
+                Some((id, entry.content_hash()))
+            })
+            .collect::<Vec<_>>();
+        for (id, hash) in hashes {
+            conn.execute("UPDATE entries SET content_hash = ?1 WHERE id = ?2", params![hash, id])?;
+        }
+    }
+    conn.execute_batch("CREATE INDEX IF NOT EXISTS entries_content_hash ON entries (content_hash)")?;
+    let indexed: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'trace_ids')",
+        [],
+        |row| row.get(0),
+    )?;
+    conn.execute_batch(TRACE_ID_SCHEMA)?;
+    if !indexed {
+        // Index the IDs in entries logged before the index existed
+        let mut rows = conn.prepare("SELECT id, entry_json FROM entries")?;
+        let entries = rows
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+            .filter_map(|row| {
+                let (id, entry_json) = row.ok()?;
+                Some((id, serde_json::from_str::<FaultLogEntry>(&entry_json).ok()?))
+            })
+            .collect::<Vec<_>>();
+        for (id, entry) in entries {
+            index_trace_ids(conn, id, &entry)?;
+        }
+    }
+    Ok(())
+}
+
+/// Decode a row; the inner error covers a corrupt status or entry
+fn read_row(row: &Row) -> rusqlite::Result<Result<HistoryEntry, String>> {
+    let id: i64 = row.get(0)?;
+    let status: String = row.get(4)?;
+    let entry_json: String = row.get(5)?;
+    let decoded = EntryStatus::parse(&status).and_then(|status| {
+        serde_json::from_str(&entry_json)
+            .map(|entry| (status, entry))
+            .map_err(|e| format!("Corrupt history entry {}: {}", id, e))
+    });
+    Ok(match decoded {
+        Ok((status, entry)) => Ok(HistoryEntry {
+            id,
+            submitted_at: row.get(1)?,
+            page_id: row.get(2)?,
+            page_title: row.get(3)?,
+            status,
+            entry,
+            row_id: row.get(6)?,
+        }),
+        Err(e) => Err(e),
+    })
+}
+
+/// Make `%`, `_` and `\` match literally in a LIKE pattern
+fn escape_like(query: &str) -> String {
+    let mut escaped = String::with_capacity(query.len());
+    for c in query.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -0,0 +1,268 @@
+// `faultnote submit`: log an entry from the command line through the same
+// submission path as the TUI (history, offline queue, audit log)
+use std::io::{self, IsTerminal};
+
+use chrono::Utc;
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::app::{AppState, FaultLogEntry, PageInfo, TargetKind};
+use crate::audit::AuditLog;
+use crate::config::{Config, same_page_id};
+use crate::context::GitContext;
+use crate::events;
+use crate::failure::{Failure, FailureClass};
+use crate::notion::client::{NotionClient, create_notion_client, fetch_pages, fetch_target};
+use crate::notion::languages;
+use crate::notion::tokens::TokenMap;
+use crate::page_settings::PageSettingsStore;
+use crate::picker;
+use crate::queue::SubmissionQueue;
+use crate::storage::History;
+
+/// Fields of an entry given on the command line, or on a line of an
+/// import file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SubmitRequest {
+    /// Page or database id (dashes optional) or title; picked
+    /// interactively when missing
+    pub page: Option<String>,
+    pub error: String,
+    pub problem: String,
+    pub solution: String,
+    pub code: Option<String>,
+    pub language: Option<String>,
+    pub verification: Option<String>,
+    pub context: Option<String>,
+}
+
+impl SubmitRequest {
+    /// The entry as `submit` fills it in, without the page's defaults
+    pub fn entry(&self) -> FaultLogEntry {
+        FaultLogEntry {
+            error: self.error.clone(),
+            problem: self.problem.clone(),
+            solution: self.solution.clone(),
+            code: self.code.clone(),
+            verification: self.verification.clone(),
+            context: self.context.clone(),
+            language: self.language.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Where a submitted entry ended up
+#[derive(Debug, Clone)]
+pub struct Submitted {
+    pub page: PageInfo,
+    /// Top-level blocks created on the page, or the new database row
+    pub block_ids: Vec<String>,
+}
+
+impl Submitted {
+    /// Link to the entry: its first block on the page, or the new row
+    pub fn url(&self) -> String {
+        entry_url(&self.page.id, self.page.kind, &self.block_ids)
+    }
+}
+
+/// Link to an entry created on a page or as a database row
+pub fn entry_url(page_id: &str, kind: TargetKind, block_ids: &[String]) -> String {
+    match (kind, block_ids.first()) {
+        (TargetKind::Database, Some(row)) => notion_url(row, None),
+        (TargetKind::Page, Some(block)) => notion_url(page_id, Some(block)),
+        (_, None) => notion_url(page_id, None),
+    }
+}
+
+/// notion.so link to a page, optionally scrolled to one of its blocks
+pub fn notion_url(page_id: &str, block_id: Option<&str>) -> String {
+    let page = page_id.replace('-', "");
+    match block_id {
+        Some(block) => format!("https://www.notion.so/{}#{}", page, block.replace('-', "")),
+        None => format!("https://www.notion.so/{}", page),
+    }
+}
+
+/// Id of the page a notion.so, notion.com or notion.site link points to,
+/// with dashes; a bare id with or without dashes is taken as well.
+/// Pages opened as a side peek name the page in `?p=`.
+pub fn page_id_from_url(link: &str) -> Option<String> {
+    let link = link.trim();
+    let candidate = match Url::parse(link) {
+        Ok(url) => {
+            let host = url.host_str().unwrap_or_default();
+            let notion = ["notion.so", "notion.com", "notion.site"]
+                .iter()
+                .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)));
+            if !notion {
+                return None;
+            }
+            match url.query_pairs().find(|(key, _)| key == "p") {
+                Some((_, peek)) => peek.to_string(),
+                None => url.path_segments()?.rfind(|segment| !segment.is_empty())?.to_string(),
+            }
+        }
+        Err(_) => link.to_string(),
+    };
+    // Titles come before the id in a link: `Login-Errors-1a2b…`
+    let hex: String = candidate.chars().filter(|c| *c != '-').collect();
+    let id = hex.get(hex.len().checked_sub(32)?..)?;
+    if !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(format!("{}-{}-{}-{}-{}", &id[..8], &id[8..12], &id[12..16], &id[16..20], &id[20..]).to_lowercase())
+}
+
+/// The page or database a link points to, fetched directly instead of
+/// searched for
+pub async fn resolve_target(client: &NotionClient, link: &str) -> Result<PageInfo, String> {
+    let id = page_id_from_url(link).ok_or_else(|| format!("{} is not a link to a Notion page", link.trim()))?;
+    fetch_target(client, &id)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", link.trim(), e))
+}
+
+/// Submit an entry and report where it went
+pub async fn run(request: SubmitRequest) -> Result<Submitted, Failure> {
+    let mut app = load_app()?;
+    let client = connect(&app)?;
+    let pages = fetch_pages(&client)
+        .await
+        .map_err(|e| Failure::new(FailureClass::for_request(&e), format!("Failed to fetch pages: {}", e)))?;
+
+    let page = match request.page.as_deref() {
+        Some(wanted) => find_page(&pages, wanted)
+            .ok_or_else(|| Failure::validation(format!("No page or database matches \"{}\"", wanted)))?,
+        None => pick_page(&pages)?,
+    };
+    submit(&mut app, &client, page, request).await
+}
+
+/// A Notion client for a subcommand; tokens it had to skip are reported
+/// on stderr
+pub fn connect(app: &AppState) -> Result<NotionClient, Failure> {
+    let (client, warnings) = create_notion_client(&app.config.tokens).map_err(Failure::auth)?;
+    for warning in warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    Ok(client)
+}
+
+/// The config for a subcommand; what it had to skip is reported on stderr
+pub fn load_config() -> Result<Config, Failure> {
+    let config = Config::load().map_err(Failure::validation)?;
+    for warning in &config.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    Ok(config)
+}
+
+/// App state with the config and local stores the TUI would use
+pub fn load_app() -> Result<AppState, Failure> {
+    let mut app = AppState::new();
+    app.config = load_config()?;
+    app.git_context = GitContext::current();
+    if let Some(path) = AuditLog::default_path() {
+        app.audit_log = AuditLog::new(path);
+    }
+    if let Some(queue) = SubmissionQueue::default_path().map(SubmissionQueue::load) {
+        app.queue = queue?;
+    }
+    if let Some(history) = History::default_path().map(History::open) {
+        app.history = history?;
+        app.history.prune(&app.config.history, Utc::now(), false)?;
+    }
+    if let Some(token_map) = TokenMap::default_path().map(TokenMap::load) {
+        app.token_map = token_map?;
+    }
+    if let Some(page_settings) = PageSettingsStore::default_path().map(PageSettingsStore::load) {
+        app.page_settings = page_settings?;
+    }
+    Ok(app)
+}
+
+/// The page with this id or link, else the one with this title ignoring
+/// case, else the only one whose title contains it
+pub fn find_page(pages: &[PageInfo], wanted: &str) -> Option<PageInfo> {
+    if let Some(id) = page_id_from_url(wanted).filter(|_| wanted.contains("://")) {
+        return pages.iter().find(|p| same_page_id(&p.id, &id)).cloned();
+    }
+    let wanted = wanted.trim();
+    let lower = wanted.to_lowercase();
+    let containing: Vec<&PageInfo> = pages.iter().filter(|p| p.title.to_lowercase().contains(&lower)).collect();
+    let only_containing = match containing.as_slice() {
+        [only] => Some(*only),
+        _ => None,
+    };
+    pages
+        .iter()
+        .find(|p| same_page_id(&p.id, wanted))
+        .or_else(|| pages.iter().find(|p| p.title.eq_ignore_ascii_case(wanted)))
+        .or(only_containing)
+        .cloned()
+}
+
+/// Ask on the terminal which page to log to; scripts without one must
+/// name the page
+fn pick_page(pages: &[PageInfo]) -> Result<PageInfo, Failure> {
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        return Err(Failure::validation("--page is required when not running on a terminal"));
+    }
+    if pages.is_empty() {
+        return Err(Failure::auth("No pages are shared with the integration"));
+    }
+    let titles = pages
+        .iter()
+        .map(|p| if p.is_database() { format!("{} (database)", p.title) } else { p.title.clone() })
+        .collect();
+    match picker::pick(titles, "Log to page ›").map_err(|e| format!("Page picker failed: {}", e))? {
+        Some(index) => Ok(pages[index].clone()),
+        None => Err("No page picked".into()),
+    }
+}
+
+/// Fill the editor with the request and submit it to `page`, waiting for
+/// the result. Entries that fail to send are kept in the offline queue.
+pub async fn submit(app: &mut AppState, client: &NotionClient, page: PageInfo, request: SubmitRequest) -> Result<Submitted, Failure> {
+    if app.config.export.replaces_notion() {
+        return Err(Failure::validation(
+            "[export] mode = \"instead\" keeps entries out of Notion; use the TUI or set mode = \"alongside\"",
+        ));
+    }
+    // The app may have sent an entry before, e.g. during an import
+    app.clear_inputs();
+    app.last_submission = None;
+    app.set_pages(vec![page.clone()]);
+    app.error_input = request.error.as_str().into();
+    app.problem_input = request.problem.as_str().into();
+    app.solution_input = request.solution.as_str().into();
+    app.code_input = request.code.as_deref().unwrap_or_default().into();
+    app.verification_input = request.verification.as_deref().unwrap_or_default().into();
+    app.context_input = request.context.as_deref().unwrap_or_default().into();
+    if let Some(language) = request.language.as_deref() {
+        app.code_language = languages::normalize(language).unwrap_or(language).to_string();
+    }
+    // Nobody is there to press Enter twice; attach overflow right away
+    app.overflow_confirmed = true;
+
+    let queued = app.queue.len();
+    events::submit_to_notion(app, Some(client)).await;
+    events::complete_submission(app).await;
+    if let Some(last) = &app.last_submission {
+        return Ok(Submitted {
+            page,
+            block_ids: last.block_ids.clone(),
+        });
+    }
+    let message = app.status_message.as_ref().map_or_else(|| "Submission failed".to_string(), |message| message.text.clone());
+    // A request that failed left the entry in the queue; anything else was
+    // refused before sending
+    let class = match app.queue.items().last() {
+        Some(item) if app.queue.len() > queued => FailureClass::for_http_status(item.http_status),
+        _ => FailureClass::Validation,
+    };
+    Err(Failure::new(class, message.trim_start_matches("✗ ")))
+}
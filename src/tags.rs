@@ -0,0 +1,25 @@
+// Entry tags: typed comma separated into the Tags field, added to the
+// page's default tags and compared without regard to case
+
+/// A tag as compared with others; two tags with the same key are one tag
+pub fn key(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// Comma separated (or one per line) tags, trimmed, without blanks or
+/// repeats
+pub fn parse(text: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    for tag in text.split([',', '\n']) {
+        push_unique(&mut tags, tag);
+    }
+    tags
+}
+
+/// Add `tag` unless it is blank or already listed
+pub fn push_unique(tags: &mut Vec<String>, tag: &str) {
+    let tag = tag.trim();
+    if !tag.is_empty() && !tags.iter().any(|listed| key(listed) == key(tag)) {
+        tags.push(tag.to_string());
+    }
+}
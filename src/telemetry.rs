@@ -0,0 +1,98 @@
+// Tracing setup: spans go to a file with --trace and, with the `otlp`
+// feature, to an OpenTelemetry collector
+use std::{fs::File, path::Path, sync::Mutex};
+
+use tracing_subscriber::{EnvFilter, Registry, fmt::format::FmtSpan, layer::SubscriberExt, util::SubscriberInitExt};
+
+/// Filter used when RUST_LOG is not set
+const DEFAULT_FILTER: &str = "faultnote=debug";
+
+/// Keeps exporters alive; dropping it flushes pending spans
+pub struct TelemetryGuard {
+    #[cfg(feature = "otlp")]
+    provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otlp")]
+        if let Some(provider) = self.provider.take() {
+            let _ = provider.shutdown();
+        }
+    }
+}
+
+/// Install the global subscriber. Without a trace file or an OTLP
+/// endpoint nothing is installed and spans cost next to nothing.
+pub fn init(trace_file: Option<&Path>) -> Result<TelemetryGuard, String> {
+    let file_layer = match trace_file {
+        Some(path) => {
+            let file = File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .with_writer(Mutex::new(file))
+                    .with_ansi(false)
+                    .with_span_events(FmtSpan::CLOSE),
+            )
+        }
+        None => None,
+    };
+
+    #[cfg(feature = "otlp")]
+    let (otel_layer, provider) = match otlp::provider()? {
+        Some(provider) => (Some(otlp::layer(&provider)), Some(provider)),
+        None => (None, None),
+    };
+    #[cfg(not(feature = "otlp"))]
+    let otel_layer: Option<tracing_subscriber::layer::Identity> = None;
+
+    if file_layer.is_some() || otel_layer.is_some() {
+        let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+        Registry::default()
+            .with(filter)
+            .with(file_layer)
+            .with(otel_layer)
+            .try_init()
+            .map_err(|e| format!("Failed to start tracing: {}", e))?;
+    }
+
+    Ok(TelemetryGuard {
+        #[cfg(feature = "otlp")]
+        provider,
+    })
+}
+
+#[cfg(feature = "otlp")]
+mod otlp {
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::{Resource, trace::SdkTracerProvider};
+
+    /// Standard variable naming the collector, e.g. http://localhost:4318
+    const ENDPOINT_VAR: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+    /// Build a batch exporter when a collector endpoint is configured
+    pub fn provider() -> Result<Option<SdkTracerProvider>, String> {
+        let Ok(endpoint) = std::env::var(ENDPOINT_VAR) else {
+            return Ok(None);
+        };
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+            .build()
+            .map_err(|e| format!("Failed to create OTLP exporter: {}", e))?;
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name("faultnote").build())
+            .build();
+        Ok(Some(provider))
+    }
+
+    /// Layer feeding spans to the provider's tracer
+    pub fn layer<S>(provider: &SdkTracerProvider) -> tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>
+    where
+        S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+    {
+        tracing_opentelemetry::layer().with_tracer(provider.tracer("faultnote"))
+    }
+}
@@ -0,0 +1,60 @@
+// Trace and correlation IDs in pasted logs, indexed in the history so an ID
+// seen before is found at once
+use crate::app::FaultLogEntry;
+
+/// Every trace or correlation ID in `text`, lowercase, in order, without
+/// duplicates: UUIDs, 32-digit hex trace IDs (OpenTelemetry, Jaeger), the
+/// trace ID of a W3C `traceparent` and AWS X-Ray IDs
+pub fn find_trace_ids(text: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for token in text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '-')) {
+        if let Some(id) = trace_id(token) {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    ids
+}
+
+/// IDs in the Error field, then in the Code field
+pub fn entry_trace_ids(entry: &FaultLogEntry) -> Vec<String> {
+    trace_ids_in([entry.error.as_str(), entry.code.as_deref().unwrap_or_default()])
+}
+
+/// IDs in several texts, in order, without duplicates
+pub fn trace_ids_in<'a>(texts: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for id in texts.into_iter().flat_map(find_trace_ids) {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    ids
+}
+
+/// The ID `query` is, if it is nothing but one, e.g. a history search
+pub fn as_trace_id(query: &str) -> Option<String> {
+    trace_id(query.trim())
+}
+
+fn trace_id(token: &str) -> Option<String> {
+    let token = token.trim_matches('-').to_ascii_lowercase();
+    let parts: Vec<&str> = token.split('-').collect();
+    let lengths: Vec<usize> = parts.iter().map(|part| part.len()).collect();
+    if !parts.iter().all(|part| is_hex(part)) {
+        return None;
+    }
+    match lengths.as_slice() {
+        [8, 4, 4, 4, 12] | [32] => Some(token),
+        // traceparent: version, trace ID, parent span ID, flags
+        [2, 32, 16, 2] => Some(parts[1].to_string()),
+        // X-Ray: version, epoch seconds, random part
+        [1, 8, 24] if parts[0] == "1" => Some(token),
+        _ => None,
+    }
+}
+
+fn is_hex(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| c.is_ascii_hexdigit())
+}
@@ -1,27 +1,302 @@
 //main ui rendering logic
-use crate::app::{AppState, InputMode};
+use crate::app::{AppState, FaultLogEntry, FaultStatus, InputMode, LogSource, RowPick, Severity, StatusKind};
+use crate::attachments;
+use crate::buffer::TextBuffer;
+use crate::fields::InputField;
+use crate::keys::Action;
+use crate::notion::capabilities::Capabilities;
+use crate::markup::{DiffLine, classify_diff_line};
+use crate::notion::languages::LANGUAGES;
+use crate::notion::preview::PreviewStyle;
+use crate::network::Health;
+use crate::page_settings::{PageSetting, position_name, style_name};
+use crate::queue::{QueueStatus, QueuedSubmission, unix_now};
+use crate::storage::{EntryStatus, HistoryEntry};
+use crate::wrap::{CURSOR, WrapCache, scroll_offset, truncate_to_width};
+use chrono::{DateTime, Local};
 use ratatui::{
     Frame,
-    layout::{Constraint, Layout, Rect},
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
 };
+use serde::Deserialize;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Smallest terminal the full layout fits in
+pub const MIN_WIDTH: u16 = 60;
+pub const MIN_HEIGHT: u16 = 16;
+
+/// Rows the mini layout uses: status, active field and keys
+pub const MINI_HEIGHT: u16 = 3;
+
+/// Widest the zen column gets, borders included; about 80 characters of
+/// text is comfortable to write in
+const ZEN_WIDTH: u16 = 84;
+
+/// Rows the open activity log takes, borders included
+const STATUS_LOG_HEIGHT: u16 = 8;
+
+/// Colors of the TUI
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Default,
+    /// No colors; highlighted cells are shown reversed instead
+    Mono,
+}
 
 /// Main render function - called from the main loop
 pub fn render(frame: &mut Frame, app: &AppState) {
-    // Create main vertical layout (3 sections)
+    render_screen(frame, app);
+    if app.config.theme == Theme::Mono {
+        strip_colors(frame.buffer_mut());
+    }
+}
+
+/// Drop the colors of every cell, reversing those that had a background
+/// so selections stay visible
+fn strip_colors(buffer: &mut Buffer) {
+    for cell in buffer.content.iter_mut() {
+        if cell.bg != Color::Reset {
+            cell.modifier.insert(Modifier::REVERSED);
+        }
+        cell.fg = Color::Reset;
+        cell.bg = Color::Reset;
+    }
+}
+
+fn render_screen(frame: &mut Frame, app: &AppState) {
+    // The mini layout covers the main screen; pickers, prompts, the
+    // confirmation dialog and other screens need the full one
+    let area = frame.area();
+    if app.mini && app.is_main_screen() && !app.has_modal() {
+        render_mini(frame, app, area);
+        return;
+    }
+    if app.zen && app.is_main_screen() && !app.has_modal() {
+        render_zen(frame, app, area);
+        return;
+    }
+
+    // A cramped terminal gets a placeholder instead of a corrupted layout
+    if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+        render_too_small(frame, app, area);
+        return;
+    }
+
+    // Create main vertical layout (3 sections); inline mode drops the
+    // borders around the title and command bars to save rows
+    let bar_height = if app.inline { 1 } else { 3 };
+    let log_height = if app.status_log_open { STATUS_LOG_HEIGHT } else { 0 };
     let main_layout = Layout::vertical([
-        Constraint::Length(3),  // Title bar
-        Constraint::Min(10),    // Main content
-        Constraint::Length(3),  // Command bar
+        Constraint::Length(bar_height), // Title bar
+        Constraint::Min(10),            // Main content
+        Constraint::Length(log_height), // Message log
+        Constraint::Length(bar_height), // Command bar
     ])
-    .split(frame.area());
+    .split(area);
 
     // Render each section
     render_title_bar(frame, app, main_layout[0]);
-    render_main_content(frame, app, main_layout[1]);
-    render_command_bar(frame, app, main_layout[2]);
+    if app.is_queue_screen() {
+        render_queue_screen(frame, app, main_layout[1]);
+    } else if app.is_history_screen() {
+        render_history_screen(frame, app, main_layout[1]);
+    } else if app.is_entries_screen() {
+        render_entries_screen(frame, app, main_layout[1]);
+    } else if app.is_preview_screen() {
+        render_preview_screen(frame, app, main_layout[1]);
+    } else if app.is_page_settings_screen() {
+        render_page_settings_screen(frame, app, main_layout[1]);
+    } else if app.is_rows_screen() {
+        render_rows_screen(frame, app, main_layout[1]);
+    } else if app.is_relations_screen() {
+        render_relations_screen(frame, app, main_layout[1]);
+    } else {
+        render_main_content(frame, app, main_layout[1]);
+        if app.language_picking {
+            render_language_picker(frame, app, main_layout[1]);
+        }
+        if app.template_picking {
+            render_template_picker(frame, app, main_layout[1]);
+        }
+        if app.attach_input.is_some() {
+            render_attach_prompt(frame, app, main_layout[1]);
+        }
+        if app.new_page.is_some() {
+            render_new_page_prompt(frame, app, main_layout[1]);
+        }
+    }
+    if app.pending_action.is_some() {
+        render_confirmation(frame, app, main_layout[1]);
+    }
+    if app.status_log_open {
+        render_status_log(frame, app, main_layout[2]);
+    }
+    render_command_bar(frame, app, main_layout[3]);
+}
+
+/// The status lines and Notion requests so far with their levels, newest
+/// at the bottom, scrolled back `status_log_scroll` records
+fn render_status_log(frame: &mut Frame, app: &AppState, area: Rect) {
+    app.status_log_area.set(area);
+    let height = area.height.saturating_sub(2) as usize;
+    let end = app.status_log.len().saturating_sub(app.status_log_scroll);
+    let start = end.saturating_sub(height);
+    let items: Vec<ListItem> = app
+        .status_log
+        .range(start..end)
+        .map(|record| {
+            let color = status_color(record.kind);
+            // Requests that went through stay plain so failures stand out
+            let message_style = match record.source {
+                LogSource::Api if record.kind != StatusKind::Error => Style::default(),
+                _ => Style::default().fg(color),
+            };
+            let mut spans = vec![
+                Span::styled(format!(" {} ", record.at.format("%H:%M:%S")), Style::default().fg(Color::DarkGray)),
+                Span::styled(format!("{:<5} ", record.kind.level()), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                Span::styled(format!("{} ", record.source.label()), Style::default().fg(Color::DarkGray)),
+                Span::styled(record.message.clone(), message_style),
+            ];
+            if record.repeats > 1 {
+                spans.push(Span::styled(format!(" (×{})", record.repeats), Style::default().fg(Color::DarkGray)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let mut title = format!(" {}Activity ({}) ", icon(app, "📜"), app.status_log.len());
+    if app.status_log_scroll > 0 {
+        title.push_str(&format!("↓ {} newer ", app.status_log_scroll));
+    }
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+    frame.render_widget(list, area);
+}
+
+/// Placeholder shown until the terminal is resized to a usable size
+fn render_too_small(frame: &mut Frame, app: &AppState, area: Rect) {
+    let mut lines = vec![
+        Line::from(Span::styled("Terminal too small", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+        Line::from(format!("needs {}x{}, have {}x{}", MIN_WIDTH, MIN_HEIGHT, area.width, area.height)),
+    ];
+    if app.is_main_screen() {
+        lines.push(Line::from(format!("{} for the mini layout", app.config.keys.label(Action::ToggleMini))));
+    }
+    let message = Paragraph::new(lines)
+    .centered()
+    .wrap(Wrap { trim: true });
+    frame.render_widget(message, area);
+}
+
+/// The compact layout: the target and status on top, the row of the
+/// active field holding the cursor, and the main keys
+fn render_mini(frame: &mut Frame, app: &AppState, area: Rect) {
+    // Nothing here is clickable; clicks must not land on the full layout
+    app.page_list_area.set(Rect::default());
+    app.field_areas.set([Rect::default(); AppState::MAX_INPUTS]);
+    app.status_log_area.set(Rect::default());
+    let rows = Layout::vertical([Constraint::Length(1); MINI_HEIGHT as usize]).split(area);
+    let keys = &app.config.keys;
+
+    let mode = match app.input_mode {
+        InputMode::Normal => Span::styled(" NORMAL ", Style::default().bg(Color::Blue).fg(Color::White)),
+        InputMode::Editing => Span::styled(" EDITING ", Style::default().bg(Color::Green).fg(Color::Black)),
+    };
+    let target = app.submission_target().map_or_else(|| "no page".to_string(), |page| page.title);
+    let mut top = vec![
+        Span::styled(format!("{}FaultNote ", icon(app, "📋")), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        mode,
+        severity_indicator(app),
+        Span::styled(format!(" → {} ", target), Style::default().fg(Color::White)),
+        queue_indicator(app),
+    ];
+    if let Some(frame) = app.spinner() {
+        top.push(Span::styled(format!(" {}", frame), Style::default().fg(Color::Cyan)));
+    }
+    if let Some(prompt) = app.confirmation_prompt() {
+        top.push(Span::styled(format!(" {} ", prompt), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
+    } else if let Some(message) = &app.status_message {
+        top.push(Span::styled(format!(" {} ", message), Style::default().fg(status_color(message.kind))));
+    }
+    frame.render_widget(Paragraph::new(Line::from(top)), rows[0]);
+
+    let field = app.active_field();
+    let label = format!("{}{}: ", icon(app, field.emoji()), app.config.template.labels.label(field));
+    let width = (rows[1].width as usize).saturating_sub(label.width());
+    let style = if app.is_editing() { Style::default().fg(Color::Green) } else { Style::default().fg(Color::Yellow) };
+    let field_line = Line::from(vec![
+        Span::styled(label, style.add_modifier(Modifier::BOLD)),
+        Span::raw(mini_field_text(app.field(field), app.is_editing(), width)),
+    ]);
+    frame.render_widget(Paragraph::new(field_line), rows[1]);
+
+    let commands = if app.is_editing() {
+        vec![
+            (keys.label(Action::ExitEdit), "Done"),
+            (keys.label(Action::NextField), "Next Field"),
+            (keys.label(Action::FieldUp) + "/" + &keys.label(Action::FieldDown), "Rows"),
+        ]
+    } else {
+        vec![
+            (keys.label(Action::EditMode), "Edit"),
+            (keys.label(Action::Up) + "/" + &keys.label(Action::Down), if app.is_input_section_focused() { "Field" } else { "Page" }),
+            (keys.label(Action::Submit), "Submit"),
+            (keys.label(Action::ToggleMini), "Expand"),
+        ]
+    };
+    let spans: Vec<Span> = commands
+        .iter()
+        .flat_map(|(key, desc)| {
+            vec![
+                Span::styled(format!("[{}] ", key), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::styled(format!("{}  ", desc), Style::default().fg(Color::Gray)),
+            ]
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(Line::from(spans)), rows[2]);
+}
+
+/// One row of a field in `width` columns: while editing the line holding
+/// the cursor, scrolled so the cursor shows, else the first line
+fn mini_field_text(content: &TextBuffer, is_editing: bool, width: usize) -> String {
+    if !is_editing {
+        let first = content.lines().next().map(|line| line.to_string()).unwrap_or_default();
+        let more = if content.len_lines() > 1 { " …" } else { "" };
+        return truncate_to_width(&(first + more), width).to_string();
+    }
+    let (line, column) = content.cursor_position();
+    let text = content.line(line).to_string();
+    let split = text.char_indices().nth(column).map_or(text.len(), |(idx, _)| idx);
+    let (before, after) = text.split_at(split);
+    // Drop the start of the line until the cursor fits
+    let mut before = before;
+    while before.width() + 1 > width && !before.is_empty() {
+        let cut = before.graphemes(true).next().map_or(1, str::len);
+        before = &before[cut..];
+    }
+    let shown = format!("{}{}{}", before, CURSOR, after);
+    truncate_to_width(&shown, width).to_string()
+}
+
+/// Color of a status message of `kind`
+fn status_color(kind: StatusKind) -> Color {
+    match kind {
+        StatusKind::Info => Color::Yellow,
+        StatusKind::Success => Color::Green,
+        StatusKind::Error => Color::Red,
+        StatusKind::Loading => Color::Cyan,
+    }
 }
 
 /// Render the title bar at the top
@@ -32,25 +307,92 @@ fn render_title_bar(frame: &mut Frame, app: &AppState, area: Rect) {
     };
 
     let status = if let Some(msg) = &app.status_message {
-        Span::styled(format!(" {} ", msg), Style::default().fg(Color::Yellow))
+        Span::styled(format!(" {} ", msg), Style::default().fg(status_color(msg.kind)))
     } else {
         Span::raw("")
     };
+    let spinner = match app.spinner() {
+        Some(frame) => Span::styled(format!(" {}", frame), Style::default().fg(Color::Cyan)),
+        None => Span::raw(""),
+    };
 
     let title_line = Line::from(vec![
-        Span::styled(" 📋 FaultNote ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Span::styled(format!(" {}FaultNote ", icon(app, "📋")), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
         Span::raw("- Error Logger "),
         mode_indicator,
+        severity_indicator(app),
         Span::raw(" "),
+        connectivity_indicator(app),
+        git_indicator(app),
+        queue_indicator(app),
+        spinner,
         status,
     ]);
 
-    let title_block = Paragraph::new(title_line)
-        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Cyan)));
+    let title_block = if app.inline {
+        Paragraph::new(title_line)
+    } else {
+        Paragraph::new(title_line)
+            .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Cyan)))
+    };
 
     frame.render_widget(title_block, area);
 }
 
+/// Severity of the entry being composed, when one is set
+fn severity_indicator(app: &AppState) -> Span<'static> {
+    let Some(severity) = app.severity else {
+        return Span::raw("");
+    };
+    let style = match severity {
+        Severity::Critical => Style::default().bg(Color::Red).fg(Color::White).add_modifier(Modifier::BOLD),
+        Severity::High => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        Severity::Medium => Style::default().fg(Color::Yellow),
+        Severity::Low => Style::default().fg(Color::Blue),
+    };
+    Span::styled(format!(" {}{} ", icon(app, severity.emoji()), severity.name()), style)
+}
+
+/// Branch and commit entries will be logged against
+fn git_indicator(app: &AppState) -> Span<'static> {
+    match &app.git_context {
+        Some(git) => Span::styled(format!(" ⎇ {} ", git.summary()), Style::default().fg(Color::Magenta)),
+        None => Span::raw(""),
+    }
+}
+
+/// Emoji prefix for a label, dropped in the plain style
+fn icon(app: &AppState, emoji: &str) -> String {
+    if app.config.template.is_plain() {
+        String::new()
+    } else {
+        format!("{} ", emoji)
+    }
+}
+
+/// Count of submissions waiting in the offline queue
+fn queue_indicator(app: &AppState) -> Span<'static> {
+    if app.queue.is_empty() {
+        Span::raw("")
+    } else {
+        Span::styled(
+            format!(" {}{} queued ", icon(app, "📤"), app.queue.len()),
+            Style::default().bg(Color::Magenta).fg(Color::White),
+        )
+    }
+}
+
+/// Badge shown while Notion is unreachable or slow
+fn connectivity_indicator(app: &AppState) -> Span<'static> {
+    if !app.online {
+        Span::styled(" OFFLINE ", Style::default().bg(Color::Red).fg(Color::White))
+    } else if app.health.is_some_and(|health| health.is_degraded()) {
+        Span::styled(" DEGRADED ", Style::default().bg(Color::Yellow).fg(Color::Black))
+    } else {
+        Span::raw("")
+    }
+}
+
 /// Render the main content area (page list + input sections)
 fn render_main_content(frame: &mut Frame, app: &AppState, area: Rect) {
     // Split horizontally: left sidebar (20%) + right content (80%)
@@ -60,8 +402,47 @@ fn render_main_content(frame: &mut Frame, app: &AppState, area: Rect) {
     ])
     .split(area);
 
-    // Render page list on the left
-    render_page_list(frame, app, content_layout[0]);
+    // Render page list on the left, with the integration and diagnostics
+    // panels below it once there is something to show
+    let links = app.links();
+    let mut panels = vec![Constraint::Min(5)];
+    if !app.sub_entries.is_empty() {
+        panels.push(Constraint::Length(app.sub_entries.len().min(5) as u16 + 2));
+    }
+    if !app.attached_files.is_empty() {
+        panels.push(Constraint::Length(app.attached_files.len().min(5) as u16 + 2));
+    }
+    if !links.is_empty() {
+        panels.push(Constraint::Length(links.len().min(5) as u16 + 2));
+    }
+    if app.capabilities.is_some() {
+        panels.push(Constraint::Length(6));
+    }
+    if app.health.is_some() {
+        panels.push(Constraint::Length(4));
+    }
+    let sidebar = Layout::vertical(panels).split(content_layout[0]);
+    render_page_list(frame, app, sidebar[0]);
+    let mut next_panel = 1;
+    if !app.sub_entries.is_empty() {
+        render_sub_entries(frame, app, sidebar[next_panel]);
+        next_panel += 1;
+    }
+    if !app.attached_files.is_empty() {
+        render_attachments(frame, app, sidebar[next_panel]);
+        next_panel += 1;
+    }
+    if !links.is_empty() {
+        render_links(frame, app, &links, sidebar[next_panel]);
+        next_panel += 1;
+    }
+    if let Some(capabilities) = &app.capabilities {
+        render_capabilities(frame, capabilities, &icon(app, "🔑"), sidebar[next_panel]);
+        next_panel += 1;
+    }
+    if let Some(health) = &app.health {
+        render_diagnostics(frame, health, &icon(app, "🩺"), sidebar[next_panel]);
+    }
 
     // Render input sections on the right
     render_input_sections(frame, app, content_layout[1]);
@@ -69,28 +450,76 @@ fn render_main_content(frame: &mut Frame, app: &AppState, area: Rect) {
 
 /// Render the Notion pages list on the left sidebar
 fn render_page_list(frame: &mut Frame, app: &AppState, area: Rect) {
-    // Create list items from notion_pages
-    let items: Vec<ListItem> = app
-        .notion_pages
-        .iter()
-        .enumerate()
-        .map(|(idx, page)| {
-            let style = if idx == app.selected_page_index && app.is_page_list_focused() {
+    // Only build items for the rows that fit inside the borders
+    let height = area.height.saturating_sub(2) as usize;
+    app.page_list_height.set(height);
+    app.page_list_area.set(area);
+    let visible = app.visible_page_range(height);
+    let offset = visible.start;
+
+    let items: Vec<ListItem> = visible
+        .map(|row| {
+            let index = app.listed_page(row);
+            let page = &app.notion_pages[index];
+            let style = if row == app.listed_selection() && app.is_page_list_focused() {
                 Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
             } else {
                 Style::default().fg(Color::White)
             };
-            ListItem::new(format!(" {} ", page.title)).style(style)
+            let check = if app.is_page_checked(&page.id) { "✓ " } else { "" };
+            // Nesting shows as indentation; matches of the filter name their
+            // parent instead
+            let tree = if app.page_filtering {
+                String::new()
+            } else {
+                let fold = match (app.has_subpages(index), app.is_page_collapsed(index)) {
+                    (false, _) => "",
+                    (true, true) => "▸ ",
+                    (true, false) => "▾ ",
+                };
+                format!("{}{}", "  ".repeat(app.page_depth(index)), fold)
+            };
+            let label = match app.chosen_rows.get(&page.id) {
+                Some(row) => format!(" {}{}{}{} › {} ", tree, check, icon(app, "📊"), page.title, row.title),
+                None if page.is_database() => format!(" {}{}{}{} ", tree, check, icon(app, "📊"), page.title),
+                None => format!(" {}{}{} ", tree, check, page.title),
+            };
+            let mut line = vec![Span::raw(label)];
+            if let Some(parent) = app.parent_page_index(index).filter(|_| app.page_filtering) {
+                line.push(Span::styled(
+                    format!("in {} ", app.notion_pages[parent].title),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            ListItem::new(Line::from(line)).style(style)
         })
         .collect();
 
     // Empty state message if no pages
+    let empty = if app.page_filtering {
+        " No matching pages"
+    } else if app.pages_loading {
+        " Loading pages…"
+    } else {
+        " No pages loaded"
+    };
     let list = if items.is_empty() {
-        List::new(vec![ListItem::new(" No pages loaded").style(Style::default().fg(Color::DarkGray))])
+        List::new(vec![ListItem::new(empty).style(Style::default().fg(Color::DarkGray))])
     } else {
         List::new(items)
     };
 
+    let mut title = format!(" {}Notion Pages ", icon(app, "📚"));
+    if app.pages_loading && !app.notion_pages.is_empty() {
+        title.push_str("⟳ ");
+    }
+    if !app.checked_pages.is_empty() {
+        title.push_str(&format!("({} checked) ", app.checked_pages.len()));
+    }
+    if app.page_filtering {
+        title = format!("{}/{}▌ ", title, app.page_filter);
+    }
+
     // Determine border color based on focus
     let border_color = if app.is_page_list_focused() {
         Color::Yellow
@@ -102,79 +531,907 @@ fn render_page_list(frame: &mut Frame, app: &AppState, area: Rect) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(" 📚 Notion Pages ")
+                .title(title)
+                .title_bottom(Line::from(format!(" {} ", app.page_position())).right_aligned())
                 .border_style(Style::default().fg(border_color)),
         )
         .highlight_style(Style::default().bg(Color::Rgb(45, 85, 155)).fg(Color::White))
         .highlight_symbol("▶ ");
 
-    // Create list state for tracking selection
+    // Selection is relative to the visible window
     let mut state = ListState::default();
-    if !app.notion_pages.is_empty() {
-        state.select(Some(app.selected_page_index));
+    if app.listed_page_count() > 0 {
+        state.select(Some(app.listed_selection() - offset));
     }
 
     frame.render_stateful_widget(list, area, &mut state);
 }
 
-/// Render the four input sections on the right
-fn render_input_sections(frame: &mut Frame, app: &AppState, area: Rect) {
-    // Split into 4 vertical sections
-    let sections = Layout::vertical([
-        Constraint::Percentage(25),
-        Constraint::Percentage(25),
-        Constraint::Percentage(25),
-        Constraint::Percentage(25),
+/// Render what the integration token may do
+fn render_capabilities(frame: &mut Frame, capabilities: &Capabilities, icon: &str, area: Rect) {
+    let check = |allowed: bool, label: &'static str| {
+        let (mark, color) = if allowed { ("✓", Color::Green) } else { ("✗", Color::Red) };
+        Line::from(vec![
+            Span::styled(format!(" {} ", mark), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+            Span::raw(label),
+        ])
+    };
+    let name = match (&capabilities.bot_name, &capabilities.workspace) {
+        (Some(bot), Some(workspace)) => format!(" {}{} @ {} ", icon, bot, workspace),
+        (Some(bot), None) => format!(" {}{} ", icon, bot),
+        _ => format!(" {}Integration ", icon),
+    };
+
+    let lines = vec![
+        check(capabilities.read_content, "Read content"),
+        check(capabilities.insert_content, "Insert content"),
+        check(capabilities.read_comments, "Read comments"),
+    ];
+    let border_color = if capabilities.submit_blocker().is_some() {
+        Color::Red
+    } else {
+        Color::DarkGray
+    };
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(name)
+            .border_style(Style::default().fg(border_color)),
+    );
+    frame.render_widget(paragraph, area);
+}
+
+/// Render the links found in the Error and Code fields, the selected one
+/// highlighted for opening or copying
+fn render_links(frame: &mut Frame, app: &AppState, links: &[String], area: Rect) {
+    let width = area.width.saturating_sub(4) as usize;
+    let items: Vec<ListItem> = links
+        .iter()
+        .map(|link| {
+            let shown = link.trim_start_matches("https://").trim_start_matches("http://");
+            ListItem::new(format!(" {}", truncate_to_width(shown, width)))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {}Links ({}) ", icon(app, "🔗"), links.len()))
+                .border_style(Style::default().fg(Color::DarkGray)),
+        )
+        .highlight_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+    let mut state = ListState::default();
+    state.select(Some(app.selected_link.min(links.len() - 1)));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// Files and links attached to the entry being written
+fn render_attachments(frame: &mut Frame, app: &AppState, area: Rect) {
+    let width = area.width.saturating_sub(2) as usize;
+    let items: Vec<ListItem> = app
+        .attached_files
+        .iter()
+        .map(|source| {
+            let kind = if source.starts_with("http") { "🔗" } else if attachments::is_image(source) { "🖼️" } else { "📄" };
+            let name = format!(" {} {}", kind, attachments::display_name(source));
+            ListItem::new(truncate_to_width(&name, width).to_string())
+        })
+        .collect();
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {}Attachments ({}) ", icon(app, "📎"), app.attached_files.len()))
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+    frame.render_widget(list, area);
+}
+
+/// Drafts stacked as sub-entries, submitted under the entry being written
+fn render_sub_entries(frame: &mut Frame, app: &AppState, area: Rect) {
+    let width = area.width.saturating_sub(2) as usize;
+    let items: Vec<ListItem> = app
+        .sub_entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let title = format!(" {}. {}", index + 1, entry.error.lines().next().unwrap_or_default().trim());
+            ListItem::new(truncate_to_width(&title, width).to_string())
+        })
+        .collect();
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {}Sub-entries ({}) ", icon(app, "🗂️"), app.sub_entries.len()))
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+    frame.render_widget(list, area);
+}
+
+/// Render the latest health ping to the Notion API
+fn render_diagnostics(frame: &mut Frame, health: &Health, icon: &str, area: Rect) {
+    let color = if !health.reachable {
+        Color::Red
+    } else if health.is_degraded() {
+        Color::Yellow
+    } else {
+        Color::Green
+    };
+    let latency = match health.latency {
+        Some(latency) => format!("{} ms", latency.as_millis()),
+        None => "no response".to_string(),
+    };
+
+    let lines = vec![
+        Line::from(vec![
+            Span::raw(" Notion API: "),
+            Span::styled(health.label(), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+        ]),
+        Line::from(format!(" Latency: {}", latency)),
+    ];
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {}Diagnostics ", icon))
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+    frame.render_widget(paragraph, area);
+}
+
+/// Render the offline queue inspector: list on the left, details on the right
+fn render_queue_screen(frame: &mut Frame, app: &AppState, area: Rect) {
+    let now = unix_now();
+    let layout = Layout::horizontal([
+        Constraint::Percentage(40),
+        Constraint::Percentage(60),
     ])
     .split(area);
 
-    // Render each input block
-    render_input_block(
-        frame,
-        "🔴 Error",
-        &app.error_input,
-        app.active_input_field == 0 && app.is_input_section_focused(),
-        app.active_input_field == 0 && app.is_editing(),
-        sections[0],
-    );
+    let items: Vec<ListItem> = app
+        .queue
+        .items()
+        .iter()
+        .map(|item| {
+            let (badge, color) = match item.status {
+                QueueStatus::Pending => ("PENDING", Color::Yellow),
+                QueueStatus::Failed => ("FAILED", Color::Red),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!(" {:<7} ", badge), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                Span::raw(format!("{} → {} ", first_line(&item.entry.error), item.page_title)),
+                Span::styled(retry_summary(item, now), Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+
+    let list = if items.is_empty() {
+        List::new(vec![ListItem::new(" Queue is empty").style(Style::default().fg(Color::DarkGray))])
+    } else {
+        List::new(items)
+    };
+    let list = list
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {}Queued Submissions ({}) ", icon(app, "📤"), app.queue.len()))
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .highlight_style(Style::default().bg(Color::Rgb(45, 85, 155)).fg(Color::White))
+        .highlight_symbol("▶ ");
+
+    let mut state = ListState::default();
+    if !app.queue.is_empty() {
+        state.select(Some(app.selected_queue_index));
+    }
+    frame.render_stateful_widget(list, layout[0], &mut state);
+
+    // Details of the selected submission
+    let details = match app.queue.get(app.selected_queue_index) {
+        Some(item) => {
+            let label = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+            let mut lines = vec![
+                Line::from(vec![Span::styled("Page: ", label), Span::raw(item.page_title.clone())]),
+                Line::from(vec![Span::styled("Attempts: ", label), Span::raw(item.attempts.to_string())]),
+                Line::from(vec![Span::styled("Next retry: ", label), Span::raw(retry_summary(item, now))]),
+                Line::from(vec![
+                    Span::styled("Last error: ", label),
+                    Span::styled(
+                        item.last_error.clone().unwrap_or_default(),
+                        Style::default().fg(Color::Red),
+                    ),
+                ]),
+                Line::raw(""),
+                Line::styled("Error", label),
+            ];
+            lines.extend(item.entry.error.lines().map(|l| Line::raw(l.to_string())));
+            lines.push(Line::raw(""));
+            lines.push(Line::styled("Problem", label));
+            lines.extend(item.entry.problem.lines().map(|l| Line::raw(l.to_string())));
+            lines.push(Line::raw(""));
+            lines.push(Line::styled("Solution", label));
+            lines.extend(item.entry.solution.lines().map(|l| Line::raw(l.to_string())));
+            Text::from(lines)
+        }
+        None => Text::from("Nothing queued. Failed submissions appear here."),
+    };
+
+    let paragraph = Paragraph::new(details)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Details ")
+                .border_style(Style::default().fg(Color::DarkGray)),
+        )
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, layout[1]);
+}
+
+/// Render the local history: searchable list on the left, the full entry on the right
+fn render_history_screen(frame: &mut Frame, app: &AppState, area: Rect) {
+    let layout = Layout::horizontal([
+        Constraint::Percentage(40),
+        Constraint::Percentage(60),
+    ])
+    .split(area);
+
+    let items: Vec<ListItem> = app
+        .history_entries
+        .iter()
+        .map(|item| {
+            let (badge, color) = match item.status {
+                EntryStatus::Submitted => ("SENT", Color::Green),
+                EntryStatus::Queued => ("QUEUED", Color::Yellow),
+                EntryStatus::Undone => ("UNDONE", Color::DarkGray),
+                EntryStatus::Discarded => ("DROPPED", Color::Red),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!(" {:<7} ", badge), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                Span::styled(format!("{} ", local_time(item)), Style::default().fg(Color::DarkGray)),
+                Span::raw(format!("{} → {}", first_line(&item.entry.error), item.page_title)),
+            ]))
+        })
+        .collect();
+
+    let empty = if app.history_query.is_empty() {
+        " No entries yet"
+    } else {
+        " No matching entries"
+    };
+    let list = if items.is_empty() {
+        List::new(vec![ListItem::new(empty).style(Style::default().fg(Color::DarkGray))])
+    } else {
+        List::new(items)
+    };
+
+    let mut title = format!(" {}History ({}) ", icon(app, "🕘"), app.history_entries.len());
+    if app.history_searching || !app.history_query.is_empty() {
+        let cursor = if app.history_searching { "▌" } else { "" };
+        title = format!("{}/{}{} ", title, app.history_query, cursor);
+    }
+    let list = list
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(Style::default().bg(Color::Rgb(45, 85, 155)).fg(Color::White))
+        .highlight_symbol("▶ ");
+
+    let mut state = ListState::default();
+    if !app.history_entries.is_empty() {
+        state.select(Some(app.selected_history_index));
+    }
+    frame.render_stateful_widget(list, layout[0], &mut state);
 
-    render_input_block(
-        frame,
-        "🟡 Problem",
-        &app.problem_input,
-        app.active_input_field == 1 && app.is_input_section_focused(),
-        app.active_input_field == 1 && app.is_editing(),
-        sections[1],
+    // Every section of the selected entry, under the configured labels
+    let details = match app.selected_history_entry() {
+        Some(item) => {
+            let label = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+            let mut lines = vec![
+                Line::from(vec![Span::styled("Page: ", label), Span::raw(item.page_title.clone())]),
+                Line::from(vec![Span::styled("When: ", label), Span::raw(local_time(item))]),
+                Line::from(vec![Span::styled("Status: ", label), Span::raw(item.status.as_str())]),
+            ];
+            if item.row_id.is_some() {
+                lines.push(Line::from(vec![
+                    Span::styled("Fault: ", label),
+                    Span::raw(item.entry.status.name()),
+                ]));
+            }
+            lines.extend(entry_lines(app, &item.entry));
+            Text::from(lines)
+        }
+        None => Text::from("Every entry you submit or queue is kept here, even without Notion."),
+    };
+
+    let paragraph = Paragraph::new(details)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Entry ")
+                .border_style(Style::default().fg(Color::DarkGray)),
+        )
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, layout[1]);
+}
+
+/// Every non-empty section of an entry under its configured label
+fn entry_lines(app: &AppState, entry: &FaultLogEntry) -> Vec<Line<'static>> {
+    let label = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+    let sections = [
+        (InputField::Error, Some(entry.error.as_str())),
+        (InputField::Problem, Some(entry.problem.as_str())),
+        (InputField::Solution, Some(entry.solution.as_str())),
+        (InputField::Code, entry.code.as_deref()),
+        (InputField::Verification, entry.verification.as_deref()),
+        (InputField::Context, entry.context.as_deref()),
+    ];
+    let mut lines = Vec::new();
+    for (field, text) in sections {
+        let Some(text) = text.filter(|t| !t.trim().is_empty()) else {
+            continue;
+        };
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(app.config.template.labels.label(field).to_string(), label));
+        lines.extend(text.lines().map(|l| Line::raw(l.to_string())));
+    }
+    if !entry.tags.is_empty() {
+        lines.push(Line::raw(""));
+        lines.push(Line::from(vec![Span::styled("Tags: ", label), Span::raw(entry.tags.join(", "))]));
+    }
+    lines
+}
+
+/// Popup over the main screen listing the languages matching the filter
+fn render_language_picker(frame: &mut Frame, app: &AppState, area: Rect) {
+    let [popup] = Layout::horizontal([Constraint::Length(32)]).flex(Flex::Center).areas(area);
+    let [popup] = Layout::vertical([Constraint::Percentage(60)]).flex(Flex::Center).areas(popup);
+
+    let items: Vec<ListItem> = app
+        .language_matches
+        .iter()
+        .map(|&index| ListItem::new(format!(" {}", LANGUAGES[index])))
+        .collect();
+    let list = if items.is_empty() {
+        List::new(vec![ListItem::new(" No matching languages").style(Style::default().fg(Color::DarkGray))])
+    } else {
+        List::new(items)
+    };
+    let list = list
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {}Language /{}▌ ", icon(app, "💻"), app.language_filter))
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(Style::default().bg(Color::Rgb(45, 85, 155)).fg(Color::White))
+        .highlight_symbol("▶ ");
+
+    let mut state = ListState::default();
+    if !app.language_matches.is_empty() {
+        state.select(Some(app.selected_language));
+    }
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, &mut state);
+}
+
+/// Popup listing entry templates, each with its Error skeleton
+fn render_template_picker(frame: &mut Frame, app: &AppState, area: Rect) {
+    let [popup] = Layout::horizontal([Constraint::Percentage(60)]).flex(Flex::Center).areas(area);
+    let [popup] = Layout::vertical([Constraint::Percentage(50)]).flex(Flex::Center).areas(popup);
+
+    let items: Vec<ListItem> = app
+        .template_matches
+        .iter()
+        .map(|(name, template)| {
+            ListItem::new(Line::from(vec![
+                Span::raw(format!(" {}  ", name)),
+                Span::styled(template.summary().to_string(), Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+    let list = if items.is_empty() {
+        List::new(vec![ListItem::new(" No matching templates").style(Style::default().fg(Color::DarkGray))])
+    } else {
+        List::new(items)
+    };
+    let list = list
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {}Template /{}▌ ", icon(app, "📋"), app.template_filter))
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(Style::default().bg(Color::Rgb(45, 85, 155)).fg(Color::White))
+        .highlight_symbol("▶ ");
+
+    let mut state = ListState::default();
+    if !app.template_matches.is_empty() {
+        state.select(Some(app.selected_template));
+    }
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, &mut state);
+}
+
+/// Prompt for the path or link of a file to attach, above the files
+/// attached so far
+fn render_attach_prompt(frame: &mut Frame, app: &AppState, area: Rect) {
+    let [popup] = Layout::horizontal([Constraint::Percentage(70)]).flex(Flex::Center).areas(area);
+    let height = app.attached_files.len().min(5) as u16 + 5;
+    let [popup] = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center).areas(popup);
+
+    let input = app.attach_input.as_deref().unwrap_or_default();
+    let mut lines = vec![
+        Line::from(format!(" {}▌", input)),
+        Line::styled(
+            " Enter attach · Esc close · Del remove the last attached · dropping a file pastes its path",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ];
+    if !app.attached_files.is_empty() {
+        lines.push(Line::raw(""));
+    }
+    for source in app.attached_files.iter().rev().take(5) {
+        lines.push(Line::styled(format!("  📎 {}", source), Style::default().fg(Color::Gray)));
+    }
+    let prompt = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {}Attach a file path or link ", icon(app, "📎")))
+            .border_style(Style::default().fg(Color::Cyan)),
     );
+    frame.render_widget(Clear, popup);
+    frame.render_widget(prompt, popup);
+}
 
-    render_input_block(
-        frame,
-        "🟢 Solution",
-        &app.solution_input,
-        app.active_input_field == 2 && app.is_input_section_focused(),
-        app.active_input_field == 2 && app.is_editing(),
-        sections[2],
+/// Prompt for the title of a new Notion page and the page it goes under
+fn render_new_page_prompt(frame: &mut Frame, app: &AppState, area: Rect) {
+    let [popup] = Layout::horizontal([Constraint::Percentage(60)]).flex(Flex::Center).areas(area);
+    let [popup] = Layout::vertical([Constraint::Length(6)]).flex(Flex::Center).areas(popup);
+
+    let title = app.new_page.as_ref().map(|prompt| prompt.title.as_str()).unwrap_or_default();
+    let parent = app.new_page_parent().map(|page| page.title.as_str()).unwrap_or_default();
+    let lines = vec![
+        Line::from(format!(" {}▌", title)),
+        Line::from(vec![
+            Span::styled(" Under: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(parent.to_string(), Style::default().fg(Color::Cyan)),
+        ]),
+        Line::styled(" Enter create · ↑↓ change parent · Esc cancel", Style::default().fg(Color::DarkGray)),
+    ];
+    let prompt = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {}New page ", icon(app, "📄")))
+            .border_style(Style::default().fg(Color::Cyan)),
     );
+    frame.render_widget(Clear, popup);
+    frame.render_widget(prompt, popup);
+}
 
-    render_input_block(
-        frame,
-        "💻 Code (optional)",
-        &app.code_input,
-        app.active_input_field == 3 && app.is_input_section_focused(),
-        app.active_input_field == 3 && app.is_editing(),
-        sections[3],
+/// Modal Yes/No dialog for the action waiting on confirmation
+fn render_confirmation(frame: &mut Frame, app: &AppState, area: Rect) {
+    let Some(prompt) = app.confirmation_prompt() else {
+        return;
+    };
+    let width = (prompt.width() as u16 + 4).clamp(30, 60);
+    let [popup] = Layout::horizontal([Constraint::Length(width)]).flex(Flex::Center).areas(area);
+    let [popup] = Layout::vertical([Constraint::Length(7)]).flex(Flex::Center).areas(popup);
+
+    let button = |label: &str, selected: bool| {
+        let style = if selected {
+            Style::default().bg(Color::Rgb(45, 85, 155)).fg(Color::White).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        Span::styled(format!("  {}  ", label), style)
+    };
+    let text = Text::from(vec![
+        Line::from(prompt),
+        Line::default(),
+        Line::from(vec![button("Yes", app.confirm_yes), Span::raw("    "), button("No", !app.confirm_yes)]).centered(),
+    ]);
+    let dialog = Paragraph::new(text).wrap(Wrap { trim: true }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {}Confirm ", icon(app, "❓")))
+            .border_style(Style::default().fg(Color::Yellow)),
     );
+    frame.render_widget(Clear, popup);
+    frame.render_widget(dialog, popup);
+}
+
+/// Rows of the selected database, filtered by the typed query
+fn render_rows_screen(frame: &mut Frame, app: &AppState, area: Rect) {
+    let items: Vec<ListItem> = app
+        .row_matches
+        .iter()
+        .map(|&index| ListItem::new(format!(" {}", app.database_rows[index].title)))
+        .collect();
+    let empty = if app.row_query.is_empty() {
+        " This database has no rows"
+    } else {
+        " No matching rows (Enter while searching asks Notion)"
+    };
+    let list = if items.is_empty() {
+        List::new(vec![ListItem::new(empty).style(Style::default().fg(Color::DarkGray))])
+    } else {
+        List::new(items)
+    };
+
+    let source = match &app.row_pick {
+        RowPick::Target => app.get_selected_page().map(|p| p.title.clone()).unwrap_or_default(),
+        RowPick::Relation { property, .. } => format!("Link {}", property),
+    };
+    let mut title = format!(" {}{} ({}) ", icon(app, "📊"), source, app.row_matches.len());
+    if app.row_searching || !app.row_query.is_empty() {
+        let cursor = if app.row_searching { "▌" } else { "" };
+        title = format!("{}/{}{} ", title, app.row_query, cursor);
+    }
+    let list = list
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(Style::default().bg(Color::Rgb(45, 85, 155)).fg(Color::White))
+        .highlight_symbol("▶ ");
+
+    let mut state = ListState::default();
+    if !app.row_matches.is_empty() {
+        state.select(Some(app.selected_row_match));
+    }
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// Relation properties of the selected database and the pages new rows
+/// link to through each
+fn render_relations_screen(frame: &mut Frame, app: &AppState, area: Rect) {
+    let items: Vec<ListItem> = app
+        .relation_properties
+        .iter()
+        .map(|property| {
+            let linked = app.linked_pages(&property.name);
+            let value = if linked.is_empty() {
+                Span::styled("none", Style::default().fg(Color::DarkGray))
+            } else {
+                Span::raw(linked.iter().map(|p| p.title.as_str()).collect::<Vec<_>>().join(", "))
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!(" {:<16}", property.name), Style::default().fg(Color::Cyan)),
+                value,
+            ]))
+        })
+        .collect();
+
+    let database = app.get_selected_page().map(|p| p.title.as_str()).unwrap_or_default();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {}Relations for new rows in {} ", icon(app, "🔗"), database))
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(Style::default().bg(Color::Rgb(45, 85, 155)).fg(Color::White))
+        .highlight_symbol("▶ ");
+
+    let mut state = ListState::default();
+    state.select(Some(app.selected_relation));
+    frame.render_stateful_widget(list, area, &mut state);
 }
 
-/// Render a single input block
-fn render_input_block(
-    frame: &mut Frame,
-    title: &str,
-    content: &str,
-    is_focused: bool,
+/// Settings remembered for the selected page; unset rows show what the
+/// config supplies instead
+fn render_page_settings_screen(frame: &mut Frame, app: &AppState, area: Rect) {
+    let Some(page) = app.submission_target() else {
+        return;
+    };
+    let settings = app.selected_page_settings().cloned().unwrap_or_default();
+    let configured = app.config.template_for(&page.id);
+
+    let items: Vec<ListItem> = PageSetting::ALL
+        .iter()
+        .enumerate()
+        .map(|(idx, setting)| {
+            let value = match app.setting_input.as_deref() {
+                Some(input) if idx == app.selected_setting => Span::raw(format!("{}▌", input)),
+                _ => {
+                    let text = settings.text(*setting);
+                    if text.is_empty() {
+                        let fallback = match setting {
+                            PageSetting::Style => style_name(configured.style).to_string(),
+                            PageSetting::Position => position_name(configured.position).to_string(),
+                            PageSetting::Anchor => configured.anchor.clone().unwrap_or_else(|| "none".to_string()),
+                            PageSetting::CodeLanguage => AppState::DEFAULT_CODE_LANGUAGE.to_string(),
+                            PageSetting::Tags => "none".to_string(),
+                        };
+                        Span::styled(format!("{} (default)", fallback), Style::default().fg(Color::DarkGray))
+                    } else {
+                        Span::raw(text)
+                    }
+                }
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!(" {:<16}", setting.label()), Style::default().fg(Color::Cyan)),
+                value,
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {}Settings for {} ", icon(app, "🔧"), page.title))
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(Style::default().bg(Color::Rgb(45, 85, 155)).fg(Color::White))
+        .highlight_symbol("▶ ");
+
+    let mut state = ListState::default();
+    state.select(Some(app.selected_setting));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// Render entries read back from the selected page: titles on the left,
+/// the selected entry on the right (PgUp/PgDn scroll)
+fn render_entries_screen(frame: &mut Frame, app: &AppState, area: Rect) {
+    let layout = Layout::horizontal([
+        Constraint::Percentage(40),
+        Constraint::Percentage(60),
+    ])
+    .split(area);
+
+    let items: Vec<ListItem> = app
+        .page_entries
+        .iter()
+        .map(|item| ListItem::new(format!(" {}", item.title)))
+        .collect();
+    let list = if items.is_empty() {
+        List::new(vec![ListItem::new(" No FaultNote entries on this page").style(Style::default().fg(Color::DarkGray))])
+    } else {
+        List::new(items)
+    };
+    let page_title = app.submission_target().map(|p| p.title).unwrap_or_default();
+    let list = list
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {}{} ({}) ", icon(app, "📖"), page_title, app.page_entries.len()))
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(Style::default().bg(Color::Rgb(45, 85, 155)).fg(Color::White))
+        .highlight_symbol("▶ ");
+
+    let mut state = ListState::default();
+    if !app.page_entries.is_empty() {
+        state.select(Some(app.selected_page_entry));
+    }
+    frame.render_stateful_widget(list, layout[0], &mut state);
+
+    let lines = match app.selected_logged_entry() {
+        Some(item) => entry_lines(app, &item.entry),
+        None => vec![Line::raw("Entries logged with FaultNote appear here.")],
+    };
+    // Keep at least the last line on screen however far PgDn went
+    let scroll = app.entry_scroll.min(lines.len().saturating_sub(1) as u16);
+    let paragraph = Paragraph::new(Text::from(lines))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Entry ")
+                .border_style(Style::default().fg(Color::DarkGray)),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
+    frame.render_widget(paragraph, layout[1]);
+}
+
+/// Render the previewed page's content as read-only scrollable text
+fn render_preview_screen(frame: &mut Frame, app: &AppState, area: Rect) {
+    let lines: Vec<Line> = if app.page_preview.is_empty() {
+        vec![Line::styled("This page is empty.", Style::default().fg(Color::DarkGray))]
+    } else {
+        app.page_preview
+            .iter()
+            .map(|line| {
+                let style = match line.style {
+                    PreviewStyle::Heading => Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                    PreviewStyle::Text => Style::default(),
+                    PreviewStyle::Code => Style::default().fg(Color::Green),
+                    PreviewStyle::Quote => Style::default().fg(Color::Yellow),
+                    PreviewStyle::Muted => Style::default().fg(Color::DarkGray),
+                };
+                Line::styled(line.text.as_str(), style)
+            })
+            .collect()
+    };
+    let paragraph = Paragraph::new(Text::from(lines))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {}{} (read-only) ", icon(app, "📄"), app.preview_title))
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((app.preview_scroll, 0));
+    frame.render_widget(paragraph, area);
+}
+
+/// Timestamp of a history entry in local time, e.g. "2024-05-01 14:03"
+fn local_time(item: &HistoryEntry) -> String {
+    DateTime::parse_from_rfc3339(&item.submitted_at)
+        .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|_| item.submitted_at.clone())
+}
+
+/// "#3 · retry in 2m05s" style summary of a queued item's schedule
+fn retry_summary(item: &QueuedSubmission, now: u64) -> String {
+    match item.retry_in(now) {
+        Some(wait) if wait.is_zero() => format!("#{} · retrying…", item.attempts),
+        Some(wait) => format!("#{} · retry in {}", item.attempts, format_duration(wait.as_secs())),
+        None => format!("#{} · needs action", item.attempts),
+    }
+}
+
+/// Compact duration like "1h02m", "3m05s" or "45s"
+fn format_duration(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// First line of a field, for one-line summaries
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default()
+}
+
+/// Render the input sections on the right
+fn render_input_sections(frame: &mut Frame, app: &AppState, area: Rect) {
+    // The six text sections share the height; the tags take one line
+    let sections = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Length(3),
+    ])
+    .split(area);
+
+    let fields = InputField::ALL.map(|field| (field_title(app, field), app.field(field)));
+
+    // Render each input block from its cached layout
+    let mut layouts = app.field_layouts.borrow_mut();
+    let mut scroll = app.field_scroll.get();
+    let mut heights = app.field_heights.get();
+    for (idx, (title, content)) in fields.into_iter().enumerate() {
+        let is_focused = app.active_input_field == idx && app.is_input_section_focused();
+        let is_editing = app.active_input_field == idx && app.is_editing();
+        let highlight_diff = InputField::from_index(idx) == Some(InputField::Code) && app.is_diff();
+        heights[idx] = sections[idx].height.saturating_sub(2) as usize;
+        let text = field_text(&mut layouts[idx], &mut scroll[idx], content, is_editing, highlight_diff, sections[idx]);
+        render_input_block(frame, &title, text, scroll[idx], is_focused, is_editing, sections[idx]);
+    }
+    app.field_scroll.set(scroll);
+    app.field_heights.set(heights);
+    let mut areas = [Rect::default(); AppState::MAX_INPUTS];
+    areas.copy_from_slice(&sections[..AppState::MAX_INPUTS]);
+    app.field_areas.set(areas);
+}
+
+/// Title of a field's block. Titles come from the field schema so renamed
+/// labels match Notion.
+fn field_title(app: &AppState, field: InputField) -> String {
+    let labels = &app.config.template.labels;
+    let (emoji, label, hint) = match field {
+        InputField::Code if app.is_mermaid() => ("📈", "Mermaid diagram", Some("optional")),
+        InputField::Code if app.is_diff() => (field.emoji(), labels.label(field), Some("diff, optional")),
+        _ => (field.emoji(), labels.label(field), field.hint()),
+    };
+    match hint {
+        Some(hint) if field == InputField::Code && !app.is_mermaid() && !app.is_diff() => {
+            format!("{}{} ({}, {})", icon(app, emoji), label, app.code_language, hint)
+        }
+        Some(hint) => format!("{}{} ({})", icon(app, emoji), label, hint),
+        None => format!("{}{}", icon(app, emoji), label),
+    }
+}
+
+/// Zen mode: the active field alone in a centered column, the other
+/// fields dimmed to one line each around it and the status line below
+fn render_zen(frame: &mut Frame, app: &AppState, area: Rect) {
+    let [column] = Layout::horizontal([Constraint::Length(ZEN_WIDTH.min(area.width))])
+        .flex(Flex::Center)
+        .areas(area);
+    let active = app.active_input_field;
+    let before = active as u16;
+    let after = (AppState::MAX_INPUTS - active - 1) as u16;
+    let [above, field_area, below, status_area] = Layout::vertical([
+        Constraint::Length(before),
+        Constraint::Min(3),
+        Constraint::Length(after),
+        Constraint::Length(1),
+    ])
+    .areas(column);
+
+    // The other fields, one dim line each
+    let dim = Style::default().fg(Color::DarkGray);
+    let width = column.width as usize;
+    let summary = |field: InputField| {
+        let label = format!(" {}{}: ", icon(app, field.emoji()), app.config.template.labels.label(field));
+        let first = app.field(field).lines().next().map(|line| line.to_string()).unwrap_or_default();
+        Line::styled(truncate_to_width(&(label + &first), width).to_string(), dim)
+    };
+    let lines: Vec<Line> = InputField::ALL[..active].iter().map(|field| summary(*field)).collect();
+    frame.render_widget(Paragraph::new(lines), above);
+    let lines: Vec<Line> = InputField::ALL[active + 1..].iter().map(|field| summary(*field)).collect();
+    frame.render_widget(Paragraph::new(lines), below);
+
+    let field = app.active_field();
+    let is_editing = app.is_editing();
+    let mut layouts = app.field_layouts.borrow_mut();
+    let mut scroll = app.field_scroll.get();
+    let mut heights = app.field_heights.get();
+    heights[active] = field_area.height.saturating_sub(2) as usize;
+    let highlight_diff = field == InputField::Code && app.is_diff();
+    let text = field_text(&mut layouts[active], &mut scroll[active], app.field(field), is_editing, highlight_diff, field_area);
+    render_input_block(frame, &field_title(app, field), text, scroll[active], true, is_editing, field_area);
+    app.field_scroll.set(scroll);
+    app.field_heights.set(heights);
+    let mut areas = [Rect::default(); AppState::MAX_INPUTS];
+    areas[active] = field_area;
+    app.field_areas.set(areas);
+    // The page list and the activity log are hidden
+    app.page_list_area.set(Rect::default());
+    app.status_log_area.set(Rect::default());
+
+    let mut status = vec![Span::styled(format!(" {} Leave zen ", app.config.keys.label(Action::ToggleZen)), dim)];
+    if let Some(message) = &app.status_message {
+        status.push(Span::styled(format!(" {} ", message), Style::default().fg(status_color(message.kind))));
+    }
+    frame.render_widget(Paragraph::new(Line::from(status)), status_area);
+}
+
+/// Rows of a field up to the bottom of its view, re-wrapping only what
+/// changed since the last frame. While editing `scroll` moves to keep the
+/// cursor visible.
+fn field_text<'a>(
+    layout: &'a mut WrapCache,
+    scroll: &mut usize,
+    content: &TextBuffer,
     is_editing: bool,
+    highlight_diff: bool,
     area: Rect,
-) {
+) -> Text<'a> {
+    let width = area.width.saturating_sub(2) as usize;
+    let height = area.height.saturating_sub(2) as usize;
+    layout.update(content, width, is_editing);
+    *scroll = scroll_offset(*scroll, layout.cursor_row(), layout.row_count(), height);
+
+    // Color added/removed lines when the content is a diff
+    let lines: Vec<Line> = layout
+        .rows(0, *scroll + height)
+        .into_iter()
+        .map(|(source, row)| {
+            if highlight_diff {
+                Line::styled(row, diff_style(source))
+            } else {
+                Line::raw(row)
+            }
+        })
+        .collect();
+    Text::from(lines)
+}
+
+/// Render a single input block, scrolled down `scroll` rows
+fn render_input_block(frame: &mut Frame, title: &str, text: Text, scroll: usize, is_focused: bool, is_editing: bool, area: Rect) {
     // Determine styling based on state
     let (border_color, title_style) = if is_editing {
         (Color::Green, Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
@@ -184,47 +1441,168 @@ fn render_input_block(
         (Color::DarkGray, Style::default().fg(Color::Gray))
     };
 
-    // Show cursor indicator when editing
-    let display_content = if is_editing {
-        format!("{}▌", content) // Add cursor
-    } else {
-        content.to_string()
-    };
-
-    // Create paragraph with content
-    let paragraph = Paragraph::new(display_content)
+    // Content arrives pre-wrapped, so the paragraph does no wrapping itself
+    let paragraph = Paragraph::new(text)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .title(Span::styled(format!(" {} ", title), title_style))
                 .border_style(Style::default().fg(border_color)),
         )
-        .wrap(Wrap { trim: false })
-        .style(Style::default().fg(Color::White));
+        .style(Style::default().fg(Color::White))
+        .scroll((scroll.min(usize::from(u16::MAX)) as u16, 0));
 
     frame.render_widget(paragraph, area);
 }
 
+/// Style a line of a unified diff like `git diff --color`
+fn diff_style(line: &str) -> Style {
+    match classify_diff_line(line) {
+        DiffLine::Added => Style::default().fg(Color::Green),
+        DiffLine::Removed => Style::default().fg(Color::Red),
+        DiffLine::Hunk => Style::default().fg(Color::Cyan),
+        DiffLine::Meta => Style::default().add_modifier(Modifier::BOLD),
+        DiffLine::Context => Style::default(),
+    }
+}
+
+/// Command bar entries for screens without configurable keys
+fn fixed_commands(commands: &[(&str, &'static str)]) -> Vec<(String, &'static str)> {
+    commands.iter().map(|(key, desc)| (key.to_string(), *desc)).collect()
+}
+
 /// Render the command bar at the bottom
 fn render_command_bar(frame: &mut Frame, app: &AppState, area: Rect) {
-    let commands = if app.is_editing() {
-        // Editing mode commands
+    let keys = &app.config.keys;
+    let commands: Vec<(String, &str)> = if app.pending_action.is_some() {
+        fixed_commands(&[("y", "Yes"), ("n/Esc", "No"), ("←→", "Choose"), ("Enter", "Confirm")])
+    } else if app.is_queue_screen() {
+        // Queue inspector commands
+        fixed_commands(&[
+            ("Esc", "Back"),
+            ("↑↓", "Navigate"),
+            ("r", "Retry"),
+            ("R", "Retry All"),
+            ("e", "Edit"),
+            ("p", "Change Page"),
+            ("x", "Discard"),
+        ])
+    } else if app.is_history_screen() {
+        if app.history_searching {
+            fixed_commands(&[("Type", "Filter"), ("Enter/Esc", "Done")])
+        } else {
+            let mut commands = vec![("Esc", "Back"), ("↑↓", "Navigate"), ("/", "Search"), ("e", "Copy to Editor")];
+            if app.selected_history_entry().is_some_and(|item| item.row_id.is_some()) {
+                commands.push(("s", "Advance Status"));
+            }
+            fixed_commands(&commands)
+        }
+    } else if app.is_entries_screen() {
+        fixed_commands(&[
+            ("Esc", "Back"),
+            ("↑↓", "Navigate"),
+            ("PgUp/PgDn", "Scroll"),
+            ("r", "Reload"),
+            ("e", "Copy to Editor"),
+        ])
+    } else if app.is_preview_screen() {
+        fixed_commands(&[
+            ("Esc", "Back"),
+            ("↑↓", "Scroll"),
+            ("PgUp/PgDn", "Page"),
+            ("Home/End", "Top/Bottom"),
+            ("r", "Reload"),
+        ])
+    } else if app.is_page_settings_screen() {
+        if app.setting_input.is_some() {
+            fixed_commands(&[("Type", "Value"), ("Enter", "Save"), ("Esc", "Cancel")])
+        } else {
+            fixed_commands(&[("Esc", "Back"), ("↑↓", "Navigate"), ("Enter", "Change"), ("x", "Reset")])
+        }
+    } else if app.is_rows_screen() {
+        if app.row_searching {
+            fixed_commands(&[("Type", "Filter"), ("Enter", "Search Notion"), ("Esc", "Done")])
+        } else {
+            let mut commands = vec![("Esc", "Back"), ("↑↓", "Navigate"), ("/", "Search"), ("Enter", "Choose")];
+            if app.row_pick == RowPick::Target {
+                commands.push(("n", "New Rows"));
+            }
+            commands.push(("r", "Reload"));
+            fixed_commands(&commands)
+        }
+    } else if app.is_relations_screen() {
+        fixed_commands(&[("Esc", "Back"), ("↑↓", "Navigate"), ("Enter", "Link Page"), ("x", "Clear")])
+    } else if app.new_page.is_some() {
+        fixed_commands(&[("Type", "Title"), ("↑↓", "Parent"), ("Enter", "Create"), ("Esc", "Cancel")])
+    } else if app.language_picking || app.template_picking {
+        fixed_commands(&[("Type", "Filter"), ("↑↓", "Navigate"), ("Enter", "Use"), ("Esc", "Cancel")])
+    } else if app.page_filtering {
+        fixed_commands(&[("Type", "Filter"), ("↑↓", "Navigate"), ("Enter", "Jump"), ("Esc", "Cancel")])
+    } else if app.is_editing() {
+        // Editing mode commands, with the configured keys
         vec![
-            ("Esc", "Exit Edit"),
-            ("Tab", "Next Field"),
-            ("Enter", "New Line"),
-            ("↑↓", "Switch Field"),
+            (keys.label(Action::ExitEdit), "Exit Edit"),
+            (keys.label(Action::NextField), "Next Field"),
+            ("Enter".to_string(), "New Line"),
+            (keys.label(Action::FieldUp) + &keys.label(Action::FieldDown), "Switch Field"),
+            (keys.label(Action::Paste), "Paste"),
+            (keys.label(Action::UndoEdit) + "/" + &keys.label(Action::RedoEdit), "Undo/Redo"),
         ]
     } else {
-        // Normal mode commands
-        vec![
-            ("q", "Quit"),
-            ("Tab", "Switch Focus"),
-            ("↑↓", "Navigate"),
-            ("e/i", "Edit"),
-            ("Enter", "Submit"),
-            ("c", "Clear"),
-        ]
+        // Normal mode commands, with the configured keys
+        let mut commands = vec![
+            (keys.label(Action::Quit), "Quit"),
+            (keys.label(Action::ToggleFocus), "Switch Focus"),
+            (keys.label(Action::Up) + &keys.label(Action::Down), "Navigate"),
+            (keys.label(Action::FilterPages), "Filter Pages"),
+            (keys.label(Action::CheckPage), "Check Page"),
+            (keys.label(Action::NewPage), "New Page"),
+            (keys.label(Action::RefreshPages), "Refresh"),
+            (keys.label(Action::EditMode), "Edit"),
+            (keys.label(Action::Submit), "Submit"),
+            (keys.label(Action::ToggleMermaid), "Mermaid"),
+            (keys.label(Action::ToggleDiff), "Diff"),
+            (keys.label(Action::PickLanguage), "Language"),
+            (keys.label(Action::PickTemplate), "Template"),
+            (keys.label(Action::CycleSeverity), "Severity"),
+            (keys.label(Action::OpenQueue), "Queue"),
+            (keys.label(Action::ViewPage), "View Page"),
+            (keys.label(Action::PreviewPage), "Preview Page"),
+            (keys.label(Action::PageSettings), "Page Settings"),
+            (keys.label(Action::History), "History"),
+            (keys.label(Action::CopyField) + "/" + &keys.label(Action::CopyEntry), "Copy Field/Entry"),
+            (keys.label(Action::ExternalEditor), "$EDITOR"),
+            (keys.label(Action::AddSubEntry), "Add Sub-entry"),
+            (keys.label(Action::Attach), "Attach"),
+            (keys.label(Action::StatusLog), "Activity"),
+            (keys.label(Action::ClearInputs), "Clear"),
+        ];
+        if !app.links().is_empty() {
+            commands.push((keys.label(Action::NextLink) + &keys.label(Action::PreviousLink), "Select Link"));
+            commands.push((keys.label(Action::OpenLink) + "/" + &keys.label(Action::CopyLink), "Open/Copy Link"));
+        }
+        if !app.trace_ids().is_empty() {
+            commands.push((keys.label(Action::FindTrace), "Seen Trace?"));
+        }
+        if app.get_selected_page().is_some_and(|p| p.is_database()) {
+            commands.push((keys.label(Action::PickRow), "Pick Row"));
+        }
+        if app.submission_target().is_some_and(|p| p.is_database()) {
+            commands.push((keys.label(Action::Relations), "Relations"));
+            let status = match app.fault_status {
+                FaultStatus::Open => "Status: Open",
+                FaultStatus::Investigating => "Status: Investigating",
+                FaultStatus::Fixed => "Status: Fixed",
+            };
+            commands.push((keys.label(Action::CycleStatus), status));
+        }
+        if app.can_undo() {
+            commands.push((keys.label(Action::Undo), "Undo"));
+        }
+        if !app.queue.is_empty() {
+            commands.push((keys.label(Action::RetryQueue), "Retry Queue"));
+        }
+        commands
     };
 
     // Build command spans with styling
@@ -241,14 +1619,17 @@ fn render_command_bar(frame: &mut Frame, app: &AppState, area: Rect) {
 
     let command_line = Line::from(spans);
 
-    let paragraph = Paragraph::new(command_line)
-        .block(
+    let paragraph = if app.inline {
+        Paragraph::new(command_line)
+    } else {
+        Paragraph::new(command_line).block(
             Block::default()
                 .borders(Borders::ALL)
                 .title(" Commands ")
                 .border_style(Style::default().fg(Color::DarkGray)),
         )
-        .centered();
+    }
+    .centered();
 
     frame.render_widget(paragraph, area);
 }
@@ -0,0 +1,121 @@
+// Template variables such as `{date}` or `{hostname}` in emitted text
+use std::collections::BTreeMap;
+use std::env;
+use std::path::Path;
+
+use chrono::Local;
+
+use crate::app::FaultLogEntry;
+use crate::notion::locale::Locale;
+
+/// Values substituted for `{name}` placeholders; `{{` and `}}` stand for
+/// literal braces and unknown names are left as written
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Vars {
+    values: BTreeMap<String, String>,
+}
+
+impl Vars {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, name: &str, value: impl Into<String>) {
+        self.values.insert(name.to_string(), value.into());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    /// Variables for submitting `entry` now: `{date}`, `{time}`,
+    /// `{hostname}`, `{project}`, `{error}`, `{status}`, `{severity}`,
+    /// `{tags}`, `{language}`, `{branch}` and `{commit}`, with the date and
+    /// time written for `locale`
+    pub fn for_entry(entry: &FaultLogEntry, project: Option<&str>, locale: Locale) -> Self {
+        let now = Local::now();
+        let mut vars = Self::new();
+        vars.set("date", now.format(locale.date_format()).to_string());
+        vars.set("time", now.format(locale.time_format()).to_string());
+        vars.set("hostname", hostname());
+        vars.set("project", project.map_or_else(detect_project, str::to_string));
+        vars.set("error", entry.error.trim());
+        vars.set("status", entry.status.name());
+        vars.set("severity", entry.severity.map_or("", |severity| severity.name()));
+        vars.set("tags", entry.tags.join(", "));
+        vars.set("language", entry.language.clone().unwrap_or_default());
+        let git = entry.git.clone().unwrap_or_default();
+        vars.set("branch", git.branch.unwrap_or_default());
+        vars.set("commit", git.sha.unwrap_or_default());
+        vars
+    }
+
+    /// Replace every known `{name}` in `text`
+    pub fn render(&self, text: &str) -> String {
+        let mut rendered = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(pos) = rest.find(['{', '}']) {
+            rendered.push_str(&rest[..pos]);
+            rest = &rest[pos..];
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                rendered.push_str(&rest[..1]);
+                rest = &rest[2..];
+                continue;
+            }
+            let value = rest
+                .strip_prefix('{')
+                .and_then(|inner| inner.split_once('}'))
+                .and_then(|(name, tail)| self.get(name.trim()).map(|value| (value, tail)));
+            match value {
+                Some((value, tail)) => {
+                    rendered.push_str(value);
+                    rest = tail;
+                }
+                None => {
+                    rendered.push_str(&rest[..1]);
+                    rest = &rest[1..];
+                }
+            }
+        }
+        rendered.push_str(rest);
+        rendered
+    }
+}
+
+/// This machine's name, or "unknown"
+pub fn hostname() -> String {
+    #[cfg(unix)]
+    {
+        let mut buf = [0u8; 256];
+        // SAFETY: the buffer is valid for its whole length and gethostname
+        // writes at most that many bytes
+        if unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) } == 0 {
+            let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+            if len > 0 {
+                return String::from_utf8_lossy(&buf[..len]).into_owned();
+            }
+        }
+    }
+    env::var("COMPUTERNAME")
+        .or_else(|_| env::var("HOSTNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// Name of the git repository around the working directory, else the
+/// directory's own name
+pub fn detect_project() -> String {
+    let Ok(cwd) = env::current_dir() else {
+        return String::new();
+    };
+    let root = cwd
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .unwrap_or(&cwd);
+    dir_name(root)
+}
+
+fn dir_name(dir: &Path) -> String {
+    dir.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
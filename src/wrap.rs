@@ -0,0 +1,263 @@
+// Cached soft-wrapping of field contents, so a keystroke only re-wraps
+// the logical lines it touched instead of the whole field
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::buffer::TextBuffer;
+
+/// Cursor glyph drawn after the content while editing
+pub const CURSOR: char = '▌';
+
+/// One logical (newline-separated) line and its wrapped rows
+#[derive(Debug, Default)]
+struct CachedLine {
+    source: String,
+    rows: Vec<String>,
+}
+
+/// Wrapped layout of a single field, kept between frames
+#[derive(Debug, Default)]
+pub struct WrapCache {
+    width: usize,
+    lines: Vec<CachedLine>,
+    row_count: usize,
+    /// Logical lines re-wrapped by the last update
+    rewrapped: usize,
+    /// Row holding the cursor, when one is shown
+    cursor_row: Option<usize>,
+    /// Display column of the cursor within its row
+    cursor_column: usize,
+    /// Column Up/Down aim for and the cursor they left, so a trip
+    /// through a short row comes back to the same column
+    goal: Option<(usize, usize)>,
+}
+
+impl WrapCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bring the layout up to date with `buffer` at `width` columns.
+    /// Lines whose text is unchanged keep their rows; a width change
+    /// re-wraps everything.
+    pub fn update(&mut self, buffer: &TextBuffer, width: usize, show_cursor: bool) {
+        let cursor = show_cursor.then(|| buffer.cursor_position());
+        if width != self.width {
+            self.width = width;
+            self.lines.clear();
+        }
+
+        self.rewrapped = 0;
+        self.cursor_row = None;
+        self.cursor_column = 0;
+        let mut count = 0;
+        let last = buffer.len_lines().saturating_sub(1);
+        for (index, line) in buffer.lines().enumerate() {
+            // Only the cursor line needs a fresh string to compare against
+            let with_cursor = match cursor {
+                Some((cursor_line, column)) if cursor_line == index => {
+                    let mut text = line.to_string();
+                    let byte = text.char_indices().nth(column).map_or(text.len(), |(idx, _)| idx);
+                    text.insert(byte, CURSOR);
+                    Some(text)
+                }
+                _ => None,
+            };
+            let unchanged = match (self.lines.get(index), &with_cursor) {
+                (Some(cached), Some(text)) => cached.source == *text,
+                (Some(cached), None) => line == cached.source.as_str(),
+                (None, _) => false,
+            };
+
+            if !unchanged {
+                let source = with_cursor.unwrap_or_else(|| line.to_string());
+                let rows = wrap_line(&source, width);
+                let cached = CachedLine { source, rows };
+                match self.lines.get_mut(index) {
+                    Some(slot) => *slot = cached,
+                    None => self.lines.push(cached),
+                }
+                self.rewrapped += 1;
+            }
+            if let Some((_, column)) = cursor.filter(|(cursor_line, _)| *cursor_line == index) {
+                let (rows, within) = spans(&self.lines[index].rows, Some(column));
+                let (start, text) = &rows[within];
+                self.cursor_row = Some(count + within);
+                self.cursor_column = text.chars().take(column - start).collect::<String>().width();
+            }
+            count += self.lines[index].rows.len();
+        }
+        self.lines.truncate(last + 1);
+        self.row_count = count;
+    }
+
+    /// Total wrapped rows
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// Row the cursor is drawn on, if shown
+    pub fn cursor_row(&self) -> Option<usize> {
+        self.cursor_row
+    }
+
+    /// Display column of the cursor within its row, once `update` showed it
+    pub fn cursor_column(&self) -> usize {
+        self.cursor_column
+    }
+
+    /// Char index one wrapped row above (`down` false) or below the
+    /// cursor in `buffer`, as near its column as the row allows. None on
+    /// the first or last row.
+    pub fn vertical_target(&mut self, buffer: &TextBuffer, down: bool) -> Option<usize> {
+        self.update(buffer, self.width, true);
+        let (line, column) = buffer.cursor_position();
+        let goal = match self.goal {
+            Some((cursor, goal)) if cursor == buffer.cursor() => goal,
+            _ => self.cursor_column,
+        };
+        let (rows, within) = spans(&self.lines[line].rows, Some(column));
+        let (line, rows, row) = match (down, within) {
+            (false, 0) if line == 0 => return None,
+            (false, 0) => {
+                let (rows, _) = spans(&self.lines[line - 1].rows, None);
+                let last = rows.len() - 1;
+                (line - 1, rows, last)
+            }
+            (false, within) => (line, rows, within - 1),
+            (true, within) if within + 1 < rows.len() => (line, rows, within + 1),
+            (true, _) if line + 1 >= self.lines.len() => return None,
+            (true, _) => (line + 1, spans(&self.lines[line + 1].rows, None).0, 0),
+        };
+        let (start, text) = &rows[row];
+        // The end of a row that wraps is the start of the next one, so the
+        // cursor stops before the row's last grapheme
+        let last = row + 1 == rows.len();
+        let offset = column_offset(text, goal, last);
+        let target = buffer.line_start(line) + start + offset;
+        self.goal = Some((target, goal));
+        Some(target)
+    }
+
+    /// Logical lines re-wrapped by the last `update`
+    pub fn rewrapped(&self) -> usize {
+        self.rewrapped
+    }
+
+    /// Up to `height` rows starting at row `start`, each paired with
+    /// the logical line it belongs to (for per-line styling)
+    pub fn rows(&self, start: usize, height: usize) -> Vec<(&str, &str)> {
+        self.lines
+            .iter()
+            .flat_map(|line| line.rows.iter().map(move |row| (line.source.as_str(), row.as_str())))
+            .skip(start)
+            .take(height)
+            .collect()
+    }
+}
+
+/// Rows of a wrapped line as (first column, text) in chars of the
+/// buffer's line, leaving out the cursor glyph drawn at `cursor`, and
+/// the row the glyph is on
+fn spans(rows: &[String], cursor: Option<usize>) -> (Vec<(usize, String)>, usize) {
+    let mut spans = Vec::with_capacity(rows.len());
+    let mut cursor_row = 0;
+    let mut glyph = cursor;
+    let mut column = 0;
+    for (index, row) in rows.iter().enumerate() {
+        let start = column;
+        let mut text = String::with_capacity(row.len());
+        for c in row.chars() {
+            if c == CURSOR && glyph == Some(column) {
+                glyph = None;
+                cursor_row = index;
+                continue;
+            }
+            text.push(c);
+            column += 1;
+        }
+        spans.push((start, text));
+    }
+    (spans, cursor_row)
+}
+
+/// Chars into `text` before the grapheme covering display column `goal`;
+/// short of the last grapheme unless `to_end`
+fn column_offset(text: &str, goal: usize, to_end: bool) -> usize {
+    let mut width = 0;
+    let mut offset = 0;
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+    let count = if to_end { graphemes.len() } else { graphemes.len().saturating_sub(1) };
+    for grapheme in &graphemes[..count] {
+        width += grapheme.width();
+        if width > goal {
+            break;
+        }
+        offset += grapheme.chars().count();
+    }
+    offset
+}
+
+/// First row to show of `row_count` rows in a view `height` rows tall,
+/// starting from the current `offset`: moved just enough to keep the
+/// cursor row in view, and never past the last screenful
+pub fn scroll_offset(offset: usize, cursor_row: Option<usize>, row_count: usize, height: usize) -> usize {
+    let mut offset = offset;
+    if let Some(row) = cursor_row {
+        if row < offset {
+            offset = row;
+        } else if height > 0 && row >= offset + height {
+            offset = row + 1 - height;
+        }
+    }
+    offset.min(row_count.saturating_sub(height))
+}
+
+/// Wrap one line to `width` columns, breaking after whitespace where
+/// possible and mid-word otherwise. Graphemes stay whole and count with
+/// their display width, so wide CJK glyphs and emoji sequences line up.
+pub fn wrap_line(line: &str, width: usize) -> Vec<String> {
+    if width == 0 {
+        return vec![line.to_string()];
+    }
+
+    let mut rows = Vec::new();
+    let mut row = String::new();
+    let mut row_width = 0;
+    // Byte offset in `row` just after the last whitespace
+    let mut break_at: Option<usize> = None;
+
+    for grapheme in line.graphemes(true) {
+        let grapheme_width = grapheme.width();
+        while row_width + grapheme_width > width && !row.is_empty() {
+            match break_at.take() {
+                Some(idx) if idx < row.len() => {
+                    let rest = row.split_off(idx);
+                    rows.push(std::mem::replace(&mut row, rest));
+                }
+                _ => rows.push(std::mem::take(&mut row)),
+            }
+            row_width = row.width();
+        }
+        row.push_str(grapheme);
+        row_width += grapheme_width;
+        if grapheme.chars().all(char::is_whitespace) {
+            break_at = Some(row.len());
+        }
+    }
+    rows.push(row);
+    rows
+}
+
+/// The longest start of `text` that fits in `width` columns, cut between
+/// graphemes
+pub fn truncate_to_width(text: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (start, grapheme) in text.grapheme_indices(true) {
+        used += grapheme.width();
+        if used > width {
+            return &text[..start];
+        }
+    }
+    text
+}
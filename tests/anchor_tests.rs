@@ -0,0 +1,47 @@
+// Tests for the anchor heading entries are inserted under
+
+use faultnote::config::Config;
+use faultnote::notion::anchor::Anchor;
+use faultnote::notion::template::HeadingLevel;
+use serde_json::json;
+
+#[test]
+fn test_parse_anchor() {
+    assert_eq!(Anchor::parse("## Fault Log").level, HeadingLevel::H2);
+    assert_eq!(Anchor::parse("# Incidents").level, HeadingLevel::H1);
+    let anchor = Anchor::parse("  ### Bugs ");
+    assert_eq!(anchor.level, HeadingLevel::H3);
+    assert_eq!(anchor.text, "Bugs");
+    assert_eq!(Anchor::parse("Fault Log").text, "Fault Log");
+}
+
+#[test]
+fn test_find_and_build_anchor() {
+    let anchor = Anchor::parse("## Fault Log");
+    let blocks = vec![
+        json!({ "id": "intro", "type": "paragraph", "paragraph": { "rich_text": [{ "plain_text": "Fault Log" }] } }),
+        json!({ "id": "anchor", "type": "heading_1", "heading_1": { "rich_text": [{ "plain_text": "Fault Log " }] } }),
+    ];
+    assert_eq!(anchor.find(&blocks).as_deref(), Some("anchor"));
+    assert_eq!(anchor.find(&blocks[..1]), None);
+
+    let block = anchor.block();
+    assert_eq!(block["type"], "heading_2");
+    assert_eq!(block["heading_2"]["rich_text"][0]["text"]["content"], "Fault Log");
+}
+
+#[test]
+fn test_anchor_per_page() {
+    let config = Config::from_toml(
+        r###"
+        [template]
+        anchor = "## Fault Log"
+
+        [pages.page-1]
+        anchor = "## Incidents"
+        "###,
+    )
+    .unwrap();
+    assert_eq!(config.template_for("other").anchor.as_deref(), Some("## Fault Log"));
+    assert_eq!(config.template_for("page-1").anchor.as_deref(), Some("## Incidents"));
+}
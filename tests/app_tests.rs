@@ -1,6 +1,7 @@
 // Tests for AppState
 
-use faultnote::app::{AppState, FocusArea, FaultLogEntry, InputMode, PageInfo};
+use faultnote::app::{AppState, FaultLogEntry, FaultStatus, FocusArea, InputMode, PageInfo};
+use faultnote::fields::InputField;
 
 #[test]
 fn test_new_app_state() {
@@ -29,9 +30,9 @@ fn test_toggle_focus() {
 fn test_page_navigation() {
     let mut app = AppState::new();
     app.set_pages(vec![
-        PageInfo { id: "1".to_string(), title: "Page 1".to_string() },
-        PageInfo { id: "2".to_string(), title: "Page 2".to_string() },
-        PageInfo { id: "3".to_string(), title: "Page 3".to_string() },
+        PageInfo { id: "1".to_string(), title: "Page 1".to_string(), ..Default::default() },
+        PageInfo { id: "2".to_string(), title: "Page 2".to_string(), ..Default::default() },
+        PageInfo { id: "3".to_string(), title: "Page 3".to_string(), ..Default::default() },
     ]);
 
     assert_eq!(app.selected_page_index, 0);
@@ -49,6 +50,37 @@ fn test_page_navigation() {
     assert_eq!(app.selected_page_index, 2);
 }
 
+#[test]
+fn test_checked_pages_are_the_submission_targets() {
+    use faultnote::app::PendingAction;
+
+    let mut app = AppState::new();
+    app.set_pages(vec![
+        PageInfo { id: "1".to_string(), title: "Backend".to_string(), ..Default::default() },
+        PageInfo { id: "2".to_string(), title: "Incidents".to_string(), ..Default::default() },
+        PageInfo { id: "3".to_string(), title: "Frontend".to_string(), ..Default::default() },
+    ]);
+    let ids = |app: &AppState| app.submission_targets().into_iter().map(|page| page.id).collect::<Vec<_>>();
+    assert_eq!(ids(&app), ["1"]);
+
+    app.next_page();
+    app.toggle_page_check();
+    assert_eq!(app.status_message.as_deref(), Some("Checked Incidents; entries go to the checked page"));
+    assert_eq!(ids(&app), ["2"]);
+    app.previous_page();
+    app.toggle_page_check();
+    assert_eq!(ids(&app), ["2", "1"]);
+    app.request_confirmation(PendingAction::Submit);
+    assert_eq!(app.confirmation_prompt().as_deref(), Some("Submit this entry to 2 pages: Incidents, Backend?"));
+
+    // Pages that disappear from the list lose their check
+    app.replace_pages(vec![PageInfo { id: "1".to_string(), title: "Backend".to_string(), ..Default::default() }]);
+    assert_eq!(app.checked_pages, ["1"]);
+    app.toggle_page_check();
+    assert!(app.checked_pages.is_empty());
+    assert_eq!(ids(&app), ["1"]);
+}
+
 #[test]
 fn test_empty_page_navigation() {
     let mut app = AppState::new();
@@ -65,13 +97,13 @@ fn test_input_navigation() {
     app.next_input();
     assert_eq!(app.active_input_field, 1);
 
-    app.next_input();
-    app.next_input();
-    app.next_input();
+    for _ in 0..6 {
+        app.next_input();
+    }
     assert_eq!(app.active_input_field, 0); // Wrapped
 
     app.previous_input();
-    assert_eq!(app.active_input_field, 3);
+    assert_eq!(app.active_input_field, 6);
 }
 
 #[test]
@@ -111,36 +143,38 @@ fn test_can_submit() {
     let mut app = AppState::new();
     assert!(!app.can_submit());
 
-    app.set_pages(vec![PageInfo { id: "1".to_string(), title: "Test".to_string() }]);
+    app.set_pages(vec![PageInfo { id: "1".to_string(), title: "Test".to_string(), ..Default::default() }]);
     assert!(!app.can_submit());
 
-    app.error_input = "Error".to_string();
+    app.error_input = "Error".into();
     assert!(!app.can_submit());
 
-    app.problem_input = "Problem".to_string();
+    app.problem_input = "Problem".into();
     assert!(!app.can_submit());
 
-    app.solution_input = "Solution".to_string();
+    app.solution_input = "Solution".into();
     assert!(app.can_submit());
 }
 
 #[test]
 fn test_whitespace_only_not_submittable() {
     let mut app = AppState::new();
-    app.set_pages(vec![PageInfo { id: "1".to_string(), title: "Test".to_string() }]);
-    app.error_input = "   ".to_string();
-    app.problem_input = "Problem".to_string();
-    app.solution_input = "Solution".to_string();
+    app.set_pages(vec![PageInfo { id: "1".to_string(), title: "Test".to_string(), ..Default::default() }]);
+    app.error_input = "   ".into();
+    app.problem_input = "Problem".into();
+    app.solution_input = "Solution".into();
     assert!(!app.can_submit());
 }
 
 #[test]
 fn test_clear_inputs() {
     let mut app = AppState::new();
-    app.error_input = "Error".to_string();
-    app.problem_input = "Problem".to_string();
-    app.solution_input = "Solution".to_string();
-    app.code_input = "Code".to_string();
+    app.error_input = "Error".into();
+    app.problem_input = "Problem".into();
+    app.solution_input = "Solution".into();
+    app.code_input = "Code".into();
+    app.verification_input = "Verify".into();
+    app.context_input = "os: linux".into();
     app.active_input_field = 2;
 
     app.clear_inputs();
@@ -149,6 +183,8 @@ fn test_clear_inputs() {
     assert!(app.problem_input.is_empty());
     assert!(app.solution_input.is_empty());
     assert!(app.code_input.is_empty());
+    assert!(app.verification_input.is_empty());
+    assert!(app.context_input.is_empty());
     assert_eq!(app.active_input_field, 0);
 }
 
@@ -173,7 +209,7 @@ fn test_status_messages() {
     assert!(app.status_message.is_none());
 
     app.set_status("Test");
-    assert_eq!(app.status_message, Some("Test".to_string()));
+    assert_eq!(app.status_message.as_deref(), Some("Test"));
 
     app.set_success("OK");
     assert!(app.status_message.as_ref().unwrap().contains("✓"));
@@ -185,29 +221,68 @@ fn test_status_messages() {
     assert!(app.status_message.is_none());
 }
 
+#[test]
+fn test_status_messages_fade_by_kind() {
+    use std::time::{Duration, Instant};
+
+    use faultnote::app::{StatusKind, StatusMessage};
+
+    let mut app = AppState::new();
+    let later = |seconds| Instant::now() + Duration::from_secs(seconds);
+
+    app.set_success("Submitted");
+    app.expire_status(later(1));
+    assert_eq!(app.status_message.as_deref(), Some("✓ Submitted"));
+    app.expire_status(later(4));
+    assert!(app.status_message.is_none());
+
+    app.set_status("Severity: High");
+    app.expire_status(later(4));
+    assert!(app.status_message.is_some());
+    app.expire_status(later(7));
+    assert!(app.status_message.is_none());
+
+    // Errors wait to be dismissed; loading messages spin until replaced
+    app.set_error("Notion returned 502");
+    app.expire_status(later(3600));
+    assert_eq!(app.status_message.as_ref().map(|message| message.kind), Some(StatusKind::Error));
+    app.set_loading("Refreshing pages…");
+    app.expire_status(later(3600));
+    assert!(app.spinner().is_some());
+    app.set_success("Loaded 2 pages");
+    assert!(app.spinner().is_none());
+
+    assert!(!StatusMessage::new(StatusKind::Success, "Saved").is_expired(Instant::now()));
+}
+
 #[test]
 fn test_submission_data() {
     let mut app = AppState::new();
     assert!(app.get_submission_data().is_none());
 
-    app.set_pages(vec![PageInfo { id: "page-id".to_string(), title: "Test".to_string() }]);
-    app.error_input = "Error".to_string();
-    app.problem_input = "Problem".to_string();
-    app.solution_input = "Solution".to_string();
-    app.code_input = "Code".to_string();
+    app.set_pages(vec![PageInfo { id: "page-id".to_string(), title: "Test".to_string(), ..Default::default() }]);
+    app.error_input = "Error".into();
+    app.problem_input = "Problem".into();
+    app.solution_input = "Solution".into();
+    app.code_input = "Code".into();
 
     let (page_id, entry) = app.get_submission_data().unwrap();
     assert_eq!(page_id, "page-id");
     assert_eq!(entry.error, "Error");
     assert_eq!(entry.code, Some("Code".to_string()));
+    assert_eq!(entry.verification, None);
+
+    app.verification_input = "Check staging".into();
+    let (_, entry) = app.get_submission_data().unwrap();
+    assert_eq!(entry.verification, Some("Check staging".to_string()));
 }
 
 #[test]
 fn test_handle_up_down() {
     let mut app = AppState::new();
     app.set_pages(vec![
-        PageInfo { id: "1".to_string(), title: "P1".to_string() },
-        PageInfo { id: "2".to_string(), title: "P2".to_string() },
+        PageInfo { id: "1".to_string(), title: "P1".to_string(), ..Default::default() },
+        PageInfo { id: "2".to_string(), title: "P2".to_string(), ..Default::default() },
     ]);
 
     app.handle_down();
@@ -229,7 +304,463 @@ fn test_fault_log_entry() {
         problem: "P".to_string(),
         solution: "S".to_string(),
         code: Some("C".to_string()),
+        ..Default::default()
     };
     assert_eq!(entry.error, "E");
     assert_eq!(entry.code, Some("C".to_string()));
 }
+
+#[test]
+fn test_toggle_mermaid() {
+    let mut app = AppState::new();
+    assert!(!app.is_mermaid());
+
+    app.toggle_mermaid();
+    assert!(app.is_mermaid());
+
+    app.set_pages(vec![PageInfo { id: "1".to_string(), title: "Test".to_string(), ..Default::default() }]);
+    app.error_input = "E".into();
+    app.problem_input = "P".into();
+    app.solution_input = "S".into();
+    let (_, entry) = app.get_submission_data().unwrap();
+    assert_eq!(entry.language.as_deref(), Some("mermaid"));
+
+    app.toggle_mermaid();
+    assert_eq!(app.code_language, "rust");
+}
+
+#[test]
+fn test_diff_language_detection() {
+    let mut app = AppState::new();
+    app.code_input = "diff --git a/x b/x\n-old\n+new".into();
+    assert!(app.is_diff());
+    assert_eq!(app.effective_code_language(), "diff");
+
+    // An explicit language wins over detection
+    app.toggle_mermaid();
+    assert!(!app.is_diff());
+
+    app.toggle_mermaid();
+    app.code_input = "let x = 1;".into();
+    assert!(!app.is_diff());
+    app.toggle_diff();
+    assert!(app.is_diff());
+}
+
+#[test]
+fn test_undo_window() {
+    use faultnote::app::LastSubmission;
+    use std::time::{Duration, Instant};
+
+    let mut app = AppState::new();
+    assert!(!app.can_undo());
+
+    let submitted_at = Instant::now();
+    app.last_submission = Some(LastSubmission {
+        page_id: "page".to_string(),
+        entry: FaultLogEntry::default(),
+        block_ids: vec!["block".to_string()],
+        token: "default".to_string(),
+        submitted_at,
+        history_id: None,
+    });
+    assert!(app.can_undo_at(submitted_at + Duration::from_secs(29)));
+    assert!(!app.can_undo_at(submitted_at + AppState::UNDO_WINDOW + Duration::from_secs(1)));
+}
+
+#[test]
+fn test_page_list_window() {
+    let mut app = AppState::new();
+    app.set_pages(
+        (1..=3400)
+            .map(|i| PageInfo { id: i.to_string(), title: format!("Page {}", i), ..Default::default() })
+            .collect(),
+    );
+    app.page_list_height.set(20);
+
+    assert_eq!(app.visible_page_range(20), 0..20);
+    assert_eq!(app.page_position(), "1/3400");
+
+    app.page_down();
+    assert_eq!(app.selected_page_index, 20);
+    assert_eq!(app.visible_page_range(20), 20..40);
+
+    app.select_last_page();
+    assert_eq!(app.page_position(), "3400/3400");
+    assert_eq!(app.visible_page_range(20), 3380..3400);
+
+    app.page_down();
+    assert_eq!(app.selected_page_index, 3399);
+
+    app.select_first_page();
+    app.page_up();
+    assert_eq!(app.selected_page_index, 0);
+}
+
+#[test]
+fn test_edit_in_middle_of_field() {
+    let mut app = AppState::new();
+    app.toggle_focus();
+    app.active_input_field = 1;
+    app.problem_input = "Conection refused".into();
+
+    app.problem_input.set_cursor(3);
+    app.add_char('n');
+    assert_eq!(app.problem_input, "Connection refused");
+
+    app.move_cursor_line_end();
+    app.delete_char();
+    app.move_cursor_line_start();
+    app.delete_char_forward();
+    assert_eq!(app.problem_input, "onnection refuse");
+}
+
+#[test]
+fn test_fault_status_follows_entry() {
+    let mut app = AppState::new();
+    app.notion_pages = vec![Default::default()];
+    app.error_input = "err".into();
+    app.problem_input = "prob".into();
+    app.solution_input = "fix".into();
+    app.fault_status = app.fault_status.next();
+    let (_, entry) = app.get_submission_data().unwrap();
+    assert_eq!(entry.status, FaultStatus::Investigating);
+
+    // A new entry starts Open; reloading one restores its status
+    app.clear_inputs();
+    assert_eq!(app.fault_status, FaultStatus::Open);
+    app.load_entry("", &entry);
+    assert_eq!(app.fault_status, FaultStatus::Investigating);
+}
+
+#[test]
+fn test_paste_multiline() {
+    let mut app = AppState::new();
+    app.error_input = "Error: ".into();
+    app.error_input.set_cursor(7);
+    app.paste("panicked at src/main.rs:10\r\n  0: backtrace\r\n  1: main");
+    assert_eq!(app.error_input, "Error: panicked at src/main.rs:10\n  0: backtrace\n  1: main");
+    assert_eq!(app.error_input.cursor_position(), (2, 9));
+    assert_eq!(app.status_message.as_deref(), Some("Pasted 3 lines"));
+}
+
+#[test]
+fn test_apply_external_edit_can_be_undone() {
+    let mut app = AppState::new();
+    app.active_input_field = InputField::Solution.index();
+    app.solution_input = "Restart".into();
+    app.apply_external_edit("Restart the pool\nthen drain the queue");
+    assert_eq!(app.solution_input, "Restart the pool\nthen drain the queue");
+    assert_eq!(app.status_message.as_deref(), Some("✓ Loaded Solution from the editor"));
+    app.undo_edit();
+    assert_eq!(app.solution_input, "Restart");
+
+    app.apply_external_edit("Restart");
+    assert_eq!(app.status_message.as_deref(), Some("No changes made in the editor"));
+}
+
+#[test]
+fn test_page_filter() {
+    let mut app = AppState::new();
+    app.set_pages(
+        ["Backend Errors", "Frontend bugs", "Bug Tracker"]
+            .iter()
+            .enumerate()
+            .map(|(i, title)| PageInfo { id: i.to_string(), title: title.to_string(), ..Default::default() })
+            .collect(),
+    );
+    app.toggle_focus();
+
+    app.start_page_filter();
+    assert!(app.is_page_list_focused());
+    app.page_filter.push_str("bug");
+    app.refresh_page_filter();
+    assert_eq!(app.page_position(), "1/2");
+    assert_eq!(app.notion_pages[app.listed_page(0)].title, "Bug Tracker");
+
+    app.next_match();
+    app.accept_page_filter();
+    assert!(!app.page_filtering);
+    assert_eq!(app.selected_page_index, 1);
+    assert_eq!(app.page_position(), "2/3");
+
+    // Cancelling keeps the previous selection
+    app.start_page_filter();
+    app.page_filter.push_str("zzz");
+    app.refresh_page_filter();
+    assert_eq!(app.listed_page_count(), 0);
+    app.cancel_page_filter();
+    assert_eq!(app.selected_page_index, 1);
+}
+
+#[test]
+fn test_undo_redo_by_word() {
+    let mut app = AppState::new();
+    app.toggle_focus();
+    app.enter_edit_mode();
+    for c in "disk full now".chars() {
+        app.add_char(c);
+    }
+    app.delete_char();
+    app.delete_char();
+
+    // The run of deletes goes first, then one word at a time
+    app.undo_edit();
+    assert_eq!(app.error_input.to_string(), "disk full now");
+    app.undo_edit();
+    assert_eq!(app.error_input.to_string(), "disk full ");
+    app.undo_edit();
+    assert_eq!(app.error_input.to_string(), "disk ");
+
+    app.redo_edit();
+    assert_eq!(app.error_input.to_string(), "disk full ");
+
+    // A new change drops what could be redone
+    app.add_char('!');
+    app.redo_edit();
+    assert_eq!(app.error_input.to_string(), "disk full !");
+    assert_eq!(app.status_message.as_deref(), Some("Nothing to redo"));
+}
+
+#[test]
+fn test_undo_is_per_field() {
+    let mut app = AppState::new();
+    app.toggle_focus();
+    app.enter_edit_mode();
+    app.paste("first\nsecond");
+    app.next_input();
+    app.add_char('x');
+
+    app.undo_edit();
+    assert!(app.problem_input.is_empty());
+    app.undo_edit();
+    assert_eq!(app.status_message.as_deref(), Some("Nothing to undo"));
+    assert_eq!(app.error_input.to_string(), "first\nsecond");
+
+    app.previous_input();
+    app.undo_edit();
+    assert!(app.error_input.is_empty());
+}
+
+#[test]
+fn test_replace_pages_keeps_selection() {
+    let page = |id: &str| PageInfo {
+        id: id.to_string(),
+        title: id.to_string(),
+        ..Default::default()
+    };
+    let mut app = AppState::new();
+    app.set_pages(vec![page("a"), page("b"), page("c")]);
+    app.selected_page_index = 1;
+
+    app.replace_pages(vec![page("new"), page("a"), page("b")]);
+    assert_eq!(app.get_selected_page().map(|p| p.id.as_str()), Some("b"));
+
+    // Gone from the list: back to the top
+    app.replace_pages(vec![page("a")]);
+    assert_eq!(app.selected_page_index, 0);
+}
+
+#[test]
+fn test_add_sub_entry_stacks_drafts_under_the_next_entry() {
+    let mut app = AppState::new();
+    app.set_pages(vec![PageInfo {
+        id: "p".to_string(),
+        title: "Backend".to_string(),
+        kind: Default::default(),
+        parent: None,
+    }]);
+    app.add_sub_entry();
+    assert!(app.sub_entries.is_empty());
+
+    app.error_input = "Timeout in payments".into();
+    app.code_input = "at pay.rs:3".into();
+    app.add_sub_entry();
+    app.error_input = "Timeout in orders".into();
+    app.add_sub_entry();
+    assert_eq!(app.sub_entries.len(), 2);
+    assert!(app.error_input.is_blank() && app.code_input.is_blank());
+    assert!(app.has_input());
+
+    app.error_input = "Checkout outage".into();
+    let entry = app.current_entry();
+    assert_eq!(entry.sub_entries[0].code.as_deref(), Some("at pay.rs:3"));
+    assert_eq!(entry.sub_entries[1].error, "Timeout in orders");
+    let single = FaultLogEntry {
+        sub_entries: Vec::new(),
+        ..entry.clone()
+    };
+    assert_ne!(entry.content_hash(), single.content_hash());
+
+    app.request_confirmation(faultnote::app::PendingAction::Submit);
+    assert_eq!(app.confirmation_prompt().unwrap(), "Submit this entry and its 2 sub-entries to Backend?");
+
+    // A failed submission puts the whole group back
+    app.clear_inputs();
+    assert!(app.sub_entries.is_empty());
+    app.load_entry("p", &entry);
+    assert_eq!(app.sub_entries.len(), 2);
+}
+
+#[test]
+fn test_status_messages_are_kept_in_the_log() {
+    let mut app = AppState::new();
+    app.set_error("Notion returned 502");
+    app.set_status("Loading pages…");
+    app.set_status("Loading pages…");
+    app.set_success("Submitted");
+    let log: Vec<(&str, usize)> = app.status_log.iter().map(|record| (record.message.as_str(), record.repeats)).collect();
+    assert_eq!(log, [("✗ Notion returned 502", 1), ("Loading pages…", 2), ("✓ Submitted", 1)]);
+
+    // Scrolling stops at the oldest record and stays put as new ones arrive
+    app.toggle_status_log();
+    app.scroll_status_log(10);
+    assert_eq!(app.status_log_scroll, 2);
+    app.set_status("Connected");
+    assert_eq!(app.status_log_scroll, 3);
+    app.toggle_status_log();
+    app.toggle_status_log();
+    assert_eq!(app.status_log_scroll, 0);
+
+    for i in 0..AppState::STATUS_LOG_LIMIT {
+        app.set_status(format!("message {}", i));
+    }
+    assert_eq!(app.status_log.len(), AppState::STATUS_LOG_LIMIT);
+    assert_eq!(app.status_log[0].message, "message 0");
+}
+
+#[test]
+fn test_api_calls_go_to_the_activity_log_only() {
+    use std::time::Duration;
+
+    use faultnote::app::{LogSource, StatusKind};
+    use faultnote::notion::activity::ApiCall;
+    use reqwest::Method;
+
+    let mut app = AppState::new();
+    app.set_status("Loading pages…");
+    let call = ApiCall {
+        method: Method::PATCH,
+        path: "/v1/blocks/01234567…/children".to_string(),
+        status: None,
+        attempts: 1,
+        elapsed: Duration::from_millis(30_000),
+    };
+    app.log_api_call(&call);
+    app.log_api_call(&ApiCall { status: Some(400), ..call.clone() });
+
+    let log: Vec<(LogSource, StatusKind, &str)> = app
+        .status_log
+        .iter()
+        .map(|record| (record.source, record.kind, record.message.as_str()))
+        .collect();
+    assert_eq!(
+        log,
+        [
+            (LogSource::App, StatusKind::Info, "Loading pages…"),
+            (LogSource::Api, StatusKind::Error, "PATCH /v1/blocks/01234567…/children → no response in 30000ms"),
+            (LogSource::Api, StatusKind::Error, "PATCH /v1/blocks/01234567…/children → 400 in 30000ms"),
+        ]
+    );
+    // The status line keeps what the app last said
+    assert_eq!(app.status_message.as_ref().unwrap().text, "Loading pages…");
+}
+
+#[test]
+fn test_new_page_prompt() {
+    use faultnote::app::TargetKind;
+
+    let mut app = AppState::new();
+    app.open_new_page_prompt();
+    assert!(app.new_page.is_none());
+
+    app.set_pages(vec![
+        PageInfo { id: "db".to_string(), title: "Bugs".to_string(), kind: TargetKind::Database, ..Default::default() },
+        PageInfo { id: "1".to_string(), title: "Backend".to_string(), ..Default::default() },
+        PageInfo { id: "2".to_string(), title: "Frontend".to_string(), ..Default::default() },
+    ]);
+    // A database cannot hold the page, so the first page is the parent
+    app.open_new_page_prompt();
+    assert_eq!(app.new_page_parent().unwrap().id, "1");
+    app.cycle_new_page_parent(true);
+    assert_eq!(app.new_page_parent().unwrap().id, "2");
+    app.cycle_new_page_parent(true);
+    assert_eq!(app.new_page_parent().unwrap().id, "1");
+    app.cycle_new_page_parent(false);
+    assert_eq!(app.new_page_parent().unwrap().id, "2");
+    // A reload that reorders the list keeps the chosen parent
+    app.notion_pages.reverse();
+    assert_eq!(app.new_page_parent().unwrap().id, "2");
+    app.notion_pages.reverse();
+
+    // The created page lands under its parent, selected
+    app.current_focus = FocusArea::InputSection;
+    app.insert_created_page(PageInfo {
+        id: "3".to_string(),
+        title: "Web Vitals".to_string(),
+        parent: Some("1".to_string()),
+        ..Default::default()
+    });
+    assert!(app.new_page.is_none());
+    assert_eq!(app.current_focus, FocusArea::PageList);
+    let titles: Vec<&str> = app.notion_pages.iter().map(|page| page.title.as_str()).collect();
+    assert_eq!(titles, ["Bugs", "Backend", "Web Vitals", "Frontend"]);
+    assert_eq!(app.page_depth(2), 1);
+    assert_eq!(app.submission_target().unwrap().id, "3");
+}
+
+#[test]
+fn test_wanted_page_is_selected_once_pages_load() {
+    let mut app = AppState::new();
+    app.wanted_page = Some("bug tracker".to_string());
+    app.select_wanted_page();
+    assert!(app.wanted_page.is_some());
+
+    app.set_pages(vec![
+        PageInfo { id: "1".to_string(), title: "Backend".to_string(), ..Default::default() },
+        PageInfo { id: "2".to_string(), title: "Bug Tracker".to_string(), ..Default::default() },
+    ]);
+    app.select_wanted_page();
+    assert!(app.wanted_page.is_none());
+    assert_eq!(app.get_selected_page().unwrap().id, "2");
+
+    app.wanted_page = Some("Frontend".to_string());
+    app.select_wanted_page();
+    assert_eq!(app.status_message.as_deref(), Some("✗ No page matches \"Frontend\""));
+    assert_eq!(app.get_selected_page().unwrap().id, "2");
+}
+
+#[test]
+fn test_page_tree_folds_with_left_and_right() {
+    let page = |id: &str, parent: Option<&str>| PageInfo {
+        id: id.to_string(),
+        title: "Errors".to_string(),
+        parent: parent.map(str::to_string),
+        ..Default::default()
+    };
+    let mut app = AppState::new();
+    app.set_pages(vec![page("a", None), page("b", None), page("a1", Some("a")), page("a2", Some("a")), page("b1", Some("b"))]);
+    let ids = |app: &AppState| (0..app.listed_page_count()).map(|row| app.notion_pages[app.listed_page(row)].id.clone()).collect::<Vec<_>>();
+    assert_eq!(ids(&app), ["a", "a1", "a2", "b", "b1"]);
+
+    // Left folds, then climbs to the parent; Right unfolds, then descends
+    app.collapse_page();
+    assert_eq!(ids(&app), ["a", "b", "b1"]);
+    app.next_page();
+    assert_eq!(app.get_selected_page().unwrap().id, "b");
+    app.previous_page();
+    app.expand_page();
+    assert_eq!(ids(&app), ["a", "a1", "a2", "b", "b1"]);
+    app.expand_page();
+    assert_eq!(app.get_selected_page().unwrap().id, "a1");
+    app.collapse_page();
+    assert_eq!(app.get_selected_page().unwrap().id, "a");
+
+    // Selecting a folded page unfolds its parent again
+    app.collapse_page();
+    app.wanted_page = Some("a2".to_string());
+    app.select_wanted_page();
+    assert_eq!(app.get_selected_page().unwrap().id, "a2");
+    assert_eq!(app.listed_selection(), 2);
+    assert!(app.collapsed_pages.is_empty());
+}
@@ -0,0 +1,67 @@
+// Tests for attaching files and links to entries
+
+use std::env;
+use std::fs;
+
+use faultnote::app::AppState;
+use faultnote::attachments::{AttachSource, content_type, display_name, is_image, resolve};
+
+#[test]
+fn test_resolve_files_and_links() {
+    let dir = env::temp_dir().join(format!("faultnote-attach-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let screenshot = dir.join("login error.png");
+    fs::write(&screenshot, b"png").unwrap();
+
+    assert_eq!(resolve(&format!("'{}'", screenshot.display())), Ok(AttachSource::File(screenshot.clone())));
+    let escaped = screenshot.display().to_string().replace(' ', "\\ ");
+    assert_eq!(resolve(&escaped), Ok(AttachSource::File(screenshot.clone())));
+    assert_eq!(
+        resolve(" https://ci.example.com/logs/812.txt "),
+        Ok(AttachSource::Link("https://ci.example.com/logs/812.txt".to_string()))
+    );
+    assert!(resolve(&dir.display().to_string()).unwrap_err().ends_with("is a directory"));
+    assert!(resolve(&dir.join("missing.log").display().to_string()).unwrap_err().ends_with("not found"));
+    assert!(resolve("  ").is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_names_and_types() {
+    assert_eq!(display_name("/tmp/shots/login.png"), "login.png");
+    assert_eq!(display_name("https://ci.example.com/logs/812.txt?token=x"), "812.txt");
+    assert_eq!(content_type("trace.LOG"), "text/plain");
+    assert_eq!(content_type("report.pdf"), "application/pdf");
+    assert!(is_image("Screen Shot.JPG"));
+    assert!(!is_image("core.dump"));
+}
+
+#[test]
+fn test_attach_prompt_adds_files_to_the_entry() {
+    let path = env::temp_dir().join(format!("faultnote-attach-app-{}.log", std::process::id()));
+    fs::write(&path, "panic at main.rs:3").unwrap();
+    let mut app = AppState::new();
+
+    app.open_attach_prompt();
+    app.attach_input = Some("/no/such/file.log".to_string());
+    app.commit_attach_input();
+    assert!(app.attach_input.is_some());
+    assert!(app.attached_files.is_empty());
+
+    app.attach_input = Some(path.display().to_string());
+    app.commit_attach_input();
+    assert!(app.attach_input.is_none());
+    assert_eq!(app.current_entry().files, [path.display().to_string()]);
+    assert!(app.has_input());
+
+    // Sub-entries leave the attachments with the summary
+    app.error_input = "Timeout".into();
+    app.add_sub_entry();
+    assert_eq!(app.attached_files.len(), 1);
+    assert!(app.sub_entries[0].files.is_empty());
+
+    app.remove_last_attachment();
+    assert!(app.attached_files.is_empty());
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,44 @@
+// Tests for the audit log
+
+use std::env;
+use std::fs;
+
+use faultnote::audit::{AuditAction, AuditLog, AuditRecord};
+use faultnote::notion::client::{created_block_ids, sha256_hex};
+use serde_json::json;
+
+#[test]
+fn test_audit_log_appends_and_exports() {
+    let path = env::temp_dir().join(format!("faultnote-audit-{}.jsonl", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let log = AuditLog::new(path.clone());
+
+    let mut first = AuditRecord::now(AuditAction::AppendBlocks, "abc");
+    first.page_id = Some("page".to_string());
+    first.created_ids = vec!["block-1".to_string()];
+    log.append(&first).unwrap();
+    log.append(&AuditRecord::now(AuditAction::UploadFile, "def")).unwrap();
+
+    let records = log.read_all().unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0], first);
+    assert_eq!(records[1].action, AuditAction::UploadFile);
+
+    let exported: serde_json::Value = serde_json::from_str(&log.export_json().unwrap()).unwrap();
+    assert_eq!(exported[0]["action"], "append_blocks");
+    assert_eq!(exported[0]["created_ids"][0], "block-1");
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_created_block_ids_and_hash() {
+    let response = json!({ "object": "list", "results": [{ "id": "a" }, { "id": "b" }] });
+    assert_eq!(created_block_ids(&response), vec!["a", "b"]);
+    assert!(created_block_ids(&json!({})).is_empty());
+
+    assert_eq!(
+        sha256_hex(b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+}
@@ -0,0 +1,69 @@
+// Tests for backup and restore
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use faultnote::backup::{create_backup, restore_backup};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("faultnote-backup-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_backup_round_trip() {
+    let root = temp_dir("roundtrip");
+    let config = root.join("config");
+    let data = root.join("data");
+    fs::create_dir_all(data.join("drafts")).unwrap();
+    fs::create_dir_all(&config).unwrap();
+    fs::write(config.join("config.toml"), "[template]\n").unwrap();
+    fs::write(data.join("queue.json"), "[]").unwrap();
+    fs::write(data.join("drafts").join("a.json"), "{}").unwrap();
+
+    let archive = root.join("backup.tar.gz");
+    assert_eq!(create_backup(&config, &data, &archive).unwrap(), 3);
+
+    // Restoring over existing state needs --force
+    assert!(restore_backup(&archive, &config, &data, false).is_err());
+
+    let new_config = root.join("new-config");
+    let new_data = root.join("new-data");
+    assert_eq!(restore_backup(&archive, &new_config, &new_data, false).unwrap(), 3);
+    assert_eq!(fs::read_to_string(new_config.join("config.toml")).unwrap(), "[template]\n");
+    assert_eq!(fs::read_to_string(new_data.join("drafts").join("a.json")).unwrap(), "{}");
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_restore_refuses_links() {
+    let root = temp_dir("links");
+    fs::create_dir_all(&root).unwrap();
+    let archive = root.join("backup.tar.gz");
+    let file = fs::File::create(&archive).unwrap();
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(file, flate2::Compression::default()));
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Symlink);
+    header.set_size(0);
+    header.set_cksum();
+    builder.append_link(&mut header, "data/queue.json", "/etc/passwd").unwrap();
+    builder.into_inner().unwrap().finish().unwrap();
+
+    let (config, data) = (root.join("config"), root.join("data"));
+    let err = restore_backup(&archive, &config, &data, false).unwrap_err();
+    assert!(err.contains("Unsupported entry"), "{}", err);
+    assert!(!data.join("queue.json").exists());
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_backup_of_missing_dirs_is_empty() {
+    let root = temp_dir("empty");
+    fs::create_dir_all(&root).unwrap();
+    let archive = root.join("backup.tar.gz");
+    assert_eq!(create_backup(&root.join("nope"), &root.join("nada"), &archive).unwrap(), 0);
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,116 @@
+// Tests for the rope-backed text buffer
+
+use faultnote::buffer::{TextBuffer, pop_grapheme};
+
+#[test]
+fn test_edit_in_the_middle() {
+    let mut buffer = TextBuffer::from("hello world");
+    buffer.insert(5, ",");
+    buffer.insert_char(12, '!');
+    assert_eq!(buffer, "hello, world!");
+
+    buffer.remove(0, 7);
+    assert_eq!(buffer, "world!");
+    assert_eq!(buffer.pop(), Some('!'));
+    assert_eq!(buffer.to_string(), "world");
+}
+
+#[test]
+fn test_lines() {
+    let buffer = TextBuffer::from("one\ntwo\n");
+    assert_eq!(buffer.len_lines(), 3);
+    assert_eq!(buffer.line(1), "two");
+    let lines: Vec<String> = buffer.lines().map(|line| line.to_string()).collect();
+    assert_eq!(lines, vec!["one", "two", ""]);
+}
+
+#[test]
+fn test_blank_and_empty() {
+    let mut buffer = TextBuffer::new();
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.pop(), None);
+
+    buffer.push(' ');
+    buffer.push('\n');
+    assert!(!buffer.is_empty());
+    assert!(buffer.is_blank());
+
+    buffer.clear();
+    assert_eq!(buffer.len_chars(), 0);
+}
+
+#[test]
+fn test_cursor_editing() {
+    let mut buffer = TextBuffer::from("helo\nworld");
+    assert_eq!(buffer.cursor(), 10);
+    assert_eq!(buffer.cursor_position(), (1, 5));
+
+    buffer.set_cursor(3);
+    buffer.insert_at_cursor('l');
+    assert_eq!(buffer, "hello\nworld");
+    assert_eq!(buffer.cursor_position(), (0, 4));
+
+    buffer.move_line_end();
+    buffer.delete_at_cursor();
+    assert_eq!(buffer, "helloworld");
+
+    buffer.move_line_start();
+    assert_eq!(buffer.cursor(), 0);
+    buffer.delete_before_cursor();
+    buffer.move_left();
+    buffer.move_right();
+    buffer.delete_before_cursor();
+    assert_eq!(buffer, "elloworld");
+}
+
+#[test]
+fn test_editing_keeps_graphemes_whole() {
+    // A family emoji (joined by zero-width joiners) and an e with a
+    // combining accent are one character each on screen
+    let mut buffer = TextBuffer::from("a👨‍👩‍👧e\u{301}\nb");
+    buffer.move_line_start();
+    buffer.move_left();
+    assert_eq!(buffer.cursor_position(), (0, 8));
+    buffer.delete_before_cursor();
+    assert_eq!(buffer, "a👨‍👩‍👧\nb");
+
+    buffer.move_left();
+    assert_eq!(buffer.cursor(), 1);
+    buffer.delete_at_cursor();
+    assert_eq!(buffer, "a\nb");
+
+    buffer.move_right();
+    buffer.delete_before_cursor();
+    assert_eq!(buffer, "ab");
+}
+
+#[test]
+fn test_graphemes_wider_than_the_segmented_window() {
+    // An e carrying forty accents is still one grapheme
+    let heavy = format!("e{}", "\u{301}".repeat(40));
+    let mut buffer = TextBuffer::from(format!("{}{}x", "a".repeat(100), heavy).as_str());
+    buffer.move_left();
+    buffer.move_left();
+    assert_eq!(buffer.cursor(), 100);
+    buffer.move_right();
+    assert_eq!(buffer.cursor(), 141);
+
+    // Flags pair regional indicators counted from the start of the run,
+    // however far back it begins
+    let flags = "🇩🇪".repeat(20);
+    let mut buffer = TextBuffer::from(format!("{}🇫", flags).as_str());
+    buffer.move_left();
+    assert_eq!(buffer.cursor(), 40);
+    buffer.move_left();
+    assert_eq!(buffer.cursor(), 38);
+}
+
+#[test]
+fn test_pop_grapheme() {
+    let mut filter = "db 👍🏽".to_string();
+    pop_grapheme(&mut filter);
+    assert_eq!(filter, "db ");
+    let mut empty = String::new();
+    pop_grapheme(&mut empty);
+    assert_eq!(empty, "");
+}
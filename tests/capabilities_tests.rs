@@ -0,0 +1,44 @@
+// Tests for integration capability introspection
+
+use faultnote::notion::capabilities::{parse_bot_info, probe_allows, Capabilities};
+use reqwest::StatusCode;
+use serde_json::json;
+
+#[test]
+fn test_parse_bot_info() {
+    let user = json!({
+        "object": "user",
+        "name": "FaultNote",
+        "type": "bot",
+        "bot": { "workspace_name": "Acme" }
+    });
+    assert_eq!(
+        parse_bot_info(&user),
+        (Some("FaultNote".to_string()), Some("Acme".to_string()))
+    );
+    assert_eq!(parse_bot_info(&json!({})), (None, None));
+}
+
+#[test]
+fn test_probe_status() {
+    assert!(probe_allows(StatusCode::OK));
+    assert!(probe_allows(StatusCode::BAD_REQUEST));
+    assert!(probe_allows(StatusCode::TOO_MANY_REQUESTS));
+    // Missing capabilities and pages not shared with the integration
+    assert!(!probe_allows(StatusCode::FORBIDDEN));
+    assert!(!probe_allows(StatusCode::NOT_FOUND));
+    assert!(!probe_allows(StatusCode::UNAUTHORIZED));
+}
+
+#[test]
+fn test_submit_blocker() {
+    let mut capabilities = Capabilities {
+        read_content: true,
+        insert_content: true,
+        ..Default::default()
+    };
+    assert!(capabilities.submit_blocker().is_none());
+
+    capabilities.insert_content = false;
+    assert!(capabilities.submit_blocker().unwrap().contains("Insert content"));
+}
@@ -0,0 +1,51 @@
+// Tests for the quick-capture window
+
+use std::path::Path;
+
+use faultnote::app::{AppState, FocusArea};
+use faultnote::capture::{CaptureConfig, DEFAULT_HOTKEY, window_command};
+use faultnote::config::Config;
+use faultnote::fields::InputField;
+
+#[test]
+fn test_capture_config() {
+    let config = Config::from_toml("[capture]\nterminal = \"wezterm start --\"\nhotkey = \" Ctrl+Shift+F \"\n").unwrap();
+    assert_eq!(config.capture.terminal.as_deref(), Some("wezterm start --"));
+    assert_eq!(config.capture.hotkey(), "Ctrl+Shift+F");
+    assert_eq!(Config::default().capture.hotkey(), DEFAULT_HOTKEY);
+    assert!(Config::from_toml("[capture]\nshell = \"zsh\"\n").is_err());
+}
+
+#[test]
+fn test_window_command() {
+    let config = CaptureConfig {
+        terminal: Some("alacritty --class faultnote -e".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        window_command(&config, Path::new("/usr/bin/faultnote")),
+        ["alacritty", "--class", "faultnote", "-e", "/usr/bin/faultnote", "--capture"]
+    );
+    // Without a configured terminal the platform's is used
+    let command = window_command(&CaptureConfig::default(), Path::new("/usr/bin/faultnote"));
+    assert!(command.last().unwrap().contains("--capture"), "{:?}", command);
+}
+
+#[cfg(not(feature = "hotkey"))]
+#[test]
+fn test_daemon_needs_feature() {
+    let err = faultnote::capture::run_daemon(&CaptureConfig::default(), DEFAULT_HOTKEY).unwrap_err();
+    assert!(err.contains("faultnote popup"), "{}", err);
+}
+
+#[test]
+fn test_start_capture() {
+    let mut app = AppState::new();
+    app.current_focus = FocusArea::PageList;
+    app.active_input_field = InputField::Solution.index();
+    app.start_capture();
+    assert!(app.capture);
+    assert_eq!(app.current_focus, FocusArea::InputSection);
+    assert_eq!(app.active_field(), InputField::Error);
+    assert!(app.is_editing());
+}
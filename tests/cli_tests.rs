@@ -0,0 +1,227 @@
+// Tests for command-line parsing and machine-readable subcommand output
+
+use clap::Parser;
+use faultnote::app::{PageInfo, TargetKind};
+use faultnote::cli::{Cli, Command, OutputFormat, error_json, submit_report};
+use faultnote::failure::{Failure, FailureClass};
+use faultnote::notion::client::NotionError;
+use faultnote::submit::{Submitted, notion_url};
+use serde_json::Value;
+
+fn submitted(kind: TargetKind) -> Submitted {
+    Submitted {
+        page: PageInfo {
+            id: "1a2b3c4d-0000-0000-0000-000000000001".to_string(),
+            title: "Backend Errors".to_string(),
+            kind,
+            parent: None,
+        },
+        block_ids: vec!["5e6f7a8b-0000-0000-0000-000000000002".to_string()],
+    }
+}
+
+#[test]
+fn test_format_is_global_and_defaults_to_text() {
+    let cli = Cli::try_parse_from(["faultnote", "config", "path"]).unwrap();
+    assert_eq!(cli.format, OutputFormat::Text);
+
+    let cli = Cli::try_parse_from(["faultnote", "submit", "--format", "json", "--error", "e", "--problem", "p", "--solution", "s"])
+        .unwrap();
+    assert_eq!(cli.format, OutputFormat::Json);
+    assert!(matches!(cli.command, Some(Command::Submit(_))));
+
+    assert!(Cli::try_parse_from(["faultnote", "--format", "yaml", "config", "path"]).is_err());
+}
+
+#[test]
+fn test_submit_report_json() {
+    let report = submit_report(&submitted(TargetKind::Page));
+    assert_eq!(report.render(OutputFormat::Text).unwrap(), "Logged to Backend Errors");
+
+    let json: Value = serde_json::from_str(&report.render(OutputFormat::Json).unwrap()).unwrap();
+    assert_eq!(json["ok"], true);
+    assert_eq!(json["page_title"], "Backend Errors");
+    assert_eq!(json["database"], false);
+    assert_eq!(json["block_ids"][0], "5e6f7a8b-0000-0000-0000-000000000002");
+    assert_eq!(
+        json["url"],
+        "https://www.notion.so/1a2b3c4d000000000000000000000001#5e6f7a8b000000000000000000000002"
+    );
+}
+
+#[test]
+fn test_submit_report_links_database_rows() {
+    let json: Value = serde_json::from_str(&submit_report(&submitted(TargetKind::Database)).render(OutputFormat::Json).unwrap())
+        .unwrap();
+    assert_eq!(json["database"], true);
+    assert_eq!(json["url"], "https://www.notion.so/5e6f7a8b000000000000000000000002");
+}
+
+#[test]
+fn test_error_json() {
+    let failure = Failure::validation("No page or database matches \"x\"");
+    let json: Value = serde_json::from_str(&error_json(&failure)).unwrap();
+    assert_eq!(json["ok"], false);
+    assert_eq!(json["error"], "No page or database matches \"x\"");
+    assert_eq!(json["class"], "validation");
+    assert_eq!(json["exit_code"], 3);
+    assert_eq!(notion_url("ab-cd", None), "https://www.notion.so/abcd");
+}
+
+#[test]
+fn test_exit_codes_are_distinct() {
+    let mut codes: Vec<i32> = FailureClass::ALL.iter().map(|class| class.exit_code()).collect();
+    codes.sort();
+    codes.dedup();
+    assert_eq!(codes.len(), FailureClass::ALL.len());
+    // 0 is success and 2 is clap's usage error
+    assert!(!codes.contains(&0) && !codes.contains(&2));
+
+    assert_eq!(Failure::from("disk full".to_string()).class, FailureClass::General);
+}
+
+#[test]
+fn test_http_failures_are_classified() {
+    assert_eq!(FailureClass::for_http_status(None), FailureClass::Network);
+    assert_eq!(FailureClass::for_http_status(Some(429)), FailureClass::Network);
+    assert_eq!(FailureClass::for_http_status(Some(502)), FailureClass::Network);
+    assert_eq!(FailureClass::for_http_status(Some(401)), FailureClass::Auth);
+    assert_eq!(FailureClass::for_http_status(Some(404)), FailureClass::Auth);
+    assert_eq!(FailureClass::for_http_status(Some(400)), FailureClass::Validation);
+    assert_eq!(
+        FailureClass::for_request(&NotionError::from_response(403, "{}")),
+        FailureClass::Auth
+    );
+}
+
+#[test]
+fn test_queue_flush_output_flags() {
+    use faultnote::cli::QueueCommand;
+
+    let cli = Cli::try_parse_from(["faultnote", "queue", "flush", "--porcelain"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Queue {
+            action: QueueCommand::Flush { quiet: false, porcelain: true }
+        })
+    ));
+    assert!(Cli::try_parse_from(["faultnote", "queue", "flush", "-q"]).is_ok());
+    assert!(Cli::try_parse_from(["faultnote", "queue", "flush", "--quiet", "--porcelain"]).is_err());
+}
+
+#[tokio::test]
+async fn test_porcelain_refuses_json_output() {
+    let cli = Cli::try_parse_from(["faultnote", "queue", "flush", "--porcelain", "--format", "json"]).unwrap();
+    let err = faultnote::cli::run(cli.command.unwrap(), cli.format).await.unwrap_err();
+    assert_eq!(err.class, FailureClass::Validation);
+    assert!(err.message.contains("--porcelain"), "{}", err.message);
+}
+
+#[test]
+fn test_prune_overrides() {
+    let cli = Cli::try_parse_from(["faultnote", "prune", "--max-age-days", "90", "--dry-run"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Prune {
+            max_age_days: Some(90),
+            max_entries: None,
+            dry_run: true
+        })
+    ));
+}
+
+#[test]
+fn test_import_resume_flag() {
+    let cli = Cli::try_parse_from(["faultnote", "import", "old.jsonl", "--page", "Backend", "--resume"]).unwrap();
+    match cli.command {
+        Some(Command::Import {
+            file,
+            page,
+            resume,
+            check_page,
+            force,
+        }) => {
+            assert_eq!(file, std::path::PathBuf::from("old.jsonl"));
+            assert_eq!(page.as_deref(), Some("Backend"));
+            assert!(resume);
+            assert!(!check_page && !force);
+        }
+        other => panic!("unexpected command {:?}", other),
+    }
+}
+
+#[test]
+fn test_flush_results() {
+    use faultnote::flush::{Flushed, failure};
+
+    let entry = |id, error: Option<&str>, http_status| Flushed {
+        id,
+        page_title: "Backend Errors".to_string(),
+        error: error.map(str::to_string),
+        http_status,
+    };
+    let sent = entry(3, None, None);
+    let rejected = entry(4, Some("HTTP 400"), Some(400));
+    assert_eq!(sent.porcelain(), "sent 3");
+    assert_eq!(rejected.porcelain(), "failed 4");
+
+    assert_eq!(failure(std::slice::from_ref(&sent)), None);
+    assert_eq!(failure(&[]), None);
+    let partial = failure(&[sent, rejected.clone()]).unwrap();
+    assert_eq!(partial.class, FailureClass::Partial);
+    assert!(partial.message.starts_with("1 of 2 queued entries still failing"));
+    assert_eq!(failure(&[rejected]).unwrap().class, FailureClass::Validation);
+    assert_eq!(failure(&[entry(5, Some("timed out"), None)]).unwrap().class, FailureClass::Network);
+}
+
+#[test]
+fn test_page_and_field_flags() {
+    use faultnote::fields::InputField;
+
+    let cli = Cli::try_parse_from([
+        "faultnote",
+        "--page",
+        "Bug Tracker",
+        "--field",
+        "error=ECONNRESET: a=b",
+        "--field",
+        "Context=env: staging",
+    ])
+    .unwrap();
+    assert!(cli.command.is_none());
+    assert_eq!(cli.page.as_deref(), Some("Bug Tracker"));
+    assert_eq!(
+        cli.fields,
+        [(InputField::Error, "ECONNRESET: a=b".to_string()), (InputField::Context, "env: staging".to_string())]
+    );
+
+    assert!(Cli::try_parse_from(["faultnote", "--field", "error"]).is_err());
+    let err = Cli::try_parse_from(["faultnote", "--field", "cause=x"]).unwrap_err().to_string();
+    assert!(err.contains("expected one of error, problem"), "{}", err);
+    // The subcommand's own --page is unaffected
+    assert!(Cli::try_parse_from(["faultnote", "sample", "--page", "Bugs"]).is_ok());
+}
+
+#[test]
+fn test_target_links_resolve_to_page_ids() {
+    use faultnote::submit::page_id_from_url;
+
+    let id = Some("1a2b3c4d-5e6f-7a8b-9c0d-1e2f3a4b5c6d".to_string());
+    assert_eq!(page_id_from_url("https://www.notion.so/acme/Login-Errors-1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d?pvs=4"), id);
+    assert_eq!(page_id_from_url("https://notion.so/1A2B3C4D5E6F7A8B9C0D1E2F3A4B5C6D#heading"), id);
+    assert_eq!(page_id_from_url("https://acme.notion.site/Errors-1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d"), id);
+    // A page open as a side peek over a database
+    assert_eq!(
+        page_id_from_url("https://www.notion.so/acme/ffff0000ffff0000ffff0000ffff0000?v=0&p=1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d&pm=s"),
+        id
+    );
+    assert_eq!(page_id_from_url(" 1a2b3c4d-5e6f-7a8b-9c0d-1e2f3a4b5c6d "), id);
+
+    assert_eq!(page_id_from_url("https://example.com/Errors-1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d"), None);
+    assert_eq!(page_id_from_url("https://www.notion.so/acme/Login-Errors"), None);
+    assert_eq!(page_id_from_url("Backend Errors"), None);
+
+    let cli = Cli::try_parse_from(["faultnote", "--target", "https://www.notion.so/x-1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d"]).unwrap();
+    assert!(cli.target.is_some());
+    assert!(Cli::try_parse_from(["faultnote", "--target", "https://notion.so/x", "--page", "Bugs"]).is_err());
+}
@@ -0,0 +1,124 @@
+// Tests for `faultnote config` file editing
+
+use std::env;
+use std::fs;
+
+use faultnote::config::Config;
+use faultnote::config_file::{self, DEFAULT_CONFIG};
+use faultnote::ui::Theme;
+
+#[test]
+fn test_starter_config_is_valid_defaults() {
+    let config = Config::from_toml(DEFAULT_CONFIG).unwrap();
+    assert_eq!(config.template, Config::default().template);
+
+    let path = env::temp_dir().join(format!("faultnote-config-init-{}", std::process::id())).join("config.toml");
+    config_file::init(&path, false).unwrap();
+    assert!(config_file::init(&path, false).unwrap_err().contains("--force"));
+    config_file::init(&path, true).unwrap();
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_set_keeps_comments_and_creates_tables() {
+    let text = "# my settings\n[template]\nstyle = \"plain\" # no emoji\n";
+    let text = config_file::set(text, "template.heading.level", "h3").unwrap();
+    let text = config_file::set(&text, "template.divider_between_entries", "true").unwrap();
+    let text = config_file::set(&text, "pages.\"1a2b.c\".position", "top").unwrap();
+
+    assert!(text.starts_with("# my settings\n"));
+    assert!(text.contains("style = \"plain\" # no emoji"));
+    let config = Config::from_toml(&text).unwrap();
+    assert!(config.template.divider_between_entries);
+    assert_eq!(config_file::get(&text, "template.heading.level").unwrap().as_deref(), Some("h3"));
+    assert_eq!(config_file::get(&text, "template.divider_between_entries").unwrap().as_deref(), Some("true"));
+    assert_eq!(config_file::get(&text, "pages.\"1a2b.c\".position").unwrap().as_deref(), Some("top"));
+    assert_eq!(config_file::get(&text, "template.anchor").unwrap(), None);
+}
+
+#[test]
+fn test_set_rejects_invalid_config() {
+    let err = config_file::set("", "template.colour", "red").unwrap_err();
+    assert!(err.contains("unknown key 'colour' at template"), "{}", err);
+
+    let err = config_file::set("", "template.position", "middle").unwrap_err();
+    assert!(err.contains("unknown value 'middle'"), "{}", err);
+
+    // Numbers become strings where the config wants text
+    let text = config_file::set("", "project", "2024").unwrap();
+    assert_eq!(Config::from_toml(&text).unwrap().project.as_deref(), Some("2024"));
+
+    assert!(config_file::split_key("template..style").is_err());
+}
+
+#[test]
+fn test_env_overrides_layer_over_file() {
+    let vars = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+        pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+    };
+    let file = "[template]\nstyle = \"emoji\"\n";
+    let (text, warnings) = config_file::apply_env(
+        file,
+        vars(&[
+            ("FAULTNOTE_TEMPLATE__STYLE", "plain"),
+            ("FAULTNOTE_TEMPLATE__HEADING__LEVEL", "h3"),
+            ("FAULTNOTE_TEMPLATE__DIVIDER_BETWEEN_ENTRIES", "true"),
+            ("FAULTNOTE_PROJECT", "ci-runner"),
+            ("FAULTNOTE_DEFAULT_PAGE", "Backend errors"),
+            ("FAULTNOTE_THEME", "mono"),
+            ("PATH", "/usr/bin"),
+        ]),
+    )
+    .unwrap();
+    assert!(warnings.is_empty(), "{:?}", warnings);
+    let config = Config::from_toml(&text).unwrap();
+    assert!(config.template.is_plain());
+    assert!(config.template.divider_between_entries);
+    assert_eq!(config.project.as_deref(), Some("ci-runner"));
+    assert_eq!(config.default_page.as_deref(), Some("Backend errors"));
+    assert_eq!(config.theme, Theme::Mono);
+    assert_eq!(config_file::get(&text, "template.heading.level").unwrap().as_deref(), Some("h3"));
+
+    // A variable naming no key is skipped with a warning
+    let (text, warnings) = config_file::apply_env(
+        "",
+        vars(&[("FAULTNOTE_TEMPLATE__STILE", "plain"), ("FAULTNOTE_PROJECT", "api")]),
+    )
+    .unwrap();
+    assert_eq!(config_file::get(&text, "project").unwrap().as_deref(), Some("api"));
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].starts_with("Ignored FAULTNOTE_TEMPLATE__STILE: "), "{}", warnings[0]);
+    assert!(warnings[0].contains("did you mean 'style'?"), "{}", warnings[0]);
+
+    // A bad value for a known key still fails
+    let err = config_file::apply_env("", vars(&[("FAULTNOTE_THEME", "neon")])).unwrap_err();
+    assert!(err.starts_with("FAULTNOTE_THEME: "), "{}", err);
+}
+
+#[test]
+fn test_profile_round_trip_leaves_personal_settings() {
+    let lead = "project = \"payments\"\n\n# House style\n[template]\nstyle = \"plain\"\n\n[template.labels]\nerror = \"Symptom\"\n\n[[tokens]]\nname = \"team\"\nkey = \"secret_abc\"\n\n[keys]\nsubmit = \"Ctrl+s\"\n";
+    let profile = config_file::export_profile(lead).unwrap();
+    assert!(profile.starts_with("# FaultNote settings profile"));
+    assert!(profile.contains("# House style\n[template]"));
+    assert!(!profile.contains("secret_abc"));
+    assert!(!profile.contains("[keys]"));
+    assert!(!profile.contains("payments"));
+
+    let mine = "[template]\nstyle = \"emoji\"\nanchor = \"## Log\"\n\n[keys]\nquit = \"x\"\n";
+    let updated = config_file::import_profile(mine, &profile).unwrap();
+    let config = Config::from_toml(&updated).unwrap();
+    assert!(config.template.is_plain());
+    assert_eq!(config.template.labels.error.as_deref(), Some("Symptom"));
+    // The profile's sections replace whole; other sections stay
+    assert_eq!(config.template.anchor, None);
+    assert!(updated.starts_with("# House style\n[template]"));
+    assert!(updated.contains("quit = \"x\""));
+}
+
+#[test]
+fn test_profiles_with_secrets_are_refused() {
+    let err = config_file::import_profile("", "[[tokens]]\nname = \"team\"\nkey = \"secret_abc\"\n").unwrap_err();
+    assert!(err.contains("remove `tokens`"));
+    assert!(config_file::export_profile("[keys]\nquit = \"x\"\n").unwrap_err().starts_with("Nothing to export"));
+}
@@ -0,0 +1,106 @@
+// Tests for configuration loading
+
+use faultnote::config::Config;
+use faultnote::notion::client::append_body;
+use faultnote::notion::template::{AppendPosition, BlockTemplate};
+use serde_json::json;
+
+#[test]
+fn test_empty_config_uses_defaults() {
+    let config = Config::from_toml("").unwrap();
+    assert_eq!(config.template, BlockTemplate::default());
+}
+
+#[test]
+fn test_template_separator_options() {
+    let config = Config::from_toml(
+        r#"
+        [template]
+        divider_between_entries = true
+        "#,
+    )
+    .unwrap();
+    assert!(config.template.divider_between_entries);
+    assert!(!config.template.blank_line_between_entries);
+}
+
+#[test]
+fn test_invalid_config_reports_error() {
+    let err = Config::from_toml("[template]\ndivider_between_entries = \"yes\"").unwrap_err();
+    assert!(err.starts_with("Invalid config"));
+}
+
+#[test]
+fn test_token_list() {
+    let config = Config::from_toml(
+        r#"
+        [[tokens]]
+        name = "team"
+        key = "secret_team"
+
+        [[tokens]]
+        name = "ci"
+        env = "FAULTNOTE_TEST_UNSET_TOKEN_VAR"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.tokens.len(), 2);
+    assert_eq!(config.tokens[0].resolve().as_deref(), Some("secret_team"));
+    assert_eq!(config.tokens[1].resolve(), None);
+}
+
+#[test]
+fn test_per_page_position() {
+    let config = Config::from_toml(
+        r#"
+        [template]
+        position = "top"
+
+        [pages."1a2b3c4d-0000-0000-0000-000000000001"]
+        position = "bottom"
+
+        [pages.1a2b3c4d000000000000000000000002]
+        after = "header-block"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.template_for("other").position, AppendPosition::Top);
+    let first = config.template_for("1a2b3c4d000000000000000000000001");
+    assert_eq!(first.position, AppendPosition::Bottom);
+    let second = config.template_for("1a2b3c4d-0000-0000-0000-000000000002");
+    assert_eq!(second.position, AppendPosition::Top);
+    assert_eq!(second.after.as_deref(), Some("header-block"));
+}
+
+#[test]
+fn test_append_body_after() {
+    let blocks = json!([{ "type": "divider" }]);
+    assert!(append_body(blocks.clone(), None).get("after").is_none());
+    assert_eq!(append_body(blocks, Some("block-1"))["after"], "block-1");
+}
+
+#[test]
+fn test_unknown_key_suggests_spelling() {
+    let err = Config::from_toml("[template.heading]\nlevel = \"h2\"\ncolour = \"red\"").unwrap_err();
+    assert_eq!(err, "Invalid config: unknown key 'colour' at template.heading (line 3) — did you mean 'color'?");
+
+    let err = Config::from_toml("[template]\nheading.colour = \"red\"").unwrap_err();
+    assert!(err.contains("unknown key 'colour' at template.heading (line 2)"), "{}", err);
+
+    let err = Config::from_toml("templat = 1").unwrap_err();
+    assert!(err.ends_with("unknown key 'templat' at the top level (line 1) — did you mean 'template'?"), "{}", err);
+
+    // Nothing close enough: list what is allowed
+    let err = Config::from_toml("[[tokens]]\nname = \"ci\"\nsecret = \"x\"").unwrap_err();
+    assert!(err.contains("unknown key 'secret' at tokens (line 3); expected one of name, key, env"), "{}", err);
+}
+
+#[test]
+fn test_unknown_value_suggests_spelling() {
+    let err = Config::from_toml("[template]\nposition = \"bottm\"").unwrap_err();
+    assert!(err.ends_with("unknown value 'bottm' for template.position (line 2) — did you mean 'bottom'?"), "{}", err);
+
+    let err = Config::from_toml("[keys]\nsumbit = \"Enter\"").unwrap_err();
+    assert!(err.contains("unknown key 'sumbit' at keys (line 2) — did you mean 'submit'?"), "{}", err);
+}
@@ -0,0 +1,67 @@
+// Tests for the git state attached to entries
+
+use faultnote::app::FaultLogEntry;
+use faultnote::context::GitContext;
+use faultnote::notion::client::create_error_block;
+use faultnote::notion::locale::Locale;
+use faultnote::notion::template::BlockTemplate;
+use faultnote::vars::Vars;
+
+const CLEAN: &str = "# branch.oid 4f1c2a9e0b7d3c5e6f8a9b0c1d2e3f4a5b6c7d8e\n# branch.head main\n# branch.upstream origin/main\n# branch.ab +0 -0\n";
+
+#[test]
+fn test_parse_clean_checkout() {
+    let git = GitContext::parse(CLEAN).unwrap();
+    assert_eq!(git.branch.as_deref(), Some("main"));
+    assert_eq!(git.sha.as_deref(), Some("4f1c2a9"));
+    assert!(!git.dirty);
+    assert_eq!(git.summary(), "main@4f1c2a9");
+    assert_eq!(git.describe(), "Git: branch main at 4f1c2a9");
+}
+
+#[test]
+fn test_parse_dirty_detached_and_empty_repositories() {
+    let dirty = format!("{}1 .M N... 100644 100644 100644 abc abc src/main.rs\n? notes.txt\n", CLEAN);
+    let git = GitContext::parse(&dirty).unwrap();
+    assert!(git.dirty);
+    assert_eq!(git.summary(), "main@4f1c2a9*");
+    assert_eq!(git.describe(), "Git: branch main at 4f1c2a9, with uncommitted changes");
+
+    let detached = GitContext::parse("# branch.oid 4f1c2a9e0b7d\n# branch.head (detached)\n").unwrap();
+    assert_eq!(detached.branch, None);
+    assert_eq!(detached.summary(), "detached@4f1c2a9");
+
+    let fresh = GitContext::parse("# branch.oid (initial)\n# branch.head main\n").unwrap();
+    assert_eq!(fresh.sha, None);
+    assert_eq!(fresh.describe(), "Git: branch main with no commits");
+
+    assert_eq!(GitContext::parse(""), None);
+}
+
+#[test]
+fn test_outside_a_repository() {
+    let dir = std::env::temp_dir().join(format!("faultnote-context-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    assert_eq!(GitContext::detect(&dir), None);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_git_state_closes_the_context_section() {
+    let entry = FaultLogEntry {
+        error: "Error".to_string(),
+        problem: "Problem".to_string(),
+        solution: "Solution".to_string(),
+        git: GitContext::parse(CLEAN),
+        ..Default::default()
+    };
+    let block = create_error_block(&entry, None, &BlockTemplate::default());
+    let children = block[0]["heading_2"]["children"].as_array().unwrap();
+    let heading = children.iter().position(|child| child["heading_3"]["rich_text"][0]["text"]["content"] == "Context");
+    let git = &children[heading.unwrap() + 1]["paragraph"];
+    assert_eq!(git["rich_text"][0]["text"]["content"], "Git: branch main at 4f1c2a9");
+    assert_eq!(git["color"], "gray");
+
+    let vars = Vars::for_entry(&entry, Some("api"), Locale::En);
+    assert_eq!(vars.render("{branch}@{commit}"), "main@4f1c2a9");
+}
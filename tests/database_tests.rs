@@ -0,0 +1,271 @@
+// Tests for databases as submission targets
+
+use faultnote::app::{AppState, FaultLogEntry, FaultStatus, PageInfo, RelationProperty, RowPick, TargetKind};
+use faultnote::config::Config;
+use faultnote::notion::client::extract_page_info;
+use faultnote::notion::database::{
+    find_title_property, property_value, query_body, relation_properties, row_properties, status_icon,
+    status_property,
+};
+use serde_json::json;
+
+fn page(id: &str, title: &str, kind: TargetKind) -> PageInfo {
+    PageInfo {
+        id: id.to_string(),
+        title: title.to_string(),
+        kind,
+        parent: None,
+    }
+}
+
+#[test]
+fn test_search_results_detect_databases() {
+    let database = json!({
+        "object": "database",
+        "id": "db-1",
+        "title": [{ "type": "text", "plain_text": "Services" }]
+    });
+    let info = extract_page_info(&database).unwrap();
+    assert!(info.is_database());
+    assert_eq!(info.title, "Services");
+
+    // Rows may call their title property anything
+    let row = json!({
+        "object": "page",
+        "id": "row-1",
+        "properties": {
+            "Owner": { "type": "people", "people": [] },
+            "Service": { "type": "title", "title": [{ "plain_text": "payments" }] }
+        }
+    });
+    let info = extract_page_info(&row).unwrap();
+    assert_eq!(info.kind, TargetKind::Page);
+    assert_eq!(info.title, "payments");
+}
+
+#[test]
+fn test_row_query_body() {
+    let database = json!({
+        "properties": {
+            "Status": { "type": "select" },
+            "Service": { "type": "title" }
+        }
+    });
+    assert_eq!(find_title_property(&database).as_deref(), Some("Service"));
+
+    let all = query_body(None, "", None);
+    assert!(all.get("filter").is_none());
+    assert_eq!(all["sorts"][0]["timestamp"], "last_edited_time");
+
+    let filtered = query_body(Some("Service"), "pay", Some("cursor-2"));
+    assert_eq!(filtered["filter"]["property"], "Service");
+    assert_eq!(filtered["filter"]["title"]["contains"], "pay");
+    assert_eq!(filtered["start_cursor"], "cursor-2");
+}
+
+#[test]
+fn test_entries_go_to_the_chosen_row() {
+    let mut app = AppState::new();
+    app.set_pages(vec![page("db-1", "Services", TargetKind::Database)]);
+    app.error_input = "err".into();
+    app.problem_input = "prob".into();
+    app.solution_input = "fix".into();
+    // Without a row, each entry becomes a new row of the database
+    assert!(app.submission_target().unwrap().is_database());
+    assert_eq!(app.get_submission_data().unwrap().0, "db-1");
+
+    app.open_rows(vec![
+        page("row-1", "Service: search", TargetKind::Page),
+        page("row-2", "Service: payments", TargetKind::Page),
+    ]);
+    assert!(app.is_rows_screen());
+    app.row_query.push_str("pay");
+    app.refresh_row_filter();
+    assert_eq!(app.row_matches, [1]);
+    app.choose_row();
+
+    assert!(!app.is_rows_screen());
+    let target = app.submission_target().unwrap();
+    assert_eq!(target.id, "row-2");
+    assert_eq!(target.title, "Services › Service: payments");
+    assert_eq!(app.get_submission_data().unwrap().0, "row-2");
+
+    app.clear_row();
+    assert_eq!(app.submission_target().unwrap().id, "db-1");
+}
+
+#[test]
+fn test_row_properties_from_entry() {
+    let database = json!({
+        "properties": {
+            "Name": { "type": "title" },
+            "Problem": { "type": "rich_text" },
+            "Tags": { "type": "multi_select" },
+            "Status": { "type": "status" },
+            "Severity": { "type": "select" },
+            "Owner": { "type": "people" }
+        }
+    });
+    let config = Config::from_toml(
+        r#"
+        [template.properties]
+        Status = { value = "Open" }
+
+        [pages.db-1.properties]
+        Severity = "language"
+        "#,
+    )
+    .unwrap();
+    let entry = FaultLogEntry {
+        error: "Timeout".to_string(),
+        problem: "Slow query".to_string(),
+        language: Some("sql".to_string()),
+        tags: vec!["db".to_string(), "prod".to_string()],
+        ..Default::default()
+    };
+
+    let properties = row_properties(&database, &entry, &config.template_for("db-1"));
+    assert_eq!(properties["Name"]["title"][0]["text"]["content"], "Timeout");
+    assert_eq!(properties["Problem"]["rich_text"][0]["text"]["content"], "Slow query");
+    assert_eq!(properties["Tags"]["multi_select"][1]["name"], "prod");
+    assert_eq!(properties["Status"]["status"]["name"], "Open");
+    assert_eq!(properties["Severity"]["select"]["name"], "sql");
+    assert!(properties.get("Owner").is_none());
+
+    // Without the page override, Severity has no source
+    let properties = row_properties(&database, &entry, &config.template);
+    assert!(properties.get("Severity").is_none());
+}
+
+#[test]
+fn test_property_values_by_type() {
+    assert_eq!(property_value("number", " 3 ").unwrap()["number"], 3.0);
+    assert!(property_value("number", "three").is_none());
+    assert_eq!(property_value("checkbox", "yes").unwrap()["checkbox"], true);
+    assert_eq!(property_value("url", "https://x.dev").unwrap()["url"], "https://x.dev");
+    assert_eq!(property_value("relation", "a, b").unwrap()["relation"][1]["id"], "b");
+    assert!(property_value("relation", " , ").is_none());
+    assert!(property_value("people", "x").is_none());
+}
+
+#[test]
+fn test_status_property_lifecycle() {
+    let database = json!({
+        "properties": {
+            "Name": { "type": "title" },
+            "status": { "type": "status" },
+            "Stage": { "type": "select" }
+        }
+    });
+    let entry = FaultLogEntry {
+        status: FaultStatus::Investigating,
+        ..Default::default()
+    };
+    let properties = row_properties(&database, &entry, &Config::default().template);
+    assert_eq!(properties["status"]["status"]["name"], "Investigating");
+    assert_eq!(FaultStatus::Fixed.next(), FaultStatus::Open);
+
+    // Rows report their property types; a mapped property wins
+    let row = json!({
+        "properties": {
+            "Status": { "type": "status", "status": null },
+            "Stage": { "type": "select", "select": null },
+            "Notes": { "type": "rich_text", "rich_text": [] }
+        }
+    });
+    let config = Config::from_toml("[template.properties]\nStage = \"status\"\nNotes = \"status\"").unwrap();
+    assert_eq!(
+        status_property(&row, &Config::default().template),
+        Some(("Status".to_string(), "status".to_string()))
+    );
+    assert_eq!(
+        status_property(&row, &config.template),
+        Some(("Stage".to_string(), "select".to_string()))
+    );
+    assert_eq!(status_property(&json!({ "properties": {} }), &config.template), None);
+}
+
+#[test]
+fn test_row_icon_and_cover() {
+    let config = Config::from_toml(
+        r#"
+        [template]
+        cover = "Red"
+
+        [pages.db-2]
+        cover = "https://example.com/fault.png"
+        "#,
+    )
+    .unwrap();
+    let template = config.template_for("db-1");
+    assert_eq!(status_icon(FaultStatus::Fixed, &template).unwrap()["emoji"], "🟢");
+    assert_eq!(
+        template.cover_value().unwrap()["external"]["url"],
+        "https://www.notion.so/images/page-cover/solid_red.png"
+    );
+    let other = config.template_for("db-2");
+    assert_eq!(other.cover_value().unwrap()["external"]["url"], "https://example.com/fault.png");
+
+    // No cover unless configured; no emoji in the plain style
+    let plain = Config::from_toml("[template]\nstyle = \"plain\"").unwrap().template;
+    assert!(plain.cover_value().is_none());
+    assert!(status_icon(FaultStatus::Open, &plain).is_none());
+}
+
+#[test]
+fn test_relation_properties_and_values() {
+    let database = json!({
+        "properties": {
+            "Name": { "type": "title" },
+            "Service": { "type": "relation", "relation": { "database_id": "services-db" } },
+            "Incidents": { "type": "relation", "relation": { "database_id": "incidents-db" } }
+        }
+    });
+    let relations = relation_properties(&database);
+    assert_eq!(relations.len(), 2);
+    assert_eq!(relations[0].name, "Incidents");
+    assert_eq!(relations[1].database_id, "services-db");
+
+    let entry = FaultLogEntry {
+        error: "Timeout".to_string(),
+        relations: [("Service".to_string(), vec!["svc-1".to_string()])].into(),
+        ..Default::default()
+    };
+    let properties = row_properties(&database, &entry, &Config::default().template);
+    assert_eq!(properties["Service"]["relation"][0]["id"], "svc-1");
+    assert!(properties.get("Incidents").is_none());
+}
+
+#[test]
+fn test_picked_relations_go_into_new_rows() {
+    let mut app = AppState::new();
+    app.set_pages(vec![page("db-1", "Bugs", TargetKind::Database)]);
+    app.error_input = "err".into();
+    app.problem_input = "prob".into();
+    app.solution_input = "fix".into();
+
+    app.open_relations(vec![RelationProperty {
+        name: "Service".to_string(),
+        database_id: "services-db".to_string(),
+    }]);
+    assert!(app.is_relations_screen());
+    app.row_pick = RowPick::Relation {
+        property: "Service".to_string(),
+        database_id: "services-db".to_string(),
+    };
+    assert_eq!(app.row_database_id().as_deref(), Some("services-db"));
+    app.open_rows(vec![page("svc-1", "payments", TargetKind::Page)]);
+    app.choose_row();
+
+    // Back on the relations screen, still adding new rows
+    assert!(app.is_relations_screen());
+    assert_eq!(app.linked_pages("Service")[0].title, "payments");
+    assert_eq!(app.submission_target().unwrap().id, "db-1");
+    let (_, entry) = app.get_submission_data().unwrap();
+    assert_eq!(entry.relations["Service"], ["svc-1"]);
+
+    app.clear_relation();
+    assert!(app.linked_pages("Service").is_empty());
+    app.close_relations();
+    assert_eq!(app.row_pick, RowPick::Target);
+}
@@ -0,0 +1,123 @@
+// Tests for reading entries back from Notion blocks
+
+use faultnote::app::Severity;
+use faultnote::config::Config;
+use faultnote::notion::client::{create_error_block, FaultLogEntry};
+use faultnote::notion::entries::parse_entries;
+use faultnote::notion::template::BlockTemplate;
+use serde_json::{json, Value};
+
+fn entry() -> FaultLogEntry {
+    FaultLogEntry {
+        error: "Connection refused".to_string(),
+        problem: "DB down\nafter deploy".to_string(),
+        solution: "Restart with $$n+1$$ replicas".to_string(),
+        code: Some("systemctl restart db".to_string()),
+        verification: Some("Run health check".to_string()),
+        context: Some("os: linux\nregion: eu".to_string()),
+        language: Some("bash".to_string()),
+        tags: vec!["db".to_string(), "prod".to_string()],
+        ..Default::default()
+    }
+}
+
+fn blocks(template: &BlockTemplate) -> Vec<Value> {
+    let mut page = vec![json!({ "type": "paragraph", "paragraph": { "rich_text": [] } })];
+    page.extend(create_error_block(&entry(), Some("bash"), template).as_array().unwrap().clone());
+    page
+}
+
+fn assert_round_trip(template: &BlockTemplate) {
+    let entries = parse_entries(&blocks(template), template);
+    assert_eq!(entries.len(), 1);
+
+    let parsed = &entries[0];
+    assert_eq!(parsed.title, "Connection refused");
+    assert_eq!(parsed.entry.error, "Connection refused");
+    assert_eq!(parsed.entry.problem, "DB down\nafter deploy");
+    assert_eq!(parsed.entry.solution, "Restart with $$n+1$$ replicas");
+    assert_eq!(parsed.entry.code.as_deref(), Some("systemctl restart db"));
+    assert_eq!(parsed.entry.language.as_deref(), Some("bash"));
+    assert_eq!(parsed.entry.verification.as_deref(), Some("Run health check"));
+    assert_eq!(parsed.entry.context.as_deref(), Some("os: linux\nregion: eu"));
+    assert_eq!(parsed.entry.tags, ["db", "prod"]);
+}
+
+#[test]
+fn test_toggle_entry_round_trip() {
+    assert_round_trip(&BlockTemplate::default());
+}
+
+#[test]
+fn test_flat_plain_entry_round_trip() {
+    let toml = r#"
+        [template]
+        style = "plain"
+        divider_between_entries = true
+        [template.heading]
+        toggleable = false
+        [template.labels]
+        problem = "Cause"
+    "#;
+    assert_round_trip(&Config::from_toml(toml).unwrap().template);
+}
+
+#[test]
+fn test_metadata_line_is_not_part_of_the_error() {
+    let template = Config::from_toml("[template]\nmetadata = \"Logged on host-1\"").unwrap().template;
+    assert_round_trip(&template);
+}
+
+#[test]
+fn test_unrelated_headings_ignored() {
+    let page = vec![
+        json!({ "type": "heading_2", "heading_2": { "rich_text": [{ "plain_text": "Notes" }] } }),
+        json!({ "type": "paragraph", "paragraph": { "rich_text": [{ "plain_text": "hello" }] } }),
+    ];
+    assert!(parse_entries(&page, &BlockTemplate::default()).is_empty());
+}
+
+#[test]
+fn test_localized_entry_round_trip() {
+    let toml = "[template]\nlocale = \"de\"\nstyle = \"plain\"";
+    assert_round_trip(&Config::from_toml(toml).unwrap().template);
+}
+
+#[test]
+fn test_english_entries_parse_under_another_locale() {
+    let page = blocks(&BlockTemplate::default());
+    let template = Config::from_toml("[template]\nlocale = \"fr\"").unwrap().template;
+    let entries = parse_entries(&page, &template);
+    assert_eq!(entries[0].entry.solution, "Restart with $$n+1$$ replicas");
+    assert_eq!(entries[0].entry.tags, ["db", "prod"]);
+}
+
+#[test]
+fn test_severity_read_back_from_heading() {
+    let template = BlockTemplate::default();
+    let marked = FaultLogEntry {
+        severity: Some(Severity::Medium),
+        ..entry()
+    };
+    let page = create_error_block(&marked, Some("bash"), &template).as_array().unwrap().clone();
+    let entries = parse_entries(&page, &template);
+    assert_eq!(entries[0].title, "Connection refused");
+    assert_eq!(entries[0].entry.severity, Some(Severity::Medium));
+
+    assert_eq!(parse_entries(&blocks(&template), &template)[0].entry.severity, None);
+}
+
+#[test]
+fn test_related_links_are_not_read_into_fields() {
+    let template = BlockTemplate {
+        related_links: true,
+        ..Default::default()
+    };
+    let mut logged = entry();
+    logged.error = "Timeout, see https://grafana.example.com/d/abc".to_string();
+    let blocks = create_error_block(&logged, Some("bash"), &template).as_array().unwrap().clone();
+    let entries = parse_entries(&blocks, &template);
+    assert_eq!(entries[0].entry.error, logged.error);
+    assert_eq!(entries[0].entry.code.as_deref(), Some("systemctl restart db"));
+    assert_eq!(entries[0].entry.tags, ["db", "prod"]);
+}
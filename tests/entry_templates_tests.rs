@@ -0,0 +1,78 @@
+// Tests for entry templates and placeholder navigation
+
+use faultnote::app::AppState;
+use faultnote::config::Config;
+use faultnote::entry_templates::find_placeholder;
+
+#[test]
+fn test_find_placeholder() {
+    let text = "CI job {{job}} failed on {{branch}}";
+    assert_eq!(find_placeholder(text, 0), Some((7, 14)));
+    assert_eq!(find_placeholder(text, 8), Some((25, 35)));
+    assert_eq!(find_placeholder(text, 26), None);
+    assert_eq!(find_placeholder("unclosed {{name", 0), None);
+    assert_eq!(find_placeholder("ünï {{x}}", 0), Some((4, 9)));
+}
+
+#[test]
+fn test_config_templates_join_builtins() {
+    let toml = r#"
+        [entry_templates.oom]
+        error = "{{service}} OOMKilled"
+        language = "yml"
+
+        [entry_templates.panic]
+        error = "panic in {{crate}}"
+    "#;
+    let config = Config::from_toml(toml).unwrap();
+    let templates = config.all_entry_templates();
+    assert!(templates.contains_key("ci-failure"));
+    assert_eq!(templates["panic"].summary(), "panic in {{crate}}");
+    assert_eq!(templates["oom"].language.as_deref(), Some("yml"));
+
+    assert!(Config::from_toml("[entry_templates.x]\neror = \"typo\"").is_err());
+}
+
+#[test]
+fn test_picking_template_fills_fields_and_visits_placeholders() {
+    let mut app = AppState::new();
+    app.config = Config::from_toml(
+        r#"
+        [entry_templates.oom]
+        error = "{{service}} OOMKilled"
+        problem = "Limit {{limit}} too low"
+        language = "yml"
+        "#,
+    )
+    .unwrap();
+    app.problem_input = "old notes".into();
+
+    app.start_template_picker();
+    for c in "oom".chars() {
+        app.template_filter.push(c);
+    }
+    app.refresh_template_filter();
+    app.accept_template();
+
+    // Editing starts at the first placeholder, already removed
+    assert!(app.is_editing());
+    assert_eq!(app.code_language, "yaml");
+    assert_eq!(app.error_input.to_string(), " OOMKilled");
+    for c in "api".chars() {
+        app.add_char(c);
+    }
+    assert_eq!(app.error_input.to_string(), "api OOMKilled");
+
+    // The next one is in a later field
+    assert!(app.next_placeholder());
+    assert_eq!(app.active_input_field, 1);
+    app.add_char('2');
+    assert_eq!(app.problem_input.to_string(), "Limit 2 too low");
+    assert!(!app.next_placeholder());
+
+    // What the template replaced can be undone
+    for _ in 0..3 {
+        app.undo_edit();
+    }
+    assert_eq!(app.problem_input.to_string(), "old notes");
+}
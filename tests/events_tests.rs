@@ -15,3 +15,611 @@ fn test_app_quit() {
     app.quit();
     assert!(!app.is_running());
 }
+
+#[tokio::test]
+async fn test_background_submission_completes() {
+    use faultnote::app::{FaultLogEntry, InFlightSubmission, PageInfo, SubmissionTask, TargetKind};
+    use faultnote::app_event::AppEvent;
+    use faultnote::events::handle_event;
+    use faultnote::notion::client::AppendedBlocks;
+
+    let mut app = AppState::new();
+    let entry = FaultLogEntry {
+        error: "Boom".to_string(),
+        ..Default::default()
+    };
+    let (release, wait) = tokio::sync::oneshot::channel::<()>();
+    let task = tokio::spawn(async move {
+        let _ = wait.await;
+        vec![Ok((
+            "default".to_string(),
+            AppendedBlocks {
+                block_ids: vec!["block-1".to_string()],
+                payload_sha256: String::new(),
+                created_anchor: None,
+            },
+        ))]
+    });
+    app.submission = Some(InFlightSubmission {
+        targets: vec![PageInfo {
+            id: "page-1".to_string(),
+            title: "Page".to_string(),
+            kind: TargetKind::Page,
+            parent: None,
+        }],
+        entry,
+        task: SubmissionTask::Notion(task),
+    });
+    app.start_loading();
+    assert!(app.spinner().is_some());
+
+    // Still running: a tick leaves it alone
+    handle_event(&mut app, AppEvent::Tick, None).await.unwrap();
+    assert!(app.submission.is_some());
+
+    release.send(()).unwrap();
+    handle_event(&mut app, AppEvent::SubmissionFinished, None).await.unwrap();
+    assert!(app.submission.is_none());
+    assert!(app.spinner().is_none());
+    assert!(app.can_undo());
+    assert_eq!(app.last_submission.unwrap().block_ids, vec!["block-1"]);
+}
+
+#[tokio::test]
+async fn test_submission_to_checked_pages_reports_each_page() {
+    use faultnote::app::{FaultLogEntry, InFlightSubmission, PageInfo, SubmissionTask, TargetKind};
+    use faultnote::events::complete_submission;
+    use faultnote::notion::client::{AppendedBlocks, NotionError};
+
+    let page = |id: &str, title: &str| PageInfo {
+        id: id.to_string(),
+        title: title.to_string(),
+        kind: TargetKind::Page,
+        parent: None,
+    };
+    let mut app = AppState::new();
+    let task = tokio::spawn(async {
+        vec![
+            Ok(("default".to_string(), AppendedBlocks::default())),
+            Err(NotionError::NotFound { message: String::new() }),
+        ]
+    });
+    app.submission = Some(InFlightSubmission {
+        targets: vec![page("page-1", "Backend"), page("page-2", "Incidents")],
+        entry: FaultLogEntry {
+            error: "Boom".to_string(),
+            ..Default::default()
+        },
+        task: SubmissionTask::Notion(task),
+    });
+    complete_submission(&mut app).await;
+
+    let message = app.status_message.clone().unwrap();
+    assert!(message.contains("Logged to 1 of 2 pages; failed on Incidents (integration lacks access"));
+    assert!(message.ends_with("Saved to queue (o to inspect)"));
+    assert_eq!(app.queue.len(), 1);
+    assert_eq!(app.queue.items()[0].page_title, "Incidents");
+    // Undo only covers a single page
+    assert!(app.last_submission.is_none());
+}
+
+#[tokio::test]
+async fn test_background_retry_completes() {
+    use faultnote::app::FaultLogEntry;
+    use faultnote::app_event::AppEvent;
+    use faultnote::events::handle_event;
+    use faultnote::notion::client::{AppendedBlocks, NotionError};
+    use faultnote::queue::QueueStatus;
+
+    let entry = |error: &str| FaultLogEntry {
+        error: error.to_string(),
+        ..Default::default()
+    };
+    let mut app = AppState::new();
+    app.queue.push("page-1", "Backend", entry("A"), QueueStatus::Pending, "timeout");
+    app.queue.push("page-2", "Incidents", entry("B"), QueueStatus::Pending, "timeout");
+    let items = app.queue.items().to_vec();
+    let (release, wait) = tokio::sync::oneshot::channel::<()>();
+    app.retry = Some(tokio::spawn(async move {
+        let _ = wait.await;
+        let mut items = items.into_iter();
+        vec![
+            (items.next().unwrap(), Ok(("default".to_string(), AppendedBlocks::default()))),
+            (items.next().unwrap(), Err(NotionError::NotFound { message: String::new() })),
+        ]
+    }));
+    assert!(app.spinner().is_some());
+
+    // Still running: a tick neither waits for it nor starts another
+    handle_event(&mut app, AppEvent::Tick, None).await.unwrap();
+    assert!(app.retry.is_some());
+    assert_eq!(app.queue.len(), 2);
+
+    release.send(()).unwrap();
+    handle_event(&mut app, AppEvent::RetryFinished, None).await.unwrap();
+    assert!(app.retry.is_none());
+    assert_eq!(app.queue.len(), 1);
+    assert_eq!(app.queue.items()[0].page_title, "Incidents");
+    assert!(app.status_message.as_deref().unwrap().ends_with("1 queued entries submitted, 1 still failing"));
+}
+
+#[tokio::test]
+async fn test_retry_queue_with_nothing_pending() {
+    use faultnote::events::retry_queue;
+
+    let mut app = AppState::new();
+    retry_queue(&mut app, None).await;
+    assert!(app.queue.is_empty());
+    assert_eq!(app.status_message.as_deref(), Some("Nothing to retry"));
+}
+
+#[tokio::test]
+async fn test_ctrl_z_undoes_while_editing() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use faultnote::events::handle_key_event;
+
+    let ctrl_z = KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL);
+    let mut app = AppState::new();
+    app.toggle_focus();
+    app.enter_edit_mode();
+    app.add_char('x');
+    handle_key_event(&mut app, ctrl_z, None).await;
+    assert!(app.error_input.is_empty());
+    assert!(!app.suspend_requested);
+
+    app.exit_edit_mode();
+    handle_key_event(&mut app, ctrl_z, None).await;
+    assert!(app.suspend_requested);
+}
+
+#[tokio::test]
+async fn test_up_and_down_recall_earlier_values_while_editing() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use faultnote::app::FaultLogEntry;
+    use faultnote::events::handle_key_event;
+    use faultnote::storage::{EntryStatus, History};
+
+    let up = KeyEvent::new(KeyCode::Up, KeyModifiers::NONE);
+    let down = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
+    let mut app = AppState::new();
+    app.history = History::open_in_memory().unwrap();
+    for error in ["payments: timeout", "auth: 502"] {
+        let entry = FaultLogEntry {
+            error: error.to_string(),
+            ..Default::default()
+        };
+        app.history.record("p", "Backend", &entry, EntryStatus::Submitted).unwrap();
+    }
+    app.toggle_focus();
+    app.enter_edit_mode();
+    app.add_char('a');
+
+    handle_key_event(&mut app, up, None).await;
+    assert_eq!(app.error_input, "auth: 502");
+    handle_key_event(&mut app, up, None).await;
+    handle_key_event(&mut app, up, None).await;
+    assert_eq!(app.error_input, "payments: timeout");
+    assert_eq!(app.status_message.as_deref(), Some("Earlier value 2 of 2 (Up/Down for more)"));
+
+    handle_key_event(&mut app, down, None).await;
+    handle_key_event(&mut app, down, None).await;
+    assert_eq!(app.error_input, "a");
+    assert!(app.is_editing());
+
+    // Past the draft, Down moves on; fields without recall always do
+    handle_key_event(&mut app, down, None).await;
+    assert!(!app.is_editing());
+    assert_eq!(app.active_input_field, 1);
+    app.enter_edit_mode();
+    handle_key_event(&mut app, up, None).await;
+    assert_eq!(app.active_input_field, 0);
+}
+
+#[tokio::test]
+async fn test_bang_cycles_severity() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use faultnote::app::Severity;
+    use faultnote::events::handle_key_event;
+
+    let bang = KeyEvent::new(KeyCode::Char('!'), KeyModifiers::SHIFT);
+    let mut app = AppState::new();
+    handle_key_event(&mut app, bang, None).await;
+    assert_eq!(app.severity, Some(Severity::Critical));
+    assert_eq!(app.status_message.as_deref(), Some("Severity: Critical"));
+    for _ in 0..4 {
+        handle_key_event(&mut app, bang, None).await;
+    }
+    assert_eq!(app.severity, None);
+
+    app.severity = Some(Severity::High);
+    app.clear_inputs();
+    assert_eq!(app.severity, None);
+}
+
+#[tokio::test]
+async fn test_r_requests_page_refresh() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use faultnote::app::PageInfo;
+    use faultnote::events::{apply_page_load, handle_key_event};
+    use faultnote::page_loader::PageLoad;
+
+    let mut app = AppState::new();
+    handle_key_event(&mut app, KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE), None).await;
+    assert!(app.refresh_requested);
+
+    app.pages_loading = true;
+    let page = |id: &str| PageInfo {
+        id: id.to_string(),
+        title: id.to_string(),
+        ..Default::default()
+    };
+    let load = PageLoad {
+        initial: false,
+        pages: Ok(vec![page("a"), page("b")]),
+        capabilities: None,
+    };
+    apply_page_load(&mut app, load, None).await;
+    assert!(!app.pages_loading);
+    assert_eq!(app.notion_pages.len(), 2);
+    assert_eq!(app.status_message.as_deref(), Some("✓ Loaded 2 pages from Notion"));
+
+    let failed = PageLoad {
+        initial: false,
+        pages: Err("offline".to_string()),
+        capabilities: None,
+    };
+    apply_page_load(&mut app, failed, None).await;
+    assert_eq!(app.notion_pages.len(), 2);
+    assert!(app.status_message.as_deref().unwrap().contains("Failed to fetch pages: offline"));
+}
+
+#[tokio::test]
+async fn test_terminal_events_drive_the_state_machine() {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+    use faultnote::app_event::AppEvent;
+    use faultnote::events::handle_event;
+
+    let press = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+    let mut release = press;
+    release.kind = KeyEventKind::Release;
+    assert!(AppEvent::from_terminal(Event::Key(release)).is_none());
+    assert!(AppEvent::from_terminal(Event::FocusGained).is_none());
+
+    let mut app = AppState::new();
+    let event = AppEvent::from_terminal(Event::Key(press)).unwrap();
+    handle_event(&mut app, event, None).await.unwrap();
+    assert!(!app.is_running());
+
+    let failed = AppEvent::InputFailed(std::io::Error::other("tty closed"));
+    assert!(handle_event(&mut AppState::new(), failed, None).await.is_err());
+}
+
+#[tokio::test]
+async fn test_page_loader_reports_through_the_event_bus() {
+    use faultnote::app_event::{AppEvent, EventBus};
+    use faultnote::notion::client::NotionClient;
+    use faultnote::page_loader::PageLoader;
+
+    // Nothing listens on this port, so the fetch fails once retries run out
+    let client = NotionClient {
+        base_url: "http://127.0.0.1:9".to_string(),
+        http_client: reqwest::Client::new(),
+        tokens: Vec::new(),
+        retries: Default::default(),
+        activity: Default::default(),
+        limiter: None,
+    };
+    let mut bus = EventBus::new();
+    let mut loader = PageLoader::new(client, bus.sender());
+    assert!(loader.refresh());
+    assert!(!loader.refresh());
+
+    let load = loop {
+        if let AppEvent::PagesLoaded(load) = bus.next().await {
+            break load;
+        }
+    };
+    assert!(load.initial);
+    assert!(load.pages.is_err());
+    assert!(loader.refresh());
+}
+
+#[tokio::test]
+async fn test_clear_asks_for_confirmation() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use faultnote::app::PendingAction;
+    use faultnote::events::handle_key_event;
+
+    let press = |code| KeyEvent::new(code, KeyModifiers::NONE);
+    let mut app = AppState::new();
+    app.error_input = "Boom".into();
+
+    handle_key_event(&mut app, press(KeyCode::Char('c')), None).await;
+    assert_eq!(app.pending_action, Some(PendingAction::ClearInputs));
+    assert!(!app.confirm_yes);
+    // Enter on the default No keeps the fields
+    handle_key_event(&mut app, press(KeyCode::Enter), None).await;
+    assert_eq!(app.pending_action, None);
+    assert!(app.has_input());
+
+    handle_key_event(&mut app, press(KeyCode::Char('c')), None).await;
+    handle_key_event(&mut app, press(KeyCode::Left), None).await;
+    assert!(app.confirm_yes);
+    handle_key_event(&mut app, press(KeyCode::Enter), None).await;
+    assert!(!app.has_input());
+    assert_eq!(app.status_message.as_deref(), Some("Inputs cleared"));
+}
+
+#[tokio::test]
+async fn test_submit_confirms_only_complete_entries() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use faultnote::app::{PageInfo, PendingAction};
+    use faultnote::events::handle_key_event;
+
+    let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+    let mut app = AppState::new();
+    app.set_pages(vec![PageInfo {
+        id: "page-1".to_string(),
+        title: "Backend".to_string(),
+        ..Default::default()
+    }]);
+
+    // Missing fields are reported without a dialog
+    handle_key_event(&mut app, enter, None).await;
+    assert_eq!(app.pending_action, None);
+    assert!(app.status_message.as_deref().unwrap().contains("Fill in Error"));
+
+    app.error_input = "Boom".into();
+    app.problem_input = "Why".into();
+    app.solution_input = "Fix".into();
+    handle_key_event(&mut app, enter, None).await;
+    assert_eq!(app.pending_action, Some(PendingAction::Submit));
+    assert_eq!(app.confirmation_prompt().as_deref(), Some("Submit this entry to Backend?"));
+    assert!(app.confirm_yes);
+
+    handle_key_event(&mut app, KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE), None).await;
+    assert_eq!(app.pending_action, None);
+    assert!(app.has_input());
+}
+
+#[tokio::test]
+async fn test_export_instead_of_notion() {
+    use faultnote::config::Config;
+    use faultnote::events::submit_to_notion;
+
+    let dir = std::env::temp_dir().join(format!("faultnote-export-only-{}", std::process::id()));
+    let mut app = AppState::new();
+    app.config = Config::from_toml(&format!("[export]\ndir = {:?}\nmode = \"instead\"\n", dir)).unwrap();
+    app.error_input = "Disk full".into();
+    app.problem_input = "Logs never rotated".into();
+    submit_to_notion(&mut app, None).await;
+    assert_eq!(app.status_message.as_deref(), Some("✗ Fill in Error, Problem, and Solution fields first"));
+
+    app.solution_input = "Enable logrotate".into();
+    submit_to_notion(&mut app, None).await;
+    assert!(app.status_message.as_deref().unwrap().starts_with("✓ Saved to "));
+    assert!(app.error_input.is_blank());
+    let files: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+    assert_eq!(files.len(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_partial_undo_restores_the_entry_and_keeps_the_rest() {
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use faultnote::app::{FaultLogEntry, LastSubmission};
+    use faultnote::events::handle_key_event;
+    use faultnote::notion::client::NotionClient;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // The first block is deleted, the second refused
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        for response in [
+            "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 2\r\n\r\n{}",
+            "HTTP/1.1 400 Bad Request\r\nConnection: close\r\nContent-Length: 2\r\n\r\n{}",
+        ] {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let _ = socket.read(&mut [0u8; 4096]).await;
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.unwrap();
+        }
+    });
+    let client = NotionClient {
+        base_url,
+        http_client: reqwest::Client::new(),
+        tokens: Vec::new(),
+        retries: Arc::default(),
+        activity: Default::default(),
+        limiter: None,
+    };
+
+    let mut app = AppState::new();
+    app.last_submission = Some(LastSubmission {
+        page_id: "page".to_string(),
+        entry: FaultLogEntry {
+            error: "Boom".to_string(),
+            ..Default::default()
+        },
+        block_ids: vec!["block-1".to_string(), "block-2".to_string()],
+        token: "default".to_string(),
+        submitted_at: Instant::now(),
+        history_id: None,
+    });
+    handle_key_event(&mut app, KeyEvent::new(KeyCode::Char('u'), KeyModifiers::NONE), Some(&client)).await;
+
+    assert_eq!(app.error_input, "Boom");
+    let last = app.last_submission.as_ref().unwrap();
+    assert_eq!(last.block_ids, ["block-2"]);
+    assert!(app.status_message.as_deref().unwrap().contains("removed 1 of 2 blocks"));
+}
+
+#[tokio::test]
+async fn test_undo_keeps_a_draft_started_since() {
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use faultnote::app::{FaultLogEntry, LastSubmission};
+    use faultnote::events::handle_key_event;
+    use faultnote::notion::client::NotionClient;
+    use faultnote::storage::{EntryStatus, History};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let _ = socket.read(&mut [0u8; 4096]).await;
+        let response = "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 2\r\n\r\n{}";
+        socket.write_all(response.as_bytes()).await.unwrap();
+        socket.shutdown().await.unwrap();
+    });
+    let client = NotionClient {
+        base_url,
+        http_client: reqwest::Client::new(),
+        tokens: Vec::new(),
+        retries: Arc::default(),
+        activity: Default::default(),
+        limiter: None,
+    };
+
+    let mut app = AppState::new();
+    app.history = History::open_in_memory().unwrap();
+    let entry = FaultLogEntry {
+        error: "Boom".to_string(),
+        ..Default::default()
+    };
+    let history_id = app.history.record("page", "Backend", &entry, EntryStatus::Submitted).unwrap();
+    app.last_submission = Some(LastSubmission {
+        page_id: "page".to_string(),
+        entry,
+        block_ids: vec!["block-1".to_string()],
+        token: "default".to_string(),
+        submitted_at: Instant::now(),
+        history_id,
+    });
+    app.error_input = "Next fault".into();
+    handle_key_event(&mut app, KeyEvent::new(KeyCode::Char('u'), KeyModifiers::NONE), Some(&client)).await;
+
+    assert_eq!(app.error_input, "Next fault");
+    assert!(app.last_submission.is_none());
+    let message = app.status_message.clone().unwrap();
+    assert!(message.contains("Your draft was kept"), "{}", message);
+    let recent = app.history.recent(1).unwrap();
+    assert_eq!(recent[0].status, EntryStatus::Undone);
+    assert_eq!(recent[0].entry.error, "Boom");
+}
+
+#[tokio::test]
+async fn test_export_waits_for_the_checks_and_happens_once() {
+    use std::sync::Arc;
+
+    use faultnote::app::{PageInfo, TargetKind};
+    use faultnote::config::Config;
+    use faultnote::events::submit_to_notion;
+    use faultnote::notion::client::NotionClient;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // Notion refuses every upload
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let _ = socket.read(&mut [0u8; 65536]).await;
+            let response = "HTTP/1.1 400 Bad Request\r\nConnection: close\r\nContent-Length: 2\r\n\r\n{}";
+            let _ = socket.write_all(response.as_bytes()).await;
+            let _ = socket.shutdown().await;
+        }
+    });
+    let client = NotionClient {
+        base_url,
+        http_client: reqwest::Client::new(),
+        tokens: Vec::new(),
+        retries: Arc::default(),
+        activity: Default::default(),
+        limiter: None,
+    };
+
+    let dir = std::env::temp_dir().join(format!("faultnote-export-once-{}", std::process::id()));
+    let mut app = AppState::new();
+    app.config = Config::from_toml(&format!("[export]\ndir = {:?}\n", dir)).unwrap();
+    app.set_pages(vec![PageInfo {
+        id: "page-1".to_string(),
+        title: "Backend".to_string(),
+        kind: TargetKind::Page,
+        parent: None,
+    }]);
+    app.error_input = "x".repeat(600 * 1024).into();
+    app.problem_input = "Runaway log".into();
+    app.solution_input = "Truncate it".into();
+    let exported = || std::fs::read_dir(&dir).map_or(0, |files| files.count());
+
+    // Asking to attach the overflow writes nothing yet
+    submit_to_notion(&mut app, Some(&client)).await;
+    assert!(app.overflow_confirmed);
+    assert_eq!(exported(), 0);
+
+    // The upload fails after the export; trying again keeps the one file
+    submit_to_notion(&mut app, Some(&client)).await;
+    assert!(app.status_message.as_deref().unwrap().contains("Failed to upload overflow"));
+    assert_eq!(exported(), 1);
+    submit_to_notion(&mut app, Some(&client)).await;
+    assert_eq!(exported(), 1);
+    assert!(app.has_input());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_sink_submission_runs_in_the_background_and_retries_failed_targets() {
+    use faultnote::app::{PageInfo, TargetKind};
+    use faultnote::events::{complete_submission, submit_to_notion};
+    use faultnote::sink::Sink;
+    use faultnote::sink::obsidian::{ObsidianConfig, ObsidianSink};
+
+    let vault = std::env::temp_dir().join(format!("faultnote-sink-retry-{}", std::process::id()));
+    // A directory where the note should be makes writing it fail
+    std::fs::create_dir_all(vault.join("Broken.md")).unwrap();
+    let config = ObsidianConfig {
+        vault: vault.display().to_string(),
+        folder: String::new(),
+    };
+    let mut app = AppState::new();
+    app.sink = Some(Sink::Obsidian(ObsidianSink::new(&config, "api").unwrap()));
+    let note = |name: &str| PageInfo {
+        id: name.to_string(),
+        title: name.to_string(),
+        kind: TargetKind::Page,
+        parent: None,
+    };
+    app.set_pages(vec![note("Broken"), note("Good")]);
+    app.checked_pages = vec!["Broken".to_string(), "Good".to_string()];
+    app.error_input = "Boom".into();
+    app.problem_input = "Bad config".into();
+    app.solution_input = "Fix it".into();
+
+    submit_to_notion(&mut app, None).await;
+    assert!(app.submission.is_some());
+    complete_submission(&mut app).await;
+    let message = app.status_message.clone().unwrap();
+    assert!(message.contains("but failed on Broken"), "{}", message);
+    assert_eq!(app.checked_pages, ["Broken"]);
+    assert!(app.has_input());
+
+    std::fs::remove_dir(vault.join("Broken.md")).unwrap();
+    submit_to_notion(&mut app, None).await;
+    complete_submission(&mut app).await;
+    assert!(!app.has_input());
+    let good = std::fs::read_to_string(vault.join("Good.md")).unwrap();
+    assert_eq!(good.matches("## Boom").count(), 1);
+    assert!(vault.join("Broken.md").is_file());
+    std::fs::remove_dir_all(&vault).unwrap();
+}
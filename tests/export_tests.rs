@@ -0,0 +1,79 @@
+// Tests for exporting entries as Markdown files
+
+use chrono::NaiveDate;
+use faultnote::app::{FaultLogEntry, FaultStatus, Severity};
+use faultnote::config::Config;
+use faultnote::export::{ExportMode, document, file_name, slug, write};
+use faultnote::notion::template::BlockTemplate;
+
+fn entry() -> FaultLogEntry {
+    FaultLogEntry {
+        error: "Connection refused: \"db\" (port 5432)\nat db.rs:12".to_string(),
+        problem: "DB down after deploy".to_string(),
+        solution: "Restart it".to_string(),
+        status: FaultStatus::Fixed,
+        severity: Some(Severity::High),
+        tags: vec!["db".to_string(), "prod".to_string()],
+        ..Default::default()
+    }
+}
+
+fn date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, 16).unwrap()
+}
+
+#[test]
+fn test_file_name_is_dated_and_slugged() {
+    assert_eq!(file_name(&entry(), date()), "2026-10-16-connection-refused-db-port-5432.md");
+    assert_eq!(slug("  --- "), "entry");
+    assert_eq!(slug("Ünïcode Fehler!"), "ünïcode-fehler");
+    assert_eq!(slug(&"word ".repeat(40)).chars().count(), 49);
+}
+
+#[test]
+fn test_document_has_frontmatter_then_the_entry() {
+    let pages = ["Backend Errors".to_string()];
+    let markdown = document(&entry(), &BlockTemplate::default(), &pages, date());
+    assert!(markdown.starts_with(
+        "---\n\
+         title: \"Connection refused: \\\"db\\\" (port 5432)\"\n\
+         date: 2026-10-16\n\
+         page: \"Backend Errors\"\n\
+         status: fixed\n\
+         severity: high\n\
+         tags: [\"db\", \"prod\"]\n\
+         ---\n\n\
+         ## Connection refused"
+    ));
+
+    let pages = ["A".to_string(), "B".to_string()];
+    let mut untagged = entry();
+    untagged.tags.clear();
+    let markdown = document(&untagged, &BlockTemplate::default(), &pages, date());
+    assert!(markdown.contains("page: [\"A\", \"B\"]\n"));
+    assert!(markdown.contains("tags: []\n"));
+}
+
+#[test]
+fn test_write_never_replaces_a_file() {
+    let dir = std::env::temp_dir().join(format!("faultnote-export-{}", std::process::id()));
+    let template = BlockTemplate::default();
+    let first = write(&dir, &entry(), &template, &[], date()).unwrap();
+    let second = write(&dir, &entry(), &template, &[], date()).unwrap();
+    assert_eq!(first.file_name().unwrap(), "2026-10-16-connection-refused-db-port-5432.md");
+    assert_eq!(second.file_name().unwrap(), "2026-10-16-connection-refused-db-port-5432-2.md");
+    assert!(std::fs::read_to_string(&second).unwrap().contains("### How did you fix it?\n\nRestart it"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_export_config() {
+    let config = Config::from_toml("[export]\ndir = \"/tmp/faults\"\nmode = \"instead\"\n").unwrap();
+    assert_eq!(config.export.mode, ExportMode::Instead);
+    assert!(config.export.replaces_notion());
+
+    let config = Config::from_toml("[export]\nmode = \"instead\"\n").unwrap();
+    assert_eq!(config.export.dir(), None);
+    assert!(!config.export.replaces_notion());
+    assert!(Config::from_toml("[export]\nmode = \"replace\"\n").is_err());
+}
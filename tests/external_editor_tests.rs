@@ -0,0 +1,43 @@
+// Tests for editing a field in an external editor
+
+#![cfg(unix)]
+
+use faultnote::external_editor::edit;
+
+#[test]
+fn test_edit_loads_the_saved_file() {
+    let edited = edit("Restart the old pool", "solution.md", "sed -i s/old/new/").unwrap();
+    assert_eq!(edited.as_deref(), Some("Restart the new pool"));
+}
+
+#[test]
+fn test_edit_drops_the_final_newline_only() {
+    let edited = edit("line one\n", "error.md", "sed -i $a\\last").unwrap();
+    assert_eq!(edited.as_deref(), Some("line one\nlast"));
+}
+
+#[test]
+fn test_edit_is_cancelled_when_the_editor_fails() {
+    assert_eq!(edit("Keep me", "problem.md", "false").unwrap(), None);
+}
+
+#[test]
+fn test_edit_reports_a_missing_editor() {
+    let error = edit("Text", "code.md", "faultnote-no-such-editor --wait").unwrap_err();
+    assert!(error.starts_with("Failed to start faultnote-no-such-editor"));
+    assert!(edit("Text", "code.md", "   ").unwrap_err().contains("No editor configured"));
+}
+
+#[test]
+fn test_edit_file_is_private_to_the_user() {
+    // An "editor" that replaces the file with its permissions
+    let dir = std::env::temp_dir().join(format!("faultnote-editor-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("mode.sh");
+    std::fs::write(&script, "#!/bin/sh\nmode=$(ls -l \"$1\" | cut -c1-10)\necho \"$mode\" > \"$1\"\n").unwrap();
+    std::fs::set_permissions(&script, std::os::unix::fs::PermissionsExt::from_mode(0o755)).unwrap();
+
+    let edited = edit("secret", "error.md", script.to_str().unwrap()).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    assert_eq!(edited.as_deref(), Some("-rw-------"));
+}
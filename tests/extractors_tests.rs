@@ -0,0 +1,82 @@
+// Tests for regex extractors from the config
+
+use faultnote::app::AppState;
+use faultnote::config::Config;
+use faultnote::extractors::{ExtractTarget, Extracted, extract_all};
+use faultnote::fields::InputField;
+
+const CONFIG: &str = r#"
+[[extractors]]
+name = "request_id"
+pattern = 'request_id=([\w-]+)'
+into = "metadata"
+
+[[extractors]]
+pattern = 'env=(\w+)'
+into = "tag"
+
+[[extractors]]
+pattern = '(?m)^Caused by: (?P<cause>.+)$'
+into = "problem"
+value = "Caused by ${cause}"
+"#;
+
+const LOG: &str = "ERROR handler failed request_id=ab-12 env=prod\n\
+                   Caused by: pool exhausted\n\
+                   retry request_id=cd-34 env=staging env=prod";
+
+#[test]
+fn test_extractors_find_values() {
+    let config = Config::from_toml(CONFIG).unwrap();
+    let found = extract_all(&config.extractors, LOG);
+    let extracted = |target, name: &str, value: &str| Extracted {
+        target,
+        name: name.to_string(),
+        value: value.to_string(),
+    };
+    assert_eq!(
+        found,
+        [
+            extracted(ExtractTarget::Metadata, "request_id", "ab-12"),
+            extracted(ExtractTarget::Tag, "extracted", "prod"),
+            extracted(ExtractTarget::Tag, "extracted", "staging"),
+            extracted(ExtractTarget::Problem, "cause", "Caused by pool exhausted"),
+        ]
+    );
+}
+
+#[test]
+fn test_bad_extractors_are_config_errors() {
+    assert!(Config::from_toml("[[extractors]]\npattern = '(unclosed'\ninto = \"tag\"\n").is_err());
+    let err = Config::from_toml("[[extractors]]\npattern = 'x'\ninto = \"tags\"\n").unwrap_err();
+    assert!(err.contains("did you mean 'tag'?"), "{}", err);
+}
+
+#[test]
+fn test_pasting_fills_empty_fields_tags_and_context() {
+    let mut app = AppState::new();
+    app.config = Config::from_toml(CONFIG).unwrap();
+    app.solution_input = "Raised the pool size".into();
+    app.context_input = "region: eu-west-1".into();
+    app.paste(LOG);
+
+    assert_eq!(app.error_input, LOG);
+    assert_eq!(app.problem_input, "Caused by pool exhausted");
+    assert_eq!(app.context_input, "region: eu-west-1\nrequest_id: ab-12");
+    assert_eq!(app.tags_input, "prod, staging");
+    assert_eq!(app.current_entry().tags, ["prod", "staging"]);
+    let status = app.status_message.clone().unwrap();
+    assert!(status.ends_with("extracted request_id, tag prod, tag staging, Problem"), "{}", status);
+
+    // A second paste does not repeat what is already there
+    app.paste(LOG);
+    assert_eq!(app.context_input, "region: eu-west-1\nrequest_id: ab-12");
+    assert_eq!(app.tags_input, "prod, staging");
+
+    // Filled fields undo like edits, and clearing drops the tags
+    app.active_input_field = InputField::Problem.index();
+    app.undo_edit();
+    assert_eq!(app.problem_input, "");
+    app.clear_inputs();
+    assert!(app.current_entry().tags.is_empty());
+}
@@ -0,0 +1,49 @@
+// Tests for the field schema and custom labels
+
+use faultnote::config::Config;
+use faultnote::fields::{FieldLabels, InputField};
+use faultnote::notion::client::{FaultLogEntry, create_error_block};
+
+#[test]
+fn test_field_order() {
+    assert_eq!(InputField::from_index(0), Some(InputField::Error));
+    assert_eq!(InputField::Context.index(), 5);
+    assert_eq!(InputField::from_index(6), Some(InputField::Tags));
+    assert_eq!(InputField::from_index(7), None);
+}
+
+#[test]
+fn test_default_labels() {
+    let labels = FieldLabels::default();
+    assert_eq!(labels.label(InputField::Solution), "Solution");
+    assert_eq!(labels.heading(InputField::Solution), "How did you fix it?");
+}
+
+#[test]
+fn test_custom_labels_reach_notion() {
+    let config = Config::from_toml(
+        "[template]\nstyle = \"plain\"\n[template.labels]\nerror = \"Symptom\"\nsolution = \"Fix\"",
+    )
+    .unwrap();
+    let labels = &config.template.labels;
+    assert_eq!(labels.label(InputField::Error), "Symptom");
+    assert_eq!(labels.heading(InputField::Solution), "Fix");
+    assert_eq!(labels.heading(InputField::Problem), "What was the problem?");
+
+    let entry = FaultLogEntry {
+        error: "E".to_string(),
+        problem: "P".to_string(),
+        solution: "S".to_string(),
+        ..Default::default()
+    };
+    let block = create_error_block(&entry, None, &config.template);
+    let children = block[0]["heading_2"]["children"].as_array().unwrap();
+    assert_eq!(children[1]["paragraph"]["rich_text"][0]["text"]["content"], "Symptom: ");
+    assert_eq!(children[4]["heading_3"]["rich_text"][0]["text"]["content"], "Fix");
+    assert_eq!(children[5]["paragraph"]["rich_text"][0]["text"]["content"], "Fix: ");
+}
+
+#[test]
+fn test_unknown_label_is_rejected() {
+    assert!(Config::from_toml("[template.labels]\nsymptom = \"x\"").is_err());
+}
@@ -0,0 +1,101 @@
+// Tests for checking and repairing local files
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{TimeZone, Utc};
+use faultnote::app::FaultLogEntry;
+use faultnote::fsck::{check, journal_path, write_atomic};
+use faultnote::queue::SubmissionQueue;
+use faultnote::storage::{EntryStatus, History};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("faultnote-fsck-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_intact_files_have_no_findings() {
+    let dir = temp_dir("intact");
+    write_atomic(&dir.join("tokens.json"), r#"{"page-1": "team"}"#).unwrap();
+    assert!(!journal_path(&dir.join("tokens.json")).exists());
+    History::open(dir.join("history.sqlite3")).unwrap();
+
+    assert_eq!(check(&dir, true, Utc::now()).unwrap(), Vec::new());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_interrupted_write_is_restored_from_its_copy() {
+    let dir = temp_dir("journal");
+    let path = dir.join("tokens.json");
+    fs::write(&path, r#"{"page-1": "te"#).unwrap();
+    fs::write(journal_path(&path), r#"{"page-1": "team"}"#).unwrap();
+
+    // A dry run changes nothing
+    let findings = check(&dir, false, Utc::now()).unwrap();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].fix, "restored from the complete copy");
+    assert!(journal_path(&path).exists());
+
+    check(&dir, true, Utc::now()).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), r#"{"page-1": "team"}"#);
+    assert!(!journal_path(&path).exists());
+    assert!(check(&dir, true, Utc::now()).unwrap().is_empty());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_unreadable_records_are_quarantined() {
+    let dir = temp_dir("quarantine");
+    let now = Utc.with_ymd_and_hms(2026, 10, 16, 9, 30, 0).unwrap();
+    fs::write(dir.join("queue.json"), r#"[{"id": 1}]"#).unwrap();
+    let audit = r#"{"timestamp":"t","action":"append_blocks","page_id":null,"created_ids":[],"payload_sha256":"","token":null}"#;
+    fs::write(dir.join("audit.jsonl"), format!("{}\n{{\"timest", audit)).unwrap();
+    fs::write(dir.join("page_settings.json"), "not json").unwrap();
+
+    let findings = check(&dir, true, now).unwrap();
+    let files: Vec<&str> = findings.iter().map(|f| f.file.as_str()).collect();
+    assert_eq!(files, ["queue.json", "page_settings.json", "audit.jsonl"]);
+    assert_eq!(findings[2].fix, "moved them to quarantine/20261016T093000-audit.jsonl");
+
+    // The readable records stay and the rest is kept aside
+    assert!(SubmissionQueue::load(dir.join("queue.json")).unwrap().is_empty());
+    assert_eq!(fs::read_to_string(dir.join("audit.jsonl")).unwrap(), format!("{}\n", audit));
+    assert!(!dir.join("page_settings.json").exists());
+    let kept = dir.join("quarantine").join("20261016T093000-queue.json");
+    assert_eq!(fs::read_to_string(kept).unwrap(), r#"{"id":1}"#);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_unreadable_history_rows_are_quarantined() {
+    let dir = temp_dir("history");
+    let path = dir.join("history.sqlite3");
+    let history = History::open(path.clone()).unwrap();
+    let entry = FaultLogEntry {
+        error: "Timeout".to_string(),
+        ..Default::default()
+    };
+    history.record("p", "Backend", &entry, EntryStatus::Submitted).unwrap();
+    let broken = history.record("p", "Backend", &entry, EntryStatus::Submitted).unwrap().unwrap();
+    drop(history);
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    conn.execute("UPDATE entries SET entry_json = '{' WHERE id = ?1", [broken]).unwrap();
+    drop(conn);
+
+    let findings = check(&dir, true, Utc::now()).unwrap();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].problem, "1 unreadable entries");
+    assert_eq!(History::open(path).unwrap().recent(10).unwrap().len(), 1);
+
+    // A file that is not a database at all is moved aside
+    fs::write(dir.join("history.sqlite3"), "garbage that is not sqlite").unwrap();
+    let findings = check(&dir, true, Utc::now()).unwrap();
+    assert!(findings[0].fix.ends_with("FaultNote starts a new history"));
+    assert!(!dir.join("history.sqlite3").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,26 @@
+// Tests for fuzzy matching
+
+use faultnote::fuzzy::{closest, rank, score};
+
+#[test]
+fn test_substring_beats_scattered_match() {
+    assert!(score("bug", "Bug Tracker").unwrap() < score("bug", "Build Unit Guide").unwrap());
+    assert_eq!(score("", "anything"), Some(0));
+    assert_eq!(score("xyz", "Bug Tracker"), None);
+}
+
+#[test]
+fn test_rank_orders_matches() {
+    let titles = ["Backend Errors", "Frontend bugs", "Bug Tracker", "Billing"];
+    assert_eq!(rank("bug", titles), [2, 1]);
+    assert_eq!(rank("be", titles), [0, 2]);
+    assert_eq!(rank("", titles), [0, 1, 2, 3]);
+}
+
+#[test]
+fn test_closest_spelling() {
+    let keys = ["color", "level", "toggleable"];
+    assert_eq!(closest("colour", keys), Some("color"));
+    assert_eq!(closest("Toggleble", keys), Some("toggleable"));
+    assert_eq!(closest("size", keys), None);
+}
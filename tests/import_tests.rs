@@ -0,0 +1,94 @@
+// Tests for importing entries from a file and resuming an import
+
+use std::path::PathBuf;
+
+use faultnote::failure::FailureClass;
+use faultnote::import::{ImportProgress, ImportStatus, Imported, failure, parse};
+
+const FILE: &str = r#"# exported from the old tracker
+{"error": "E1", "problem": "P1", "solution": "S1", "page": "Backend"}
+
+{"error": "E2", "problem": "P2", "solution": "S2", "code": "x = 1", "language": "py"}
+"#;
+
+#[test]
+fn test_parse_reads_one_entry_per_line() {
+    let lines = parse(FILE).unwrap();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].number, 2);
+    assert_eq!(lines[0].request.page.as_deref(), Some("Backend"));
+    assert_eq!(lines[1].number, 4);
+    assert_eq!(lines[1].request.language.as_deref(), Some("py"));
+    assert_ne!(lines[0].sha256, lines[1].sha256);
+
+    assert!(parse("{\"error\": \"E\"").unwrap_err().starts_with("Line 1:"));
+    assert_eq!(parse("{\"problem\": \"P\"}").unwrap_err(), "Line 1: \"error\" is empty");
+}
+
+#[test]
+fn test_resume_keeps_outcomes_of_unchanged_lines() {
+    let source = PathBuf::from("entries.jsonl");
+    let lines = parse(FILE).unwrap();
+    let mut progress = ImportProgress::new(source.clone(), &lines, None);
+    assert!(!progress.has_started());
+    progress.entries[0].status = ImportStatus::Sent;
+    assert!(progress.has_started());
+
+    // A line inserted above and an edited second entry: the first entry is
+    // still done, the edited one is new
+    let edited = format!(
+        "{{\"error\": \"E0\"}}\n{}",
+        FILE.replace("\"S2\"", "\"S2, for real\"")
+    );
+    let lines = parse(&edited).unwrap();
+    let resumed = ImportProgress::new(source, &lines, Some(&progress));
+    let statuses: Vec<ImportStatus> = resumed.entries.iter().map(|entry| entry.status).collect();
+    assert_eq!(statuses, [ImportStatus::Pending, ImportStatus::Sent, ImportStatus::Pending]);
+    assert_eq!(resumed.entries[1].line, 3);
+}
+
+#[test]
+fn test_import_failure_is_partial_when_some_entries_went_through() {
+    let lines = parse(FILE).unwrap();
+    let mut progress = ImportProgress::new(PathBuf::from("entries.jsonl"), &lines, None);
+    progress.entries[1].error = Some("No page or database matches \"Backend\"".to_string());
+    let imported = |progress: &ImportProgress| Imported {
+        progress: progress.clone(),
+        skipped: 0,
+    };
+    assert_eq!(failure(&imported(&progress)).unwrap().class, FailureClass::Validation);
+
+    progress.entries[0].status = ImportStatus::Queued;
+    let partial = failure(&imported(&progress)).unwrap();
+    assert_eq!(partial.class, FailureClass::Partial);
+    assert!(partial.message.contains("1 of 2 entries not imported"));
+    assert!(partial.message.contains("line 4: No page"));
+
+    progress.entries[1].status = ImportStatus::Sent;
+    assert!(failure(&imported(&progress)).is_none());
+}
+
+#[test]
+fn test_duplicates_stay_skipped_on_resume() {
+    let lines = parse(FILE).unwrap();
+    let mut progress = ImportProgress::new(PathBuf::from("entries.jsonl"), &lines, None);
+    progress.entries[0].status = ImportStatus::Duplicate;
+    progress.entries[0].error = Some("already in the local history".to_string());
+    assert!(progress.has_started());
+
+    let resumed = ImportProgress::new(PathBuf::from("entries.jsonl"), &lines, Some(&progress));
+    assert_eq!(resumed.entries[0].status, ImportStatus::Duplicate);
+    // Skipped duplicates are not a failure
+    let imported = Imported {
+        progress: resumed,
+        skipped: 1,
+    };
+    assert_eq!(failure(&imported).unwrap().message.split(';').next(), Some("1 of 2 entries not imported"));
+
+    // The hash ignores whitespace around fields, so a reformatted line is
+    // still the same entry
+    let request = &lines[1].request;
+    let mut reformatted = request.entry();
+    reformatted.solution = format!("  {}  ", reformatted.solution);
+    assert_eq!(reformatted.content_hash(), request.entry().content_hash());
+}
@@ -0,0 +1,53 @@
+// Tests for configurable key bindings
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use faultnote::config::Config;
+use faultnote::keys::{Action, KeyBindings};
+
+fn key(c: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+}
+
+#[test]
+fn test_default_bindings() {
+    let keys = KeyBindings::default();
+    assert_eq!(keys.normal_action(&key('q')), Some(Action::Quit));
+    assert_eq!(keys.normal_action(&key('i')), Some(Action::EditMode));
+    assert_eq!(keys.normal_action(&KeyEvent::new(KeyCode::Char('R'), KeyModifiers::SHIFT)), Some(Action::RetryQueue));
+    assert_eq!(keys.normal_action(&key('z')), None);
+    // Same key, different mode
+    let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+    assert_eq!(keys.normal_action(&esc), Some(Action::ClearStatus));
+    assert_eq!(keys.editing_action(&esc), Some(Action::ExitEdit));
+    assert_eq!(keys.editing_action(&key('q')), None);
+}
+
+#[test]
+fn test_rebinding_from_config() {
+    let config = Config::from_toml(
+        r#"
+        [keys]
+        quit = "Ctrl+q"
+        edit_mode = ["a", "Enter"]
+        submit = "Ctrl+s"
+        exit_edit = "Ctrl+c"
+        "#,
+    )
+    .unwrap();
+    let keys = &config.keys;
+
+    assert_eq!(keys.normal_action(&key('q')), None);
+    assert_eq!(keys.normal_action(&KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL)), Some(Action::Quit));
+    assert_eq!(keys.normal_action(&KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)), Some(Action::EditMode));
+    assert_eq!(keys.label(Action::Quit), "Ctrl+q");
+    assert_eq!(keys.label(Action::Submit), "Ctrl+s");
+}
+
+#[test]
+fn test_invalid_bindings_rejected() {
+    let conflict = Config::from_toml("[keys]\nquit = \"c\"").unwrap_err();
+    assert!(conflict.contains("bound to both"), "{}", conflict);
+
+    assert!(Config::from_toml("[keys]\nquit = \"Hyper\"").is_err());
+    assert!(Config::from_toml("[keys]\nfly = \"f\"").is_err());
+}
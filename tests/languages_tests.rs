@@ -0,0 +1,47 @@
+// Tests for the code language list and picker
+
+use faultnote::app::AppState;
+use faultnote::notion::languages::{DEFAULT_LANGUAGE, LANGUAGES, normalize};
+
+#[test]
+fn test_normalize_language_names() {
+    assert_eq!(normalize("Python"), Some("python"));
+    assert_eq!(normalize(" ts "), Some("typescript"));
+    assert_eq!(normalize("golang"), Some("go"));
+    assert_eq!(normalize("C++"), Some("c++"));
+    assert_eq!(normalize("brainfuck"), None);
+    assert!(LANGUAGES.contains(&DEFAULT_LANGUAGE));
+    assert!(LANGUAGES.contains(&AppState::MERMAID_LANGUAGE));
+    assert!(LANGUAGES.contains(&AppState::DIFF_LANGUAGE));
+}
+
+#[test]
+fn test_language_picker() {
+    let mut app = AppState::new();
+    app.start_language_picker();
+    assert!(app.language_picking);
+    assert_eq!(LANGUAGES[app.language_matches[app.selected_language]], "rust");
+
+    for c in "pyth".chars() {
+        app.language_filter.push(c);
+        app.refresh_language_filter();
+    }
+    assert_eq!(LANGUAGES[app.language_matches[0]], "python");
+    app.accept_language();
+    assert!(!app.language_picking);
+    assert_eq!(app.code_language, "python");
+
+    app.error_input = "err".into();
+    app.problem_input = "prob".into();
+    app.solution_input = "fix".into();
+    app.notion_pages = vec![Default::default()];
+    let (_, entry) = app.get_submission_data().unwrap();
+    assert_eq!(entry.language.as_deref(), Some("python"));
+
+    // Cancelling keeps the language
+    app.start_language_picker();
+    app.language_filter.push('g');
+    app.refresh_language_filter();
+    app.cancel_language_picker();
+    assert_eq!(app.code_language, "python");
+}
@@ -0,0 +1,56 @@
+// Tests for Notion payload limits and overflow handling
+
+use faultnote::notion::client::{create_error_block, FaultLogEntry};
+use faultnote::notion::limits::{split_overflow, PayloadReport, MAX_TEXT_LENGTH};
+use faultnote::notion::template::BlockTemplate;
+
+fn entry() -> FaultLogEntry {
+    FaultLogEntry {
+        error: "Error".to_string(),
+        problem: "Problem".to_string(),
+        solution: "Solution".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_small_entry_within_limits() {
+    let blocks = create_error_block(&entry(), None, &BlockTemplate::default());
+    let report = PayloadReport::of(&blocks);
+    assert_eq!(report.blocks, 8); // Heading + 7 children
+    assert!(!report.exceeds_limits());
+}
+
+#[test]
+fn test_long_text_split_into_runs() {
+    let mut entry = entry();
+    entry.code = Some("x".repeat(MAX_TEXT_LENGTH * 2 + 10));
+    let blocks = create_error_block(&entry, None, &BlockTemplate::default());
+
+    let children = blocks[0]["heading_2"]["children"].as_array().unwrap();
+    let code = children.iter().find(|c| c["type"] == "code").unwrap();
+    let runs = code["code"]["rich_text"].as_array().unwrap();
+    assert_eq!(runs.len(), 3);
+    assert_eq!(runs[2]["text"]["content"].as_str().unwrap().len(), 10);
+}
+
+#[test]
+fn test_oversized_checklist_exceeds_limits_and_splits() {
+    let mut entry = entry();
+    let steps: Vec<String> = (0..150).map(|i| format!("step {}", i)).collect();
+    entry.verification = Some(steps.join("\n"));
+
+    let blocks = create_error_block(&entry, None, &BlockTemplate::default());
+    assert!(PayloadReport::of(&blocks).exceeds_limits());
+
+    let (trimmed, overflow) = split_overflow(&entry).unwrap();
+    let blocks = create_error_block(&trimmed, None, &BlockTemplate::default());
+    assert!(!PayloadReport::of(&blocks).exceeds_limits());
+    assert!(overflow.contains("===== Verification ====="));
+    assert!(overflow.contains("step 149"));
+}
+
+#[test]
+fn test_nothing_to_split() {
+    assert!(split_overflow(&entry()).is_none());
+}
@@ -0,0 +1,84 @@
+// Tests for links found in the Error and Code fields
+
+use faultnote::app::{AppState, FaultLogEntry};
+use faultnote::links::{entry_links, find_links};
+use faultnote::notion::client::create_error_block;
+use faultnote::notion::template::BlockTemplate;
+
+#[test]
+fn test_find_links_trims_punctuation() {
+    let text = "See https://grafana.example.com/d/abc?from=now-1h. Trace: (http://jaeger:16686/trace/4bf92f)\n\
+                \"https://ci.example.com/run/42\", https://ci.example.com/run/42 again";
+    assert_eq!(
+        find_links(text),
+        [
+            "https://grafana.example.com/d/abc?from=now-1h",
+            "http://jaeger:16686/trace/4bf92f",
+            "https://ci.example.com/run/42",
+        ]
+    );
+}
+
+#[test]
+fn test_find_links_keeps_brackets_of_the_url() {
+    assert_eq!(
+        find_links("docs at https://en.wikipedia.org/wiki/Rust_(programming_language)."),
+        ["https://en.wikipedia.org/wiki/Rust_(programming_language)"]
+    );
+    assert!(find_links("no link: https:// or ftp://host").is_empty());
+}
+
+#[test]
+fn test_entry_links_read_error_then_code() {
+    let entry = FaultLogEntry {
+        error: "Failed, see https://b.example.com".to_string(),
+        code: Some("curl https://a.example.com https://b.example.com".to_string()),
+        solution: "https://not.scanned.example.com".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(entry_links(&entry), ["https://b.example.com", "https://a.example.com"]);
+}
+
+#[test]
+fn test_related_links_become_bookmarks() {
+    let entry = FaultLogEntry {
+        error: "Slow query https://grafana.example.com/d/db".to_string(),
+        problem: "p".to_string(),
+        solution: "s".to_string(),
+        ..Default::default()
+    };
+    let find_bookmarks = |template: &BlockTemplate| {
+        let blocks = create_error_block(&entry, None, template);
+        let children = blocks[0]["heading_2"]["children"].as_array().unwrap().clone();
+        children
+            .iter()
+            .filter(|block| block["type"] == "bookmark")
+            .map(|block| block["bookmark"]["url"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert!(find_bookmarks(&BlockTemplate::default()).is_empty());
+    let template = BlockTemplate {
+        related_links: true,
+        ..Default::default()
+    };
+    assert_eq!(find_bookmarks(&template), ["https://grafana.example.com/d/db"]);
+}
+
+#[test]
+fn test_link_selection_wraps() {
+    let mut app = AppState::new();
+    assert_eq!(app.selected_link(), None);
+    app.error_input = "https://one.example.com and https://two.example.com".into();
+    app.code_input = "https://three.example.com".into();
+    assert_eq!(app.selected_link().as_deref(), Some("https://one.example.com"));
+    app.previous_link();
+    assert_eq!(app.selected_link().as_deref(), Some("https://three.example.com"));
+    app.next_link();
+    app.next_link();
+    assert_eq!(app.selected_link().as_deref(), Some("https://two.example.com"));
+
+    // Removing links keeps a valid selection
+    app.code_input = "".into();
+    app.error_input = "https://one.example.com".into();
+    assert_eq!(app.selected_link().as_deref(), Some("https://one.example.com"));
+}
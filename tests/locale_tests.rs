@@ -0,0 +1,70 @@
+// Tests for the locale of emitted Notion content
+
+use faultnote::app::FaultLogEntry;
+use faultnote::config::Config;
+use faultnote::fields::InputField;
+use faultnote::notion::client::create_error_block;
+use faultnote::notion::locale::Locale;
+use faultnote::vars::Vars;
+
+#[test]
+fn test_locale_codes() {
+    assert_eq!(Locale::parse("de"), Some(Locale::De));
+    assert_eq!(Locale::parse("pt-BR"), Some(Locale::Pt));
+    assert_eq!(Locale::parse("fr_FR.UTF-8"), Some(Locale::Fr));
+    assert_eq!(Locale::parse("xx"), None);
+
+    let err = Config::from_toml("[template]\nlocale = \"klingon\"").unwrap_err();
+    assert!(err.contains("unknown locale \"klingon\""), "{}", err);
+}
+
+#[test]
+fn test_renamed_labels_win_over_locale() {
+    let toml = r#"
+        [template]
+        locale = "es"
+        [template.labels]
+        solution = "Arreglo"
+    "#;
+    let template = Config::from_toml(toml).unwrap().template;
+    assert_eq!(template.heading(InputField::Problem), "¿Cuál era el problema?");
+    assert_eq!(template.heading(InputField::Solution), "Arreglo");
+    assert_eq!(template.label(InputField::Error), "Error");
+    // The TUI keeps its English titles
+    assert_eq!(template.labels.label(InputField::Problem), "Problem");
+}
+
+#[test]
+fn test_page_locale_override() {
+    let toml = r#"
+        [template]
+        locale = "de"
+        [pages.abc]
+        locale = "nl"
+    "#;
+    let config = Config::from_toml(toml).unwrap();
+    assert_eq!(config.template_for("abc").locale, Locale::Nl);
+    assert_eq!(config.template_for("other").locale, Locale::De);
+}
+
+#[test]
+fn test_emitted_blocks_follow_locale() {
+    let entry = FaultLogEntry {
+        error: "Timeout".to_string(),
+        problem: "Slow DNS".to_string(),
+        solution: "Cache lookups".to_string(),
+        tags: vec!["net".to_string()],
+        ..Default::default()
+    };
+    let template = Config::from_toml("[template]\nlocale = \"de\"\ntitle = \"{date} {error}\"").unwrap().template;
+    let vars = Vars::for_entry(&entry, None, template.locale);
+    let date = vars.get("date").unwrap().to_string();
+    assert_eq!(date.matches('.').count(), 2, "{}", date);
+
+    let json = create_error_block(&entry, None, &template.resolve(&vars)).to_string();
+    assert!(json.contains("Was war das Problem?"));
+    assert!(json.contains("Wie wurde es behoben?"));
+    assert!(json.contains("Schlagwörter: "));
+    assert!(json.contains(&format!("{} Timeout", date)));
+    assert!(!json.contains("What was the problem?"));
+}
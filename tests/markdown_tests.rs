@@ -0,0 +1,85 @@
+// Tests for entries as Markdown
+
+use faultnote::app::{AppState, FaultLogEntry};
+use faultnote::markdown::entry_markdown;
+use faultnote::notion::locale::Locale;
+use faultnote::notion::template::BlockTemplate;
+
+fn entry() -> FaultLogEntry {
+    FaultLogEntry {
+        error: "Connection refused\nat db.rs:12".to_string(),
+        problem: "DB down after deploy".to_string(),
+        solution: "Restart it".to_string(),
+        code: Some("systemctl restart db".to_string()),
+        language: Some("bash".to_string()),
+        verification: Some("- health check\n\nquery runs".to_string()),
+        context: Some("region: eu\nhotfix".to_string()),
+        tags: vec!["db".to_string(), "prod".to_string()],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_entry_markdown_sections() {
+    let markdown = entry_markdown(&entry(), &BlockTemplate::default());
+    assert_eq!(
+        markdown,
+        "## Connection refused\n\n\
+         ### Error\n\nConnection refused\nat db.rs:12\n\n\
+         ### What was the problem?\n\nDB down after deploy\n\n\
+         ### How did you fix it?\n\nRestart it\n\n\
+         ### Code Reference\n\n```bash\nsystemctl restart db\n```\n\n\
+         ### How to verify?\n\n- [ ] health check\n- [ ] query runs\n\n\
+         ### Context\n\n- **region**: eu\n- hotfix\n\n\
+         **Tags:** db, prod\n"
+    );
+}
+
+#[test]
+fn test_entry_markdown_follows_the_template() {
+    let template = BlockTemplate {
+        title: Some("Incident 42".to_string()),
+        locale: Locale::De,
+        ..Default::default()
+    };
+    let entry = FaultLogEntry {
+        error: "Timeout".to_string(),
+        code: Some("   ".to_string()),
+        language: Some("plain text".to_string()),
+        ..Default::default()
+    };
+    let markdown = entry_markdown(&entry, &template);
+    assert!(markdown.starts_with("## Incident 42\n\n### Fehler\n\nTimeout"));
+    assert!(!markdown.contains("```"));
+}
+
+#[test]
+fn test_app_entry_markdown_uses_the_editor() {
+    let mut app = AppState::new();
+    app.error_input = "Panic in worker".into();
+    app.code_input = "unwrap()".into();
+    app.code_language = "rust".to_string();
+    let markdown = app.entry_markdown();
+    assert!(markdown.starts_with("## Panic in worker\n"));
+    assert!(markdown.contains("```rust\nunwrap()\n```"));
+}
+
+#[test]
+fn test_sub_entries_in_markdown() {
+    let mut group = FaultLogEntry {
+        error: "Checkout outage".to_string(),
+        problem: "Three services failed".to_string(),
+        solution: "Rolled back".to_string(),
+        ..Default::default()
+    };
+    group.sub_entries = vec![FaultLogEntry {
+        error: "Timeout in payments\nat pay.rs:3".to_string(),
+        solution: "Raised the limit".to_string(),
+        ..Default::default()
+    }];
+    let markdown = entry_markdown(&group, &BlockTemplate::default());
+    assert!(markdown.ends_with(
+        "### How did you fix it?\n\nRolled back\n\n---\n\n### 1. Timeout in payments\n\n\
+         #### Error\n\nTimeout in payments\nat pay.rs:3\n\n#### How did you fix it?\n\nRaised the limit\n"
+    ));
+}
@@ -0,0 +1,59 @@
+// Tests for special field content helpers
+
+use faultnote::markup::{
+    classify_diff_line, display_equation, looks_like_diff, split_equations, validate_mermaid, DiffLine,
+    Segment,
+};
+
+#[test]
+fn test_valid_mermaid_flowchart() {
+    let diagram = "%% retry flow\ngraph TD\n  A[Request] --> B{429?}\n  subgraph retry\n    B --> A\n  end";
+    assert!(validate_mermaid(diagram).is_ok());
+}
+
+#[test]
+fn test_mermaid_unknown_diagram_type() {
+    let err = validate_mermaid("grpah TD\nA --> B").unwrap_err();
+    assert!(err.contains("grpah"));
+}
+
+#[test]
+fn test_mermaid_unbalanced() {
+    assert!(validate_mermaid("graph TD\nA[Start --> B").is_err());
+    assert!(validate_mermaid("sequenceDiagram\nloop every minute\nA->>B: ping").is_err());
+    assert!(validate_mermaid("   \n").is_err());
+}
+
+#[test]
+fn test_split_equations() {
+    assert_eq!(
+        split_equations("loss $$L = -\\log p$$ exploded"),
+        vec![
+            Segment::Text("loss "),
+            Segment::Equation("L = -\\log p"),
+            Segment::Text(" exploded"),
+        ]
+    );
+    assert_eq!(split_equations("costs $$5"), vec![Segment::Text("costs $$5")]);
+    assert_eq!(split_equations(""), vec![Segment::Text("")]);
+}
+
+#[test]
+fn test_display_equation() {
+    assert_eq!(display_equation("  $$ e^{i\\pi} + 1 = 0 $$ "), Some("e^{i\\pi} + 1 = 0"));
+    assert_eq!(display_equation("see $$x$$"), None);
+    assert_eq!(display_equation("$$a$$ and $$b$$"), None);
+}
+
+#[test]
+fn test_diff_detection_and_classification() {
+    assert!(looks_like_diff("diff --git a/src/main.rs b/src/main.rs\n--- a/src/main.rs"));
+    assert!(looks_like_diff("\n@@ -1,3 +1,4 @@\n fn main() {"));
+    assert!(!looks_like_diff("fn main() {}\n-1"));
+
+    assert_eq!(classify_diff_line("+++ b/src/main.rs"), DiffLine::Meta);
+    assert_eq!(classify_diff_line("+    retry();"), DiffLine::Added);
+    assert_eq!(classify_diff_line("-    panic!()"), DiffLine::Removed);
+    assert_eq!(classify_diff_line("@@ -1 +1 @@"), DiffLine::Hunk);
+    assert_eq!(classify_diff_line("     ok"), DiffLine::Context);
+}
@@ -0,0 +1,61 @@
+// Tests for connectivity monitoring
+
+use std::time::Duration;
+
+use faultnote::app::AppState;
+use faultnote::network::{ConnectivityMonitor, Health, ping};
+use faultnote::notion::client::NotionClient;
+use tokio::sync::mpsc;
+
+#[tokio::test]
+async fn test_monitor_forwards_each_ping() {
+    use faultnote::app_event::AppEvent;
+
+    // Port 9 on localhost (discard) is closed in test environments
+    let client = NotionClient {
+        base_url: "http://127.0.0.1:9".to_string(),
+        http_client: reqwest::Client::new(),
+        tokens: Vec::new(),
+        retries: Default::default(),
+        activity: Default::default(),
+        limiter: None,
+    };
+    let (events, mut received) = mpsc::unbounded_channel();
+    ConnectivityMonitor::spawn(client).forward(events);
+
+    // The first ping goes out right away
+    assert!(matches!(received.recv().await, Some(AppEvent::Health(health)) if health == Health::offline()));
+}
+
+#[test]
+fn test_degraded_health() {
+    assert_eq!(Health::online(Duration::from_millis(120)).label(), "healthy");
+    assert!(Health::online(Duration::from_secs(3)).is_degraded());
+    assert_eq!(Health::online(Duration::from_secs(3)).label(), "degraded");
+    assert!(!Health::offline().is_degraded());
+    assert_eq!(Health::offline().label(), "offline");
+}
+
+#[test]
+fn test_record_health_reports_reachability_changes() {
+    let mut app = AppState::new();
+    assert!(!app.record_health(Health::online(Duration::from_millis(50))));
+    assert!(app.record_health(Health::offline()));
+    assert!(!app.online);
+    assert!(app.record_health(Health::online(Duration::from_millis(50))));
+    assert!(app.online);
+}
+
+#[tokio::test]
+async fn test_ping_unreachable_host() {
+    // Port 9 on localhost (discard) is closed in test environments
+    let client = NotionClient {
+        base_url: "http://127.0.0.1:9".to_string(),
+        http_client: reqwest::Client::new(),
+        tokens: Vec::new(),
+        retries: Default::default(),
+        activity: Default::default(),
+        limiter: None,
+    };
+    assert_eq!(ping(&client).await, Health::offline());
+}
@@ -0,0 +1,111 @@
+// Tests for webhook notifications
+
+use faultnote::app::{FaultLogEntry, Severity};
+use faultnote::config::Config;
+use faultnote::notify::{Notice, NotifyConfig, NotifyFormat, payload, send};
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+fn entry() -> FaultLogEntry {
+    FaultLogEntry {
+        error: "Connection refused".to_string(),
+        problem: "\nDB <primary> down after deploy\nsecond line".to_string(),
+        solution: "Restart it".to_string(),
+        severity: Some(Severity::High),
+        tags: vec!["db".to_string()],
+        ..Default::default()
+    }
+}
+
+fn notice(entry: &FaultLogEntry) -> Notice<'_> {
+    Notice {
+        title: "Connection refused",
+        page: "Backend & DB",
+        url: "https://www.notion.so/abc#def",
+        entry,
+    }
+}
+
+#[test]
+fn test_slack_payload_summarizes_the_entry() {
+    let entry = entry();
+    let body = payload(NotifyFormat::Slack, &notice(&entry));
+    assert_eq!(
+        body["text"],
+        "🔥 *Connection refused* logged to <https://www.notion.so/abc#def|Backend &amp; DB>\n\
+         *Problem:* DB &lt;primary&gt; down after deploy\n*Fix:* Restart it"
+    );
+
+    let unlinked = Notice { url: "", ..notice(&entry) };
+    assert!(payload(NotifyFormat::Slack, &unlinked)["text"].as_str().unwrap().contains("logged to *Backend &amp; DB*"));
+}
+
+#[test]
+fn test_json_payload_has_the_fields() {
+    let entry = entry();
+    let body = payload(NotifyFormat::Json, &notice(&entry));
+    assert_eq!(body["url"], "https://www.notion.so/abc#def");
+    assert_eq!(body["severity"], "high");
+    assert_eq!(body["status"], "open");
+    assert_eq!(body["tags"], json!(["db"]));
+}
+
+#[test]
+fn test_notify_config() {
+    assert!(Config::default().notify.webhook_url().is_none());
+    let config = Config::from_toml("[notify]\nurl_env = \"FAULTNOTE_TEST_UNSET_WEBHOOK\"\nformat = \"json\"\n").unwrap();
+    assert_eq!(config.notify.format, NotifyFormat::Json);
+    let err = config.notify.webhook_url().unwrap().unwrap_err();
+    assert!(err.contains("FAULTNOTE_TEST_UNSET_WEBHOOK"), "{}", err);
+
+    // Any variable that is set will do
+    let config = NotifyConfig {
+        url_env: Some("PATH".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(config.webhook_url().unwrap().unwrap(), std::env::var("PATH").unwrap());
+}
+
+/// Answer one request with `response` and hand over what was received
+async fn serve_once(response: &'static str) -> (String, oneshot::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let (sent, received) = oneshot::channel();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            let read = socket.read(&mut chunk).await.unwrap();
+            request.extend_from_slice(&chunk[..read]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if read == 0 || text.ends_with('}') {
+                let _ = sent.send(text);
+                break;
+            }
+        }
+        socket.write_all(response.as_bytes()).await.unwrap();
+        socket.shutdown().await.unwrap();
+    });
+    (format!("http://{}/hook", address), received)
+}
+
+#[tokio::test]
+async fn test_send_posts_the_payload() {
+    let (url, received) = serve_once("HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\nok").await;
+    let entry = entry();
+    send(&url, NotifyFormat::Slack, &notice(&entry)).await.unwrap();
+    let request = received.await.unwrap();
+    assert!(request.starts_with("POST /hook "));
+    assert!(request.contains("logged to <https://www.notion.so/abc#def|"));
+}
+
+#[tokio::test]
+async fn test_send_reports_webhook_errors() {
+    let (url, _) = serve_once("HTTP/1.1 404 Not Found\r\ncontent-length: 14\r\n\r\nno_such_hook\r\n").await;
+    let entry = entry();
+    let err = send(&url, NotifyFormat::Slack, &notice(&entry)).await.unwrap_err();
+    assert_eq!(err, "Webhook returned 404: no_such_hook");
+}
@@ -1,40 +1,162 @@
 // Tests for Notion client
 
-use faultnote::notion::client::{create_error_block, FaultLogEntry};
+use faultnote::notion::client::{context_pairs, create_error_block, tag_color, verification_steps, FaultLogEntry, NotionError};
+use faultnote::app::Severity;
+use faultnote::config::Config;
+use faultnote::notion::template::{BlockStyle, BlockTemplate};
+
+fn entry(code: Option<&str>) -> FaultLogEntry {
+    FaultLogEntry {
+        error: "Error".to_string(),
+        problem: "Problem".to_string(),
+        solution: "Solution".to_string(),
+        code: code.map(|c| c.to_string()),
+        ..Default::default()
+    }
+}
 
 #[test]
 fn test_create_error_block_without_code() {
-    let block = create_error_block("Error", "Problem", "Solution", None, None);
+    let block = create_error_block(&entry(None), None, &BlockTemplate::default());
 
     assert!(block.is_array());
     let arr = block.as_array().unwrap();
     assert_eq!(arr.len(), 1);
-    assert_eq!(arr[0]["type"], "heading_3");
+    assert_eq!(arr[0]["type"], "heading_2");
+
+    let children = arr[0]["heading_2"]["children"].as_array().unwrap();
+    assert_eq!(children.len(), 7);
+}
+
+#[test]
+fn test_tags_are_colored_code_chips() {
+    let tagged = FaultLogEntry {
+        tags: vec!["db".to_string(), "prod".to_string()],
+        ..entry(None)
+    };
+    let block = create_error_block(&tagged, None, &BlockTemplate::default());
+    let children = block[0]["heading_2"]["children"].as_array().unwrap();
+    let runs: Vec<&serde_json::Value> = children
+        .iter()
+        .filter_map(|child| child["paragraph"]["rich_text"].as_array())
+        .flatten()
+        .collect();
+    let chips: Vec<(&str, &str)> = runs
+        .iter()
+        .filter(|run| run["annotations"]["code"] == true)
+        .map(|run| (run["text"]["content"].as_str().unwrap(), run["annotations"]["color"].as_str().unwrap()))
+        .collect();
+    assert_eq!(chips, [("db", tag_color("db")), ("prod", tag_color("prod"))]);
+    assert!(runs.iter().any(|run| run["text"]["content"] == "Tags: " && run["annotations"]["bold"] == true));
 
-    let children = arr[0]["heading_3"]["children"].as_array().unwrap();
-    assert_eq!(children.len(), 3);
+    // A tag keeps its color whatever its case, as it dedupes
+    assert_eq!(tag_color("DB"), tag_color("db"));
+    assert_eq!(tag_color("ÜBER"), tag_color("über"));
+    assert!(tag_color("db").ends_with("_background"));
 }
 
 #[test]
 fn test_create_error_block_with_code() {
-    let block = create_error_block("Error", "Problem", "Solution", Some("fn main() {}"), Some("rust"));
+    let block = create_error_block(&entry(Some("fn main() {}")), Some("rust"), &BlockTemplate::default());
 
     let arr = block.as_array().unwrap();
-    let children = arr[0]["heading_3"]["children"].as_array().unwrap();
-    assert_eq!(children.len(), 4);
+    let children = arr[0]["heading_2"]["children"].as_array().unwrap();
+    assert_eq!(children.len(), 9);
 
-    let code_block = &children[3];
+    let code_block = &children[7];
     assert_eq!(code_block["type"], "code");
     assert_eq!(code_block["code"]["language"], "rust");
 }
 
 #[test]
 fn test_create_error_block_empty_code_ignored() {
-    let block = create_error_block("Error", "Problem", "Solution", Some("   "), None);
+    let block = create_error_block(&entry(Some("   ")), None, &BlockTemplate::default());
 
     let arr = block.as_array().unwrap();
-    let children = arr[0]["heading_3"]["children"].as_array().unwrap();
-    assert_eq!(children.len(), 3); // Whitespace code is ignored
+    let children = arr[0]["heading_2"]["children"].as_array().unwrap();
+    assert_eq!(children.len(), 7); // Whitespace code is ignored
+}
+
+#[test]
+fn test_create_error_block_with_verification() {
+    let mut entry = entry(None);
+    entry.verification = Some("- [ ] Run on staging\n\n* Check logs\n- [X] Ping the team".to_string());
+    let block = create_error_block(&entry, None, &BlockTemplate::default());
+
+    let children = block[0]["heading_2"]["children"].as_array().unwrap();
+    let todos: Vec<_> = children.iter().filter(|c| c["type"] == "to_do").collect();
+    assert_eq!(todos.len(), 3);
+    assert_eq!(todos[0]["to_do"]["rich_text"][0]["text"]["content"], "Run on staging");
+    assert_eq!(todos[0]["to_do"]["checked"], false);
+    assert_eq!(todos[2]["to_do"]["rich_text"][0]["text"]["content"], "Ping the team");
+    assert_eq!(todos[2]["to_do"]["checked"], true);
+}
+
+#[test]
+fn test_separator_blocks_between_entries() {
+    let default = create_error_block(&entry(None), None, &BlockTemplate::default());
+    assert_eq!(default.as_array().unwrap().len(), 1);
+
+    let template = BlockTemplate {
+        divider_between_entries: true,
+        blank_line_between_entries: true,
+        ..Default::default()
+    };
+    let blocks = create_error_block(&entry(None), None, &template);
+    let arr = blocks.as_array().unwrap();
+    assert_eq!(arr.len(), 3);
+    assert_eq!(arr[1]["type"], "divider");
+    assert_eq!(arr[2]["type"], "paragraph");
+}
+
+#[test]
+fn test_equations_in_sections() {
+    let mut entry = entry(None);
+    entry.problem = "Gradient $$\\nabla L$$ was NaN\n$$ L = \\sum_i x_i^2 $$".to_string();
+    let block = create_error_block(&entry, None, &BlockTemplate::default());
+
+    let children = block[0]["heading_2"]["children"].as_array().unwrap();
+    assert_eq!(children.len(), 8); // One extra equation block
+
+    let callout = &children[3]["callout"]["rich_text"];
+    assert_eq!(callout[1]["type"], "equation");
+    assert_eq!(callout[1]["equation"]["expression"], "\\nabla L");
+
+    assert_eq!(children[4]["type"], "equation");
+    assert_eq!(children[4]["equation"]["expression"], "L = \\sum_i x_i^2");
+}
+
+#[test]
+fn test_verification_steps() {
+    assert_eq!(
+        verification_steps("a\n  \n- b\n- [ ] c"),
+        vec![("a", false), ("b", false), ("c", false)]
+    );
+    assert!(verification_steps("   ").is_empty());
+    // Steps marked done in either case stay checked
+    assert_eq!(verification_steps("- [x] d\n- [X] e"), vec![("d", true), ("e", true)]);
+}
+
+#[test]
+fn test_create_error_block_with_context_table() {
+    let mut entry = entry(None);
+    entry.context = Some("rustc: 1.80\nregion: eu-west-1\n".to_string());
+    let block = create_error_block(&entry, None, &BlockTemplate::default());
+
+    let children = block[0]["heading_2"]["children"].as_array().unwrap();
+    let table = children.iter().find(|c| c["type"] == "table").unwrap();
+    assert_eq!(table["table"]["table_width"], 2);
+
+    let rows = table["table"]["children"].as_array().unwrap();
+    assert_eq!(rows.len(), 3); // Header + 2 rows
+    assert_eq!(rows[2]["table_row"]["cells"][0][0]["text"]["content"], "region");
+    assert_eq!(rows[2]["table_row"]["cells"][1][0]["text"]["content"], "eu-west-1");
+}
+
+#[test]
+fn test_context_pairs() {
+    let pairs = context_pairs("url: http://x:8080\nflag-only\n\n");
+    assert_eq!(pairs, vec![("url", "http://x:8080"), ("flag-only", "")]);
 }
 
 #[test]
@@ -44,6 +166,7 @@ fn test_fault_log_entry() {
         problem: "P".to_string(),
         solution: "S".to_string(),
         code: Some("C".to_string()),
+        ..Default::default()
     };
     assert_eq!(entry.error, "E");
     assert!(entry.code.is_some());
@@ -53,6 +176,231 @@ fn test_fault_log_entry() {
         problem: "P".to_string(),
         solution: "S".to_string(),
         code: None,
+        ..Default::default()
     };
     assert!(entry2.code.is_none());
+    assert!(entry2.verification.is_none());
+}
+
+#[test]
+fn test_token_order_prefers_recorded_token() {
+    let config = faultnote::config::Config::from_toml(
+        "[[tokens]]\nname = \"personal\"\nkey = \"a\"\n[[tokens]]\nname = \"team\"\nkey = \"b\"\n\
+         [[tokens]]\nname = \"ci\"\nenv = \"FAULTNOTE_TEST_UNSET_TOKEN\"\n",
+    )
+    .unwrap();
+    let (client, warnings) = faultnote::notion::client::create_notion_client(&config.tokens).unwrap();
+
+    // A token that cannot be used is skipped with a warning
+    let names: Vec<&str> = client.tokens.iter().map(|t| t.name.as_str()).collect();
+    assert!(names.ends_with(&["personal", "team"]));
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("'ci'"), "{}", warnings[0]);
+    assert_eq!(client.token_order(Some("team"))[0], "team");
+    assert_eq!(client.token_order(Some("unknown"))[0], names[0]);
+    assert!(client.with_token("team").is_some());
+}
+
+#[test]
+fn test_plain_style_has_no_emoji() {
+    let template = Config::from_toml("[template]\nstyle = \"plain\"").unwrap().template;
+    assert_eq!(template.style, BlockStyle::Plain);
+
+    let block = create_error_block(&entry(None), None, &template);
+    let heading = &block[0]["heading_2"];
+    assert_eq!(heading["rich_text"][0]["text"]["content"], "");
+
+    let children = heading["children"].as_array().unwrap();
+    assert!(children.iter().all(|child| child["type"] != "callout"));
+    assert_eq!(children[1]["type"], "paragraph");
+    assert_eq!(children[1]["paragraph"]["rich_text"][0]["text"]["content"], "Error: ");
+    assert_eq!(children[1]["paragraph"]["rich_text"][0]["annotations"]["bold"], true);
+    assert_eq!(children[1]["paragraph"]["rich_text"][1]["text"]["content"], "Error");
+}
+
+#[test]
+fn test_flat_heading_options() {
+    let toml = "[template.heading]\nlevel = \"h3\"\ntoggleable = false\ncolor = \"gray\"";
+    let template = Config::from_toml(toml).unwrap().template;
+
+    let block = create_error_block(&entry(None), None, &template);
+    let arr = block.as_array().unwrap();
+    assert_eq!(arr.len(), 8); // Heading followed by its 7 sections
+    assert_eq!(arr[0]["type"], "heading_3");
+    assert_eq!(arr[0]["heading_3"]["is_toggleable"], false);
+    assert_eq!(arr[0]["heading_3"]["color"], "gray");
+    assert!(arr[0]["heading_3"].get("children").is_none());
+    assert_eq!(arr[1]["type"], "divider");
+
+    assert!(Config::from_toml("[template.heading]\nlevel = \"h4\"").is_err());
+}
+
+#[test]
+fn test_severity_sets_heading_emoji_and_color() {
+    let template = BlockTemplate::default();
+    let heading = |severity| {
+        let entry = FaultLogEntry {
+            severity,
+            ..entry(None)
+        };
+        create_error_block(&entry, None, &template)[0]["heading_2"].clone()
+    };
+
+    let unmarked = heading(None);
+    assert_eq!(unmarked["rich_text"][0]["text"]["content"], "🐛 ");
+    assert_eq!(unmarked["color"], "red");
+
+    let critical = heading(Some(Severity::Critical));
+    assert_eq!(critical["rich_text"][0]["text"]["content"], "🚨 ");
+    assert_eq!(critical["color"], "red_background");
+    assert_eq!(heading(Some(Severity::Low))["color"], "blue");
+
+    assert_eq!(Severity::cycle(None), Some(Severity::Critical));
+    assert_eq!(Severity::cycle(Some(Severity::Medium)), Some(Severity::Low));
+    assert_eq!(Severity::cycle(Some(Severity::Low)), None);
+}
+
+#[test]
+fn test_notion_error_reads_code_and_message() {
+    let body = r#"{"object":"error","status":400,"code":"validation_error","message":"body.children[0] should be defined"}"#;
+    let error = NotionError::from_response(400, body);
+    assert_eq!(error.status(), Some(400));
+    assert!(!error.is_access_error());
+    assert_eq!(
+        error.to_string(),
+        "Notion rejected the request (validation_error): body.children[0] should be defined"
+    );
+
+    // Bodies that are not Notion's JSON are shown as they are
+    let error = NotionError::from_response(502, "Bad Gateway\n");
+    assert_eq!(error.to_string(), "Notion had a server error (502): Bad Gateway");
+}
+
+#[test]
+fn test_notion_access_errors_say_what_to_do() {
+    let body = r#"{"object":"error","status":404,"code":"object_not_found","message":"Could not find block"}"#;
+    let not_found = NotionError::from_response(404, body);
+    assert!(matches!(not_found, NotionError::NotFound { .. }));
+    assert!(not_found.is_access_error());
+    assert!(not_found.to_string().contains("share it with the integration"));
+
+    let unauthorized = NotionError::from_response(401, r#"{"code":"unauthorized","message":"API token is invalid."}"#);
+    assert!(unauthorized.is_access_error());
+    assert!(unauthorized.to_string().contains("API_KEY"));
+
+    let limited = NotionError::from_response(429, "{}");
+    assert_eq!(limited.status(), Some(429));
+    assert!(!limited.is_access_error());
+}
+
+#[test]
+fn test_sub_entries_follow_the_summary_under_numbered_headings() {
+    let mut group = entry(None);
+    let mut second = entry(Some("SELECT 1;"));
+    second.error = "Deadlock in orders\nat db.rs:12".to_string();
+    second.severity = Some(Severity::High);
+    group.sub_entries = vec![entry(None), second];
+    let block = create_error_block(&group, None, &BlockTemplate::default());
+
+    let children = block[0]["heading_2"]["children"].as_array().unwrap();
+    let headings: Vec<String> = children
+        .iter()
+        .filter(|c| c["type"] == "heading_3" && c["heading_3"]["rich_text"][0]["text"]["content"].as_str().unwrap().contains(". "))
+        .map(|c| {
+            let text = &c["heading_3"]["rich_text"];
+            format!("{}{}", text[0]["text"]["content"].as_str().unwrap(), text[1]["text"]["content"].as_str().unwrap())
+        })
+        .collect();
+    assert_eq!(headings, ["1. 🐛 Error", "2. 🔥 Deadlock in orders"]);
+    // Flat under the parent, and closed by a single divider
+    assert!(children.iter().all(|c| c["heading_3"].get("children").is_none()));
+    assert_eq!(children.iter().filter(|c| c["type"] == "divider").count(), 2);
+    assert_eq!(children.iter().filter(|c| c["type"] == "code").count(), 1);
+}
+
+#[test]
+fn test_attachments_become_image_and_file_blocks() {
+    use faultnote::notion::client::Attachment;
+
+    let mut with_files = entry(None);
+    with_files.attachments = vec![
+        Attachment {
+            name: "login.png".to_string(),
+            upload_id: "up-1".to_string(),
+            url: None,
+        },
+        Attachment {
+            name: "812.txt".to_string(),
+            upload_id: String::new(),
+            url: Some("https://ci.example.com/logs/812.txt".to_string()),
+        },
+    ];
+    let block = create_error_block(&with_files, None, &BlockTemplate::default());
+    let children = block[0]["heading_2"]["children"].as_array().unwrap();
+
+    let image = children.iter().find(|c| c["type"] == "image").unwrap();
+    assert_eq!(image["image"]["file_upload"]["id"], "up-1");
+    let file = children.iter().find(|c| c["type"] == "file").unwrap();
+    assert_eq!(file["file"]["external"]["url"], "https://ci.example.com/logs/812.txt");
+    assert_eq!(file["file"]["name"], "812.txt");
+}
+
+#[test]
+fn test_new_page_body() {
+    use faultnote::notion::client::new_page_body;
+
+    let body = new_page_body("parent-1", "Payments Errors");
+    assert_eq!(body["parent"]["page_id"], "parent-1");
+    assert_eq!(body["properties"]["title"]["title"][0]["text"]["content"], "Payments Errors");
+}
+
+#[tokio::test]
+async fn test_created_page_without_an_id_is_an_error() {
+    use std::sync::Arc;
+
+    use faultnote::notion::client::{NotionClient, NotionError, create_page};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let _ = socket.read(&mut [0u8; 4096]).await;
+        let response = "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 2\r\n\r\n{}";
+        socket.write_all(response.as_bytes()).await.unwrap();
+        socket.shutdown().await.unwrap();
+    });
+    let client = NotionClient {
+        base_url,
+        http_client: reqwest::Client::new(),
+        tokens: Vec::new(),
+        retries: Arc::default(),
+        activity: Default::default(),
+        limiter: None,
+    };
+
+    let result = create_page(&client, "parent-1", "Payments Errors").await;
+    assert!(matches!(result, Err(NotionError::Unexpected { .. })));
+}
+
+#[test]
+fn test_search_results_name_their_parent() {
+    use faultnote::notion::client::{extract_page_info, parent_id};
+    use serde_json::json;
+
+    let nested = json!({
+        "object": "page",
+        "id": "p2",
+        "parent": { "type": "page_id", "page_id": "p1" },
+        "properties": { "title": { "title": [{ "plain_text": "Errors" }] } }
+    });
+    let page = extract_page_info(&nested).unwrap();
+    assert_eq!(page.title, "Errors");
+    assert_eq!(page.parent.as_deref(), Some("p1"));
+
+    let row = json!({ "parent": { "type": "database_id", "database_id": "db" } });
+    assert_eq!(parent_id(&row).as_deref(), Some("db"));
+    let top = json!({ "parent": { "type": "workspace", "workspace": true } });
+    assert_eq!(parent_id(&top), None);
 }
@@ -0,0 +1,162 @@
+// Tests for per-page settings memory
+
+use std::env;
+use std::fs;
+
+use faultnote::app::{AppState, PageInfo};
+use faultnote::config::Config;
+use faultnote::notion::template::{AppendPosition, BlockStyle};
+use faultnote::page_settings::{PageSetting, PageSettings, PageSettingsStore};
+
+fn page(id: &str) -> PageInfo {
+    PageInfo {
+        id: id.to_string(),
+        title: id.to_uppercase(),
+        ..Default::default()
+    }
+}
+
+fn app_with_settings() -> AppState {
+    let mut app = AppState::new();
+    let settings = PageSettings {
+        code_language: Some("python".to_string()),
+        tags: vec!["backend".to_string()],
+        ..Default::default()
+    };
+    app.page_settings.set("b", settings).unwrap();
+    app.set_pages(vec![page("a"), page("b")]);
+    app
+}
+
+#[test]
+fn test_page_settings_round_trip() {
+    let path = env::temp_dir().join(format!("faultnote-page-settings-{}.json", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let mut store = PageSettingsStore::load(path.clone()).unwrap();
+    let mut settings = PageSettings::default();
+    settings.cycle(PageSetting::Style);
+    settings.cycle(PageSetting::Style);
+    settings.set_text(PageSetting::Tags, " db, , prod ");
+    store.set("page", settings).unwrap();
+
+    let reloaded = PageSettingsStore::load(path.clone()).unwrap();
+    let settings = reloaded.get("page").unwrap();
+    assert_eq!(settings.style, Some(BlockStyle::Plain));
+    assert_eq!(settings.tags, ["db", "prod"]);
+
+    // Resetting everything forgets the page
+    let mut store = reloaded;
+    store.set("page", PageSettings::default()).unwrap();
+    assert!(PageSettingsStore::load(path.clone()).unwrap().get("page").is_none());
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_settings_layer_over_config() {
+    let config = Config::from_toml(
+        r#"
+        [template]
+        position = "top"
+        after = "header-block"
+        "#,
+    )
+    .unwrap();
+    let mut app = AppState::new();
+    app.config = config;
+    let settings = PageSettings {
+        style: Some(BlockStyle::Plain),
+        anchor: Some("## Fault Log".to_string()),
+        ..Default::default()
+    };
+    app.page_settings.set("page", settings).unwrap();
+
+    let template = app.template_for("page");
+    assert!(template.is_plain());
+    assert_eq!(template.position, AppendPosition::Top);
+    assert_eq!(template.anchor.as_deref(), Some("## Fault Log"));
+    assert_eq!(template.after, None);
+
+    let other = app.template_for("other");
+    assert!(!other.is_plain());
+    assert_eq!(other.after.as_deref(), Some("header-block"));
+}
+
+#[test]
+fn test_switching_pages_applies_language_and_tags() {
+    let mut app = app_with_settings();
+    assert_eq!(app.code_language, AppState::DEFAULT_CODE_LANGUAGE);
+
+    app.next_page();
+    assert_eq!(app.code_language, "python");
+
+    app.error_input = "err".into();
+    app.problem_input = "prob".into();
+    app.solution_input = "fix".into();
+    let (_, entry) = app.get_submission_data().unwrap();
+    assert_eq!(entry.tags, ["backend"]);
+
+    app.previous_page();
+    assert_eq!(app.code_language, AppState::DEFAULT_CODE_LANGUAGE);
+    let (_, entry) = app.get_submission_data().unwrap();
+    assert!(entry.tags.is_empty());
+}
+
+#[test]
+fn test_typed_tags_follow_the_page_tags() {
+    let mut app = app_with_settings();
+    app.next_page();
+    app.error_input = "err".into();
+    app.problem_input = "prob".into();
+    app.solution_input = "fix".into();
+    app.tags_input = "BACKEND, db,\nprod".into();
+    let (page_id, entry) = app.get_submission_data().unwrap();
+    assert_eq!(entry.tags, ["backend", "db", "prod"]);
+
+    // Loading the entry back keeps only the tags that were typed
+    app.clear_inputs();
+    assert!(app.tags_input.is_empty());
+    app.load_entry(&page_id, &entry);
+    assert_eq!(app.tags_input.to_string(), "db, prod");
+}
+
+#[test]
+fn test_switching_pages_keeps_chosen_language() {
+    let mut app = app_with_settings();
+    app.toggle_mermaid();
+    app.next_page();
+    assert!(app.is_mermaid());
+
+    // Toggling off returns to the page's language
+    app.toggle_mermaid();
+    assert_eq!(app.code_language, "python");
+}
+
+#[test]
+fn test_settings_screen_edits_selected_page() {
+    let mut app = app_with_settings();
+    app.open_page_settings();
+    assert!(app.is_page_settings_screen());
+
+    app.next_setting();
+    app.change_setting();
+    assert_eq!(app.selected_page_settings().and_then(|s| s.position), Some(AppendPosition::Bottom));
+
+    app.next_setting();
+    app.next_setting();
+    app.change_setting();
+    assert_eq!(app.setting_input.as_deref(), Some(""));
+    app.setting_input = Some("Go".to_string());
+    app.commit_setting_input();
+    assert_eq!(app.code_language, "go");
+
+    // Common aliases become Notion's names
+    app.change_setting();
+    app.setting_input = Some("JS".to_string());
+    app.commit_setting_input();
+    assert_eq!(app.code_language, "javascript");
+
+    app.reset_setting();
+    assert_eq!(app.code_language, AppState::DEFAULT_CODE_LANGUAGE);
+}
@@ -0,0 +1,46 @@
+// Tests for ordering the page list as a tree
+
+use faultnote::app::PageInfo;
+use faultnote::page_tree::tree_order;
+
+fn page(id: &str, parent: Option<&str>) -> PageInfo {
+    PageInfo {
+        id: id.to_string(),
+        title: id.to_uppercase(),
+        parent: parent.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+fn layout(pages: Vec<PageInfo>) -> Vec<(String, usize)> {
+    tree_order(pages).into_iter().map(|(page, depth)| (page.id, depth)).collect()
+}
+
+#[test]
+fn test_subpages_follow_their_parent() {
+    let pages = vec![
+        page("errors-b", Some("b")),
+        page("a", None),
+        page("b", None),
+        page("errors-a", Some("a")),
+        page("deep", Some("errors-a")),
+        page("notes-a", Some("a")),
+    ];
+    let expected = [("a", 1), ("errors-a", 2), ("deep", 3), ("notes-a", 2), ("b", 1), ("errors-b", 2)];
+    let expected: Vec<(String, usize)> = expected.iter().map(|(id, depth)| (id.to_string(), depth - 1)).collect();
+    assert_eq!(layout(pages), expected);
+}
+
+#[test]
+fn test_unlisted_parents_and_cycles_stay_listed() {
+    // A parent not shared with the integration leaves its page at the top
+    assert_eq!(layout(vec![page("x", Some("hidden")), page("y", None)]), [("x".to_string(), 0), ("y".to_string(), 0)]);
+
+    // Nothing is lost to a parent cycle
+    let ids: Vec<String> = layout(vec![page("p", Some("q")), page("q", Some("p")), page("r", Some("r"))])
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    assert_eq!(ids.len(), 3);
+    assert!(ids.contains(&"p".to_string()) && ids.contains(&"q".to_string()) && ids.contains(&"r".to_string()));
+}
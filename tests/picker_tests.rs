@@ -0,0 +1,65 @@
+// Tests for the inline page picker and page lookup of `faultnote submit`
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use faultnote::app::{PageInfo, TargetKind};
+use faultnote::picker::{Picker, PickerOutcome};
+use faultnote::submit::find_page;
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn page(id: &str, title: &str) -> PageInfo {
+    PageInfo {
+        id: id.to_string(),
+        title: title.to_string(),
+        kind: TargetKind::Page,
+        parent: None,
+    }
+}
+
+#[test]
+fn test_picker_filters_and_picks() {
+    let mut picker = Picker::new(vec!["Backend Errors".into(), "Frontend Bugs".into(), "Bug Tracker".into()]);
+    assert_eq!(picker.matches.len(), 3);
+
+    for c in "bug".chars() {
+        assert_eq!(picker.handle_key(key(KeyCode::Char(c))), PickerOutcome::Pending);
+    }
+    assert_eq!(picker.matches, [2, 1]);
+    picker.handle_key(key(KeyCode::Down));
+    picker.handle_key(key(KeyCode::Down));
+    assert_eq!(picker.handle_key(key(KeyCode::Enter)), PickerOutcome::Picked(1));
+
+    // Nothing to pick while nothing matches
+    for c in "zzz".chars() {
+        picker.handle_key(key(KeyCode::Char(c)));
+    }
+    assert_eq!(picker.handle_key(key(KeyCode::Enter)), PickerOutcome::Pending);
+    assert!(picker.lines(">").iter().any(|(line, _)| line.contains("no matches")));
+
+    let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+    assert_eq!(picker.handle_key(ctrl_c), PickerOutcome::Cancelled);
+}
+
+#[test]
+fn test_find_page_by_id_or_title() {
+    let pages = vec![
+        page("1a2b3c4d-0000-0000-0000-000000000001", "Backend Errors"),
+        page("1a2b3c4d-0000-0000-0000-000000000002", "Frontend Errors"),
+    ];
+    let found = |wanted: &str| find_page(&pages, wanted).map(|p| p.title);
+
+    assert_eq!(found("1a2b3c4d000000000000000000000002").as_deref(), Some("Frontend Errors"));
+    assert_eq!(found("backend errors").as_deref(), Some("Backend Errors"));
+    assert_eq!(found("front").as_deref(), Some("Frontend Errors"));
+    // Ambiguous or unknown names are not guessed
+    assert_eq!(found("errors"), None);
+    assert_eq!(found("billing"), None);
+    // Links copied from Notion name the page by id
+    assert_eq!(
+        found("https://www.notion.so/acme/Frontend-Errors-1a2b3c4d000000000000000000000002?pvs=4").as_deref(),
+        Some("Frontend Errors")
+    );
+    assert_eq!(found("https://www.notion.so/acme/Billing-ffff0000000000000000000000000009"), None);
+}
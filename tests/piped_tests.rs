@@ -0,0 +1,45 @@
+// Tests for prefilling the editor from piped output
+
+use faultnote::app::AppState;
+use faultnote::piped::{OUTPUT_LANGUAGE, error_line, prefill, strip_ansi};
+
+const CARGO_OUTPUT: &str = "   Compiling faultnote v0.1.0
+error[E0308]: mismatched types
+  --> src/main.rs:4:18
+   |
+4  |     let x: u8 = \"a\";
+   |                 ^^^ expected `u8`, found `&str`
+";
+
+#[test]
+fn test_error_line() {
+    assert_eq!(error_line(CARGO_OUTPUT), Some("error[E0308]: mismatched types"));
+    assert_eq!(
+        error_line("thread 'main' panicked at src/lib.rs:3:5:\nboom"),
+        Some("thread 'main' panicked at src/lib.rs:3:5:")
+    );
+    // Without an error-looking line, the first non-blank one names it
+    assert_eq!(error_line("\n  make: nothing to do\n"), Some("make: nothing to do"));
+    assert_eq!(error_line(" \n"), None);
+}
+
+#[test]
+fn test_strip_ansi() {
+    assert_eq!(strip_ansi("\u{1b}[1m\u{1b}[31merror\u{1b}[0m: boom"), "error: boom");
+    assert_eq!(strip_ansi("plain ✓"), "plain ✓");
+}
+
+#[test]
+fn test_prefill() {
+    let mut app = AppState::new();
+    prefill(&mut app, CARGO_OUTPUT, false);
+    assert_eq!(app.error_input, "error[E0308]: mismatched types");
+    assert_eq!(app.code_input.lines().count(), 6);
+    assert_eq!(app.code_language, OUTPUT_LANGUAGE);
+
+    let mut app = AppState::new();
+    prefill(&mut app, CARGO_OUTPUT, true);
+    assert_eq!(app.error_input, "error[E0308]: mismatched types");
+    assert!(app.code_input.is_empty());
+    assert_eq!(app.code_language, AppState::DEFAULT_CODE_LANGUAGE);
+}
@@ -0,0 +1,83 @@
+// Tests for the read-only page preview
+
+use faultnote::app::AppState;
+use faultnote::notion::preview::{PreviewLine, PreviewStyle, preview_lines};
+use serde_json::{Value, json};
+
+fn block(kind: &str, body: Value) -> Value {
+    json!({ "type": kind, kind: body })
+}
+
+fn text(content: &str) -> Value {
+    json!({ "rich_text": [{ "type": "text", "plain_text": content }] })
+}
+
+fn texts(lines: &[PreviewLine]) -> Vec<&str> {
+    lines.iter().map(|line| line.text.as_str()).collect()
+}
+
+#[test]
+fn test_preview_lines_render_common_blocks() {
+    let blocks = vec![
+        block("heading_2", text("Conventions")),
+        block("paragraph", text("Log every outage.\nEven small ones.")),
+        block("paragraph", json!({ "rich_text": [] })),
+        block("numbered_list_item", text("Error")),
+        block("numbered_list_item", text("Fix")),
+        block("to_do", json!({ "rich_text": [{ "plain_text": "Review" }], "checked": true })),
+        block("code", json!({ "rich_text": [{ "plain_text": "cargo test\ncargo run" }], "language": "bash" })),
+        block("divider", json!({})),
+        block("image", json!({})),
+    ];
+    let lines = preview_lines(&blocks);
+    assert_eq!(
+        texts(&lines),
+        [
+            "## Conventions",
+            "Log every outage.",
+            "Even small ones.",
+            "",
+            "1. Error",
+            "2. Fix",
+            "☑ Review",
+            "```bash",
+            "cargo test",
+            "cargo run",
+            "```",
+            "────────",
+            "[image]",
+        ]
+    );
+    assert_eq!(lines[0].style, PreviewStyle::Heading);
+    assert_eq!(lines[8].style, PreviewStyle::Code);
+    assert_eq!(lines[12].style, PreviewStyle::Muted);
+}
+
+#[test]
+fn test_preview_lines_indent_children() {
+    let mut toggle = text("Details");
+    toggle["children"] = json!([block("bulleted_list_item", text("first\nsecond"))]);
+    let table = json!({
+        "type": "table",
+        "table": { "children": [block("table_row", json!({ "cells": [
+            [{ "plain_text": "region" }],
+            [{ "plain_text": "eu" }],
+        ] }))] },
+    });
+    let lines = preview_lines(&[block("toggle", toggle), table]);
+    assert_eq!(texts(&lines), ["▸ Details", "  • first", "    second", "│ region │ eu │"]);
+}
+
+#[test]
+fn test_preview_scroll_stops_at_the_last_line() {
+    let mut app = AppState::new();
+    let lines = preview_lines(&[block("paragraph", text("one\ntwo\nthree"))]);
+    app.open_preview("Notes", lines);
+    assert!(app.is_preview_screen());
+    app.scroll_preview(10);
+    assert_eq!(app.preview_scroll, 2);
+    app.scroll_preview(-10);
+    assert_eq!(app.preview_scroll, 0);
+    app.close_preview();
+    assert!(!app.is_preview_screen());
+}
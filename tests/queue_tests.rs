@@ -0,0 +1,103 @@
+// Tests for the offline submission queue
+
+use std::env;
+use std::fs;
+use std::time::Duration;
+
+use faultnote::app::{AppState, FaultLogEntry, PageInfo};
+use faultnote::queue::{backoff_delay, unix_now, QueueStatus, SubmissionQueue};
+
+fn entry(error: &str) -> FaultLogEntry {
+    FaultLogEntry {
+        error: error.to_string(),
+        problem: "P".to_string(),
+        solution: "S".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_queue_persists_to_disk() {
+    let path = env::temp_dir().join(format!("faultnote-queue-{}.json", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let mut queue = SubmissionQueue::load(path.clone()).unwrap();
+    assert!(queue.is_empty());
+    let first = queue.push("page-1", "Bugs", entry("E1"), QueueStatus::Pending, "timeout");
+    let second = queue.push("page-1", "Bugs", entry("E2"), QueueStatus::Failed, "404");
+    assert_eq!(second, first + 1);
+    queue.save().unwrap();
+
+    let reloaded = SubmissionQueue::load(path.clone()).unwrap();
+    assert_eq!(reloaded.len(), 2);
+    assert_eq!(reloaded.items()[1].entry.error, "E2");
+    assert_eq!(reloaded.items()[1].status, QueueStatus::Failed);
+    assert_eq!(reloaded.items()[0].last_error.as_deref(), Some("timeout"));
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_queue_navigation_and_removal() {
+    let mut app = AppState::new();
+    app.queue.push("p", "Page", entry("A"), QueueStatus::Pending, "x");
+    app.queue.push("p", "Page", entry("B"), QueueStatus::Pending, "x");
+
+    app.open_queue();
+    assert!(app.is_queue_screen());
+    app.previous_queue_item();
+    assert_eq!(app.selected_queue_index, 1);
+
+    app.queue.remove(1);
+    app.clamp_queue_selection();
+    assert_eq!(app.selected_queue_index, 0);
+
+    app.close_queue();
+    assert!(!app.is_queue_screen());
+}
+
+#[test]
+fn test_load_entry_into_editor() {
+    let mut app = AppState::new();
+    app.set_pages(vec![
+        PageInfo { id: "a".to_string(), title: "A".to_string(), ..Default::default() },
+        PageInfo { id: "b".to_string(), title: "B".to_string(), ..Default::default() },
+    ]);
+    let mut queued = entry("Boom");
+    queued.code = Some("graph TD".to_string());
+    queued.language = Some("mermaid".to_string());
+
+    app.load_entry("b", &queued);
+    assert_eq!(app.error_input, "Boom");
+    assert_eq!(app.code_input, "graph TD");
+    assert!(app.is_mermaid());
+    assert_eq!(app.selected_page_index, 1);
+}
+
+#[test]
+fn test_backoff_delay_doubles_and_caps() {
+    assert_eq!(backoff_delay(1), Duration::from_secs(30));
+    assert_eq!(backoff_delay(2), Duration::from_secs(60));
+    assert_eq!(backoff_delay(4), Duration::from_secs(240));
+    assert_eq!(backoff_delay(50), Duration::from_secs(3600));
+}
+
+#[test]
+fn test_retry_schedule() {
+    let mut queue = SubmissionQueue::default();
+    queue.push("p", "Page", entry("A"), QueueStatus::Pending, "timeout");
+    queue.push("p", "Page", entry("B"), QueueStatus::Failed, "404");
+
+    let now = unix_now();
+    assert!(queue.due_indices(now).is_empty());
+    assert_eq!(queue.due_indices(now + 30), vec![0]); // Failed items wait for the user
+
+    let item = queue.get_mut(0).unwrap();
+    item.record_failure(QueueStatus::Pending, "503", now);
+    assert_eq!(item.attempts, 2);
+    assert_eq!(item.retry_in(now), Some(Duration::from_secs(60)));
+    assert!(item.is_due(now + 60));
+
+    let item = queue.get_mut(1).unwrap();
+    assert_eq!(item.retry_in(now), None);
+}
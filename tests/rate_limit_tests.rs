@@ -0,0 +1,51 @@
+// Tests for request pacing and batch progress
+
+use std::time::{Duration, Instant};
+
+use faultnote::notion::rate_limit::{BatchProgress, REQUESTS_PER_SECOND, TokenBucket};
+
+#[test]
+fn test_token_bucket_allows_a_burst_then_paces() {
+    let start = Instant::now();
+    let mut bucket = TokenBucket::new(3.0, REQUESTS_PER_SECOND, start);
+    for _ in 0..3 {
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+    }
+
+    // Each further request waits one more refill, in the order reserved
+    let fourth = bucket.reserve(start);
+    let fifth = bucket.reserve(start);
+    assert!((fourth.as_secs_f64() - 1.0 / 3.0).abs() < 1e-6);
+    assert!((fifth.as_secs_f64() - 2.0 / 3.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_token_bucket_refills_up_to_capacity() {
+    let start = Instant::now();
+    let mut bucket = TokenBucket::new(3.0, REQUESTS_PER_SECOND, start);
+    for _ in 0..3 {
+        bucket.reserve(start);
+    }
+
+    // A long pause refills the bucket, but never beyond its capacity
+    let later = start + Duration::from_secs(60);
+    for _ in 0..3 {
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+    }
+    assert!(bucket.reserve(later) > Duration::ZERO);
+}
+
+#[test]
+fn test_batch_eta_uses_the_slower_of_rate_and_observed_pace() {
+    let start = Instant::now();
+    let mut progress = BatchProgress::new(31, start);
+    assert_eq!(progress.eta(start).as_secs(), 10);
+
+    // Slow responses: 2s per entry observed so far
+    progress.done = 5;
+    assert_eq!(progress.eta(start + Duration::from_secs(10)).as_secs(), 52);
+    assert_eq!(progress.label(start + Duration::from_secs(10)), "5/31, about 52s left");
+
+    progress.done = 31;
+    assert_eq!(progress.label(start + Duration::from_secs(20)), "31/31");
+}
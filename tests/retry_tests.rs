@@ -0,0 +1,188 @@
+// Tests for retrying rate-limited and failed Notion requests
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use faultnote::notion::activity::short_path;
+use faultnote::notion::client::NotionClient;
+use faultnote::notion::rate_limit::{REQUESTS_PER_SECOND, RateLimiter};
+use faultnote::notion::retry::{BASE_DELAY, MAX_RETRY_AFTER, RetryNotice, RetryReason, retry_delay, retry_reason};
+use reqwest::Method;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[test]
+fn test_rate_limits_are_always_retried() {
+    assert_eq!(retry_reason(&Method::GET, Some(429), true), Some(RetryReason::RateLimited));
+    assert_eq!(retry_reason(&Method::PATCH, Some(429), true), Some(RetryReason::RateLimited));
+    assert_eq!(retry_reason(&Method::POST, Some(503), true), Some(RetryReason::ServerError(503)));
+    assert_eq!(retry_reason(&Method::GET, Some(404), true), None);
+    assert_eq!(retry_reason(&Method::GET, Some(200), true), None);
+}
+
+#[test]
+fn test_writes_that_may_have_landed_are_not_repeated() {
+    // A 502 or a timeout after sending may hide an entry that was created
+    assert_eq!(retry_reason(&Method::GET, Some(502), true), Some(RetryReason::ServerError(502)));
+    assert_eq!(retry_reason(&Method::PATCH, Some(502), true), None);
+    assert_eq!(retry_reason(&Method::POST, None, true), None);
+    assert_eq!(retry_reason(&Method::POST, None, false), Some(RetryReason::Unreachable));
+    assert_eq!(retry_reason(&Method::GET, None, true), Some(RetryReason::Unreachable));
+}
+
+#[test]
+fn test_retry_delay_prefers_retry_after() {
+    assert_eq!(retry_delay(2, Some("3"), 0.9), Some(Duration::from_secs(3)));
+    assert_eq!(retry_delay(2, Some("3600"), 0.0), None);
+    assert!(MAX_RETRY_AFTER >= Duration::from_secs(3));
+
+    // Without it: doubling backoff, between half and one and a half times
+    assert_eq!(retry_delay(2, None, 0.5), Some(BASE_DELAY));
+    assert_eq!(retry_delay(3, None, 0.5), Some(BASE_DELAY * 2));
+    assert_eq!(retry_delay(3, Some("soon"), 0.0), Some(BASE_DELAY));
+}
+
+#[test]
+fn test_retry_notice_message() {
+    let notice = RetryNotice {
+        reason: RetryReason::RateLimited,
+        attempt: 2,
+        wait: Duration::from_millis(1500),
+    };
+    assert_eq!(notice.message(), "Notion is rate limiting; retrying in 2s (attempt 2 of 4)");
+}
+
+#[tokio::test]
+async fn test_retry_watcher_forwards_notices() {
+    use faultnote::app_event::AppEvent;
+    use tokio::sync::mpsc;
+
+    let client = NotionClient {
+        base_url: String::new(),
+        http_client: reqwest::Client::new(),
+        tokens: Vec::new(),
+        retries: Arc::default(),
+        activity: Default::default(),
+        limiter: None,
+    };
+    let (events, mut received) = mpsc::unbounded_channel();
+    client.retry_watcher().forward(events);
+
+    let notice = |attempt| RetryNotice {
+        reason: RetryReason::RateLimited,
+        attempt,
+        wait: Duration::ZERO,
+    };
+    client.retries.send_replace(Some(notice(2)));
+    assert!(matches!(received.recv().await, Some(AppEvent::Retrying(notice)) if notice.attempt == 2));
+    // Clearing the notice is not news for the main loop
+    client.retries.send_replace(None);
+    client.retries.send_replace(Some(notice(3)));
+    assert!(matches!(received.recv().await, Some(AppEvent::Retrying(notice)) if notice.attempt == 3));
+}
+
+/// Serve the given raw HTTP responses, one connection each
+async fn serve(responses: Vec<&'static str>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        for response in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let _ = socket.read(&mut request).await;
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.unwrap();
+        }
+    });
+    format!("http://{}", address)
+}
+
+#[tokio::test]
+async fn test_send_waits_out_a_rate_limit() {
+    let base_url = serve(vec![
+        "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nConnection: close\r\nContent-Length: 2\r\n\r\n{}",
+        "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 11\r\n\r\n{\"ok\":true}",
+    ])
+    .await;
+    let client = NotionClient {
+        base_url: base_url.clone(),
+        http_client: reqwest::Client::new(),
+        tokens: Vec::new(),
+        retries: Arc::default(),
+        activity: Default::default(),
+        limiter: None,
+    };
+    let mut activity = client.activity.subscribe();
+
+    let response = client.send(client.http_client.get(&base_url)).await.unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    // The retry notice is cleared once the request settles
+    assert!(client.retries.borrow().is_none());
+
+    // The activity log gets the request once, after its last attempt
+    let call = activity.try_recv().unwrap();
+    assert_eq!((&call.method, call.status, call.attempts), (&Method::GET, Some(200), 2));
+    assert!(call.message().starts_with("GET / → 200 in "));
+    assert!(call.message().ends_with("(2 attempts)"));
+    assert!(activity.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_send_gives_up_on_errors_that_need_action() {
+    let base_url = serve(vec![
+        "HTTP/1.1 404 Not Found\r\nConnection: close\r\nContent-Length: 58\r\n\r\n{\"object\":\"error\",\"code\":\"object_not_found\",\"message\":\"x\"}",
+    ])
+    .await;
+    let client = NotionClient {
+        base_url: base_url.clone(),
+        http_client: reqwest::Client::new(),
+        tokens: Vec::new(),
+        retries: Arc::default(),
+        activity: Default::default(),
+        limiter: None,
+    };
+
+    // Only one response is served, so a retry would fail to connect
+    let response = client.send(client.http_client.get(&base_url)).await.unwrap();
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn test_paced_client_takes_a_token_per_attempt() {
+    let ok = "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 2\r\n\r\n{}";
+    let base_url = serve(vec![
+        "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nConnection: close\r\nContent-Length: 2\r\n\r\n{}",
+        ok,
+        ok,
+        ok,
+    ])
+    .await;
+    let client = NotionClient {
+        base_url: base_url.clone(),
+        http_client: reqwest::Client::new(),
+        tokens: Vec::new(),
+        retries: Arc::default(),
+        activity: Default::default(),
+        limiter: None,
+    }
+    .paced(RateLimiter::notion());
+
+    // Three requests, one of them retried, make one attempt more than the
+    // burst of three allows
+    let started = Instant::now();
+    for _ in 0..3 {
+        let response = client.send(client.http_client.get(&base_url)).await.unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+    }
+    assert!(started.elapsed().as_secs_f64() >= 0.9 / REQUESTS_PER_SECOND, "{:?}", started.elapsed());
+}
+
+#[test]
+fn test_activity_log_paths_shorten_ids() {
+    assert_eq!(
+        short_path("/v1/blocks/0123456789abcdef0123456789abcdef/children"),
+        "/v1/blocks/01234567…/children"
+    );
+    assert_eq!(short_path("/v1/pages/01234567-89ab-cdef-0123-456789abcdef"), "/v1/pages/01234567…");
+    assert_eq!(short_path("/v1/search"), "/v1/search");
+}
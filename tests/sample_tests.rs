@@ -0,0 +1,46 @@
+// Tests for the sample entry that checks a page end to end
+
+use chrono::{TimeZone, Utc};
+use faultnote::notion::client::create_error_block;
+use faultnote::notion::entries::parse_entries;
+use faultnote::notion::template::{BlockStyle, BlockTemplate};
+use faultnote::sample::{SAMPLE_MARKER, sample_entry, verify};
+
+#[test]
+fn test_sample_entry_is_marked_and_unique() {
+    let first = sample_entry(Utc.with_ymd_and_hms(2026, 10, 16, 9, 30, 0).unwrap());
+    let second = sample_entry(Utc.with_ymd_and_hms(2026, 10, 16, 9, 31, 0).unwrap());
+    assert!(first.error.starts_with(SAMPLE_MARKER));
+    assert!(first.error.contains("2026-10-16 09:30:00 UTC"));
+    assert_ne!(first.error, second.error);
+}
+
+#[test]
+fn test_sample_entry_reads_back_from_its_blocks() {
+    let entry = sample_entry(Utc::now());
+    let plain = BlockTemplate {
+        style: BlockStyle::Plain,
+        ..Default::default()
+    };
+    for template in [BlockTemplate::default(), plain] {
+        let blocks = create_error_block(&entry, None, &template).as_array().unwrap().clone();
+        let logged = parse_entries(&blocks, &template);
+        assert_eq!(verify(&entry, &logged), Ok(()));
+    }
+}
+
+#[test]
+fn test_verify_reports_a_missing_or_changed_entry() {
+    let entry = sample_entry(Utc::now());
+    let template = BlockTemplate::default();
+    let earlier = sample_entry(Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap());
+    let blocks = create_error_block(&earlier, None, &template).as_array().unwrap().clone();
+    let error = verify(&entry, &parse_entries(&blocks, &template)).unwrap_err();
+    assert!(error.contains("not on the page"));
+
+    let mut changed = entry.clone();
+    changed.solution = "Something else".to_string();
+    let blocks = create_error_block(&changed, None, &template).as_array().unwrap().clone();
+    let error = verify(&entry, &parse_entries(&blocks, &template)).unwrap_err();
+    assert!(error.starts_with("the solution reads back as \"Something else\""));
+}
@@ -0,0 +1,23 @@
+// Tests for screen dumps
+
+use faultnote::screenshot::{buffer_to_ansi, buffer_to_text};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+};
+
+#[test]
+fn test_buffer_to_text() {
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 10, 2));
+    buffer.set_string(0, 0, "📋 Note", Style::default());
+    buffer.set_string(2, 1, "ok", Style::default());
+    assert_eq!(buffer_to_text(&buffer), "📋 Note\n  ok");
+}
+
+#[test]
+fn test_buffer_to_ansi() {
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 3, 1));
+    buffer.set_string(0, 0, "ab", Style::default().fg(Color::Red));
+    assert_eq!(buffer_to_ansi(&buffer), "\x1b[0;31mab\x1b[0m \x1b[0m\n");
+}
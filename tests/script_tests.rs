@@ -0,0 +1,51 @@
+// Tests for scripted key replay
+
+use std::time::Duration;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use faultnote::app::AppState;
+use faultnote::keys::parse_key;
+use faultnote::script::{Script, ScriptStep, run_step};
+
+#[test]
+fn test_parse_script() {
+    let mut script = Script::parse(
+        "# open the editor\nkey e\ntype Hello world\n\nwait 250\nkey Ctrl+z\nexpect-status Saved",
+    )
+    .unwrap();
+
+    assert_eq!(script.next_step(), Some((2, ScriptStep::Key(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE)))));
+    assert_eq!(script.next_step(), Some((3, ScriptStep::Type("Hello world".to_string()))));
+    assert_eq!(script.next_step(), Some((5, ScriptStep::Wait(Duration::from_millis(250)))));
+    assert_eq!(script.next_step(), Some((6, ScriptStep::Key(KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL)))));
+    assert_eq!(script.next_step(), Some((7, ScriptStep::ExpectStatus("Saved".to_string()))));
+    assert!(script.is_empty());
+}
+
+#[test]
+fn test_parse_errors_name_the_line() {
+    assert_eq!(Script::parse("key e\npress x").unwrap_err(), "Line 2: unknown command 'press'");
+    assert!(Script::parse("wait soon").unwrap_err().starts_with("Line 1"));
+    assert!(parse_key("Hyper").is_err());
+}
+
+async fn replay(app: &mut AppState, content: &str) -> Result<(), String> {
+    let mut script = Script::parse(content)?;
+    while let Some((line, step)) = script.next_step() {
+        run_step(app, None, line, step).await?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replay_edits_and_checks_status() {
+    let mut app = AppState::new();
+    replay(&mut app, "key Tab\nkey e\ntype Boom\nkey Esc").await.unwrap();
+    assert_eq!(app.error_input, "Boom");
+
+    replay(&mut app, "key c\nkey y\nexpect-status cleared").await.unwrap();
+    assert!(app.error_input.is_empty());
+
+    let failed = replay(&mut app, "key Esc\nexpect-status Submitted").await;
+    assert_eq!(failed.unwrap_err(), "Script line 2: expected status containing 'Submitted', got ''");
+}
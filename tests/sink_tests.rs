@@ -0,0 +1,176 @@
+// Tests for logging to sinks other than Notion
+
+use faultnote::app::{FaultLogEntry, PageInfo, TargetKind};
+use faultnote::config::Config;
+use faultnote::notion::template::BlockTemplate;
+use faultnote::sink::github::{GitHubConfig, GitHubSink, issue};
+use faultnote::sink::obsidian::{ObsidianConfig, ObsidianSink, note_name, vault_entry};
+use faultnote::sink::{FaultSink, SinkKind};
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+fn entry() -> FaultLogEntry {
+    FaultLogEntry {
+        error: "Connection refused\nat db.rs:12".to_string(),
+        problem: "DB down after deploy".to_string(),
+        solution: "Restart it".to_string(),
+        tags: vec!["db".to_string(), "faultnote".to_string()],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_issue_has_the_title_sections_and_labels() {
+    let issue = issue(&entry(), &BlockTemplate::default(), &["faultnote".to_string()]);
+    assert_eq!(issue["title"], "Connection refused");
+    assert_eq!(issue["labels"], json!(["faultnote", "db"]));
+    let body = issue["body"].as_str().unwrap();
+    assert!(body.starts_with("### Error\n\nConnection refused\nat db.rs:12\n\n### What was the problem?"));
+}
+
+#[test]
+fn test_sink_config() {
+    let config = Config::from_toml("[sink]\nkind = \"github\"\n[sink.github]\nrepos = [\"acme/api\"]\n").unwrap();
+    assert_eq!(config.sink.kind, SinkKind::GitHub);
+    assert_eq!(config.sink.github.repos, ["acme/api"]);
+    assert_eq!(config.sink.github.token_env, "GITHUB_TOKEN");
+    assert_eq!(Config::default().sink.kind, SinkKind::Notion);
+    assert!(Config::from_toml("[sink]\nkind = \"jira\"\n").is_err());
+}
+
+#[tokio::test]
+async fn test_configured_repositories_are_the_targets() {
+    let config = GitHubConfig {
+        repos: vec!["acme/api".to_string(), "acme/web".to_string()],
+        ..Default::default()
+    };
+    let sink = GitHubSink::with_token(&config, "token").unwrap();
+    let targets = sink.list_targets().await.unwrap();
+    let names: Vec<&str> = targets.iter().map(|target| target.id.as_str()).collect();
+    assert_eq!(names, ["acme/api", "acme/web"]);
+}
+
+/// Answer one request with `response` and hand back what was sent
+async fn serve_once(response: String) -> (String, oneshot::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let (sent, received) = oneshot::channel();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut chunk = [0u8; 4096];
+        // Read the headers, then as much body as they announce
+        loop {
+            let read = socket.read(&mut chunk).await.unwrap();
+            request.extend_from_slice(&chunk[..read]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            let Some((head, body)) = text.split_once("\r\n\r\n") else {
+                continue;
+            };
+            let length = head
+                .lines()
+                .find_map(|line| line.to_lowercase().strip_prefix("content-length: ").map(str::to_string))
+                .and_then(|length| length.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+            if read == 0 || body.len() >= length {
+                let _ = sent.send(text);
+                break;
+            }
+        }
+        socket.write_all(response.as_bytes()).await.unwrap();
+        socket.shutdown().await.unwrap();
+    });
+    (format!("http://{}", address), received)
+}
+
+#[tokio::test]
+async fn test_submit_entry_opens_an_issue() {
+    let body = r#"{"number":42,"html_url":"https://github.com/acme/api/issues/42"}"#;
+    let response = format!("HTTP/1.1 201 Created\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+    let (api_url, request) = serve_once(response).await;
+    let config = GitHubConfig {
+        api_url,
+        ..Default::default()
+    };
+    let sink = GitHubSink::with_token(&config, "secret").unwrap();
+    let target = PageInfo {
+        id: "acme/api".to_string(),
+        title: "acme/api".to_string(),
+        kind: TargetKind::Page,
+        parent: None,
+    };
+    let receipt = sink.submit_entry(&target, &entry(), &BlockTemplate::default()).await.unwrap();
+    assert_eq!(receipt.ids, ["42"]);
+    assert_eq!(receipt.url, "https://github.com/acme/api/issues/42");
+
+    let request = request.await.unwrap();
+    assert!(request.starts_with("POST /repos/acme/api/issues HTTP/1.1"));
+    assert!(request.to_lowercase().contains("authorization: bearer secret"));
+    assert!(request.contains(r#""title":"Connection refused""#));
+}
+
+#[tokio::test]
+async fn test_submit_entry_reports_github_errors() {
+    let body = r#"{"message":"Not Found"}"#;
+    let response = format!("HTTP/1.1 404 Not Found\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+    let (api_url, _request) = serve_once(response).await;
+    let config = GitHubConfig {
+        api_url,
+        ..Default::default()
+    };
+    let sink = GitHubSink::with_token(&config, "secret").unwrap();
+    let target = PageInfo {
+        id: "acme/missing".to_string(),
+        title: "acme/missing".to_string(),
+        kind: TargetKind::Page,
+        parent: None,
+    };
+    let error = sink.submit_entry(&target, &entry(), &BlockTemplate::default()).await.unwrap_err();
+    assert_eq!(error, "GitHub returned 404: Not Found");
+}
+
+#[test]
+fn test_vault_entry_uses_callouts_and_tags() {
+    let mut entry = entry();
+    entry.code = Some("psql -h db".to_string());
+    entry.language = Some("shell".to_string());
+    let note = vault_entry(&entry, &BlockTemplate::default());
+    assert!(note.starts_with("## Connection refused\n\n> [!bug] Error\n> "));
+    assert!(note.contains("> [!question] What was the problem?\n> DB down after deploy"));
+    assert!(note.contains("> [!success]"));
+    assert!(note.contains("```shell\npsql -h db\n```"));
+    assert!(note.ends_with("#db #faultnote\n\n---\n"));
+}
+
+#[test]
+fn test_note_name_drops_characters_obsidian_refuses() {
+    assert_eq!(note_name("api/server: errors"), "api-server- errors");
+    assert_eq!(note_name(".."), "Fault Log");
+}
+
+#[tokio::test]
+async fn test_obsidian_sink_appends_to_the_project_note() {
+    let vault = std::env::temp_dir().join(format!("faultnote-vault-{}", std::process::id()));
+    std::fs::create_dir_all(&vault).unwrap();
+    let config = ObsidianConfig {
+        vault: vault.display().to_string(),
+        ..Default::default()
+    };
+    let sink = ObsidianSink::new(&config, "api").unwrap();
+    let targets = sink.list_targets().await.unwrap();
+    assert_eq!(targets.iter().map(|t| t.title.as_str()).collect::<Vec<_>>(), ["api"]);
+
+    let template = BlockTemplate::default();
+    let receipt = sink.submit_entry(&targets[0], &entry(), &template).await.unwrap();
+    sink.submit_entry(&targets[0], &entry(), &template).await.unwrap();
+    let vault_name = vault.file_name().unwrap().to_string_lossy();
+    assert_eq!(receipt.url, format!("obsidian://open?vault={}&file=FaultNote%2Fapi", vault_name));
+    let note = std::fs::read_to_string(vault.join("FaultNote/api.md")).unwrap();
+    assert_eq!(note.matches("## Connection refused").count(), 2);
+    assert!(note.contains("---\n\n## Connection refused"));
+    std::fs::remove_dir_all(&vault).unwrap();
+
+    assert!(ObsidianSink::new(&ObsidianConfig::default(), "api").is_err());
+}
@@ -0,0 +1,260 @@
+// Tests for the local SQLite history
+
+use std::env;
+use std::fs;
+
+use faultnote::app::{AppState, FaultLogEntry, FaultStatus};
+use faultnote::fields::InputField;
+use faultnote::storage::{EntryStatus, History};
+
+fn entry(error: &str, solution: &str) -> FaultLogEntry {
+    FaultLogEntry {
+        error: error.to_string(),
+        problem: "Problem".to_string(),
+        solution: solution.to_string(),
+        code: Some("SELECT 1;".to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_record_and_read_back() {
+    let history = History::open_in_memory().unwrap();
+    let id = history
+        .record("page-1", "Backend", &entry("Timeout", "Raise limit"), EntryStatus::Submitted)
+        .unwrap()
+        .unwrap();
+
+    let stored = history.get(id).unwrap().unwrap();
+    assert_eq!(stored.page_title, "Backend");
+    assert_eq!(stored.status, EntryStatus::Submitted);
+    assert_eq!(stored.entry.error, "Timeout");
+    assert_eq!(stored.entry.code.as_deref(), Some("SELECT 1;"));
+
+    history.set_status(id, EntryStatus::Undone).unwrap();
+    assert_eq!(history.get(id).unwrap().unwrap().status, EntryStatus::Undone);
+}
+
+#[test]
+fn test_field_values_are_distinct_and_most_recent_first() {
+    let history = History::open_in_memory().unwrap();
+    for error in ["Timeout", "Deadlock", " Timeout ", "OOM"] {
+        history.record("page-1", "Backend", &entry(error, "Fix"), EntryStatus::Submitted).unwrap();
+    }
+
+    let values = history.field_values(InputField::Error, 10).unwrap();
+    assert_eq!(values, ["OOM", "Timeout", "Deadlock"]);
+    assert_eq!(history.field_values(InputField::Error, 1).unwrap(), ["OOM"]);
+    assert!(history.field_values(InputField::Verification, 10).unwrap().is_empty());
+}
+
+#[test]
+fn test_row_status_updates() {
+    let history = History::open_in_memory().unwrap();
+    let id = history
+        .record("db-1", "Bugs", &entry("Timeout", "Raise limit"), EntryStatus::Submitted)
+        .unwrap()
+        .unwrap();
+    assert_eq!(history.get(id).unwrap().unwrap().row_id, None);
+
+    history.set_row_id(id, "row-1").unwrap();
+    let mut stored = history.get(id).unwrap().unwrap();
+    assert_eq!(stored.row_id.as_deref(), Some("row-1"));
+    assert_eq!(stored.entry.status, FaultStatus::Open);
+
+    stored.entry.status = stored.entry.status.next();
+    history.update_entry(id, &stored.entry).unwrap();
+    assert_eq!(history.get(id).unwrap().unwrap().entry.status, FaultStatus::Investigating);
+}
+
+#[test]
+fn test_older_history_gains_row_ids() {
+    let path = env::temp_dir().join(format!("faultnote-history-{}.sqlite3", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE entries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT, submitted_at TEXT NOT NULL, page_id TEXT NOT NULL,
+            page_title TEXT NOT NULL, status TEXT NOT NULL, error TEXT NOT NULL, problem TEXT NOT NULL,
+            solution TEXT NOT NULL, code TEXT, entry_json TEXT NOT NULL
+        );
+        INSERT INTO entries (submitted_at, page_id, page_title, status, error, problem, solution, entry_json)
+        VALUES ('2025-01-01T00:00:00Z', 'p', 'Old', 'submitted', 'e', 'p', 's', '{\"error\":\"e 4bf92f3577b34da6a3ce929d0e0e4736\"}');",
+    )
+    .unwrap();
+    drop(conn);
+
+    let history = History::open(path.clone()).unwrap();
+    let old = &history.recent(10).unwrap()[0];
+    assert_eq!(old.entry.error, "e 4bf92f3577b34da6a3ce929d0e0e4736");
+    assert_eq!(old.row_id, None);
+    history.set_row_id(old.id, "row-1").unwrap();
+    assert_eq!(history.get(old.id).unwrap().unwrap().row_id.as_deref(), Some("row-1"));
+    // Older entries are hashed on upgrade, so they count as duplicates
+    assert!(history.contains("p", &old.entry.content_hash()).unwrap());
+    // and their trace IDs indexed
+    assert_eq!(history.with_trace_id("4bf92f3577b34da6a3ce929d0e0e4736", 10).unwrap().len(), 1);
+
+    drop(history);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_search_by_tag() {
+    let history = History::open_in_memory().unwrap();
+    let mut tagged = entry("Timeout", "Retry");
+    tagged.tags = vec!["db".to_string(), "Über".to_string()];
+    history.record("p", "Backend", &tagged, EntryStatus::Submitted).unwrap();
+    history.record("p", "Backend", &entry("über outage", "Rollback"), EntryStatus::Submitted).unwrap();
+
+    let found = history.search("#ÜBER", 10).unwrap();
+    assert_eq!(found.iter().map(|logged| logged.entry.error.as_str()).collect::<Vec<_>>(), ["Timeout"]);
+    assert_eq!(history.search(" #DB ", 10).unwrap().len(), 1);
+    assert!(history.with_tag("staging", 10).unwrap().is_empty());
+    // A bare # is a plain text search
+    assert!(history.search("#", 10).unwrap().is_empty());
+}
+
+#[test]
+fn test_contains_finds_the_same_content_on_the_same_page() {
+    let history = History::open_in_memory().unwrap();
+    let logged = entry("Timeout", "Raise limit");
+    let id = history
+        .record("1a2b-3c4d", "Backend", &logged, EntryStatus::Submitted)
+        .unwrap()
+        .unwrap();
+
+    // Whitespace around fields and ids without dashes still match
+    let padded = FaultLogEntry {
+        error: "  Timeout\n".to_string(),
+        ..logged.clone()
+    };
+    assert!(history.contains("1a2b3c4d", &padded.content_hash()).unwrap());
+    assert!(!history.contains("other-page", &logged.content_hash()).unwrap());
+    assert!(!history.contains("1a2b-3c4d", &entry("Timeout", "Restart").content_hash()).unwrap());
+
+    // Undone entries are no longer on the page
+    history.set_status(id, EntryStatus::Undone).unwrap();
+    assert!(!history.contains("1a2b-3c4d", &logged.content_hash()).unwrap());
+}
+
+#[test]
+fn test_search_newest_first() {
+    let history = History::open_in_memory().unwrap();
+    history.record("p", "Backend", &entry("Timeout on DB", "Pool size"), EntryStatus::Submitted).unwrap();
+    history.record("p", "Frontend", &entry("Blank page", "Fix 100% width"), EntryStatus::Queued).unwrap();
+    history.record("p", "Backend", &entry("DB deadlock", "Order locks"), EntryStatus::Submitted).unwrap();
+
+    let all = history.recent(10).unwrap();
+    assert_eq!(all.len(), 3);
+    assert_eq!(all[0].entry.error, "DB deadlock");
+
+    let db = history.search("db", 10).unwrap();
+    assert_eq!(db.len(), 2);
+    assert_eq!(history.search("frontend", 10).unwrap().len(), 1);
+    // LIKE wildcards in the query match literally
+    assert_eq!(history.search("100%", 10).unwrap().len(), 1);
+    assert_eq!(history.search("%", 10).unwrap().len(), 1);
+    assert_eq!(history.recent(1).unwrap().len(), 1);
+}
+
+#[test]
+fn test_search_by_trace_id_uses_the_index() {
+    let history = History::open_in_memory().unwrap();
+    let trace = "4bf92f3577b34da6a3ce929d0e0e4736";
+    let first = history
+        .record("p", "Backend", &entry(&format!("Timeout trace={}", trace), "Retry"), EntryStatus::Submitted)
+        .unwrap()
+        .unwrap();
+    history.record("p", "Backend", &entry("Panic", "Unwrap"), EntryStatus::Submitted).unwrap();
+    let mut later = entry("Timeout again", "Retry");
+    later.code = Some(format!("traceparent: 00-{}-00f067aa0ba902b7-01", trace.to_uppercase()));
+    history.record("p", "Backend", &later, EntryStatus::Submitted).unwrap();
+
+    let found = history.search(&trace.to_uppercase(), 10).unwrap();
+    assert_eq!(found.iter().map(|logged| logged.entry.error.as_str()).collect::<Vec<_>>(), ["Timeout again", &format!("Timeout trace={}", trace)]);
+
+    // Edited and deleted entries leave the index
+    history.update_entry(first, &entry("Timeout", "Retry")).unwrap();
+    assert_eq!(history.with_trace_id(trace, 10).unwrap().len(), 1);
+    history.delete(&[first + 2]).unwrap();
+    assert!(history.search(trace, 10).unwrap().is_empty());
+}
+
+#[test]
+fn test_find_trace_opens_the_history_on_the_id() {
+    let mut app = AppState::new();
+    app.history = History::open_in_memory().unwrap();
+    app.history
+        .record("p", "Backend", &entry("id 3f2504e0-4f89-11d3-9a0c-0305e82c3301", "Retry"), EntryStatus::Submitted)
+        .unwrap();
+
+    app.find_trace();
+    assert!(!app.is_history_screen());
+
+    app.error_input = "Failed: 3F2504E0-4F89-11D3-9A0C-0305E82C3301".into();
+    app.find_trace();
+    assert!(app.is_history_screen());
+    assert_eq!(app.history_query, "3f2504e0-4f89-11d3-9a0c-0305e82c3301");
+    assert_eq!(app.history_entries.len(), 1);
+    assert!(app.status_message.as_deref().unwrap().contains("logged once before"));
+}
+
+#[test]
+fn test_default_history_drops_records() {
+    let history = History::default();
+    assert_eq!(history.record("p", "t", &entry("e", "s"), EntryStatus::Queued).unwrap(), None);
+    assert!(history.recent(10).unwrap().is_empty());
+}
+
+#[test]
+fn test_history_screen_filters() {
+    let mut app = AppState::new();
+    app.history = History::open_in_memory().unwrap();
+    app.history.record("p", "Backend", &entry("Timeout", "Retry"), EntryStatus::Submitted).unwrap();
+    app.history.record("p", "Backend", &entry("Panic", "Unwrap"), EntryStatus::Submitted).unwrap();
+
+    app.open_history();
+    assert!(app.is_history_screen());
+    assert_eq!(app.history_entries.len(), 2);
+
+    app.history_query = "panic".to_string();
+    app.refresh_history();
+    assert_eq!(app.selected_history_entry().unwrap().entry.error, "Panic");
+
+    app.close_history();
+    assert!(!app.is_history_screen());
+}
+
+#[test]
+fn test_prune_keeps_queued_and_newest_entries() {
+    use chrono::{Duration, Utc};
+    use faultnote::storage::{Pruned, Retention};
+
+    let history = History::open_in_memory().unwrap();
+    history.record("p", "t", &entry("Queued", "s"), EntryStatus::Queued).unwrap();
+    for error in ["One", "Two", "Three"] {
+        history.record("p", "t", &entry(error, "s"), EntryStatus::Submitted).unwrap();
+    }
+
+    let newest_two = Retention {
+        max_age_days: 0,
+        max_entries: 2,
+    };
+    let dry = history.prune(&newest_two, Utc::now(), true).unwrap();
+    assert_eq!(dry, Pruned { removed: 1, kept: 3 });
+    assert_eq!(history.recent(10).unwrap().len(), 4);
+
+    history.prune(&newest_two, Utc::now(), false).unwrap();
+    let errors: Vec<String> = history.recent(10).unwrap().into_iter().map(|e| e.entry.error).collect();
+    assert_eq!(errors, ["Three", "Two", "Queued"]);
+
+    // Everything is a day old two days from now; the queued entry stays
+    let one_day = Retention {
+        max_age_days: 1,
+        max_entries: 0,
+    };
+    let pruned = history.prune(&one_day, Utc::now() + Duration::days(2), false).unwrap();
+    assert_eq!(pruned, Pruned { removed: 2, kept: 1 });
+    assert_eq!(Retention::default(), Retention { max_age_days: 0, max_entries: 0 });
+}
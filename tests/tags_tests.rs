@@ -0,0 +1,16 @@
+// Tests for parsing and comparing entry tags
+
+use faultnote::tags::{key, parse};
+
+#[test]
+fn test_tags_are_split_trimmed_and_deduplicated() {
+    assert_eq!(parse(" db, prod,,DB , api\nnet"), ["db", "prod", "api", "net"]);
+    assert!(parse(" , \n").is_empty());
+}
+
+#[test]
+fn test_case_is_ignored_beyond_ascii() {
+    assert_eq!(key(" Ünïcode "), key("üNÏCODE"));
+    assert_eq!(parse("Straße, STRASSE, straße"), ["Straße", "STRASSE"]);
+    assert_eq!(parse("Éclair, éCLAIR"), ["Éclair"]);
+}
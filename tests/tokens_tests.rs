@@ -0,0 +1,21 @@
+// Tests for the per-page token memory
+
+use std::env;
+use std::fs;
+
+use faultnote::notion::tokens::TokenMap;
+
+#[test]
+fn test_token_map_round_trip() {
+    let path = env::temp_dir().join(format!("faultnote-tokens-{}.json", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let mut map = TokenMap::load(path.clone()).unwrap();
+    assert_eq!(map.get("page"), None);
+    map.record("page", "team").unwrap();
+
+    let reloaded = TokenMap::load(path.clone()).unwrap();
+    assert_eq!(reloaded.get("page"), Some("team"));
+
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,43 @@
+// Tests for finding trace and correlation IDs
+
+use faultnote::app::FaultLogEntry;
+use faultnote::trace_ids::{as_trace_id, entry_trace_ids, find_trace_ids};
+
+#[test]
+fn test_finds_uuids_and_trace_ids() {
+    let text = "request_id=3F2504E0-4F89-11D3-9A0C-0305E82C3301 failed\n\
+                trace 4bf92f3577b34da6a3ce929d0e0e4736, again 4bf92f3577b34da6a3ce929d0e0e4736";
+    assert_eq!(
+        find_trace_ids(text),
+        ["3f2504e0-4f89-11d3-9a0c-0305e82c3301", "4bf92f3577b34da6a3ce929d0e0e4736"]
+    );
+}
+
+#[test]
+fn test_traceparent_gives_its_trace_id_and_xray_ids_are_kept_whole() {
+    let text = "traceparent: 00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01\n\
+                X-Amzn-Trace-Id: Root=1-5759e988-bd862e3fe1be46a994272793";
+    assert_eq!(
+        find_trace_ids(text),
+        ["4bf92f3577b34da6a3ce929d0e0e4736", "1-5759e988-bd862e3fe1be46a994272793"]
+    );
+}
+
+#[test]
+fn test_ignores_hashes_and_words() {
+    // A git commit, a short hex id and a dashed word are not trace IDs
+    let text = "at 9fceb02d0ae598e95dc970b74767f19372d61af8 (deadbeef) in so-called code";
+    assert!(find_trace_ids(text).is_empty());
+}
+
+#[test]
+fn test_entry_ids_and_queries() {
+    let entry = FaultLogEntry {
+        error: "trace 4bf92f3577b34da6a3ce929d0e0e4736".to_string(),
+        code: Some("id: 3f2504e0-4f89-11d3-9a0c-0305e82c3301".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(entry_trace_ids(&entry).len(), 2);
+    assert_eq!(as_trace_id(" 4BF92F3577B34DA6A3CE929D0E0E4736 ").as_deref(), Some("4bf92f3577b34da6a3ce929d0e0e4736"));
+    assert_eq!(as_trace_id("timeout 4bf92f3577b34da6a3ce929d0e0e4736"), None);
+}
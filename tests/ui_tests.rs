@@ -0,0 +1,303 @@
+// Tests for rendering
+
+use faultnote::app::AppState;
+use faultnote::ui::{self, MIN_HEIGHT, MIN_WIDTH, Theme};
+use ratatui::{Terminal, backend::TestBackend, layout::Rect, style::Color};
+
+fn rendered_text(width: u16, height: u16) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    let app = AppState::new();
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect()
+}
+
+#[test]
+fn test_small_terminal_shows_placeholder() {
+    let text = rendered_text(40, 10);
+    assert!(text.contains("Terminal too small"));
+    assert!(text.contains("needs 60x16"));
+}
+
+#[test]
+fn test_minimum_size_renders_layout() {
+    let text = rendered_text(MIN_WIDTH, MIN_HEIGHT);
+    assert!(!text.contains("Terminal too small"));
+    assert!(text.contains("FaultNote"));
+}
+
+#[test]
+fn test_page_down_scrolls_focused_field() {
+    let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+    let mut app = AppState::new();
+    app.error_input = (1..=30).map(|i| format!("trace line {}", i)).collect::<Vec<_>>().join("\n").into();
+    app.toggle_focus();
+    let screen = |terminal: &Terminal<TestBackend>| -> String {
+        terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect()
+    };
+
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    assert!(screen(&terminal).contains("trace line 1 "));
+    assert!(!screen(&terminal).contains("trace line 30"));
+
+    // Paging past the end stops at the last screenful
+    for _ in 0..20 {
+        app.handle_page_down();
+    }
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    assert!(!screen(&terminal).contains("trace line 1 "));
+    assert!(screen(&terminal).contains("trace line 30"));
+    assert!(app.field_scroll.get()[0] > 0);
+
+    app.handle_page_up();
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    assert!(!screen(&terminal).contains("trace line 30"));
+}
+
+#[test]
+fn test_mouse_selects_pages_and_focuses_fields() {
+    use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+    use faultnote::app::{PageInfo, TargetKind};
+    use faultnote::events::handle_mouse_event;
+
+    let mouse = |kind, column, row| MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    };
+    let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+    let mut app = AppState::new();
+    app.set_pages(
+        (0..5)
+            .map(|i| PageInfo {
+                id: format!("page-{}", i),
+                title: format!("Page {}", i),
+                kind: TargetKind::Page,
+                parent: None,
+            })
+            .collect(),
+    );
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+
+    // Third row inside the page list's border
+    let list = app.page_list_area.get();
+    handle_mouse_event(&mut app, mouse(MouseEventKind::Down(MouseButton::Left), list.x + 2, list.y + 3));
+    assert_eq!(app.selected_page_index, 2);
+    handle_mouse_event(&mut app, mouse(MouseEventKind::ScrollDown, list.x + 2, list.y + 3));
+    assert_eq!(app.selected_page_index, 4);
+
+    // Clicking a field focuses it
+    let solution = app.field_areas.get()[2];
+    handle_mouse_event(&mut app, mouse(MouseEventKind::Down(MouseButton::Left), solution.x + 3, solution.y + 1));
+    assert!(app.is_input_section_focused());
+    assert_eq!(app.active_input_field, 2);
+
+    // The wheel scrolls the field under the pointer
+    app.context_input = (1..=30).map(|i| format!("line {}", i)).collect::<Vec<_>>().join("\n").into();
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    let context = app.field_areas.get()[5];
+    handle_mouse_event(&mut app, mouse(MouseEventKind::ScrollDown, context.x + 3, context.y + 1));
+    assert_eq!(app.field_scroll.get()[5], 3);
+    assert_eq!(app.active_input_field, 2);
+
+    // An open prompt takes the clicks
+    app.attach_input = Some(String::new());
+    assert!(app.has_modal());
+    handle_mouse_event(&mut app, mouse(MouseEventKind::Down(MouseButton::Left), list.x + 2, list.y + 1));
+    assert_eq!(app.selected_page_index, 4);
+}
+
+#[test]
+fn test_page_list_shows_loading_placeholder() {
+    let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+    let mut app = AppState::new();
+    app.pages_loading = true;
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+    assert!(text.contains("Loading pages…"));
+    assert!(!text.contains("No pages loaded"));
+}
+
+#[test]
+fn test_confirmation_dialog_is_drawn_over_the_screen() {
+    use faultnote::app::PendingAction;
+
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    let mut app = AppState::new();
+    app.request_confirmation(PendingAction::ClearInputs);
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+    assert!(text.contains("Clear all fields?"));
+    assert!(text.contains("Yes"));
+    assert!(text.contains("n/Esc"));
+}
+
+#[test]
+fn test_mini_layout_fits_a_thin_pane() {
+    let mut terminal = Terminal::new(TestBackend::new(50, 3)).unwrap();
+    let mut app = AppState::new();
+    app.error_input = "connection refused\nat db.rs:4".into();
+    app.toggle_focus();
+    let screen = |terminal: &Terminal<TestBackend>| -> String {
+        terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect()
+    };
+
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    assert!(screen(&terminal).contains("Terminal too small"));
+    assert!(screen(&terminal).contains("M for the mini layout"));
+
+    app.toggle_mini();
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    assert!(screen(&terminal).contains("NORMAL"));
+    assert!(screen(&terminal).contains("connection refused …"));
+    assert!(screen(&terminal).contains("[M] Expand"));
+
+    // While editing the row with the cursor scrolls to keep it in view
+    app.enter_edit_mode();
+    app.error_input.set_cursor(0);
+    for c in "a very long prefix typed before the original error text ".chars() {
+        app.add_char(c);
+    }
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    assert!(screen(&terminal).contains("original error text ▌"));
+    assert!(!screen(&terminal).contains("a very long"));
+}
+
+#[test]
+fn test_message_log_panel_shows_earlier_messages() {
+    use std::time::Duration;
+
+    use faultnote::notion::activity::ApiCall;
+    use reqwest::Method;
+
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    let mut app = AppState::new();
+    app.set_error("Notion returned 502");
+    app.set_status("Connected");
+    app.log_api_call(&ApiCall {
+        method: Method::POST,
+        path: "/v1/search".to_string(),
+        status: Some(200),
+        attempts: 1,
+        elapsed: Duration::from_millis(87),
+    });
+    app.toggle_status_log();
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+    assert!(text.contains("Activity (3)"));
+    assert!(text.contains("ERROR app ✗ Notion returned 502"));
+    assert!(text.contains("OK    api POST /v1/search → 200 in 87ms"));
+}
+
+#[test]
+fn test_zen_mode_shows_the_active_field_alone() {
+    use faultnote::fields::InputField;
+
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    let mut app = AppState::new();
+    app.error_input = "connection refused".into();
+    app.solution_input = "Restarted the pool".into();
+    app.toggle_zen();
+    app.next_input();
+    app.next_input();
+    assert_eq!(app.active_field(), InputField::Solution);
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+
+    assert!(!text.contains("Notion Pages"));
+    assert!(!text.contains("Commands"));
+    assert!(text.contains("Error: connection refused"));
+    assert!(text.contains("Restarted the pool"));
+    assert!(text.contains("Z Leave zen"));
+    // Centered: the column starts away from the left edge
+    let first_row: String = text.chars().take(120).collect();
+    assert!(first_row.starts_with("                  "));
+}
+
+#[test]
+fn test_page_list_indents_subpages() {
+    use faultnote::app::PageInfo;
+
+    let page = |id: &str, title: &str, parent: Option<&str>| PageInfo {
+        id: id.to_string(),
+        title: title.to_string(),
+        parent: parent.map(str::to_string),
+        ..Default::default()
+    };
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    let mut app = AppState::new();
+    app.set_pages(vec![
+        page("a", "Project A", None),
+        page("b", "Project B", None),
+        page("a1", "Errors", Some("a")),
+        page("b1", "Errors", Some("b")),
+    ]);
+    // Project B is listed after Project A's subpage
+    app.selected_page_index = 2;
+    app.collapse_page();
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    let lines: Vec<String> = terminal
+        .backend()
+        .buffer()
+        .content()
+        .chunks(100)
+        .map(|row| row.iter().map(|cell| cell.symbol()).collect())
+        .collect();
+    let row = |title: &str| lines.iter().find(|line| line.contains(title)).cloned().unwrap_or_default();
+    assert!(row("Project A").contains("▾ Project A"), "{:?}", lines);
+    assert!(row("Errors").contains("    Errors"), "{:?}", lines);
+    assert!(row("Project B").contains("▸ Project B"), "{:?}", lines);
+    assert_eq!(lines.iter().filter(|line| line.contains("Errors")).count(), 1);
+
+    // Filter matches name their parent instead
+    app.start_page_filter();
+    app.page_filter = "errors".to_string();
+    app.refresh_page_filter();
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+    assert!(text.contains("Errors in Project A"));
+    assert!(text.contains("Errors in Project B"));
+}
+
+#[test]
+fn test_mono_theme_drops_colors() {
+    let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+    let mut app = AppState::new();
+    app.config.theme = Theme::Mono;
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    let buffer = terminal.backend().buffer();
+    assert!(buffer.content().iter().all(|cell| cell.fg == Color::Reset && cell.bg == Color::Reset));
+    assert!(buffer.content().iter().any(|cell| cell.symbol() != " "));
+}
+
+#[test]
+fn test_mini_layout_leaves_nothing_to_click() {
+    let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+    let mut app = AppState::new();
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    assert_ne!(app.field_areas.get()[0], Rect::default());
+
+    app.mini = true;
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    assert_eq!(app.page_list_area.get(), Rect::default());
+    assert!(app.field_areas.get().iter().all(|area| *area == Rect::default()));
+}
+
+#[test]
+fn test_zen_mode_hides_the_page_list_from_clicks() {
+    let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+    let mut app = AppState::new();
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    assert_ne!(app.page_list_area.get(), Rect::default());
+
+    app.zen = true;
+    terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+    assert_eq!(app.page_list_area.get(), Rect::default());
+    assert_ne!(app.field_areas.get()[app.active_input_field], Rect::default());
+}
@@ -0,0 +1,71 @@
+// Tests for template variables
+
+use faultnote::app::{FaultLogEntry, FaultStatus, Severity};
+use faultnote::config::Config;
+use faultnote::notion::client::create_error_block;
+use faultnote::notion::locale::Locale;
+use faultnote::vars::Vars;
+
+fn vars() -> Vars {
+    let mut vars = Vars::new();
+    vars.set("date", "2025-03-01");
+    vars.set("project", "faultnote");
+    vars
+}
+
+#[test]
+fn test_render_variables() {
+    let vars = vars();
+    assert_eq!(vars.render("{date} in {project}"), "2025-03-01 in faultnote");
+    assert_eq!(vars.render("{ date }!"), "2025-03-01!");
+    // Unknown names and stray braces stay as written; doubled braces escape
+    assert_eq!(vars.render("{nope} {date"), "{nope} {date");
+    assert_eq!(vars.render("{{date}} }"), "{date} }");
+    assert_eq!(vars.render("ünïcode {date} ✓"), "ünïcode 2025-03-01 ✓");
+}
+
+#[test]
+fn test_entry_variables() {
+    let entry = FaultLogEntry {
+        error: " Timeout ".to_string(),
+        status: FaultStatus::Fixed,
+        severity: Some(Severity::High),
+        tags: vec!["db".to_string(), "prod".to_string()],
+        ..Default::default()
+    };
+    let vars = Vars::for_entry(&entry, Some("api"), Locale::En);
+    assert_eq!(vars.render("[{project}] {error} ({status}; {tags})"), "[api] Timeout (Fixed; db, prod)");
+    assert_eq!(vars.render("{severity}: {error}"), "High: Timeout");
+    assert_eq!(Vars::for_entry(&FaultLogEntry::default(), None, Locale::En).get("severity"), Some(""));
+    assert_eq!(vars.get("date").unwrap().len(), 10);
+    assert!(!vars.get("hostname").unwrap().is_empty());
+}
+
+#[test]
+fn test_template_resolves_title_metadata_and_labels() {
+    let config = Config::from_toml(
+        r#"
+        [template]
+        title = "{date} · {error}"
+        metadata = "Logged {date} for {project}"
+        [template.labels]
+        solution = "Fix ({project})"
+        "#,
+    )
+    .unwrap();
+    let template = config.template.resolve(&vars());
+    assert_eq!(template.labels.solution.as_deref(), Some("Fix (faultnote)"));
+    assert_eq!(template.labels.problem, None);
+
+    let entry = FaultLogEntry {
+        error: "Timeout".to_string(),
+        ..Default::default()
+    };
+    let block = create_error_block(&entry, None, &template.resolve(&Vars::for_entry(&entry, None, Locale::En)));
+    let heading = &block[0]["heading_2"];
+    // Names left unknown the first time resolve on a later pass
+    assert_eq!(heading["rich_text"][1]["text"]["content"], "2025-03-01 · Timeout");
+    let sections = heading["children"].as_array().unwrap();
+    assert_eq!(sections[1]["paragraph"]["rich_text"][0]["text"]["content"], "Logged 2025-03-01 for faultnote");
+    assert_eq!(sections[1]["paragraph"]["color"], "gray");
+}
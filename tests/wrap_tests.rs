@@ -0,0 +1,103 @@
+// Tests for cached field wrapping
+
+use faultnote::buffer::TextBuffer;
+use faultnote::wrap::{WrapCache, scroll_offset, truncate_to_width, wrap_line};
+
+#[test]
+fn test_wrap_line() {
+    assert_eq!(wrap_line("hello world", 20), vec!["hello world"]);
+    assert_eq!(wrap_line("hello world", 8), vec!["hello ", "world"]);
+    assert_eq!(wrap_line("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+    assert_eq!(wrap_line("", 4), vec![""]);
+    // Wide characters take two columns
+    assert_eq!(wrap_line("日本語", 4), vec!["日本", "語"]);
+    // Emoji sequences and accents stay whole
+    assert_eq!(wrap_line("ab👨‍👩‍👧c", 3), vec!["ab", "👨‍👩‍👧c"]);
+    assert_eq!(wrap_line("cafe\u{301}s", 4), vec!["cafe\u{301}", "s"]);
+}
+
+#[test]
+fn test_truncate_to_width() {
+    assert_eq!(truncate_to_width("日本語", 5), "日本");
+    assert_eq!(truncate_to_width("a👍🏽b", 2), "a");
+    assert_eq!(truncate_to_width("short", 10), "short");
+}
+
+#[test]
+fn test_wrap_cache_rewraps_only_changed_lines() {
+    let mut cache = WrapCache::new();
+    let text: String = (0..1000).map(|i| format!("line {}\n", i)).collect();
+    let mut content = TextBuffer::from(text + "last");
+
+    cache.update(&content, 40, false);
+    assert_eq!(cache.rewrapped(), 1001);
+    assert_eq!(cache.row_count(), 1001);
+
+    // Typing at the end touches only the last line
+    content.push('!');
+    cache.update(&content, 40, true);
+    assert_eq!(cache.rewrapped(), 1);
+    assert_eq!(cache.rows(1000, 1), vec![("last!▌", "last!▌")]);
+
+    // A new width invalidates everything
+    cache.update(&content, 20, true);
+    assert_eq!(cache.rewrapped(), 1001);
+}
+
+#[test]
+fn test_scroll_offset_follows_cursor() {
+    // Cursor below the view pulls it down just far enough
+    assert_eq!(scroll_offset(0, Some(12), 40, 5), 8);
+    // Cursor above the view pulls it up to the cursor row
+    assert_eq!(scroll_offset(20, Some(3), 40, 5), 3);
+    // A visible cursor leaves the view alone
+    assert_eq!(scroll_offset(10, Some(12), 40, 5), 10);
+    // Without a cursor the offset stops at the last screenful
+    assert_eq!(scroll_offset(100, None, 40, 5), 35);
+    assert_eq!(scroll_offset(3, None, 4, 5), 0);
+}
+
+#[test]
+fn test_cursor_row_and_column_on_wrapped_lines() {
+    let mut cache = WrapCache::new();
+    let mut content = TextBuffer::from("hello world again\nx");
+    content.set_cursor(8);
+    cache.update(&content, 8, true);
+    // "hello " / "wo▌rld " / "again" / "x"
+    assert_eq!(cache.cursor_row(), Some(1));
+    assert_eq!(cache.cursor_column(), 2);
+
+    content.set_cursor(content.len_chars());
+    cache.update(&content, 8, true);
+    assert_eq!(cache.cursor_row(), Some(3));
+    assert_eq!(cache.cursor_column(), 1);
+}
+
+#[test]
+fn test_up_and_down_move_between_wrapped_rows() {
+    let mut cache = WrapCache::new();
+    let mut content = TextBuffer::from("hello world again\nxy");
+    cache.update(&content, 8, true);
+    content.set_cursor(content.len_chars());
+
+    // Up from the end of "xy" lands in "again" at the same column
+    let target = cache.vertical_target(&content, false).unwrap();
+    assert_eq!(target, 14);
+    content.set_cursor(target);
+    // Then into "world ", before its trailing space at most
+    content.set_cursor(cache.vertical_target(&content, false).unwrap());
+    assert_eq!(content.cursor(), 8);
+    content.set_cursor(cache.vertical_target(&content, false).unwrap());
+    assert_eq!(content.cursor(), 2);
+    assert_eq!(cache.vertical_target(&content, false), None);
+
+    // Down keeps aiming for the column it started from
+    content.set_cursor(4);
+    content.set_cursor(cache.vertical_target(&content, true).unwrap());
+    content.set_cursor(cache.vertical_target(&content, true).unwrap());
+    content.set_cursor(cache.vertical_target(&content, true).unwrap());
+    assert_eq!(content.cursor(), content.len_chars());
+    content.set_cursor(cache.vertical_target(&content, false).unwrap());
+    assert_eq!(content.cursor(), 16);
+    assert_eq!(cache.vertical_target(&content, true), Some(20));
+}